@@ -27,14 +27,17 @@ echo "Building all platforms..."
 echo "Building Windows version..."
 cross build --release --target x86_64-pc-windows-gnu
 cp target/x86_64-pc-windows-gnu/release/hardware_id.exe target/release/output/
+cp target/x86_64-pc-windows-gnu/release/hardware_id_agent.exe target/release/output/
 
 echo "Building Linux version..."
 CROSS_COMPILE=x86_64-linux-musl- cross build --release --target x86_64-unknown-linux-musl
 cp target/x86_64-unknown-linux-musl/release/hardware_id target/release/output/hardware_id_linux
+cp target/x86_64-unknown-linux-musl/release/hardware_id_agent target/release/output/hardware_id_agent_linux
 
 echo "Building macOS Intel version..."
 cargo build --release --target x86_64-apple-darwin
 cp target/x86_64-apple-darwin/release/hardware_id target/release/output/hardware_id_mac
+cp target/x86_64-apple-darwin/release/hardware_id_agent target/release/output/hardware_id_agent_mac
 
 echo "Build completed!"
 ls -l target/release/output/ 
\ No newline at end of file
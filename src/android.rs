@@ -0,0 +1,63 @@
+// Android（NDK）上的采集辅助：系统属性与 Wi-Fi 网卡的 MAC
+// Android 10 起普通应用读不到 ro.serialno 与真实 MAC，读到的是空值或固定占位值，此时报告 PermissionDenied
+
+use std::ffi::{CStr, CString};
+use crate::error::HardwareError;
+
+// 系统属性值的最大长度（含结尾 NUL），见 <sys/system_properties.h>
+const PROP_VALUE_MAX: usize = 92;
+
+// 未设置或为空时为 None
+pub(crate) fn property(name: &str) -> Option<String> {
+    let name = CString::new(name).ok()?;
+    let mut value = [0 as libc::c_char; PROP_VALUE_MAX];
+    // SAFETY: name 以 NUL 结尾，value 的长度为 PROP_VALUE_MAX，系统保证写入的值不超过该长度
+    let len = unsafe { libc::__system_property_get(name.as_ptr(), value.as_mut_ptr()) };
+    if len <= 0 {
+        return None;
+    }
+    // SAFETY: __system_property_get 写入的值以 NUL 结尾
+    let value = unsafe { CStr::from_ptr(value.as_ptr()) }.to_string_lossy().trim().to_string();
+    Some(value).filter(|value| !value.is_empty())
+}
+
+// 设备序列号：依次尝试 ro.serialno 与 ro.boot.serialno；受限时系统返回空值或 "unknown"
+pub(crate) fn serial_number() -> Result<String, HardwareError> {
+    ["ro.serialno", "ro.boot.serialno"]
+        .into_iter()
+        .filter_map(property)
+        .find(|serial| !serial.eq_ignore_ascii_case("unknown"))
+        .ok_or_else(|| {
+            HardwareError::PermissionDenied(
+                "ro.serialno is not readable (restricted since Android 10 without READ_PRIVILEGED_PHONE_STATE)"
+                    .to_string(),
+            )
+        })
+}
+
+// 受限时文件不可读，或读到系统为普通应用返回的固定值 02:00:00:00:00:00
+pub(crate) fn wlan_mac() -> Result<String, HardwareError> {
+    const PATH: &str = "/sys/class/net/wlan0/address";
+    const RESTRICTED_MAC: &str = "02:00:00:00:00:00";
+    let mac = match std::fs::read_to_string(PATH) {
+        Ok(mac) => mac.trim().to_lowercase(),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            return Err(HardwareError::PermissionDenied(format!("{}: {}", PATH, e)))
+        }
+        Err(e) => return Err(HardwareError::FileError(format!("{}: {}", PATH, e))),
+    };
+    if mac == RESTRICTED_MAC {
+        return Err(HardwareError::PermissionDenied(format!("{}: the MAC address is hidden from apps", PATH)));
+    }
+    Ok(mac)
+}
+
+// Wi-Fi 关闭时 operstate 为 "down"；仅供显示
+pub(crate) fn wlan_is_up() -> bool {
+    std::fs::read_to_string("/sys/class/net/wlan0/operstate").is_ok_and(|state| state.trim() == "up")
+}
+
+// Android 上没有来源的字段（没有 DMI、SMBIOS，普通应用也不能枚举块设备）
+pub(crate) fn unsupported<T>(field: &str) -> Result<T, HardwareError> {
+    Err(HardwareError::UnsupportedSystem(format!("{} on Android", field)))
+}
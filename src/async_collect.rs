@@ -0,0 +1,131 @@
+// 异步采集：可提前执行的命令经 tokio::process 并发执行并受同样的超时限制，其余读取与解析
+// 复用同步采集的代码（在阻塞线程池中完成），两种方式的结果相同
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use crate::command_runner::{self, SystemCommandRunner};
+use crate::error::HardwareError;
+use crate::hardware_info::{
+    CollectOptions, CollectionReport, CommandLine, CommandResult, HardwareInfo, ProbeCommandLine, PREFETCH_CONCURRENCY,
+};
+use crate::progress::ProgressTracker;
+
+impl HardwareInfo {
+    pub async fn collect_async() -> Result<Self, HardwareError> {
+        Self::collect_async_with(&CollectOptions::default()).await
+    }
+
+    // 与 collect_with 相同：可选字段以外的字段读取失败时返回第一个错误
+    pub async fn collect_async_with(options: &CollectOptions) -> Result<Self, HardwareError> {
+        let (info, report) = Self::collect_lenient_async_with(options).await;
+        Self::require_fields(info, report)
+    }
+
+    // 丢弃返回的 future 时结束尚未完成的命令；已进入阻塞线程池的部分会执行完毕，结果被丢弃
+    pub async fn collect_lenient_async_with(options: &CollectOptions) -> (Self, CollectionReport) {
+        let (ahead, skipped) = Self::probe_plan(options);
+        let progress = Arc::new(Self::progress_tracker(options, &ahead, &skipped));
+        let prefetched = run_all(ahead, options.timeout(), progress).await;
+        let options = options.clone();
+        let collected = tokio::task::spawn_blocking(move || {
+            Self::collect_lenient_prefetched(&SystemCommandRunner::new(options.timeout()), &options, prefetched)
+        })
+        .await;
+        match collected {
+            Ok(collected) => collected,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+}
+
+// 最多同时执行 PREFETCH_CONCURRENCY 条命令
+async fn run_all(
+    commands: Vec<ProbeCommandLine>,
+    timeout: Duration,
+    progress: Arc<ProgressTracker>,
+) -> HashMap<CommandLine, CommandResult> {
+    let permits = Arc::new(Semaphore::new(PREFETCH_CONCURRENCY));
+    let mut tasks = JoinSet::new();
+    for (program, args) in commands {
+        let permits = Arc::clone(&permits);
+        let progress = Arc::clone(&progress);
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+            let command = command_runner::command_line(program, &arg_refs);
+            progress.started(&command);
+            let started = Instant::now();
+            let result = run(program, &args, timeout).await;
+            progress.finished(&command, started.elapsed(), &result);
+            ((program.to_string(), args), result)
+        });
+    }
+    let mut results = HashMap::new();
+    while let Some(done) = tasks.join_next().await {
+        if let Ok((command, result)) = done {
+            results.insert(command, result);
+        }
+    }
+    results
+}
+
+// 与 SystemCommandRunner::run 相同的语义：超时后结束子进程，启动失败、超时或非零退出都带上完整命令行
+async fn run(program: &str, args: &[String], timeout: Duration) -> CommandResult {
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    let command = command_runner::command_line(program, &arg_refs);
+    let child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| command_runner::spawn_error(&command, e))?;
+    // 超时后 wait_with_output 的 future 连同子进程一起被丢弃，kill_on_drop 结束子进程
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(output) => command_runner::check_status(&command, output?),
+        Err(_) => Err(command_runner::timeout_error(&command, timeout)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::{ComponentSet, FingerprintBuilder};
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(future)
+    }
+
+    // 序列化后的全部字段，以及读取失败的字段
+    fn comparable(info: &HardwareInfo, report: &CollectionReport) -> (serde_json::Value, Vec<&'static str>) {
+        (serde_json::to_value(info).unwrap(), report.errors().iter().map(|(field, _)| *field).collect())
+    }
+
+    #[test]
+    fn async_collection_matches_the_sync_path() {
+        for options in [CollectOptions::new(), CollectOptions::new().with_components(ComponentSet::default())] {
+            let (sync_info, sync_report) = HardwareInfo::collect_lenient_with(&options);
+            let (async_info, async_report) = block_on(HardwareInfo::collect_lenient_async_with(&options));
+            assert_eq!(comparable(&async_info, &async_report), comparable(&sync_info, &sync_report));
+
+            let fingerprint = FingerprintBuilder::new();
+            assert_eq!(fingerprint.hash_input(&async_info), fingerprint.hash_input(&sync_info));
+            let code = |info: &HardwareInfo| fingerprint.hash(info).map_err(|e| e.to_string());
+            assert_eq!(code(&async_info), code(&sync_info));
+        }
+    }
+
+    // 严格采集同样返回相同的结果或相同的错误
+    #[test]
+    fn strict_async_collection_matches_the_sync_path() {
+        let hash_input = |info: HardwareInfo| FingerprintBuilder::new().hash_input(&info);
+        let sync = HardwareInfo::collect_with(&CollectOptions::new()).map(hash_input);
+        let asynchronous = block_on(HardwareInfo::collect_async()).map(hash_input);
+        assert_eq!(asynchronous.map_err(|e| e.to_string()), sync.map_err(|e| e.to_string()));
+    }
+}
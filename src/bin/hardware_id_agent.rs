@@ -0,0 +1,75 @@
+use std::process::ExitCode;
+use log::error;
+use hardware_id::{CollectOptions, ComponentSet, FingerprintBuilder, HardwareError, HardwareInfo};
+
+const USAGE: &str = "\
+Usage: hardware_id_agent [verify <CODE>]
+
+Prints only the unique code on stdout; diagnostics go to stderr.
+With verify, prints match or mismatch and exits with 1 on a mismatch.
+
+Options:
+  -h, --help     Print help
+  -V, --version  Print version
+
+Exit status: the same as hardware_id (0 success, 1 verify mismatch, 2 invalid arguments or other errors,
+3 critical hardware information missing, 4 permission denied, 5 unsupported system or environment)
+";
+
+// 精简版代理程序：与不带选项的 hardware_id hash / hardware_id verify 使用相同的采集方式、参数与退出码，
+// 同一台机器上两者的唯一码相同
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let expected = match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        [] => None,
+        ["-h" | "--help"] => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        ["-V" | "--version"] => {
+            println!("hardware_id_agent {}", env!("CARGO_PKG_VERSION"));
+            return ExitCode::SUCCESS;
+        }
+        ["verify", code] => Some(code.trim().to_string()),
+        ["verify"] => {
+            eprintln!("Error: verify requires the code to compare");
+            return ExitCode::from(2);
+        }
+        [other, ..] => {
+            eprintln!("Error: unexpected argument '{}'", other);
+            return ExitCode::from(2);
+        }
+    };
+
+    env_logger::init();
+
+    let result = collect().and_then(|info| {
+        let builder = FingerprintBuilder::new();
+        match &expected {
+            Some(code) => builder.verify(&info, code).inspect(|matches| {
+                println!("{}", if *matches { "match" } else { "mismatch" });
+            }),
+            None => builder.hash(&info).map(|code| {
+                println!("{}", code);
+                true
+            }),
+        }
+    });
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            error!("Failed to generate unique code: {}", e);
+            eprintln!("Error: {}", e);
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+// 只采集默认字段，不读取内存条、显卡等与唯一码无关的信息；个别字段读取失败不影响，
+// 能否生成唯一码由 FingerprintBuilder 的关键字段检查决定
+fn collect() -> Result<HardwareInfo, HardwareError> {
+    let options = CollectOptions::new().with_components(ComponentSet::default());
+    options.validate()?;
+    Ok(HardwareInfo::collect_lenient_with(&options).0)
+}
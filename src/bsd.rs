@@ -0,0 +1,185 @@
+// FreeBSD、OpenBSD、NetBSD 共用的采集辅助：各字段在 kenv/sysctl 中的名称、磁盘列表与 dmesg 的解析，
+// 以及经 getifaddrs 枚举网卡
+// 各 BSD 只用到其中一部分，只用于某一系统的项按该系统编译
+
+use std::ffi::CStr;
+use crate::error::HardwareError;
+
+// 固件/系统信息字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Field {
+    SystemUuid,
+    SystemSerial,
+    BoardSerial,
+    BoardVendor,
+    BoardProduct,
+    BoardAssetTag,
+    ChassisSerial,
+    ChassisType,
+    ChassisAssetTag,
+    BiosVendor,
+    BiosVersion,
+    BiosDate,
+    CpuModel,
+}
+
+// FreeBSD 的 loader 把 SMBIOS 写入内核环境（kenv，普通用户可读）；OpenBSD 与 NetBSD 经 sysctl 提供
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Source {
+    #[cfg(target_os = "freebsd")]
+    Kenv(&'static str),
+    Sysctl(&'static str),
+}
+
+// 字段在当前系统上的来源，没有对应项时为 None
+pub(crate) fn source(field: Field) -> Option<Source> {
+    #[cfg(target_os = "freebsd")]
+    let source = match field {
+        Field::SystemUuid => Source::Kenv("smbios.system.uuid"),
+        Field::SystemSerial => Source::Kenv("smbios.system.serial"),
+        Field::BoardSerial => Source::Kenv("smbios.planar.serial"),
+        Field::BoardVendor => Source::Kenv("smbios.planar.maker"),
+        Field::BoardProduct => Source::Kenv("smbios.planar.product"),
+        Field::BoardAssetTag => Source::Kenv("smbios.planar.tag"),
+        Field::ChassisSerial => Source::Kenv("smbios.chassis.serial"),
+        Field::ChassisType => Source::Kenv("smbios.chassis.type"),
+        Field::ChassisAssetTag => Source::Kenv("smbios.chassis.tag"),
+        Field::BiosVendor => Source::Kenv("smbios.bios.vendor"),
+        Field::BiosVersion => Source::Kenv("smbios.bios.version"),
+        Field::BiosDate => Source::Kenv("smbios.bios.reldate"),
+        Field::CpuModel => Source::Sysctl("hw.model"),
+    };
+
+    // OpenBSD 只提供系统级（SMBIOS 类型 1）的信息，主板字段取系统的对应值
+    #[cfg(target_os = "openbsd")]
+    let source = match field {
+        Field::SystemUuid => Source::Sysctl("hw.uuid"),
+        Field::SystemSerial | Field::BoardSerial => Source::Sysctl("hw.serialno"),
+        Field::BoardVendor => Source::Sysctl("hw.vendor"),
+        Field::BoardProduct => Source::Sysctl("hw.product"),
+        Field::CpuModel => Source::Sysctl("hw.model"),
+        _ => return None,
+    };
+
+    #[cfg(target_os = "netbsd")]
+    let source = match field {
+        Field::SystemUuid => Source::Sysctl("machdep.dmi.system-uuid"),
+        Field::SystemSerial => Source::Sysctl("machdep.dmi.system-serial"),
+        Field::BoardSerial => Source::Sysctl("machdep.dmi.board-serial"),
+        Field::BoardVendor => Source::Sysctl("machdep.dmi.board-vendor"),
+        Field::BoardProduct => Source::Sysctl("machdep.dmi.board-product"),
+        Field::BoardAssetTag => Source::Sysctl("machdep.dmi.board-asset-tag"),
+        Field::ChassisSerial => Source::Sysctl("machdep.dmi.chassis-serial"),
+        Field::ChassisAssetTag => Source::Sysctl("machdep.dmi.chassis-asset-tag"),
+        Field::BiosVendor => Source::Sysctl("machdep.dmi.bios-vendor"),
+        Field::BiosVersion => Source::Sysctl("machdep.dmi.bios-version"),
+        Field::BiosDate => Source::Sysctl("machdep.dmi.bios-date"),
+        Field::CpuModel => Source::Sysctl("hw.model"),
+        _ => return None,
+    };
+
+    Some(source)
+}
+
+// 磁盘列表：FreeBSD 的 kern.disks 以空格分隔，OpenBSD 的 hw.disknames 为 "sd0:<DUID>,cd0:"，
+// NetBSD 的 hw.disknames 以空格分隔；返回 (名称, DUID)，只保留固定磁盘，按名称排序
+pub(crate) fn parse_disknames(output: &str) -> Vec<(String, Option<String>)> {
+    const FIXED_DISKS: [&str; 8] = ["ada", "da", "nvd", "nda", "vtbd", "sd", "wd", "ld"];
+    let mut disks: Vec<(String, Option<String>)> = output
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once(':') {
+            Some((name, duid)) => (name.to_string(), Some(duid.to_string()).filter(|duid| !duid.is_empty())),
+            None => (entry.to_string(), None),
+        })
+        .filter(|(name, _)| {
+            let prefix = name.trim_end_matches(|c: char| c.is_ascii_digit());
+            prefix.len() < name.len() && FIXED_DISKS.contains(&prefix)
+        })
+        .collect();
+    disks.sort();
+    disks
+}
+
+// 启动信息中磁盘的型号与固件版本：OpenBSD 为 "sd0 at scsibus1 targ 0 lun 0: <ATA, Samsung SSD 860, RVT0>"，
+// NetBSD 为 "wd0: <Samsung SSD 860 EVO 500GB>"，FreeBSD 为 "ada0: <Samsung SSD 860 EVO 500GB RVT04B6Q> ACS-4 ATA SATA 3.x device"
+pub(crate) fn parse_dmesg_disk(dmesg: &str, disk: &str) -> Option<(String, Option<String>)> {
+    let description = dmesg.lines().find_map(|line| {
+        if !line.starts_with(&format!("{} at ", disk)) && !line.starts_with(&format!("{}: <", disk)) {
+            return None;
+        }
+        let start = line.find('<')? + 1;
+        Some(&line[start..start + line[start..].find('>')?])
+    })?;
+    let parts: Vec<&str> = description.split(", ").map(str::trim).collect();
+    match parts.as_slice() {
+        [_, model, revision] => Some((model.to_string(), Some(revision.to_string()))),
+        _ => Some((description.trim().to_string(), None)),
+    }
+}
+
+// FreeBSD diskinfo -v 的输出：每行为 "<值>\t# <说明>"，如 "S3Z2NB0K123456X\t# Disk ident."
+#[cfg(target_os = "freebsd")]
+pub(crate) fn parse_diskinfo(output: &str, label: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| {
+            let (value, comment) = line.split_once('#')?;
+            (comment.trim() == label).then(|| value.trim().to_string())
+        })
+        .filter(|value| !value.is_empty())
+}
+
+// 一个链路层接口：名称、MAC、是否已启用
+pub(crate) struct LinkInterface {
+    pub(crate) name: String,
+    pub(crate) mac: String,
+    pub(crate) is_up: bool,
+}
+
+// getifaddrs 中 AF_LINK 的以太网接口（IFT_ETHER），排除回环与全 0 MAC，按名称排序
+pub(crate) fn ethernet_interfaces() -> Result<Vec<LinkInterface>, HardwareError> {
+    const IFT_ETHER: u8 = 0x06;
+    let mut head: *mut libc::ifaddrs = std::ptr::null_mut();
+    // SAFETY: 成功时 head 指向系统分配的链表，遍历结束后用 freeifaddrs 释放
+    if unsafe { libc::getifaddrs(&mut head) } != 0 {
+        return Err(HardwareError::Io(std::io::Error::last_os_error()));
+    }
+
+    let mut interfaces = Vec::new();
+    let mut current = head;
+    while !current.is_null() {
+        // SAFETY: current 是 getifaddrs 返回的链表中的节点，ifa_name 为以 NUL 结尾的字符串
+        let entry = unsafe { &*current };
+        current = entry.ifa_next;
+        if entry.ifa_addr.is_null() || entry.ifa_flags & libc::IFF_LOOPBACK as u32 != 0 {
+            continue;
+        }
+        // SAFETY: ifa_addr 非空；sockaddr_dl 固定部分为 8 字节：长度、族、索引（2 字节）、类型、
+        // 名称长度、地址长度、选择子长度，之后 sdl_data 依次为名称与链路层地址
+        let (family, kind, name_len, addr_len, data) = unsafe {
+            let bytes = entry.ifa_addr as *const u8;
+            (*bytes.add(1), *bytes.add(4), *bytes.add(5) as usize, *bytes.add(6) as usize, bytes.add(8))
+        };
+        if i32::from(family) != libc::AF_LINK || kind != IFT_ETHER || addr_len != 6 {
+            continue;
+        }
+        // SAFETY: sockaddr_dl 的长度覆盖名称与地址（sdl_len 至少为 8 + 名称长度 + 地址长度）
+        let mac = unsafe { std::slice::from_raw_parts(data.add(name_len), addr_len) };
+        if mac.iter().all(|b| *b == 0) {
+            continue;
+        }
+        // SAFETY: 见上
+        let name = unsafe { CStr::from_ptr(entry.ifa_name) }.to_string_lossy().into_owned();
+        interfaces.push(LinkInterface {
+            name,
+            mac: mac.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":"),
+            is_up: entry.ifa_flags & libc::IFF_UP as u32 != 0,
+        });
+    }
+    // SAFETY: head 来自成功的 getifaddrs 调用，只释放一次
+    unsafe { libc::freeifaddrs(head) };
+
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(interfaces)
+}
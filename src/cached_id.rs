@@ -0,0 +1,91 @@
+// 进程内缓存的唯一码：许可证校验、遥测、崩溃报告等多处调用时只采集一次
+use std::sync::OnceLock;
+use crate::command_runner::{CommandRunner, SystemCommandRunner};
+use crate::error::HardwareError;
+use crate::fingerprint::{ComponentSet, FingerprintBuilder};
+use crate::hardware_info::{CollectOptions, HardwareInfo};
+
+static ID: OnceLock<Result<String, HardwareError>> = OnceLock::new();
+static RUNNER: OnceLock<Box<dyn CommandRunner>> = OnceLock::new();
+
+// id() 首次采集的次数，只在测试中统计
+#[cfg(test)]
+static COLLECTIONS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+// 默认参数生成的唯一码（与 hardware_id_agent 的输出相同），每个进程只采集一次，
+// 之后的调用直接返回首次的结果（包括失败）；多个线程同时首次调用时只有一个线程执行采集，其余等待其结果
+pub fn id() -> Result<String, HardwareError> {
+    ID.get_or_init(|| {
+        #[cfg(test)]
+        COLLECTIONS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        id_with_runner(runner())
+    })
+    .clone()
+}
+
+// 每次调用都重新采集，不读取也不更新 id() 的缓存
+pub fn id_uncached() -> Result<String, HardwareError> {
+    id_with_runner(runner())
+}
+
+// 替换 id() 与 id_uncached() 执行外部命令的方式（如经沙箱代理执行）；须在二者首次调用之前设置，
+// 已设置过或已开始采集时不替换并返回 false
+pub fn set_id_runner(runner: impl CommandRunner + 'static) -> bool {
+    RUNNER.set(Box::new(runner)).is_ok()
+}
+
+fn runner() -> &'static dyn CommandRunner {
+    RUNNER.get_or_init(|| Box::new(SystemCommandRunner::new(CollectOptions::DEFAULT_TIMEOUT))).as_ref()
+}
+
+// 与 hardware_id hash 一样宽松采集默认字段：个别字段读取失败不影响，能否生成唯一码由关键字段检查决定
+fn id_with_runner(runner: &dyn CommandRunner) -> Result<String, HardwareError> {
+    let options = CollectOptions::new().with_components(ComponentSet::default());
+    options.validate()?;
+    FingerprintBuilder::new().hash(&HardwareInfo::collect_lenient_inner(runner, &options).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use crate::command_runner::MockRunner;
+
+    // 测试中共享同一个 MockRunner，便于统计 id() 执行的命令
+    struct SharedRunner(Arc<MockRunner>);
+
+    impl CommandRunner for SharedRunner {
+        fn run(&self, program: &str, args: &[&str]) -> Result<std::process::Output, HardwareError> {
+            self.0.run(program, args)
+        }
+    }
+
+    // 进程内只有这一个测试调用 id()：同时首次调用的线程中只有一个执行采集，所有线程得到同一结果，
+    // 执行的命令与单独一次采集相同
+    #[test]
+    fn concurrent_first_calls_collect_once() {
+        let runner = Arc::new(MockRunner::new().with_stdout("lsblk -no MODEL", "Samsung SSD 970 EVO Plus 1TB\n"));
+        assert!(set_id_runner(SharedRunner(Arc::clone(&runner))));
+        assert!(!set_id_runner(MockRunner::new()));
+
+        let results: Vec<Result<String, String>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8).map(|_| scope.spawn(|| id().map_err(|e| e.to_string()))).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        assert_eq!(COLLECTIONS.load(Ordering::SeqCst), 1);
+        assert!(results.windows(2).all(|pair| pair[0] == pair[1]), "{:?}", results);
+
+        let single = MockRunner::new().with_stdout("lsblk -no MODEL", "Samsung SSD 970 EVO Plus 1TB\n");
+        let uncached = id_with_runner(&single).map_err(|e| e.to_string());
+        assert_eq!(runner.calls(), single.calls());
+        assert_eq!(results[0], uncached);
+
+        // 之后的调用返回缓存的结果，不再执行命令；id_uncached 每次都重新采集
+        assert_eq!(id().map_err(|e| e.to_string()), results[0]);
+        assert_eq!(COLLECTIONS.load(Ordering::SeqCst), 1);
+        let calls = runner.calls().len();
+        assert_eq!(id_uncached().map_err(|e| e.to_string()), uncached);
+        assert_eq!(runner.calls().len(), calls * 2);
+    }
+}
@@ -0,0 +1,271 @@
+use std::io::Read;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::error::HardwareError;
+use crate::hardware_info::CollectOptions;
+
+// 平台采集代码执行外部命令的入口，可替换为返回固定输出的实现以便离线解析
+pub trait CommandRunner: Send + Sync {
+    fn run(&self, program: &str, args: &[&str]) -> Result<Output, HardwareError>;
+}
+
+// 实际执行命令的默认实现
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemCommandRunner {
+    timeout: Duration,
+    max_output: usize,
+}
+
+impl Default for SystemCommandRunner {
+    fn default() -> Self {
+        Self::new(CollectOptions::DEFAULT_TIMEOUT)
+    }
+}
+
+impl SystemCommandRunner {
+    // stdout、stderr 各自允许的最大字节数
+    pub const DEFAULT_MAX_OUTPUT: usize = 4 * 1024 * 1024;
+
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout, max_output: Self::DEFAULT_MAX_OUTPUT }
+    }
+
+    // 输出超过上限时结束子进程并返回 HardwareError::OutputTooLarge
+    pub fn with_max_output(mut self, bytes: usize) -> Self {
+        self.max_output = bytes;
+        self
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn max_output(&self) -> usize {
+        self.max_output
+    }
+}
+
+impl CommandRunner for SystemCommandRunner {
+    // 超时后结束子进程；启动失败、超时或非零退出都带上完整命令行与 stderr
+    fn run(&self, program: &str, args: &[&str]) -> Result<Output, HardwareError> {
+        let command = command_line(program, args);
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| spawn_error(&command, e))?;
+
+        // 在后台线程读取输出，避免输出较多时管道写满导致子进程阻塞；最多读取 max_output + 1 字节
+        let limit = self.max_output;
+        let overflowed = Arc::new(AtomicBool::new(false));
+        let read_pipe = |pipe: Option<Box<dyn Read + Send>>| {
+            let overflowed = Arc::clone(&overflowed);
+            thread::spawn(move || {
+                let mut buffer = Vec::new();
+                if let Some(pipe) = pipe {
+                    let _ = pipe.take(limit as u64 + 1).read_to_end(&mut buffer);
+                }
+                if buffer.len() > limit {
+                    overflowed.store(true, Ordering::SeqCst);
+                }
+                buffer
+            })
+        };
+        let stdout = read_pipe(child.stdout.take().map(|pipe| Box::new(pipe) as Box<dyn Read + Send>));
+        let stderr = read_pipe(child.stderr.take().map(|pipe| Box::new(pipe) as Box<dyn Read + Send>));
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            if overflowed.load(Ordering::SeqCst) {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(output_too_large_error(&command, limit));
+            }
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(timeout_error(&command, self.timeout));
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+
+        let output = Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        };
+        // 子进程可能在主循环检查之前就已写完并退出
+        if overflowed.load(Ordering::SeqCst) {
+            return Err(output_too_large_error(&command, limit));
+        }
+        check_status(&command, output)
+    }
+}
+
+// 以下几个函数同时用于异步采集，两种执行方式的错误信息保持一致
+pub(crate) fn command_line(program: &str, args: &[&str]) -> String {
+    std::iter::once(program).chain(args.iter().copied()).collect::<Vec<_>>().join(" ")
+}
+
+pub(crate) fn spawn_error(command: &str, e: std::io::Error) -> HardwareError {
+    match e.kind() {
+        std::io::ErrorKind::PermissionDenied => HardwareError::PermissionDenied(command.to_string()),
+        _ => HardwareError::CommandError(format!("{}: {}", command, e)),
+    }
+}
+
+pub(crate) fn timeout_error(command: &str, timeout: Duration) -> HardwareError {
+    HardwareError::CommandError(format!("{}: timed out after {:?}", command, timeout))
+}
+
+fn output_too_large_error(command: &str, limit: usize) -> HardwareError {
+    HardwareError::OutputTooLarge(format!("{}: output exceeded {} bytes", command, limit))
+}
+
+pub(crate) fn check_status(command: &str, output: Output) -> Result<Output, HardwareError> {
+    if !output.status.success() {
+        return Err(HardwareError::CommandError(format!(
+            "{}: {}: {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output)
+}
+
+// 测试用：按完整命令行返回预先设定的输出或错误，并记录执行过的命令；未设定的命令视为不存在
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct MockRunner {
+    responses: std::collections::HashMap<String, MockResponse>,
+    calls: std::sync::Mutex<Vec<String>>,
+}
+
+#[cfg(test)]
+enum MockResponse {
+    Stdout(String),
+    // 以非零状态退出，内容为 stderr
+    Failure(String),
+    TimedOut(Duration),
+}
+
+#[cfg(test)]
+impl MockRunner {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn with_stdout(mut self, command: &str, stdout: &str) -> Self {
+        self.responses.insert(command.to_string(), MockResponse::Stdout(stdout.to_string()));
+        self
+    }
+
+    pub(crate) fn with_failure(mut self, command: &str, stderr: &str) -> Self {
+        self.responses.insert(command.to_string(), MockResponse::Failure(stderr.to_string()));
+        self
+    }
+
+    pub(crate) fn with_timeout(mut self, command: &str, timeout: Duration) -> Self {
+        self.responses.insert(command.to_string(), MockResponse::TimedOut(timeout));
+        self
+    }
+
+    // 按执行顺序排列的完整命令行
+    pub(crate) fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl CommandRunner for MockRunner {
+    fn run(&self, program: &str, args: &[&str]) -> Result<Output, HardwareError> {
+        let command = command_line(program, args);
+        self.calls.lock().unwrap().push(command.clone());
+        match self.responses.get(&command) {
+            Some(MockResponse::Stdout(stdout)) => Ok(Output {
+                status: std::process::ExitStatus::default(),
+                stdout: stdout.as_bytes().to_vec(),
+                stderr: Vec::new(),
+            }),
+            Some(MockResponse::Failure(stderr)) => {
+                Err(HardwareError::CommandError(format!("{}: exit status: 1: {}", command, stderr.trim())))
+            }
+            Some(MockResponse::TimedOut(timeout)) => Err(timeout_error(&command, *timeout)),
+            None => Err(spawn_error(&command, std::io::ErrorKind::NotFound.into())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn output_within_the_cap_is_returned() {
+        let runner = SystemCommandRunner::default().with_max_output(16);
+        let output = runner.run("printf", &["0123456789abcdef"]).unwrap();
+        assert_eq!(output.stdout, b"0123456789abcdef");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn oversized_output_is_a_typed_error() {
+        let runner = SystemCommandRunner::default().with_max_output(1024);
+        let error = runner.run("head", &["-c", "1048576", "/dev/zero"]).unwrap_err();
+        assert!(matches!(error, HardwareError::OutputTooLarge(_)), "{error:?}");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn endless_output_is_stopped_before_the_timeout() {
+        let runner = SystemCommandRunner::new(Duration::from_secs(30)).with_max_output(1024);
+        let started = Instant::now();
+        let error = runner.run("yes", &[]).unwrap_err();
+        assert!(matches!(error, HardwareError::OutputTooLarge(_)), "{error:?}");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn missing_program_and_failing_command_are_command_errors() {
+        let runner = SystemCommandRunner::default();
+        let error = runner.run("hardware-id-no-such-program", &[]).unwrap_err();
+        assert!(matches!(&error, HardwareError::CommandError(message) if message.starts_with("hardware-id-no-such-program")), "{error:?}");
+        let error = runner.run("false", &[]).unwrap_err();
+        assert!(matches!(error, HardwareError::CommandError(_)), "{error:?}");
+    }
+
+    #[test]
+    fn mock_runner_returns_the_canned_results_and_records_the_calls() {
+        let runner = MockRunner::new()
+            .with_stdout("uname -m", "x86_64\n")
+            .with_failure("sudo -n dmidecode -t memory", "sudo: a password is required\n")
+            .with_timeout("lspci -mm", Duration::from_secs(5));
+        assert_eq!(runner.run("uname", &["-m"]).unwrap().stdout, b"x86_64\n");
+        let error = runner.run("sudo", &["-n", "dmidecode", "-t", "memory"]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to execute command: sudo -n dmidecode -t memory: exit status: 1: sudo: a password is required"
+        );
+        let error = runner.run("lspci", &["-mm"]).unwrap_err();
+        assert!(error.to_string().ends_with("lspci -mm: timed out after 5s"), "{error}");
+        let error = runner.run("ethtool", &["-P", "eth0"]).unwrap_err();
+        assert!(matches!(&error, HardwareError::CommandError(message) if message.starts_with("ethtool -P eth0")), "{error:?}");
+        assert_eq!(runner.calls(), ["uname -m", "sudo -n dmidecode -t memory", "lspci -mm", "ethtool -P eth0"]);
+    }
+
+    #[test]
+    fn default_cap_is_four_megabytes() {
+        assert_eq!(SystemCommandRunner::default().max_output(), 4 * 1024 * 1024);
+    }
+}
@@ -0,0 +1,335 @@
+// 供其他语言的服务端实现核对唯一码算法的一致性测试套件：规范化的各种边界情况各对应一份 HardwareInfo，
+// 附上每种方案（v1/v2，是否排除 MAC）的哈希输入（十六进制）与唯一码，全部由 FingerprintBuilder 生成。
+// 本库没有相似度评分，套件中不含相似度
+use std::collections::BTreeMap;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::error::HardwareError;
+use crate::fingerprint::{FingerprintBuilder, FingerprintVersion};
+use crate::hardware_info::HardwareInfo;
+
+pub const MANIFEST: &str = "manifest.json";
+pub const FIXTURES: &str = "fixtures";
+
+// 各用例以此为基础修改个别字段
+const BASE: &str = include_str!("../tests/fixtures/hardware_info.json");
+
+const README: &str = "\
+# hardware_id conformance kit
+
+Generated by `hardware_id conformance generate`; re-verify with `hardware_id conformance check --dir <dir>`.
+
+`fixtures/<case>.json` is the HardwareInfo of each case as the collector writes it. `manifest.json` lists,
+for each case and each variant, the expected hash input as hex (`canonical_input_hex`) and the expected code,
+or the error kind when no code can be generated (e.g. `missing_field` for a placeholder motherboard serial).
+
+Variants: `v1` and `v2` are the fingerprint schemes (v2 codes start with `v2:`); `_nomac` leaves the MAC
+addresses out (codes carry the `nomac:` marker after the scheme prefix).
+
+Unicode is not normalized: the NFC and NFD spellings of the same text give different codes.
+Similarity scores are not part of the kit; hardware_id has no similarity scoring.
+";
+
+// 一个用例：名称（即 fixtures 下的文件名）、说明与采集结果
+#[derive(Debug)]
+pub struct ConformanceCase {
+    name: &'static str,
+    description: &'static str,
+    info: HardwareInfo,
+}
+
+impl ConformanceCase {
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    pub fn description(&self) -> &str {
+        self.description
+    }
+
+    pub fn info(&self) -> &HardwareInfo {
+        &self.info
+    }
+}
+
+// 清单中的一项；info 不写入清单，单独保存在 fixtures 下
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestCase {
+    name: String,
+    description: String,
+    fixture: String,
+    expected: BTreeMap<String, Expected>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    generated_by: String,
+    cases: Vec<ManifestCase>,
+}
+
+// 某一方案下的期望结果：唯一码与错误种类（HardwareError::kind）二者有一
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Expected {
+    canonical_input_hex: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Expected {
+    pub fn canonical_input_hex(&self) -> &str {
+        &self.canonical_input_hex
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+}
+
+// 方案名称、版本与是否包含 MAC
+const VARIANTS: [(&str, FingerprintVersion, bool); 4] = [
+    ("v1", FingerprintVersion::V1, true),
+    ("v1_nomac", FingerprintVersion::V1, false),
+    ("v2", FingerprintVersion::V2, true),
+    ("v2_nomac", FingerprintVersion::V2, false),
+];
+
+pub fn cases() -> Vec<ConformanceCase> {
+    let case = |name, description, edit: fn(&mut serde_json::Value)| {
+        let mut value = serde_json::from_str(BASE).expect("the base fixture is a HardwareInfo");
+        edit(&mut value);
+        let info = serde_json::from_value(value).expect("conformance cases are HardwareInfo");
+        ConformanceCase { name, description, info }
+    };
+    vec![
+        case("baseline", "A desktop with every default component present", |_| {}),
+        case(
+            "placeholder_board_serial",
+            "The motherboard serial is a vendor placeholder, so the critical field is missing",
+            |value| value["board"]["serial"] = "Default string".into(),
+        ),
+        case(
+            "placeholder_board_product",
+            "The motherboard product is a vendor placeholder and hashes like an empty product",
+            |value| value["board"]["product"] = "To be filled by O.E.M.".into(),
+        ),
+        case(
+            "crlf_and_padding",
+            "Values carry CRLF line endings and SMBIOS space padding; v2 hashes them like the baseline",
+            |value| {
+                value["board"]["product"] = "0NNNCT\r\n".into();
+                value["disks"][0]["model"] = "PM981a  NVMe SAMSUNG 512GB          \r\n".into();
+            },
+        ),
+        case(
+            "uuid_braces_uppercase",
+            "The motherboard UUID in braces and upper case; v2 hashes it like the baseline",
+            |value| value["board"]["uuid"] = "{4C4C4544-0058-4A10-8038-B7C04F513532}".into(),
+        ),
+        case("unicode_nfc", "The motherboard product with a precomposed e-acute (NFC)", |value| {
+            value["board"]["product"] = "Pr\u{e9}cision 3630".into()
+        }),
+        case(
+            "unicode_nfd",
+            "The same product with e + combining acute accent (NFD); not normalized, so codes differ from NFC",
+            |value| value["board"]["product"] = "Pre\u{301}cision 3630".into(),
+        ),
+        case(
+            "multi_nic",
+            "Three interfaces listed out of order, one MAC upper case with dashes; v1 and v2 sort the MACs",
+            |value| {
+                let mut interfaces = value["network_interfaces"].as_array().cloned().unwrap_or_default();
+                interfaces[1]["mac_address"] = "A4-34-D9-65-43-21".into();
+                let mut usb = interfaces[0].clone();
+                usb["name"] = "enx0050b6123456".into();
+                usb["mac_address"] = "00:50:b6:12:34:56".into();
+                usb["current_mac_address"] = "00:50:b6:12:34:56".into();
+                usb["is_primary"] = false.into();
+                interfaces.push(usb);
+                interfaces.reverse();
+                value["network_interfaces"] = interfaces.into();
+            },
+        ),
+    ]
+}
+
+// 按当前的算法计算用例在各方案下的结果
+pub fn expected(info: &HardwareInfo) -> BTreeMap<String, Expected> {
+    VARIANTS
+        .iter()
+        .map(|(name, version, mac)| {
+            let builder = FingerprintBuilder::new().version(*version);
+            let builder = if *mac { builder } else { builder.with_mac(false) };
+            let result = builder.hash(info);
+            let expected = Expected {
+                canonical_input_hex: hex::encode(builder.hash_input(info)),
+                error: result.as_ref().err().map(|e| e.kind().to_string()),
+                code: result.ok(),
+            };
+            (name.to_string(), expected)
+        })
+        .collect()
+}
+
+// 在 dir 下写入 README.md、manifest.json 与 fixtures/*.json（已有的同名文件被覆盖），返回用例数
+pub fn generate(dir: &Path) -> Result<usize, HardwareError> {
+    let cases = cases();
+    let mut manifest = Manifest { generated_by: format!("hardware_id {}", env!("CARGO_PKG_VERSION")), cases: Vec::new() };
+    create_dir(&dir.join(FIXTURES))?;
+    for case in &cases {
+        let fixture = format!("{}/{}.json", FIXTURES, case.name);
+        write(&dir.join(&fixture), &to_json(&case.info)?)?;
+        manifest.cases.push(ManifestCase {
+            name: case.name.to_string(),
+            description: case.description.to_string(),
+            fixture,
+            expected: expected(&case.info),
+        });
+    }
+    write(&dir.join(MANIFEST), &to_json(&manifest)?)?;
+    write(&dir.join("README.md"), README)?;
+    Ok(cases.len())
+}
+
+// 以当前的算法重新计算 dir 中每个用例，返回不一致之处（每项一行）；为空表示全部一致
+pub fn check(dir: &Path) -> Result<Vec<String>, HardwareError> {
+    let manifest: Manifest = from_json(&dir.join(MANIFEST))?;
+    let mut mismatches = Vec::new();
+    for case in &manifest.cases {
+        let info: HardwareInfo = from_json(&dir.join(&case.fixture))?;
+        let actual = expected(&info);
+        for (variant, expected) in &case.expected {
+            match actual.get(variant) {
+                Some(actual) if actual == expected => {}
+                Some(actual) => mismatches.push(format!(
+                    "{} {}: expected {}, got {}",
+                    case.name,
+                    variant,
+                    describe(expected),
+                    describe(actual)
+                )),
+                None => mismatches.push(format!("{} {}: unknown variant", case.name, variant)),
+            }
+        }
+        for variant in actual.keys().filter(|variant| !case.expected.contains_key(*variant)) {
+            mismatches.push(format!("{} {}: missing from the manifest", case.name, variant));
+        }
+    }
+    Ok(mismatches)
+}
+
+fn describe(expected: &Expected) -> String {
+    let result = match (&expected.code, &expected.error) {
+        (Some(code), _) => code.clone(),
+        (None, Some(error)) => format!("error {}", error),
+        (None, None) => "no code".to_string(),
+    };
+    format!("{} (input {})", result, expected.canonical_input_hex)
+}
+
+fn to_json(value: &impl Serialize) -> Result<String, HardwareError> {
+    serde_json::to_string_pretty(value)
+        .map(|json| json + "\n")
+        .map_err(|e| HardwareError::ParseError(e.to_string()))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, HardwareError> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| HardwareError::FileError(format!("{}: {}", path.display(), e)))?;
+    serde_json::from_str(&contents).map_err(|e| HardwareError::ParseError(format!("{}: {}", path.display(), e)))
+}
+
+fn create_dir(path: &Path) -> Result<(), HardwareError> {
+    std::fs::create_dir_all(path).map_err(|e| HardwareError::FileError(format!("{}: {}", path.display(), e)))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), HardwareError> {
+    std::fs::write(path, contents).map_err(|e| HardwareError::FileError(format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    fn scratch_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("hardware_id-conformance-{}", Uuid::new_v4().simple()))
+    }
+
+    fn files(dir: &Path) -> BTreeMap<String, String> {
+        let mut files = BTreeMap::new();
+        for path in [dir.to_path_buf(), dir.join(FIXTURES)] {
+            for entry in std::fs::read_dir(&path).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_file() {
+                    let name = path.strip_prefix(dir).unwrap().to_string_lossy().into_owned();
+                    files.insert(name, std::fs::read_to_string(&path).unwrap());
+                }
+            }
+        }
+        files
+    }
+
+    #[test]
+    fn generated_kit_is_stable() {
+        let (first, second) = (scratch_dir(), scratch_dir());
+        assert_eq!(generate(&first).unwrap(), cases().len());
+        generate(&second).unwrap();
+        let files = files(&first);
+        assert_eq!(files.len(), cases().len() + 2);
+        assert_eq!(files, self::files(&second));
+        for dir in [first, second] {
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn generated_kit_passes_the_check() {
+        let dir = scratch_dir();
+        generate(&dir).unwrap();
+        assert_eq!(check(&dir).unwrap(), Vec::<String>::new());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn check_reports_changed_expectations() {
+        let dir = scratch_dir();
+        generate(&dir).unwrap();
+        let path = dir.join(MANIFEST);
+        let manifest = std::fs::read_to_string(&path).unwrap().replace("1a6b-8bdd-c72f-61ec", "0000-0000-0000-0000");
+        std::fs::write(&path, manifest).unwrap();
+        let mismatches = check(&dir).unwrap();
+        assert_eq!(mismatches.len(), 1, "{:?}", mismatches);
+        assert!(mismatches[0].starts_with("baseline v1: expected 0000-0000-0000-0000"), "{}", mismatches[0]);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(check(&dir), Err(HardwareError::FileError(_))));
+    }
+
+    // 与 fingerprint.rs 的固定测试向量一致；各边界情况在 v1/v2 中与基准用例的关系
+    #[test]
+    fn cases_cover_the_normalization_rules() {
+        let cases = cases();
+        let expected: BTreeMap<&str, BTreeMap<String, Expected>> =
+            cases.iter().map(|case| (case.name(), super::expected(case.info()))).collect();
+        let code = |case: &str, variant: &str| expected[case][variant].code().map(str::to_string);
+        assert_eq!(code("baseline", "v1").as_deref(), Some("1a6b-8bdd-c72f-61ec"));
+        assert_eq!(code("baseline", "v2").as_deref(), Some("v2:cf45-e033-05c8-f56f"));
+        assert!(code("baseline", "v2_nomac").unwrap().starts_with("v2:nomac:"));
+        for variant in ["v1", "v2"] {
+            assert_eq!(expected["placeholder_board_serial"][variant].error(), Some("missing_field"));
+            assert_ne!(code("unicode_nfc", variant), code("unicode_nfd", variant));
+            assert_ne!(code("multi_nic", variant), code("baseline", variant));
+            assert_eq!(code("multi_nic", &format!("{}_nomac", variant)), code("baseline", &format!("{}_nomac", variant)));
+        }
+        for case in ["crlf_and_padding", "uuid_braces_uppercase"] {
+            assert_ne!(code(case, "v1"), code("baseline", "v1"), "{}", case);
+            assert_eq!(code(case, "v2"), code("baseline", "v2"), "{}", case);
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use md5::{Md5, Digest};
+use serde::{Serialize, Deserialize};
+
+// 基于 CPUID（x86）或 MIDR（ARM）的 CPU 标识，同一颗 CPU 在不同操作系统下保持一致
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CpuIdentity {
+    vendor: String,
+    family: u32,
+    model: u32,
+    stepping: u32,
+    features_hash: String,
+}
+
+impl CpuIdentity {
+    pub fn vendor(&self) -> &str {
+        &self.vendor
+    }
+
+    pub fn family(&self) -> u32 {
+        self.family
+    }
+
+    pub fn model(&self) -> u32 {
+        self.model
+    }
+
+    pub fn stepping(&self) -> u32 {
+        self.stepping
+    }
+
+    pub fn features_hash(&self) -> &str {
+        &self.features_hash
+    }
+
+    pub fn collect() -> Self {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            Self::from_cpuid()
+        }
+
+        #[cfg(all(target_arch = "aarch64", any(target_os = "linux", target_os = "android")))]
+        {
+            Self::from_midr().unwrap_or_default()
+        }
+
+        #[cfg(not(any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            all(target_arch = "aarch64", any(target_os = "linux", target_os = "android"))
+        )))]
+        {
+            Self::default()
+        }
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn from_cpuid() -> Self {
+        #[cfg(target_arch = "x86")]
+        use std::arch::x86::{__cpuid, __cpuid_count};
+        #[cfg(target_arch = "x86_64")]
+        use std::arch::x86_64::{__cpuid, __cpuid_count};
+
+        // 叶 0：最大叶号与厂商字符串（EBX, EDX, ECX）
+        let leaf0 = __cpuid(0);
+        let mut vendor_bytes = Vec::with_capacity(12);
+        vendor_bytes.extend_from_slice(&leaf0.ebx.to_le_bytes());
+        vendor_bytes.extend_from_slice(&leaf0.edx.to_le_bytes());
+        vendor_bytes.extend_from_slice(&leaf0.ecx.to_le_bytes());
+        let vendor = String::from_utf8_lossy(&vendor_bytes).trim().to_string();
+
+        // 叶 1：family/model/stepping 与基础特性位
+        let leaf1 = __cpuid(1);
+        let stepping = leaf1.eax & 0xF;
+        let base_model = (leaf1.eax >> 4) & 0xF;
+        let base_family = (leaf1.eax >> 8) & 0xF;
+        let ext_model = (leaf1.eax >> 16) & 0xF;
+        let ext_family = (leaf1.eax >> 20) & 0xFF;
+        let family = if base_family == 0xF { base_family + ext_family } else { base_family };
+        let model = if base_family == 0x6 || base_family == 0xF {
+            (ext_model << 4) | base_model
+        } else {
+            base_model
+        };
+
+        // 屏蔽由操作系统或虚拟化决定的位：OSXSAVE（ECX 27）、HYPERVISOR（ECX 31）、OSPKE（叶 7 ECX 4）
+        let mut features = vec![leaf1.ecx & !((1 << 27) | (1 << 31)), leaf1.edx];
+        if leaf0.eax >= 7 {
+            let leaf7 = __cpuid_count(7, 0);
+            features.extend([leaf7.ebx, leaf7.ecx & !(1 << 4), leaf7.edx]);
+        }
+
+        let mut hasher = Md5::new();
+        for register in &features {
+            hasher.update(register.to_le_bytes());
+        }
+
+        Self {
+            vendor,
+            family,
+            model,
+            stepping,
+            features_hash: hex::encode(hasher.finalize()),
+        }
+    }
+
+    // CPUID 叶 1 ECX 第 31 位表示运行在虚拟机中，叶 0x40000000 的 EBX、ECX、EDX 为虚拟化厂商签名；
+    // 非 x86 或没有该位时返回 None
+    pub fn hypervisor_signature() -> Option<String> {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            #[cfg(target_arch = "x86")]
+            use std::arch::x86::__cpuid;
+            #[cfg(target_arch = "x86_64")]
+            use std::arch::x86_64::__cpuid;
+
+            if __cpuid(1).ecx & (1 << 31) == 0 {
+                return None;
+            }
+            let leaf = __cpuid(0x4000_0000);
+            let mut signature = Vec::with_capacity(12);
+            signature.extend_from_slice(&leaf.ebx.to_le_bytes());
+            signature.extend_from_slice(&leaf.ecx.to_le_bytes());
+            signature.extend_from_slice(&leaf.edx.to_le_bytes());
+            Some(String::from_utf8_lossy(&signature).trim_matches(|c: char| c == '\0' || c.is_whitespace()).to_string())
+        }
+
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        {
+            None
+        }
+    }
+
+    #[cfg(all(target_arch = "aarch64", any(target_os = "linux", target_os = "android")))]
+    fn from_midr() -> Option<Self> {
+        use std::fs;
+        let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+        let field = |name: &str| {
+            cpuinfo
+                .lines()
+                .find(|line| line.starts_with(name))
+                .and_then(|line| line.split(':').nth(1))
+                .map(|value| value.trim().to_string())
+        };
+        let parse_hex = |value: &str| u64::from_str_radix(value.trim().trim_start_matches("0x"), 16).ok();
+
+        // 优先读取 MIDR_EL1，不可用时由 /proc/cpuinfo 的各字段拼出
+        let midr = fs::read_to_string("/sys/devices/system/cpu/cpu0/regs/identification/midr_el1")
+            .ok()
+            .and_then(|value| parse_hex(&value))
+            .or_else(|| {
+                let implementer = parse_hex(&field("CPU implementer")?)?;
+                let variant = parse_hex(&field("CPU variant")?)?;
+                let part = parse_hex(&field("CPU part")?)?;
+                let revision = field("CPU revision")?.parse::<u64>().ok()?;
+                // ARMv8 及之后 MIDR 的 architecture 字段恒为 0xF，cpuinfo 中的 "CPU architecture" 不是该值
+                Some((implementer << 24) | (variant << 20) | (0xF << 16) | (part << 4) | revision)
+            })?;
+
+        let mut flags: Vec<String> = field("Features")
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        flags.sort();
+
+        let mut hasher = Md5::new();
+        hasher.update(flags.join(" ").as_bytes());
+
+        Some(Self {
+            vendor: format!("0x{:02x}", (midr >> 24) & 0xFF),
+            family: ((midr >> 16) & 0xF) as u32,
+            model: ((midr >> 4) & 0xFFF) as u32,
+            stepping: ((((midr >> 20) & 0xF) << 4) | (midr & 0xF)) as u32,
+            features_hash: hex::encode(hasher.finalize()),
+        })
+    }
+}
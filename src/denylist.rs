@@ -0,0 +1,246 @@
+#[cfg(feature = "placeholder-regex")]
+use crate::error::HardwareError;
+
+// 已知被整批机器共用的 UUID/序列号（小写，按字节序排序，用于二分查找）
+pub const KNOWN_DUPLICATE_IDS: &[&str] = &[
+    "00000000-0000-0000-0000-000000000000",
+    "0000000000",
+    "00020003-0004-0005-0006-000700080009",
+    "0123456789",
+    "03000200-0400-0500-0006-000700080009",
+    "11111111-1111-1111-1111-111111111111",
+    "1234567890",
+    "ffffffff-ffff-ffff-ffff-ffffffffffff",
+];
+
+const _: () = assert!(is_sorted(KNOWN_DUPLICATE_IDS), "KNOWN_DUPLICATE_IDS must stay sorted");
+
+// 不区分大小写，忽略首尾空白
+pub fn is_known_duplicate(value: &str) -> bool {
+    let value = value.trim().to_lowercase();
+    KNOWN_DUPLICATE_IDS.binary_search(&value.as_str()).is_ok()
+}
+
+const fn is_sorted(list: &[&str]) -> bool {
+    let mut i = 1;
+    while i < list.len() {
+        if !less_than(list[i - 1].as_bytes(), list[i].as_bytes()) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn less_than(a: &[u8], b: &[u8]) -> bool {
+    let mut i = 0;
+    while i < a.len() && i < b.len() {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+        i += 1;
+    }
+    a.len() < b.len()
+}
+
+// 固件未填写时常见的占位值（小写），采集与哈希时都按缺失处理；需要补充时可通过 FingerprintBuilder::placeholder 追加
+pub const PLACEHOLDER_VALUES: &[&str] = &[
+    "0123456789",
+    "base board serial number",
+    "chassis serial number",
+    "default string",
+    "invalid",
+    "n/a",
+    "none",
+    "not applicable",
+    "not available",
+    "not specified",
+    "o.e.m.",
+    "oem",
+    "system product name",
+    "system serial number",
+    "to be filled by o.e.m.",
+    "unknown",
+];
+
+// 不区分大小写，忽略首尾空白
+pub fn is_placeholder(value: &str) -> bool {
+    let value = value.trim().to_lowercase();
+    PLACEHOLDER_VALUES.contains(&value.as_str())
+}
+
+// 只在采集序列号、UUID 与型号时按占位值处理的值：单独的 "0" 在这些字段中是未填写，
+// 但 Linux cpuinfo 的 physical id 在单路机器上就是 "0"，不能在哈希时清空
+pub const PLACEHOLDER_SERIALS: &[&str] = &["0"];
+
+// 序列号、UUID 与型号：PLACEHOLDER_VALUES 加上 PLACEHOLDER_SERIALS
+pub fn is_placeholder_serial(value: &str) -> bool {
+    is_placeholder(value) || PLACEHOLDER_SERIALS.contains(&value.trim())
+}
+
+// 调用方在内置规则之外追加的占位值规则（CollectOptions::with_extra_placeholder_patterns）；
+// 内置规则先应用，命中追加规则的字段按缺失处理，并记入 CollectionReport::placeholder_matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaceholderRule {
+    // 去掉首尾空白后完全相同
+    Exact(String),
+    // 去掉首尾空白后忽略大小写相同
+    CaseInsensitive(String),
+    // 去掉首尾空白后整个值匹配，由 PlaceholderRule::regex 构造
+    #[cfg(feature = "placeholder-regex")]
+    Regex(PlaceholderPattern),
+}
+
+impl PlaceholderRule {
+    // 正则表达式的长度上限（字节）与编译后的大小上限；regex 按线性时间匹配，上限防止超大表达式拖慢采集
+    pub const MAX_PATTERN_LEN: usize = 256;
+    pub const MAX_COMPILED_SIZE: usize = 64 * 1024;
+
+    pub fn exact(value: &str) -> Self {
+        PlaceholderRule::Exact(value.trim().to_string())
+    }
+
+    pub fn case_insensitive(value: &str) -> Self {
+        PlaceholderRule::CaseInsensitive(value.trim().to_lowercase())
+    }
+
+    // 自动加上 ^(?:…)$，只匹配整个值；超过长度或编译大小上限、语法错误时返回 ParseError
+    #[cfg(feature = "placeholder-regex")]
+    pub fn regex(pattern: &str) -> Result<Self, HardwareError> {
+        if pattern.len() > Self::MAX_PATTERN_LEN {
+            return Err(HardwareError::ParseError(format!(
+                "placeholder pattern is {} bytes long, at most {} allowed",
+                pattern.len(),
+                Self::MAX_PATTERN_LEN
+            )));
+        }
+        regex::RegexBuilder::new(&format!("^(?:{})$", pattern))
+            .size_limit(Self::MAX_COMPILED_SIZE)
+            .dfa_size_limit(Self::MAX_COMPILED_SIZE)
+            .nest_limit(16)
+            .build()
+            .map(|regex| PlaceholderRule::Regex(PlaceholderPattern(regex)))
+            .map_err(|e| HardwareError::ParseError(format!("invalid placeholder pattern '{}': {}", pattern, e)))
+    }
+
+    pub fn matches(&self, value: &str) -> bool {
+        let value = value.trim();
+        match self {
+            PlaceholderRule::Exact(expected) => value == expected,
+            PlaceholderRule::CaseInsensitive(expected) => value.to_lowercase() == *expected,
+            #[cfg(feature = "placeholder-regex")]
+            PlaceholderRule::Regex(pattern) => pattern.0.is_match(value),
+        }
+    }
+}
+
+// 记入采集报告的规则描述，如 exact:"System Serial Number"、regex:^(?:X{8})$
+impl std::fmt::Display for PlaceholderRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlaceholderRule::Exact(value) => write!(f, "exact:{:?}", value),
+            PlaceholderRule::CaseInsensitive(value) => write!(f, "case-insensitive:{:?}", value),
+            #[cfg(feature = "placeholder-regex")]
+            PlaceholderRule::Regex(pattern) => write!(f, "regex:{}", pattern.0.as_str()),
+        }
+    }
+}
+
+// 已编译的正则表达式，按表达式文本比较
+#[cfg(feature = "placeholder-regex")]
+#[derive(Debug, Clone)]
+pub struct PlaceholderPattern(regex::Regex);
+
+#[cfg(feature = "placeholder-regex")]
+impl PartialEq for PlaceholderPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+#[cfg(feature = "placeholder-regex")]
+impl Eq for PlaceholderPattern {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vendor_fillers_are_placeholders() {
+        for value in ["To be filled by O.E.M.", "TO BE FILLED BY O.E.M.", "  Default string ", "System Serial Number"] {
+            assert!(is_placeholder(value), "{:?}", value);
+            assert!(is_placeholder_serial(value), "{:?}", value);
+        }
+        for value in ["/7XJ8Q52/CN1296385B00KL/", "To be filled", "Default string 2", ""] {
+            assert!(!is_placeholder(value), "{:?}", value);
+            assert!(!is_placeholder_serial(value), "{:?}", value);
+        }
+    }
+
+    // "0" 只在序列号中是占位值
+    #[test]
+    fn zero_is_a_placeholder_serial_only() {
+        assert!(is_placeholder_serial(" 0 "));
+        assert!(!is_placeholder("0"));
+        assert!(!is_placeholder_serial("00"));
+    }
+
+    #[test]
+    fn all_zero_and_all_f_uuids_are_known_duplicates() {
+        for value in [
+            "00000000-0000-0000-0000-000000000000",
+            "FFFFFFFF-FFFF-FFFF-FFFF-FFFFFFFFFFFF",
+            " ffffffff-ffff-ffff-ffff-ffffffffffff\n",
+            "03000200-0400-0500-0006-000700080009",
+        ] {
+            assert!(is_known_duplicate(value), "{:?}", value);
+        }
+        assert!(!is_known_duplicate("4c4c4544-0058-4a10-8038-b7c04f513532"));
+        assert!(!is_known_duplicate("00000000-0000-0000-0000-000000000001"));
+    }
+
+    // 只有占位值不同的两份采集结果，哈希输入与没有序列号时相同；FingerprintBuilder::placeholder 可追加占位值
+    #[test]
+    fn placeholder_serials_hash_like_no_serial() {
+        use crate::fingerprint::{FingerprintBuilder, FingerprintVersion};
+        use crate::hardware_info::tests::sample_with;
+
+        let serial = |serial: &str| sample_with(|value| value["board"]["serial"] = serial.into());
+        for version in [FingerprintVersion::V1, FingerprintVersion::V2] {
+            let builder = FingerprintBuilder::new().version(version);
+            let empty = builder.hash_input(&serial(""));
+            assert_eq!(builder.hash_input(&serial("To be filled by O.E.M.")), empty);
+            assert_eq!(builder.hash_input(&serial(" SYSTEM SERIAL NUMBER ")), empty);
+            assert_ne!(builder.hash_input(&serial("MB-00042")), empty);
+            assert_eq!(builder.clone().placeholder("MB-00042").hash_input(&serial("mb-00042")), empty);
+        }
+    }
+
+    #[test]
+    fn extra_rules_match_the_trimmed_value() {
+        assert!(PlaceholderRule::exact(" Serial ").matches("Serial  "));
+        assert!(!PlaceholderRule::exact("Serial").matches("SERIAL"));
+        assert!(PlaceholderRule::case_insensitive("System Serial").matches(" SYSTEM SERIAL "));
+        assert!(!PlaceholderRule::case_insensitive("System Serial").matches("System Serial 2"));
+    }
+
+    #[cfg(feature = "placeholder-regex")]
+    #[test]
+    fn regex_rules_are_anchored() {
+        let rule = PlaceholderRule::regex("X{4}-[0-9]+").unwrap();
+        assert!(rule.matches(" XXXX-0042 "));
+        assert!(!rule.matches("AXXXX-0042"));
+        assert!(!rule.matches("XXXX-0042B"));
+        assert_eq!(rule.to_string(), "regex:^(?:X{4}-[0-9]+)$");
+    }
+
+    #[cfg(feature = "placeholder-regex")]
+    #[test]
+    fn oversized_regex_rules_are_rejected() {
+        let long = "a".repeat(PlaceholderRule::MAX_PATTERN_LEN + 1);
+        assert!(matches!(PlaceholderRule::regex(&long), Err(HardwareError::ParseError(_))));
+        // 短表达式经重复展开后超出编译大小上限
+        assert!(matches!(PlaceholderRule::regex("(\\w{100}){100}"), Err(HardwareError::ParseError(_))));
+        assert!(matches!(PlaceholderRule::regex("(unclosed"), Err(HardwareError::ParseError(_))));
+    }
+}
@@ -0,0 +1,319 @@
+use std::fmt;
+use serde::{Serialize, Deserialize};
+#[cfg(target_os = "linux")]
+use std::path::Path;
+#[cfg(target_os = "linux")]
+use log::debug;
+
+// 容器运行时；容器内读到的是宿主机的 DMI（挂载了 /sys 时）或根本读不到，唯一码不代表容器本身
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerKind {
+    Docker,
+    Podman,
+    Kubernetes,
+    Lxc,
+    SystemdNspawn,
+    Unknown,
+}
+
+impl ContainerKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContainerKind::Docker => "docker",
+            ContainerKind::Podman => "podman",
+            ContainerKind::Kubernetes => "kubernetes",
+            ContainerKind::Lxc => "lxc",
+            ContainerKind::SystemdNspawn => "systemd-nspawn",
+            ContainerKind::Unknown => "unknown",
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    // /run/systemd/container 与 PID 1 的 container 环境变量中的名称
+    fn from_name(name: &str) -> Self {
+        match name.trim() {
+            "docker" => ContainerKind::Docker,
+            "podman" => ContainerKind::Podman,
+            "lxc" | "lxc-libvirt" => ContainerKind::Lxc,
+            "systemd-nspawn" => ContainerKind::SystemdNspawn,
+            _ => ContainerKind::Unknown,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    // /proc/1/cgroup：v1 每个控制器一行（"12:pids:/docker/<id>"），v2 只有一行（"0::/kubepods/..."）
+    pub(crate) fn from_cgroup(cgroup: &str) -> Option<Self> {
+        let paths = cgroup.lines().filter_map(|line| line.splitn(3, ':').nth(2));
+        let mut kind = None;
+        for path in paths {
+            // kubepods 下的容器同样带有 docker/containerd 路径，以 Kubernetes 为准
+            if path.contains("kubepods") {
+                return Some(ContainerKind::Kubernetes);
+            }
+            kind = kind.or(if path.contains("/docker") || path.contains("docker-") {
+                Some(ContainerKind::Docker)
+            } else if path.contains("libpod") {
+                Some(ContainerKind::Podman)
+            } else if path.contains("/lxc") || path.contains(".lxc") {
+                Some(ContainerKind::Lxc)
+            } else if path.contains("machine.slice/machine-") || path.contains("nspawn") {
+                Some(ContainerKind::SystemdNspawn)
+            } else {
+                None
+            });
+        }
+        kind
+    }
+}
+
+impl fmt::Display for ContainerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// WSL1 直接转换系统调用，看到的是宿主机的网卡；WSL2 运行在轻量 Hyper-V 虚拟机中，DMI 为虚拟值，
+// 网卡 MAC 每次启动随机生成
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WslVersion {
+    Wsl1,
+    Wsl2,
+}
+
+impl WslVersion {
+    // /proc/sys/kernel/osrelease 或 /proc/version：WSL1 为 "4.4.0-19041-Microsoft"，
+    // WSL2 为 "5.15.90.1-microsoft-standard-WSL2"
+    #[cfg(target_os = "linux")]
+    pub(crate) fn from_kernel_release(release: &str) -> Option<Self> {
+        let release = release.to_lowercase();
+        if !release.contains("microsoft") {
+            return None;
+        }
+        if release.contains("wsl2") || release.contains("microsoft-standard") {
+            Some(WslVersion::Wsl2)
+        } else {
+            Some(WslVersion::Wsl1)
+        }
+    }
+}
+
+impl fmt::Display for WslVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WslVersion::Wsl1 => f.write_str("WSL1"),
+            WslVersion::Wsl2 => f.write_str("WSL2"),
+        }
+    }
+}
+
+// WSL 下采用的策略，记录在采集报告中
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WslStrategy {
+    // 经 cmd.exe 读取 Windows 宿主机的 MachineGuid（CollectOptions::with_wsl_host_identity）
+    HostMachineGuid,
+    // 只使用 WSL 内可见的信息，WSL2 的虚拟网卡 MAC 不参与
+    GuestOnly,
+}
+
+impl WslStrategy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WslStrategy::HostMachineGuid => "host_machine_guid",
+            WslStrategy::GuestOnly => "guest_only",
+        }
+    }
+}
+
+impl fmt::Display for WslStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// 采集时所处的运行环境
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Environment {
+    #[default]
+    Native,
+    Container(ContainerKind),
+    Wsl(WslVersion),
+}
+
+impl Environment {
+    pub fn is_container(&self) -> bool {
+        matches!(self, Environment::Container(_))
+    }
+
+    pub fn wsl_version(&self) -> Option<WslVersion> {
+        match self {
+            Environment::Wsl(version) => Some(*version),
+            _ => None,
+        }
+    }
+
+    // 依次检查运行时留下的标记文件、systemd 的 /run/systemd/container 以及 PID 1 的 cgroup 路径，
+    // 不在容器中时再由内核版本字符串判断是否为 WSL
+    pub fn detect() -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self::detect_in(Path::new("/"))
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            Environment::Native
+        }
+    }
+
+    // 在 root 下按相同的路径检查（测试中为临时目录）
+    #[cfg(target_os = "linux")]
+    fn detect_in(root: &Path) -> Self {
+        let path = |path: &str| root.join(path.trim_start_matches('/'));
+        let read = |file: &str| std::fs::read_to_string(path(file));
+        let kind = if path("/.dockerenv").exists() {
+            Some(ContainerKind::Docker)
+        } else if path("/run/.containerenv").exists() {
+            Some(ContainerKind::Podman)
+        } else if let Ok(name) = read("/run/systemd/container") {
+            Some(ContainerKind::from_name(&name))
+        } else {
+            read("/proc/1/cgroup").ok().and_then(|cgroup| ContainerKind::from_cgroup(&cgroup))
+        };
+        if let Some(kind) = kind {
+            debug!("Running inside a {} container", kind);
+            return Environment::Container(kind);
+        }
+        let wsl = ["/proc/sys/kernel/osrelease", "/proc/version"]
+            .iter()
+            .filter_map(|file| read(file).ok())
+            .find_map(|release| WslVersion::from_kernel_release(&release));
+        match wsl {
+            Some(version) => {
+                debug!("Running under {}", version);
+                Environment::Wsl(version)
+            }
+            None => Environment::Native,
+        }
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Environment::Native => f.write_str("native"),
+            Environment::Container(kind) => write!(f, "container ({})", kind),
+            Environment::Wsl(version) => write!(f, "{}", version),
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    // 临时目录中的文件系统根，只放入各用例需要的文件
+    struct FakeRoot(PathBuf);
+
+    impl FakeRoot {
+        fn new() -> Self {
+            let root = std::env::temp_dir().join(format!("hardware_id-env-{}", Uuid::new_v4().simple()));
+            std::fs::create_dir_all(&root).unwrap();
+            Self(root)
+        }
+
+        fn with(self, path: &str, contents: &str) -> Self {
+            let path = self.0.join(path.trim_start_matches('/'));
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+            self
+        }
+
+        fn detect(&self) -> Environment {
+            Environment::detect_in(&self.0)
+        }
+    }
+
+    impl Drop for FakeRoot {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const HOST_CGROUP_V2: &str = "0::/init.scope\n";
+    const DOCKER_CGROUP_V1: &str = "12:pids:/docker/3f4e8a1b2c5d\n11:memory:/docker/3f4e8a1b2c5d\n1:name=systemd:/docker/3f4e8a1b2c5d\n";
+    const KUBERNETES_CGROUP_V2: &str =
+        "0::/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod1a2b.slice/docker-3f4e8a1b2c5d.scope\n";
+    const NSPAWN_CGROUP_V2: &str = "0::/machine.slice/machine-debian.scope/payload\n";
+
+    #[test]
+    fn cgroup_paths_name_the_container_runtime() {
+        assert_eq!(ContainerKind::from_cgroup(DOCKER_CGROUP_V1), Some(ContainerKind::Docker));
+        assert_eq!(ContainerKind::from_cgroup("0::/system.slice/docker-3f4e8a1b2c5d.scope\n"), Some(ContainerKind::Docker));
+        // kubepods 下的 docker 路径以 Kubernetes 为准
+        assert_eq!(ContainerKind::from_cgroup(KUBERNETES_CGROUP_V2), Some(ContainerKind::Kubernetes));
+        assert_eq!(ContainerKind::from_cgroup(NSPAWN_CGROUP_V2), Some(ContainerKind::SystemdNspawn));
+        assert_eq!(ContainerKind::from_cgroup("0::/machine.slice/libpod-3f4e8a1b2c5d.scope\n"), Some(ContainerKind::Podman));
+        assert_eq!(ContainerKind::from_cgroup("0::/lxc.payload.web/init.scope\n"), Some(ContainerKind::Lxc));
+        assert_eq!(ContainerKind::from_cgroup(HOST_CGROUP_V2), None);
+        assert_eq!(ContainerKind::from_cgroup("0::/user.slice/user-1000.slice/session-2.scope\n"), None);
+        assert_eq!(ContainerKind::from_cgroup(""), None);
+    }
+
+    #[test]
+    fn marker_files_come_before_the_cgroup() {
+        let docker = FakeRoot::new().with("/.dockerenv", "").with("/proc/1/cgroup", HOST_CGROUP_V2);
+        assert_eq!(docker.detect(), Environment::Container(ContainerKind::Docker));
+        let podman = FakeRoot::new().with("/run/.containerenv", "engine=\"podman-4.9.3\"\n");
+        assert_eq!(podman.detect(), Environment::Container(ContainerKind::Podman));
+        let nspawn = FakeRoot::new().with("/run/systemd/container", "systemd-nspawn\n");
+        assert_eq!(nspawn.detect(), Environment::Container(ContainerKind::SystemdNspawn));
+        let unknown = FakeRoot::new().with("/run/systemd/container", "oci\n");
+        assert_eq!(unknown.detect(), Environment::Container(ContainerKind::Unknown));
+
+        let kubernetes = FakeRoot::new().with("/proc/1/cgroup", KUBERNETES_CGROUP_V2);
+        assert_eq!(kubernetes.detect(), Environment::Container(ContainerKind::Kubernetes));
+        assert!(kubernetes.detect().is_container());
+    }
+
+    #[test]
+    fn a_host_without_markers_is_native() {
+        let host = FakeRoot::new()
+            .with("/proc/1/cgroup", HOST_CGROUP_V2)
+            .with("/proc/sys/kernel/osrelease", "6.8.0-45-generic\n");
+        assert_eq!(host.detect(), Environment::Native);
+        assert_eq!(FakeRoot::new().detect(), Environment::Native);
+    }
+
+    #[test]
+    fn kernel_release_tells_wsl1_from_wsl2() {
+        assert_eq!(WslVersion::from_kernel_release("4.4.0-19041-Microsoft\n"), Some(WslVersion::Wsl1));
+        assert_eq!(WslVersion::from_kernel_release("5.15.153.1-microsoft-standard-WSL2\n"), Some(WslVersion::Wsl2));
+        assert_eq!(WslVersion::from_kernel_release("4.19.128-microsoft-standard\n"), Some(WslVersion::Wsl2));
+        assert_eq!(WslVersion::from_kernel_release("6.8.0-45-generic\n"), None);
+    }
+
+    #[test]
+    fn wsl_is_detected_from_osrelease_or_proc_version() {
+        let wsl1 = FakeRoot::new().with("/proc/sys/kernel/osrelease", "4.4.0-19041-Microsoft\n");
+        assert_eq!(wsl1.detect(), Environment::Wsl(WslVersion::Wsl1));
+        let wsl2 = FakeRoot::new().with(
+            "/proc/version",
+            "Linux version 5.15.153.1-microsoft-standard-WSL2 (root@941d701f84f1) (gcc (GCC) 11.2.0) #1 SMP\n",
+        );
+        assert_eq!(wsl2.detect(), Environment::Wsl(WslVersion::Wsl2));
+        assert_eq!(wsl2.detect().wsl_version(), Some(WslVersion::Wsl2));
+        assert!(!wsl2.detect().is_container());
+
+        // 在 WSL 中运行的容器按容器处理
+        let docker_in_wsl = FakeRoot::new()
+            .with("/.dockerenv", "")
+            .with("/proc/sys/kernel/osrelease", "5.15.153.1-microsoft-standard-WSL2\n");
+        assert_eq!(docker_in_wsl.detect(), Environment::Container(ContainerKind::Docker));
+    }
+}
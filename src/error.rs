@@ -13,4 +13,83 @@ pub enum HardwareError {
     
     #[error("System not supported: {0}")]
     UnsupportedSystem(String),
-} 
\ No newline at end of file
+
+    #[error("Unsupported environment: {0}")]
+    UnsupportedEnvironment(String),
+
+    #[error("Critical hardware information missing: {0}")]
+    MissingField(String),
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("Command output too large: {0}")]
+    OutputTooLarge(String),
+
+    #[error("Invalid collect options: {0}")]
+    InvalidOptions(#[from] OptionsError),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+// CollectOptions::validate 拒绝的设置
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum OptionsError {
+    #[error("the command timeout must be greater than zero")]
+    ZeroTimeout,
+
+    #[error("the command timeout of {}s exceeds the maximum of {}s", .timeout.as_secs_f64(), .max.as_secs())]
+    TimeoutTooLong { timeout: std::time::Duration, max: std::time::Duration },
+
+    #[error("the Android ID must not contain whitespace or control characters")]
+    InvalidAndroidId,
+}
+
+impl HardwareError {
+    // 错误类别的固定名称，用于机器可读的输出（hardware_id --quiet）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            HardwareError::CommandError(_) => "command_error",
+            HardwareError::FileError(_) => "file_error",
+            HardwareError::ParseError(_) => "parse_error",
+            HardwareError::UnsupportedSystem(_) => "unsupported_system",
+            HardwareError::UnsupportedEnvironment(_) => "unsupported_environment",
+            HardwareError::MissingField(_) => "missing_field",
+            HardwareError::PermissionDenied(_) => "permission_denied",
+            HardwareError::OutputTooLarge(_) => "output_too_large",
+            HardwareError::InvalidOptions(_) => "invalid_options",
+            HardwareError::Io(_) => "io",
+        }
+    }
+
+    // 两个程序共用的退出码：2 参数错误（含采集选项有误）或其他错误，3 关键硬件信息缺失，4 权限不足，
+    // 5 不支持的系统或运行环境；1 留给 verify/diff 的不一致。采集错误不统一为 2：脚本需要区分
+    // “换台机器/加 sudo/加 --allow-container”几种处理，只关心成败的脚本以 2 及以上判断错误即可
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            HardwareError::MissingField(_) => 3,
+            HardwareError::PermissionDenied(_) => 4,
+            HardwareError::UnsupportedSystem(_) | HardwareError::UnsupportedEnvironment(_) => 5,
+            _ => 2,
+        }
+    }
+}
+
+// std::io::Error 不能复制，复制时保留错误类型与描述
+impl Clone for HardwareError {
+    fn clone(&self) -> Self {
+        match self {
+            HardwareError::CommandError(message) => HardwareError::CommandError(message.clone()),
+            HardwareError::FileError(message) => HardwareError::FileError(message.clone()),
+            HardwareError::ParseError(message) => HardwareError::ParseError(message.clone()),
+            HardwareError::UnsupportedSystem(message) => HardwareError::UnsupportedSystem(message.clone()),
+            HardwareError::UnsupportedEnvironment(message) => HardwareError::UnsupportedEnvironment(message.clone()),
+            HardwareError::MissingField(message) => HardwareError::MissingField(message.clone()),
+            HardwareError::PermissionDenied(message) => HardwareError::PermissionDenied(message.clone()),
+            HardwareError::OutputTooLarge(message) => HardwareError::OutputTooLarge(message.clone()),
+            HardwareError::InvalidOptions(e) => HardwareError::InvalidOptions(e.clone()),
+            HardwareError::Io(e) => HardwareError::Io(std::io::Error::new(e.kind(), e.to_string())),
+        }
+    }
+}
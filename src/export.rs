@@ -0,0 +1,295 @@
+// collect --format 的输出：HardwareInfo 加上元数据，字段名属于对外接口，只增不改
+//   tool_version    写入时的工具版本（CARGO_PKG_VERSION）
+//   schema_version  本结构的版本，字段含义变化时递增（见 Export::SCHEMA_VERSION）
+//   collected_at    采集时间，RFC 3339 UTC（秒精度，如 2026-10-15T08:30:00Z）
+//   hostname        主机名，仅在调用方要求时写入，否则省略
+//   codes           --multi-disk-codes 时的唯一码列表 [{ kind, disk, code }]：primary 在前，其后为 alternate，否则省略
+//   warnings        采集时的全部提示 [{ id, message }]（见 warning.rs），没有时为空列表；不受 --no-warnings 与本机确认影响
+//   info            HardwareInfo，字段见 hardware_info.rs 中的序列化结构说明
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::error::HardwareError;
+use crate::fingerprint::DiskCode;
+use crate::hardware_info::HardwareInfo;
+use crate::warning::Warning;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Export {
+    tool_version: String,
+    schema_version: u32,
+    #[serde(with = "rfc3339")]
+    collected_at: SystemTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hostname: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    codes: Vec<DiskCode>,
+    #[serde(default)]
+    warnings: Vec<Warning>,
+    info: HardwareInfo,
+}
+
+impl Export {
+    pub const SCHEMA_VERSION: u32 = 1;
+
+    // 以当前时间与工具版本包装刚采集的结果
+    pub fn new(info: HardwareInfo, hostname: Option<String>) -> Self {
+        Self {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_version: Self::SCHEMA_VERSION,
+            // 只保留到秒，与写出的 RFC 3339 一致，读回后的 Export 与写出前相同
+            collected_at: UNIX_EPOCH
+                + Duration::from_secs(SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())),
+            hostname,
+            codes: Vec::new(),
+            warnings: Vec::new(),
+            info,
+        }
+    }
+
+    // 附带 FingerprintBuilder::disk_codes 的结果
+    pub fn with_codes(mut self, codes: Vec<DiskCode>) -> Self {
+        self.codes = codes;
+        self
+    }
+
+    // 附带 warning::warnings 的结果
+    pub fn with_warnings(mut self, warnings: Vec<Warning>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    pub fn tool_version(&self) -> &str {
+        &self.tool_version
+    }
+
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    pub fn collected_at(&self) -> SystemTime {
+        self.collected_at
+    }
+
+    pub fn hostname(&self) -> Option<&str> {
+        self.hostname.as_deref()
+    }
+
+    pub fn codes(&self) -> &[DiskCode] {
+        &self.codes
+    }
+
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    pub fn info(&self) -> &HardwareInfo {
+        &self.info
+    }
+
+    pub fn into_info(self) -> HardwareInfo {
+        self.info
+    }
+
+    // compact 只影响 JSON（单行）；YAML 与 TOML 没有紧凑格式
+    pub fn serialize(&self, format: ExportFormat, compact: bool) -> Result<String, HardwareError> {
+        let invalid = |e: &dyn fmt::Display| {
+            HardwareError::ParseError(format!("failed to serialize as {}: {}", format, e))
+        };
+        match format {
+            ExportFormat::Json if compact => serde_json::to_string(self).map_err(|e| invalid(&e)),
+            ExportFormat::Json => serde_json::to_string_pretty(self).map_err(|e| invalid(&e)),
+            ExportFormat::Yaml => serde_yaml::to_string(self).map_err(|e| invalid(&e)),
+            ExportFormat::Toml => toml::to_string_pretty(self).map_err(|e| invalid(&e)),
+        }
+    }
+
+    pub fn deserialize(format: ExportFormat, input: &str) -> Result<Self, HardwareError> {
+        let invalid = |e: &dyn fmt::Display| HardwareError::ParseError(format!("invalid {}: {}", format, e));
+        match format {
+            ExportFormat::Json => serde_json::from_str(input).map_err(|e| invalid(&e)),
+            ExportFormat::Yaml => serde_yaml::from_str(input).map_err(|e| invalid(&e)),
+            ExportFormat::Toml => toml::from_str(input).map_err(|e| invalid(&e)),
+        }
+    }
+}
+
+// 导入的文档（diff 读取的快照）的时间最多可以晚于本机时间多久：超出时只提示写入方的时钟可能不准
+pub const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
+// written 晚于 now 超过 MAX_CLOCK_SKEW 时返回超出 now 的时长
+pub fn future_skew(written: SystemTime, now: SystemTime) -> Option<Duration> {
+    written.duration_since(now).ok().filter(|skew| *skew > MAX_CLOCK_SKEW)
+}
+
+// 写入的时间一律为 RFC 3339 UTC（秒精度），Export 与快照缓存共用
+pub(crate) mod rfc3339 {
+    use std::time::SystemTime;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&humantime::format_rfc3339_seconds(*time))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        humantime::parse_rfc3339(&text).map_err(|e| serde::de::Error::custom(format!("invalid time '{}': {}", text, e)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ExportFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Yaml => "yaml",
+            ExportFormat::Toml => "toml",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(ExportFormat::Json),
+            "yaml" | "yml" => Ok(ExportFormat::Yaml),
+            "toml" => Ok(ExportFormat::Toml),
+            other => Err(format!("unknown format '{}' (expected json, yaml or toml)", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::FingerprintBuilder;
+    use crate::hardware_info::tests::{sample, sample_with};
+    use crate::output_format::OutputFormat;
+    use crate::warning::WarningId;
+
+    const FORMATS: [ExportFormat; 3] = [ExportFormat::Json, ExportFormat::Yaml, ExportFormat::Toml];
+
+    // 两块磁盘各有一个唯一码，带主机名与提示
+    fn full_export() -> Export {
+        let info = sample();
+        let codes = FingerprintBuilder::new().disk_codes(&info, OutputFormat::Grouped, None).unwrap();
+        let warnings = vec![Warning::new(WarningId::LiveBoot, "live boot session")];
+        Export::new(info, Some("build-07".to_string())).with_codes(codes).with_warnings(warnings)
+    }
+
+    // 写出再读回后与原结构相同（逐字段比较序列化后的值，以及唯一码的哈希输入）
+    fn assert_round_trip(export: &Export, format: ExportFormat, compact: bool) {
+        let text = export.serialize(format, compact).unwrap();
+        let read = Export::deserialize(format, &text).unwrap_or_else(|e| panic!("{}: {}\n{}", format, e, text));
+        assert_eq!(serde_json::to_value(&read).unwrap(), serde_json::to_value(export).unwrap(), "{}", format);
+        assert_eq!(read.tool_version(), export.tool_version());
+        assert_eq!(read.schema_version(), Export::SCHEMA_VERSION);
+        assert_eq!(read.collected_at(), export.collected_at());
+        assert_eq!(read.hostname(), export.hostname());
+        assert_eq!(read.codes(), export.codes());
+        assert_eq!(read.warnings(), export.warnings());
+        let fingerprint = FingerprintBuilder::new();
+        assert_eq!(fingerprint.hash_input(read.info()), fingerprint.hash_input(export.info()));
+    }
+
+    #[test]
+    fn every_format_round_trips() {
+        let export = full_export();
+        assert_eq!(export.codes().len(), 2);
+        for format in FORMATS {
+            assert_round_trip(&export, format, false);
+        }
+        assert_round_trip(&export, ExportFormat::Json, true);
+    }
+
+    // 省略的主机名、唯一码列表与取值为 None 的字段读回后不变（TOML 没有 null，直接省略）
+    #[test]
+    fn absent_values_round_trip() {
+        let info = sample_with(|value| {
+            value["bios"]["release_date"] = serde_json::Value::Null;
+            value["root_fs_uuid"] = serde_json::Value::Null;
+            value["gpu_info"] = serde_json::json!([]);
+        });
+        let export = Export::new(info, None);
+        for format in FORMATS {
+            assert_round_trip(&export, format, false);
+            let text = export.serialize(format, false).unwrap();
+            assert!(!text.contains("hostname") && !text.contains("codes"), "{}", text);
+        }
+        // 没有提示时 warnings 仍然写出（空列表）；之前写入的文件没有 warnings 也能读取
+        let json = export.serialize(ExportFormat::Json, true).unwrap();
+        assert!(json.contains(r#""warnings":[]"#), "{}", json);
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value.as_object_mut().unwrap().remove("warnings");
+        assert!(Export::deserialize(ExportFormat::Json, &value.to_string()).unwrap().warnings().is_empty());
+    }
+
+    #[test]
+    fn compact_json_is_a_single_line() {
+        let export = full_export();
+        assert_eq!(export.serialize(ExportFormat::Json, true).unwrap().lines().count(), 1);
+        // YAML 与 TOML 忽略 compact
+        for format in [ExportFormat::Yaml, ExportFormat::Toml] {
+            assert_eq!(export.serialize(format, true).unwrap(), export.serialize(format, false).unwrap());
+        }
+    }
+
+    #[test]
+    fn a_document_in_another_format_is_rejected() {
+        let json = full_export().serialize(ExportFormat::Json, false).unwrap();
+        let error = Export::deserialize(ExportFormat::Toml, &json).unwrap_err();
+        assert!(matches!(error, HardwareError::ParseError(_)), "{:?}", error);
+        assert!(error.to_string().contains("invalid toml"), "{}", error);
+    }
+
+    #[test]
+    fn collected_at_is_written_as_rfc3339_utc() {
+        let mut export = Export::new(sample(), None);
+        export.collected_at = UNIX_EPOCH + Duration::from_secs(1_760_517_000);
+        let json = export.serialize(ExportFormat::Json, true).unwrap();
+        assert!(json.contains(r#""collected_at":"2025-10-15T08:30:00Z""#), "{}", json);
+        let read = Export::deserialize(ExportFormat::Json, &json).unwrap();
+        assert_eq!(read.collected_at(), export.collected_at());
+    }
+
+    #[test]
+    fn unix_timestamps_are_rejected() {
+        let mut json = serde_json::to_value(Export::new(sample(), None)).unwrap();
+        json["collected_at"] = serde_json::json!(1_760_517_000u64);
+        assert!(Export::deserialize(ExportFormat::Json, &json.to_string()).is_err());
+    }
+
+    // 晚于本机时间不超过 MAX_CLOCK_SKEW 时视为时钟误差，不提示
+    #[test]
+    fn only_timestamps_far_in_the_future_are_flagged() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_760_517_000);
+        assert_eq!(future_skew(now - Duration::from_secs(86_400), now), None);
+        assert_eq!(future_skew(now + MAX_CLOCK_SKEW, now), None);
+        let ahead = Duration::from_secs(3 * 60 * 60);
+        assert_eq!(future_skew(now + ahead, now), Some(ahead));
+    }
+
+    #[test]
+    fn malformed_timestamps_are_rejected() {
+        let mut json = serde_json::to_value(Export::new(sample(), None)).unwrap();
+        json["collected_at"] = serde_json::json!("yesterday");
+        let error = Export::deserialize(ExportFormat::Json, &json.to_string()).unwrap_err();
+        assert!(error.to_string().contains("invalid time 'yesterday'"), "{}", error);
+    }
+}
@@ -0,0 +1,8 @@
+// 没有专门实现的目标系统（illumos、DragonFly、Haiku 等）：所有硬件字段都返回 UnsupportedSystem，
+// 保证 crate 仍能编译；collect_lenient 在这些系统上只得到 sysinfo 提供的系统名称与网卡
+
+use crate::error::HardwareError;
+
+pub(crate) fn unsupported<T>() -> Result<T, HardwareError> {
+    Err(HardwareError::UnsupportedSystem(std::env::consts::OS.to_string()))
+}
@@ -0,0 +1,1137 @@
+use std::str::FromStr;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+use crate::denylist;
+use crate::error::HardwareError;
+use crate::hardware_info::{DiskInfo, HardwareInfo};
+use crate::hash_algorithm::HashAlgorithm;
+use crate::normalize;
+use crate::output_format::OutputFormat;
+
+// 可参与哈希的硬件组件，声明顺序即规范顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Component {
+    MotherboardSerial,
+    MotherboardUuid,
+    MacAddress,
+    CpuPhysicalId,
+    MotherboardProductName,
+    DiskModel,
+    DiskSerial,
+    // 部分系统上需要管理员/root 权限才能读取，因此不在默认字段中
+    MemorySerial,
+    BiosVersion,
+    MachineIdentity,
+    // 外接显卡会随时接入、拔出，因此只能显式启用
+    Gpu,
+    // 读取背书密钥通常需要提升权限，因此只能显式启用
+    TpmEkHash,
+    // 克隆镜像时可能重复，因此只能显式启用；启用后可在主板信息无法读取时代替关键字段
+    MachineId,
+    // 重新格式化后会改变，只能显式启用
+    RootFsUuid,
+}
+
+impl Component {
+    pub const ALL: [Component; 14] = [
+        Component::MotherboardSerial,
+        Component::MotherboardUuid,
+        Component::MacAddress,
+        Component::CpuPhysicalId,
+        Component::MotherboardProductName,
+        Component::DiskModel,
+        Component::DiskSerial,
+        Component::MemorySerial,
+        Component::BiosVersion,
+        Component::MachineIdentity,
+        Component::Gpu,
+        Component::TpmEkHash,
+        Component::MachineId,
+        Component::RootFsUuid,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Component::MotherboardSerial => "motherboard_serial",
+            Component::MotherboardUuid => "motherboard_uuid",
+            Component::MacAddress => "mac_address",
+            Component::CpuPhysicalId => "cpu_physical_id",
+            Component::MotherboardProductName => "motherboard_product_name",
+            Component::DiskModel => "disk_model",
+            Component::DiskSerial => "disk_serial",
+            Component::MemorySerial => "memory_serial",
+            Component::BiosVersion => "bios_version",
+            Component::MachineIdentity => "machine_identity",
+            Component::Gpu => "gpu",
+            Component::TpmEkHash => "tpm_ek_pub_hash",
+            Component::MachineId => "machine_id",
+            Component::RootFsUuid => "root_fs_uuid",
+        }
+    }
+
+    // v2 哈希前的规范化规则
+    pub fn normalize(&self, value: &str) -> String {
+        match self {
+            Component::MotherboardUuid | Component::RootFsUuid => normalize::normalize_uuid(value),
+            Component::MacAddress => normalize::normalize_mac(value),
+            _ => normalize::normalize_text(value),
+        }
+    }
+
+    fn bit(&self) -> u32 {
+        1 << (*self as u32)
+    }
+}
+
+// 按 name() 解析，忽略大小写，"-" 与 "_" 等同；未知名称的错误信息列出全部可用名称
+impl FromStr for Component {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let name = value.trim().to_ascii_lowercase().replace('-', "_");
+        Component::ALL.iter().copied().find(|component| component.name() == name).ok_or_else(|| {
+            let names: Vec<&str> = Component::ALL.iter().map(Component::name).collect();
+            format!("unknown field '{}' (valid fields: {})", value.trim(), names.join(", "))
+        })
+    }
+}
+
+// 组件集合（位掩码），与加入顺序无关
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ComponentSet(u32);
+
+impl ComponentSet {
+    pub const fn empty() -> Self {
+        ComponentSet(0)
+    }
+
+    pub fn all() -> Self {
+        Component::ALL.iter().fold(Self::empty(), |set, c| set.with(*c))
+    }
+
+    pub fn with(self, component: Component) -> Self {
+        ComponentSet(self.0 | component.bit())
+    }
+
+    pub fn without(self, component: Component) -> Self {
+        ComponentSet(self.0 & !component.bit())
+    }
+
+    pub fn contains(&self, component: Component) -> bool {
+        self.0 & component.bit() != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    // 按规范顺序遍历
+    pub fn iter(&self) -> impl Iterator<Item = Component> + '_ {
+        Component::ALL.into_iter().filter(|c| self.contains(*c))
+    }
+}
+
+// 默认集合即 v1 方案的字段列表；Android 上没有主板 UUID、序列号通常也不可读，
+// 再加入调用方提供的 ANDROID_ID（machine_id，见 CollectOptions::with_android_id）代替关键字段
+impl Default for ComponentSet {
+    fn default() -> Self {
+        let set: Self = FingerprintVersion::V1.fields().iter().copied().collect();
+        if cfg!(all(target_os = "android", feature = "android")) {
+            set.with(Component::MachineId)
+        } else {
+            set
+        }
+    }
+}
+
+impl FromIterator<Component> for ComponentSet {
+    fn from_iter<I: IntoIterator<Item = Component>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::empty(), |set, c| set.with(c))
+    }
+}
+
+// 指纹方案版本：v1 为原始布局（无前缀），v2 为长度前缀的规范编码（前缀 "v2:"）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FingerprintVersion {
+    #[default]
+    V1,
+    V2,
+}
+
+impl FingerprintVersion {
+    // v1：主要标识符直接拼接，0xFF 分隔，次要标识符以 ":" 连接
+    const V1_PRIMARY: [Component; 3] = [
+        Component::MotherboardSerial,
+        Component::MotherboardUuid,
+        Component::MacAddress,
+    ];
+    const V1_SECONDARY: [Component; 3] = [
+        Component::CpuPhysicalId,
+        Component::MotherboardProductName,
+        Component::DiskModel,
+    ];
+
+    // 各版本默认参与哈希的字段（按哈希顺序）
+    pub fn fields(&self) -> &'static [Component] {
+        match self {
+            FingerprintVersion::V1 | FingerprintVersion::V2 => &[
+                Component::MotherboardSerial,
+                Component::MotherboardUuid,
+                Component::MacAddress,
+                Component::CpuPhysicalId,
+                Component::MotherboardProductName,
+                Component::DiskModel,
+            ],
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FingerprintVersion::V1 => "v1",
+            FingerprintVersion::V2 => "v2",
+        }
+    }
+
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            FingerprintVersion::V1 => "",
+            FingerprintVersion::V2 => "v2:",
+        }
+    }
+
+    // 根据前缀识别版本，返回版本与去掉前缀后的唯一码
+    pub fn detect(code: &str) -> (Self, &str) {
+        match code.strip_prefix(FingerprintVersion::V2.prefix()) {
+            Some(rest) => (FingerprintVersion::V2, rest),
+            None => (FingerprintVersion::V1, code),
+        }
+    }
+
+    // selected 按 Component::ALL 的规范顺序给出每个选中组件的值列表
+    fn encode(&self, selected: &[(Component, Vec<String>)]) -> Vec<u8> {
+        let joined = |component: Component| {
+            selected
+                .iter()
+                .find(|(c, _)| *c == component)
+                .map(|(_, items)| items.concat())
+                .unwrap_or_default()
+        };
+
+        match self {
+            FingerprintVersion::V1 => {
+                let mut input = Vec::new();
+                for component in Self::V1_PRIMARY {
+                    input.extend_from_slice(joined(component).as_bytes());
+                }
+
+                // v1 之外的组件按规范顺序追加到次要标识符之后
+                let mut secondary: Vec<String> = Self::V1_SECONDARY.iter().map(|c| joined(*c)).collect();
+                secondary.extend(
+                    selected
+                        .iter()
+                        .filter(|(c, _)| !Self::V1_PRIMARY.contains(c) && !Self::V1_SECONDARY.contains(c))
+                        .map(|(_, items)| items.concat()),
+                );
+                input.push(0xFF);
+                input.extend_from_slice(secondary.join(":").as_bytes());
+                input
+            }
+            FingerprintVersion::V2 => canonical_encode(selected),
+        }
+    }
+}
+
+// 规范编码（v2）："v2" ‖ 每个组件：长度前缀的名称 ‖ 值个数 ‖ 长度前缀的各个值
+// 长度与个数均为 u32 大端；组件按规范顺序排列，因此任意字段内容（含 ":"、空串、多字节 UTF-8）都不会产生歧义
+fn canonical_encode(selected: &[(Component, Vec<String>)]) -> Vec<u8> {
+    let mut input = b"v2".to_vec();
+    for (component, items) in selected {
+        push_length_prefixed(&mut input, component.name().as_bytes());
+        input.extend_from_slice(&(items.len() as u32).to_be_bytes());
+        for item in items {
+            push_length_prefixed(&mut input, item.as_bytes());
+        }
+    }
+    input
+}
+
+fn push_length_prefixed(input: &mut Vec<u8>, bytes: &[u8]) {
+    input.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    input.extend_from_slice(bytes);
+}
+
+impl FromStr for FingerprintVersion {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "v1" | "1" => Ok(FingerprintVersion::V1),
+            "v2" | "2" => Ok(FingerprintVersion::V2),
+            other => Err(format!("unknown fingerprint version '{}' (expected v1 or v2)", other)),
+        }
+    }
+}
+
+// 生成唯一码前对已采集字段的要求，只统计选中且有值的组件
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CriticalFieldPolicy {
+    // 选中的主板序列号与 UUID 都必须有值（原有行为）；选中且读到 machine_id 时由它代替，设备树单板机改为要求 cpu_physical_id
+    #[default]
+    RequireAll,
+    // 至少 n 个选中的组件有值，适用于主板序列号为空的虚拟机等
+    RequireAny(usize),
+    // 列出的组件都必须有值
+    Custom(Vec<Component>),
+}
+
+// 磁盘组件取哪些磁盘
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiskSelection {
+    // 只取系统盘（原有行为）
+    #[default]
+    Root,
+    // 全部固定磁盘，按序列号排序，与枚举顺序无关；增减数据盘会改变唯一码
+    All,
+}
+
+// MAC 组件取哪些网卡
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MacSelection {
+    // 全部物理网卡，按 MAC 排序，与枚举顺序无关；更换其中一块网卡只改变部分输入
+    #[default]
+    All,
+    // 只取主网卡（NetworkInfo::is_primary），与只保留一块网卡时生成的唯一码一致
+    Primary,
+}
+
+// 选择参与哈希的组件；默认配置与 generate_unique_code 结果一致
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FingerprintBuilder {
+    components: ComponentSet,
+    algorithm: HashAlgorithm,
+    version: FingerprintVersion,
+    key: Option<Vec<u8>>,
+    policy: CriticalFieldPolicy,
+    placeholders: Vec<String>,
+    disks: DiskSelection,
+    macs: MacSelection,
+    allow_container: bool,
+    // 由 with_mac(false) 设置：唯一码带 NO_MAC_MARKER，与读不到网卡时的唯一码区分开
+    no_mac: bool,
+    alternate_uuid: bool,
+    // 由 system_disk 设置：以这块磁盘代替实际的系统盘
+    system_disk: Option<String>,
+}
+
+// 多块内置磁盘轮流作为系统盘时（两块磁盘上分别装有不同的系统），每块磁盘作为系统盘时的唯一码
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskCode {
+    kind: DiskCodeKind,
+    disk: String,
+    code: String,
+}
+
+impl DiskCode {
+    pub fn kind(&self) -> DiskCodeKind {
+        self.kind
+    }
+
+    pub fn disk(&self) -> &str {
+        &self.disk
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+}
+
+// primary 为实际系统盘的唯一码，alternate 为以另一块内置磁盘作为系统盘时的唯一码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiskCodeKind {
+    Primary,
+    Alternate,
+}
+
+// 排除 MAC 的唯一码在版本前缀之后带此标记（"nomac:ABCD-…"、"v2:nomac:ABCD-…"），
+// 服务端据此知道存储的是哪种变体；哈希输入与未选中 MacAddress 时相同
+pub const NO_MAC_MARKER: &str = "nomac:";
+
+impl FingerprintBuilder {
+    // disk_codes 最多给出的备用唯一码个数
+    pub const MAX_ALTERNATE_CODES: usize = 4;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn components(mut self, components: ComponentSet) -> Self {
+        self.components = components;
+        self
+    }
+
+    pub fn with_component(mut self, component: Component, enabled: bool) -> Self {
+        self.components = if enabled {
+            self.components.with(component)
+        } else {
+            self.components.without(component)
+        };
+        self
+    }
+
+    // 主板：序列号、UUID 与产品名称
+    pub fn with_motherboard(self, enabled: bool) -> Self {
+        self.with_component(Component::MotherboardSerial, enabled)
+            .with_component(Component::MotherboardUuid, enabled)
+            .with_component(Component::MotherboardProductName, enabled)
+    }
+
+    // 关闭后 MAC 不参与哈希（扩展坞、USB 网卡使 MAC 频繁变化的场景），采集结果中仍保留网卡信息
+    pub fn with_mac(mut self, enabled: bool) -> Self {
+        self.no_mac = !enabled;
+        self.with_component(Component::MacAddress, enabled)
+    }
+
+    // 磁盘：型号与序列号
+    pub fn with_disk(self, enabled: bool) -> Self {
+        self.with_component(Component::DiskModel, enabled)
+            .with_component(Component::DiskSerial, enabled)
+    }
+
+    pub fn with_cpu(self, enabled: bool) -> Self {
+        self.with_component(Component::CpuPhysicalId, enabled)
+    }
+
+    pub fn with_gpu(self, enabled: bool) -> Self {
+        self.with_component(Component::Gpu, enabled)
+    }
+
+    pub fn with_tpm(self, enabled: bool) -> Self {
+        self.with_component(Component::TpmEkHash, enabled)
+    }
+
+    pub fn with_machine_id(self, enabled: bool) -> Self {
+        self.with_component(Component::MachineId, enabled)
+    }
+
+    pub fn with_root_fs_uuid(self, enabled: bool) -> Self {
+        self.with_component(Component::RootFsUuid, enabled)
+    }
+
+    pub fn algorithm(mut self, algorithm: HashAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    pub fn version(mut self, version: FingerprintVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    // 设置密钥后改用 HMAC-SHA256，algorithm 不再生效
+    pub fn key(mut self, key: &[u8]) -> Self {
+        self.key = Some(key.to_vec());
+        self
+    }
+
+    // 在 denylist::PLACEHOLDER_VALUES 之外追加占位值（不区分大小写），命中的字段按空值处理
+    pub fn placeholder(mut self, value: &str) -> Self {
+        self.placeholders.push(value.trim().to_lowercase());
+        self
+    }
+
+    pub fn policy(mut self, policy: CriticalFieldPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn disks(mut self, selection: DiskSelection) -> Self {
+        self.disks = selection;
+        self
+    }
+
+    pub fn macs(mut self, selection: MacSelection) -> Self {
+        self.macs = selection;
+        self
+    }
+
+    // 容器内采集的信息默认拒绝生成唯一码（UnsupportedEnvironment），避免授权绑定到临时容器
+    pub fn allow_container(mut self, allow: bool) -> Self {
+        self.allow_container = allow;
+        self
+    }
+
+    // 主板 UUID 改用另一种 SMBIOS 字节序的读法（BoardInfo::alternate_uuid），没有时不变；
+    // 用于比对其他工具或旧版本按另一字节序生成的唯一码
+    pub fn alternate_uuid(mut self, enabled: bool) -> Self {
+        self.alternate_uuid = enabled;
+        self
+    }
+
+    // 以 info.disks() 中名为 name 的磁盘代替系统盘计算磁盘组件（DiskSelection::Root 时）；没有该磁盘时磁盘组件为空
+    pub fn system_disk(mut self, name: &str) -> Self {
+        self.system_disk = Some(name.to_string());
+        self
+    }
+
+    pub fn component_set(&self) -> ComponentSet {
+        self.components
+    }
+
+    // 选中且实际有值、参与了哈希的组件
+    pub fn used_components(&self, info: &HardwareInfo) -> ComponentSet {
+        self.components
+            .iter()
+            .filter(|c| self.values(info, *c).iter().any(|v| !v.is_empty()))
+            .collect()
+    }
+
+    pub fn hash(&self, info: &HardwareInfo) -> Result<String, HardwareError> {
+        self.code(info, OutputFormat::Grouped, None)
+    }
+
+    // 带版本前缀的唯一码；with_mac(false) 时前缀后再加 NO_MAC_MARKER
+    pub fn code(
+        &self,
+        info: &HardwareInfo,
+        format: OutputFormat,
+        length: Option<usize>,
+    ) -> Result<String, HardwareError> {
+        let digest = self.digest(info)?;
+        let marker = if self.no_mac && !self.components.contains(Component::MacAddress) { NO_MAC_MARKER } else { "" };
+        Ok(format!("{}{}{}", self.version.prefix(), marker, format.encode(&digest, length)))
+    }
+
+    pub fn digest(&self, info: &HardwareInfo) -> Result<Vec<u8>, HardwareError> {
+        self.check(info)?;
+        let input = self.hash_input(info);
+        match &self.key {
+            Some(key) => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(key)
+                    .expect("HMAC accepts keys of any length");
+                mac.update(&input);
+                Ok(mac.finalize().into_bytes().to_vec())
+            }
+            None => Ok(self.algorithm.digest(&input)),
+        }
+    }
+
+    // 以哈希输入生成 UUIDv5，与密钥、算法和输出格式无关
+    pub fn uuid(&self, info: &HardwareInfo, namespace: Uuid) -> Result<Uuid, HardwareError> {
+        self.check(info)?;
+        Ok(Uuid::new_v5(&namespace, &self.hash_input(info)))
+    }
+
+    // 按唯一码前缀选择版本（及是否排除 MAC）重新计算并比较（分组格式，忽略大小写）；
+    // 不一致时再按主板 UUID 的另一种字节序比较一次
+    pub fn verify(&self, info: &HardwareInfo, code: &str) -> Result<bool, HardwareError> {
+        let (version, rest) = FingerprintVersion::detect(code.trim());
+        let mut builder = self.clone().version(version);
+        if rest.starts_with(NO_MAC_MARKER) {
+            builder = builder.with_mac(false);
+        }
+        if builder.hash(info)?.eq_ignore_ascii_case(code.trim()) {
+            return Ok(true);
+        }
+        if info.board().alternate_uuid().is_none() || !builder.components.contains(Component::MotherboardUuid) {
+            return Ok(false);
+        }
+        Ok(builder.alternate_uuid(true).hash(info)?.eq_ignore_ascii_case(code.trim()))
+    }
+
+    // 可代替系统盘生成备用唯一码的其他内置磁盘（按 info.disks() 的顺序，最多 MAX_ALTERNATE_CODES 块）；
+    // 未选中磁盘组件、DiskSelection::All 或 live 启动时磁盘与系统盘无关，为空
+    pub fn alternate_disks<'a>(&self, info: &'a HardwareInfo) -> Vec<&'a str> {
+        let disk_selected = self.components.contains(Component::DiskModel) || self.components.contains(Component::DiskSerial);
+        if !disk_selected || self.disks != DiskSelection::Root || info.live_boot() {
+            return Vec::new();
+        }
+        info.disks()
+            .iter()
+            .filter(|disk| !disk.is_root() && (!disk.model().is_empty() || !disk.serial().is_empty()))
+            .map(DiskInfo::name)
+            .take(Self::MAX_ALTERNATE_CODES)
+            .collect()
+    }
+
+    // 实际系统盘的唯一码在前，其后为每块备用磁盘作为系统盘时的唯一码；与已列出的唯一码相同的备用项省略
+    pub fn disk_codes(
+        &self,
+        info: &HardwareInfo,
+        format: OutputFormat,
+        length: Option<usize>,
+    ) -> Result<Vec<DiskCode>, HardwareError> {
+        let primary = DiskCode {
+            kind: DiskCodeKind::Primary,
+            disk: info.root_disk().map_or("", DiskInfo::name).to_string(),
+            code: self.code(info, format, length)?,
+        };
+        let mut codes = vec![primary];
+        for disk in self.alternate_disks(info) {
+            let code = self.clone().system_disk(disk).code(info, format, length)?;
+            if codes.iter().all(|listed| listed.code != code) {
+                codes.push(DiskCode { kind: DiskCodeKind::Alternate, disk: disk.to_string(), code });
+            }
+        }
+        Ok(codes)
+    }
+
+    // codes 中任一唯一码与本机一致即通过：按实际系统盘比较，再依次以每块备用磁盘作为系统盘比较，
+    // 因此无论登记时从哪块磁盘启动，都能与 disk_codes 列出的某一项匹配
+    pub fn verify_any_alternate<S: AsRef<str>>(&self, info: &HardwareInfo, codes: &[S]) -> Result<bool, HardwareError> {
+        let builders: Vec<Self> = std::iter::once(self.clone())
+            .chain(self.alternate_disks(info).into_iter().map(|disk| self.clone().system_disk(disk)))
+            .collect();
+        for code in codes {
+            for builder in &builders {
+                if builder.verify(info, code.as_ref())? {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    // 不在未允许的容器中，按 CriticalFieldPolicy 检查，且至少有一个选中的组件有值
+    pub fn check(&self, info: &HardwareInfo) -> Result<(), HardwareError> {
+        if info.environment().is_container() && !self.allow_container {
+            return Err(HardwareError::UnsupportedEnvironment(format!(
+                "running inside a {}; the code would not identify this machine",
+                info.environment()
+            )));
+        }
+        let used = self.used_components(info);
+        match &self.policy {
+            CriticalFieldPolicy::RequireAll => {
+                // 非 root 用户读不到 DMI 时，machine_id 作为关键字段的替代；
+                // 设备树单板机没有主板序列号与 UUID，改为要求 SoC 序列号（cpu_physical_id）
+                let critical = if used.contains(Component::MachineId) {
+                    &[][..]
+                } else if info.board().is_device_tree() {
+                    &[Component::CpuPhysicalId][..]
+                } else {
+                    &[Component::MotherboardSerial, Component::MotherboardUuid][..]
+                };
+                if let Some(missing) = critical.iter().find(|c| self.components.contains(**c) && !used.contains(**c)) {
+                    return Err(HardwareError::MissingField(missing.name().to_string()));
+                }
+            }
+            CriticalFieldPolicy::RequireAny(n) => {
+                let count = used.iter().count();
+                if count < *n {
+                    return Err(HardwareError::MissingField(format!(
+                        "only {} selected components are available, {} required",
+                        count, n
+                    )));
+                }
+            }
+            CriticalFieldPolicy::Custom(required) => {
+                if let Some(missing) = required.iter().find(|c| !used.contains(**c)) {
+                    return Err(HardwareError::MissingField(missing.name().to_string()));
+                }
+            }
+        }
+        if used.is_empty() {
+            let names: Vec<&str> = self.components.iter().map(|c| c.name()).collect();
+            return Err(HardwareError::MissingField(format!(
+                "no selected hardware component is available ({})",
+                names.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    // 哈希输入，编码由版本决定（见 FingerprintVersion::encode）：
+    //   v1：motherboard_serial ‖ motherboard_uuid ‖ 各网卡的 mac_address（按 MAC 排序；与网卡是否启用无关；
+    //       MacSelection::Primary 时只有主网卡）
+    //       ‖ 0xFF ‖ "cpu_physical_id:motherboard_product_name:disk_model"（UTF-8）
+    //       ‖ 其余选中组件按规范顺序逐个追加 ":" + 值；未选中的 v1 组件按空字符串处理
+    //   v2：仅编码选中的组件，值先经 Component::normalize 规范化（MAC 规范化后重新排序），
+    //       再按规范顺序逐个长度前缀
+    // 占位值按空字符串处理；live 启动时磁盘组件为空，DiskSelection::All 时磁盘组件为各磁盘按序列号排序的值；可选字段为 None 时该组件没有值（v1 中等同空字符串，v2 中值个数为 0）
+    pub fn hash_input(&self, info: &HardwareInfo) -> Vec<u8> {
+        let selected: Vec<(Component, Vec<String>)> = self
+            .components
+            .iter()
+            .map(|c| {
+                let mut values = self.values(info, c);
+                if self.version == FingerprintVersion::V2 {
+                    values = values.iter().map(|v| c.normalize(v)).collect();
+                    values.sort();
+                }
+                (c, values)
+            })
+            .collect();
+        self.version.encode(&selected)
+    }
+
+    // 占位值替换为空字符串，与采集时被清空的字段得到相同的哈希输入；
+    // 主板序列号因此为空时以 Windows MachineGuid 代替
+    fn values(&self, info: &HardwareInfo, component: Component) -> Vec<String> {
+        let values: Vec<String> = self.raw_values(info, component)
+            .into_iter()
+            .map(|value| if self.is_placeholder(&value) { String::new() } else { value })
+            .collect();
+        if component == Component::MotherboardSerial && values.iter().all(|v| v.is_empty()) {
+            if let Some(guid) = info.machine_guid().filter(|guid| !guid.is_empty()) {
+                return vec![guid.to_string()];
+            }
+        }
+        values
+    }
+
+    fn is_placeholder(&self, value: &str) -> bool {
+        denylist::is_placeholder(value) || self.placeholders.contains(&value.trim().to_lowercase())
+    }
+
+    fn raw_values(&self, info: &HardwareInfo, component: Component) -> Vec<String> {
+        let disk = |root: &str, field: fn(&DiskInfo) -> &str| match (info.live_boot(), self.disks) {
+            (true, _) => Vec::new(),
+            (false, DiskSelection::Root) => match &self.system_disk {
+                Some(name) => info.disks().iter().filter(|disk| disk.name() == name).map(|disk| field(disk).to_string()).collect(),
+                None => vec![root.to_string()],
+            },
+            (false, DiskSelection::All) => info.disks().iter().map(|disk| field(disk).to_string()).collect(),
+        };
+        match component {
+            Component::MotherboardSerial => vec![info.motherboard_serial().to_string()],
+            Component::MotherboardUuid => {
+                let alternate = info.board().alternate_uuid().filter(|_| self.alternate_uuid);
+                vec![alternate.unwrap_or(info.motherboard_uuid()).to_string()]
+            }
+            Component::MacAddress => {
+                let interfaces = info.network_interfaces();
+                // 旧版本序列化的结果没有 is_primary，当时只保留了一块网卡
+                let has_primary = interfaces.iter().any(|interface| interface.is_primary());
+                interfaces
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, interface)| match self.macs {
+                        MacSelection::All => true,
+                        MacSelection::Primary => interface.is_primary() || (!has_primary && *i == 0),
+                    })
+                    .map(|(_, interface)| interface.mac_address())
+                    .filter(|mac| !mac.is_zero())
+                    .map(|mac| mac.to_string())
+                    .collect()
+            }
+            Component::CpuPhysicalId => vec![info.cpu_physical_id().to_string()],
+            Component::MotherboardProductName => vec![info.motherboard_product_name().to_string()],
+            Component::DiskModel => disk(info.disk_model(), DiskInfo::model),
+            Component::DiskSerial => disk(info.disk_serial(), DiskInfo::serial),
+            Component::MemorySerial => info.memory_modules().to_vec(),
+            Component::BiosVersion => info.bios_version().map(str::to_string).into_iter().collect(),
+            Component::MachineIdentity => vec![info.machine_identity().value().to_string()],
+            // 有 UUID 时用 UUID，否则用厂商与型号
+            Component::Gpu => info
+                .gpu_info()
+                .iter()
+                .map(|gpu| gpu.uuid().map_or_else(|| format!("{} {}", gpu.vendor(), gpu.model()), str::to_string))
+                .collect(),
+            Component::TpmEkHash => info
+                .tpm()
+                .and_then(|tpm| tpm.ek_pub_hash())
+                .map(str::to_string)
+                .into_iter()
+                .collect(),
+            Component::MachineId => info.machine_id().map(str::to_string).into_iter().collect(),
+            Component::RootFsUuid => info.root_fs_uuid().map(str::to_string).into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware_info::tests::{sample, sample_with};
+
+    // 2.6 之前的读法：前三段按网络字节序
+    const SWAPPED_UUID: &str = "44454c4c-5800-104a-8038-b7c04f513532";
+
+    fn with_alternate_uuid() -> HardwareInfo {
+        sample_with(|value| value["board"]["alternate_uuid"] = SWAPPED_UUID.into())
+    }
+
+    #[test]
+    fn verify_accepts_a_code_built_from_the_swapped_uuid() {
+        let other_tool = sample_with(|value| value["board"]["uuid"] = SWAPPED_UUID.into());
+        for version in [FingerprintVersion::V1, FingerprintVersion::V2] {
+            let builder = FingerprintBuilder::new().version(version);
+            let code = builder.hash(&other_tool).unwrap();
+            assert_ne!(code, builder.hash(&with_alternate_uuid()).unwrap());
+            assert!(builder.verify(&with_alternate_uuid(), &code).unwrap());
+            assert!(!builder.verify(&sample(), &code).unwrap());
+        }
+    }
+
+    #[test]
+    fn alternate_uuid_is_ignored_when_absent() {
+        let builder = FingerprintBuilder::new();
+        assert_eq!(builder.clone().alternate_uuid(true).hash(&sample()).unwrap(), builder.hash(&sample()).unwrap());
+    }
+
+    // 同一台机器从另一块磁盘上的系统采集：系统盘为 sda
+    fn booted_from_sda() -> HardwareInfo {
+        sample_with(|value| {
+            value["disks"][0]["is_root"] = false.into();
+            value["disks"][1]["is_root"] = true.into();
+        })
+    }
+
+    #[test]
+    fn disk_codes_list_the_primary_then_each_alternate_disk() {
+        let codes = FingerprintBuilder::new().disk_codes(&sample(), OutputFormat::Grouped, None).unwrap();
+        let labels: Vec<(DiskCodeKind, &str)> = codes.iter().map(|code| (code.kind(), code.disk())).collect();
+        assert_eq!(labels, [(DiskCodeKind::Primary, "nvme0n1"), (DiskCodeKind::Alternate, "sda")]);
+        assert_eq!(codes[0].code(), FingerprintBuilder::new().hash(&sample()).unwrap());
+        assert_ne!(codes[0].code(), codes[1].code());
+    }
+
+    #[test]
+    fn codes_collected_from_either_system_disk_overlap() {
+        let builder = FingerprintBuilder::new();
+        let linux = builder.disk_codes(&sample(), OutputFormat::Grouped, None).unwrap();
+        let windows = builder.disk_codes(&booted_from_sda(), OutputFormat::Grouped, None).unwrap();
+        assert!(linux.iter().any(|code| windows.iter().any(|other| other.code() == code.code())));
+
+        let registered: Vec<&str> = linux.iter().map(DiskCode::code).collect();
+        assert!(builder.verify_any_alternate(&booted_from_sda(), &registered).unwrap());
+        assert!(!builder.verify(&booted_from_sda(), registered[0]).unwrap());
+        assert!(!builder.verify_any_alternate(&booted_from_sda(), &["0000-0000-0000-0000"]).unwrap());
+    }
+
+    #[test]
+    fn alternate_codes_are_capped_and_skipped_without_disk_components() {
+        let many = sample_with(|value| {
+            let disks = value["disks"].as_array_mut().unwrap();
+            for i in 0..6 {
+                disks.push(serde_json::json!({
+                    "name": format!("sd{}", (b'b' + i) as char),
+                    "model": format!("ST2000DM00{}", i),
+                    "serial": format!("ZFL2{:04}", i),
+                    "firmware": null,
+                    "is_root": false
+                }));
+            }
+        });
+        let builder = FingerprintBuilder::new();
+        assert_eq!(builder.alternate_disks(&many).len(), FingerprintBuilder::MAX_ALTERNATE_CODES);
+        assert_eq!(
+            builder.disk_codes(&many, OutputFormat::Grouped, None).unwrap().len(),
+            FingerprintBuilder::MAX_ALTERNATE_CODES + 1
+        );
+        assert!(builder.clone().with_disk(false).alternate_disks(&many).is_empty());
+        assert!(builder.disks(DiskSelection::All).alternate_disks(&many).is_empty());
+    }
+
+    #[test]
+    fn component_set_is_a_mask_independent_of_order() {
+        let a = ComponentSet::empty().with(Component::DiskSerial).with(Component::MotherboardUuid);
+        let b: ComponentSet = [Component::MotherboardUuid, Component::DiskSerial, Component::DiskSerial].into_iter().collect();
+        assert_eq!(a, b);
+        assert_eq!(a.iter().collect::<Vec<_>>(), [Component::MotherboardUuid, Component::DiskSerial]);
+        assert!(!a.without(Component::DiskSerial).contains(Component::DiskSerial));
+        assert_eq!(a.without(Component::Gpu), a);
+        assert!(a.without(Component::DiskSerial).without(Component::MotherboardUuid).is_empty());
+        assert_eq!(ComponentSet::all().iter().collect::<Vec<_>>(), Component::ALL);
+        assert_eq!(ComponentSet::default().iter().collect::<Vec<_>>(), FingerprintVersion::V1.fields());
+    }
+
+    #[test]
+    fn component_names_parse_loosely() {
+        for component in Component::ALL {
+            assert_eq!(component.name().parse(), Ok(component));
+        }
+        assert_eq!(" Disk-Serial ".parse(), Ok(Component::DiskSerial));
+        let error = "disk".parse::<Component>().unwrap_err();
+        assert!(error.starts_with("unknown field 'disk' (valid fields: motherboard_serial, "), "{}", error);
+    }
+
+    // 未选中的组件变化不影响唯一码，选中的组件变化一定影响
+    #[test]
+    fn only_selected_components_affect_the_code() {
+        let builder = FingerprintBuilder::new()
+            .components(ComponentSet::empty().with(Component::MotherboardSerial).with(Component::DiskSerial));
+        let code = builder.hash(&sample()).unwrap();
+        let new_cpu = sample_with(|value| value["cpu"]["physical_id"] = "BFEBFBFF000A0655".into());
+        let new_nic = sample_with(|value| value["network_interfaces"][1]["mac_address"] = "a4:34:d9:00:00:01".into());
+        let new_disk = sample_with(|value| value["disks"][0]["serial"] = "S4GNNF0N654321".into());
+        assert_eq!(builder.hash(&new_cpu).unwrap(), code);
+        assert_eq!(builder.hash(&new_nic).unwrap(), code);
+        assert_ne!(builder.hash(&new_disk).unwrap(), code);
+
+        // 默认组件中的网卡：全部网卡（MacSelection::All）或只取主网卡
+        let default = FingerprintBuilder::new();
+        assert_ne!(default.hash(&new_nic).unwrap(), default.hash(&sample()).unwrap());
+        let primary = FingerprintBuilder::new().macs(MacSelection::Primary);
+        assert_eq!(primary.hash(&new_nic).unwrap(), primary.hash(&sample()).unwrap());
+    }
+
+    #[test]
+    fn used_components_leave_out_the_missing_values() {
+        let info = sample_with(|value| value["bios"]["version"] = serde_json::Value::Null);
+        let builder = FingerprintBuilder::new().components(
+            ComponentSet::empty().with(Component::MotherboardSerial).with(Component::BiosVersion).with(Component::TpmEkHash),
+        );
+        assert_eq!(builder.used_components(&info), ComponentSet::empty().with(Component::MotherboardSerial));
+        assert_eq!(builder.used_components(&sample()).iter().count(), 2);
+    }
+
+    // 可选字段为 None 时没有值（序列化为 null），占位值在 v1 中与 None 的哈希输入相同
+    #[test]
+    fn optional_fields_may_be_none_or_hold_a_placeholder() {
+        let optional = [Component::BiosVersion, Component::MachineId, Component::RootFsUuid];
+        let components = optional.into_iter().fold(ComponentSet::empty().with(Component::MotherboardSerial), ComponentSet::with);
+        let nones = sample_with(|value| {
+            value["bios"]["version"] = serde_json::Value::Null;
+            value["machine_id"] = serde_json::Value::Null;
+            value["root_fs_uuid"] = serde_json::Value::Null;
+        });
+        let json = serde_json::to_value(&nones).unwrap();
+        assert!(json["bios"]["version"].is_null() && json["machine_id"].is_null() && json["root_fs_uuid"].is_null());
+        for version in [FingerprintVersion::V1, FingerprintVersion::V2] {
+            let builder = FingerprintBuilder::new().version(version).components(components);
+            assert_eq!(builder.used_components(&nones), ComponentSet::empty().with(Component::MotherboardSerial));
+            assert!(builder.hash(&nones).is_ok());
+        }
+
+        let placeholder = sample_with(|value| {
+            value["bios"]["version"] = "To be filled by O.E.M.".into();
+            value["machine_id"] = serde_json::Value::Null;
+            value["root_fs_uuid"] = serde_json::Value::Null;
+        });
+        let v1 = FingerprintBuilder::new().components(components);
+        assert_eq!(v1.hash_input(&placeholder), v1.hash_input(&nones));
+        assert_eq!(v1.used_components(&placeholder), v1.used_components(&nones));
+    }
+
+    #[test]
+    fn critical_fields_follow_the_policy() {
+        let no_serial = sample_with(|value| value["board"]["serial"] = "".into());
+        assert!(matches!(
+            FingerprintBuilder::new().hash(&no_serial),
+            Err(HardwareError::MissingField(field)) if field == "motherboard_serial"
+        ));
+        assert!(FingerprintBuilder::new().policy(CriticalFieldPolicy::RequireAny(5)).hash(&no_serial).is_ok());
+        assert!(FingerprintBuilder::new().policy(CriticalFieldPolicy::RequireAny(6)).hash(&no_serial).is_err());
+        // 未选中的关键字段不要求
+        assert!(FingerprintBuilder::new().with_component(Component::MotherboardSerial, false).hash(&no_serial).is_ok());
+        assert!(FingerprintBuilder::new().components(ComponentSet::empty()).hash(&sample()).is_err());
+    }
+
+    // 虚拟机常见的占位主板序列号：默认策略下缺少关键字段，放宽的策略下由其余组件生成唯一码
+    #[test]
+    fn relaxed_policy_accepts_a_placeholder_serial() {
+        let vm = sample_with(|value| value["board"]["serial"] = "None".into());
+        assert!(matches!(
+            FingerprintBuilder::new().hash(&vm),
+            Err(HardwareError::MissingField(field)) if field == "motherboard_serial"
+        ));
+        let (code, used) = vm.generate_unique_code_with_policy(CriticalFieldPolicy::RequireAny(3)).unwrap();
+        assert!(!used.contains(Component::MotherboardSerial) && used.contains(Component::MotherboardUuid));
+        assert_eq!(code, FingerprintBuilder::new().policy(CriticalFieldPolicy::RequireAny(3)).hash(&vm).unwrap());
+        let custom = CriticalFieldPolicy::Custom(vec![Component::MotherboardUuid, Component::MacAddress]);
+        assert!(FingerprintBuilder::new().policy(custom).hash(&vm).is_ok());
+    }
+
+    #[test]
+    fn v1_and_v2_codes_are_fixed_and_distinguishable() {
+        let v1 = FingerprintBuilder::new().hash(&sample()).unwrap();
+        let v2 = FingerprintBuilder::new().version(FingerprintVersion::V2).hash(&sample()).unwrap();
+        assert_eq!(v1, "1a6b-8bdd-c72f-61ec");
+        assert_eq!(v2, "v2:cf45-e033-05c8-f56f");
+        assert_eq!(FingerprintVersion::detect(&v2), (FingerprintVersion::V2, &v2[3..]));
+        assert_eq!(FingerprintVersion::detect(&v1), (FingerprintVersion::V1, v1.as_str()));
+        assert_eq!(
+            FingerprintBuilder::new().with_mac(false).hash(&sample()).unwrap(),
+            format!("{}{}", NO_MAC_MARKER, FingerprintBuilder::new().with_component(Component::MacAddress, false).hash(&sample()).unwrap())
+        );
+    }
+
+    // 最初版本 generate_unique_code 的算法，原样保留作对照：
+    // MD5(主板序列号 ‖ 主板 UUID ‖ 已启用网卡的 MAC ‖ 0xFF ‖ "CPU ID:主板型号:磁盘型号") 的前 16 个十六进制字符
+    fn original_formula(serial: &str, uuid: &str, macs: &[&str], secondary: [&str; 3]) -> String {
+        use md5::{Digest, Md5};
+        let mut hasher = Md5::new();
+        hasher.update(serial.as_bytes());
+        hasher.update(uuid.as_bytes());
+        for mac in macs {
+            hasher.update(mac.as_bytes());
+        }
+        hasher.update([0xFF]);
+        hasher.update(secondary.join(":").as_bytes());
+        let hash = hex::encode(hasher.finalize());
+        format!("{}-{}-{}-{}", &hash[0..4], &hash[4..8], &hash[8..12], &hash[12..16])
+    }
+
+    // 只有一块网卡时，默认的 v1 唯一码与最初版本完全相同，已签发的唯一码不因重构而失效
+    #[test]
+    fn v1_with_one_nic_matches_the_original_formula() {
+        let one_nic = sample_with(|value| value["network_interfaces"].as_array_mut().unwrap().truncate(1));
+        let expected = original_formula(
+            "/7XJ8Q52/CN1296385B00KL/",
+            "4c4c4544-0058-4a10-8038-b7c04f513532",
+            &["d8:9e:f3:12:34:56"],
+            ["BFEBFBFF000906EA", "0NNNCT", "PM981a NVMe SAMSUNG 512GB"],
+        );
+        assert_eq!(expected, "4a9c-ae62-4b39-bdec");
+        assert_eq!(FingerprintBuilder::new().hash(&one_nic).unwrap(), expected);
+        assert_eq!(one_nic.generate_unique_code_with(HashAlgorithm::Md5).unwrap(), expected);
+        // 多块网卡时只取主网卡，同样得到最初版本的唯一码
+        assert_eq!(FingerprintBuilder::new().macs(MacSelection::Primary).hash(&sample()).unwrap(), expected);
+    }
+
+    // Linux 单路机器的 cpuinfo physical id 为 "0"，与其他值一样原样哈希，不按占位值清空
+    #[test]
+    fn v1_hashes_a_zero_cpu_physical_id() {
+        let linux = sample_with(|value| {
+            value["network_interfaces"].as_array_mut().unwrap().truncate(1);
+            value["cpu"]["physical_id"] = "0".into();
+        });
+        let expected = original_formula(
+            "/7XJ8Q52/CN1296385B00KL/",
+            "4c4c4544-0058-4a10-8038-b7c04f513532",
+            &["d8:9e:f3:12:34:56"],
+            ["0", "0NNNCT", "PM981a NVMe SAMSUNG 512GB"],
+        );
+        assert_eq!(expected, "83ca-7fb9-250c-94df");
+        assert_eq!(FingerprintBuilder::new().hash(&linux).unwrap(), expected);
+    }
+
+    // v2 先规范化再哈希：UUID 大小写与首尾空白不同的同一台机器得到同一个唯一码；v1 保持原样哈希
+    #[test]
+    fn v2_normalizes_values_and_v1_does_not() {
+        let reformatted = sample_with(|value| {
+            value["board"]["uuid"] = "4C4C4544-0058-4A10-8038-B7C04F513532".into();
+            value["board"]["product"] = "  0NNNCT ".into();
+        });
+        let v2 = FingerprintBuilder::new().version(FingerprintVersion::V2);
+        assert_eq!(v2.hash(&reformatted).unwrap(), v2.hash(&sample()).unwrap());
+        let v1 = FingerprintBuilder::new();
+        assert_ne!(v1.hash(&reformatted).unwrap(), v1.hash(&sample()).unwrap());
+    }
+
+    #[test]
+    fn containers_need_an_explicit_opt_in() {
+        let container = sample_with(|value| value["environment"] = serde_json::json!({ "container": "docker" }));
+        assert!(matches!(FingerprintBuilder::new().hash(&container), Err(HardwareError::UnsupportedEnvironment(_))));
+        assert_eq!(
+            FingerprintBuilder::new().allow_container(true).hash(&container).unwrap(),
+            FingerprintBuilder::new().hash(&sample()).unwrap()
+        );
+    }
+
+    // 规范编码的逆过程：能无歧义地解码，说明不同的输入不可能得到相同的字节
+    fn decode_canonical(mut bytes: &[u8]) -> Vec<(String, Vec<String>)> {
+        fn take<'a>(bytes: &mut &'a [u8], n: usize) -> &'a [u8] {
+            let (head, rest) = bytes.split_at(n);
+            *bytes = rest;
+            head
+        }
+        fn length(bytes: &mut &[u8]) -> usize {
+            u32::from_be_bytes(take(bytes, 4).try_into().unwrap()) as usize
+        }
+        fn text(bytes: &mut &[u8]) -> String {
+            let n = length(bytes);
+            String::from_utf8(take(bytes, n).to_vec()).unwrap()
+        }
+
+        assert_eq!(take(&mut bytes, 2), b"v2");
+        let mut decoded = Vec::new();
+        while !bytes.is_empty() {
+            let name = text(&mut bytes);
+            let count = length(&mut bytes);
+            decoded.push((name, (0..count).map(|_| text(&mut bytes)).collect()));
+        }
+        decoded
+    }
+
+    // 由 pieces 组成的 0 至 2 项的值列表，包括空串、":"、多字节 UTF-8 与 NUL
+    fn value_lists() -> Vec<Vec<String>> {
+        let pieces = ["", "a", "b", "ab", ":", "a:b", "é", "\u{0}"];
+        let mut lists = vec![Vec::new()];
+        for first in pieces {
+            lists.push(vec![first.to_string()]);
+            for second in pieces {
+                lists.push(vec![first.to_string(), second.to_string()]);
+            }
+        }
+        lists
+    }
+
+    #[test]
+    fn canonical_encoding_never_collides() {
+        let lists = value_lists();
+        let mut inputs: Vec<Vec<(Component, Vec<String>)>> = Vec::new();
+        for model in &lists {
+            inputs.push(vec![(Component::DiskModel, model.clone())]);
+            inputs.push(vec![(Component::DiskSerial, model.clone())]);
+            for serial in &lists {
+                inputs.push(vec![(Component::DiskModel, model.clone()), (Component::DiskSerial, serial.clone())]);
+            }
+        }
+        let mut seen = std::collections::HashMap::new();
+        for input in &inputs {
+            let bytes = canonical_encode(input);
+            let expected: Vec<(String, Vec<String>)> =
+                input.iter().map(|(component, values)| (component.name().to_string(), values.clone())).collect();
+            assert_eq!(decode_canonical(&bytes), expected);
+            if let Some(other) = seen.insert(bytes, input) {
+                panic!("{:?} and {:?} encode to the same bytes", other, input);
+            }
+        }
+        assert_eq!(seen.len(), inputs.len());
+    }
+
+    // v1 直接拼接主板序列号与 UUID，"ab"+"c" 与 "a"+"bc" 的哈希输入相同；v2 不同
+    #[test]
+    fn shifted_field_boundaries_differ_only_in_v2() {
+        let left = sample_with(|value| {
+            value["board"]["serial"] = "ab".into();
+            value["board"]["uuid"] = "c".into();
+        });
+        let right = sample_with(|value| {
+            value["board"]["serial"] = "a".into();
+            value["board"]["uuid"] = "bc".into();
+        });
+        assert_ne!(left.canonical_bytes(), right.canonical_bytes());
+        let v1 = FingerprintBuilder::new();
+        assert_eq!(v1.hash_input(&left), v1.hash_input(&right));
+
+        let items = |values: &[&str]| vec![(Component::MemorySerial, values.iter().map(|v| v.to_string()).collect())];
+        assert_ne!(canonical_encode(&items(&["ab", "c"])), canonical_encode(&items(&["a", "bc"])));
+    }
+
+    // 组件按 Component::ALL 的规范顺序编码，与选择时的先后无关；字节布局固定
+    #[test]
+    fn canonical_field_order_is_stable() {
+        let names: Vec<String> = decode_canonical(&sample().canonical_bytes()).into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, FingerprintVersion::V2.fields().iter().map(|c| c.name()).collect::<Vec<_>>());
+
+        let forward = FingerprintBuilder::new()
+            .version(FingerprintVersion::V2)
+            .components(ComponentSet::empty().with(Component::RootFsUuid).with(Component::MotherboardSerial));
+        let backward = FingerprintBuilder::new()
+            .version(FingerprintVersion::V2)
+            .components(ComponentSet::empty().with(Component::MotherboardSerial).with(Component::RootFsUuid));
+        assert_eq!(forward.hash_input(&sample()), backward.hash_input(&sample()));
+
+        let bytes = canonical_encode(&[(Component::MachineId, vec!["x".to_string()])]);
+        assert_eq!(bytes, b"v2\0\0\0\x0amachine_id\0\0\0\x01\0\0\0\x01x");
+    }
+
+    #[test]
+    fn v2_codes_carry_the_prefix_in_every_format() {
+        let builder = FingerprintBuilder::new().version(FingerprintVersion::V2);
+        for format in [OutputFormat::Grouped, OutputFormat::Hex, OutputFormat::Base32, OutputFormat::Base58] {
+            let code = builder.code(&sample(), format, None).unwrap();
+            assert!(code.starts_with("v2:"), "{:?}: {}", format, code);
+            assert!(!FingerprintBuilder::new().code(&sample(), format, None).unwrap().starts_with("v2:"));
+        }
+        // verify 按前缀识别方案，默认（v1）的 builder 也能校验 v2 唯一码
+        assert!(FingerprintBuilder::new().verify(&sample(), &builder.hash(&sample()).unwrap()).unwrap());
+        assert!(builder.with_mac(false).hash(&sample()).unwrap().starts_with("v2:nomac:"));
+    }
+}
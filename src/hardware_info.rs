@@ -1,753 +1,5820 @@
-use sysinfo::{System, SystemExt, NetworkExt};
-use std::error::Error;
-use md5::{Md5, Digest};
+use sysinfo::{CpuRefreshKind, RefreshKind, System, SystemExt};
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "illumos",
+    target_os = "solaris",
+    all(target_os = "android", feature = "android")
+)))]
+use sysinfo::NetworkExt;
 use serde::{Serialize, Deserialize};
+use std::path::Path;
+use std::time::Duration;
+use uuid::Uuid;
+use log::{debug, info, warn};
+use crate::command_runner::{self, CommandRunner, SystemCommandRunner};
+use crate::cpu_identity::CpuIdentity;
+use crate::denylist::{self, PlaceholderRule};
+use crate::environment::{Environment, WslStrategy, WslVersion};
+use crate::mac_address::MacAddress;
+use crate::machine_identity::{IdentitySource, MachineIdentity};
+use crate::error::{HardwareError, OptionsError};
+use crate::fingerprint::{Component, ComponentSet, CriticalFieldPolicy, FingerprintBuilder, FingerprintVersion};
+use crate::hash_algorithm::HashAlgorithm;
+use crate::output_format::OutputFormat;
+use crate::parsers;
+use crate::progress::{ProgressCallback, ProgressEvent, ProgressTracker};
+use crate::normalize;
+use crate::offline::OfflineRoot;
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "illumos", target_os = "solaris"))]
+use crate::smbios;
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+use crate::smbios::SmbiosTable;
+#[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+use crate::bsd;
+#[cfg(any(target_os = "illumos", target_os = "solaris"))]
+use crate::illumos;
+#[cfg(target_os = "linux")]
+use crate::linux;
+#[cfg(all(target_os = "android", feature = "android"))]
+use crate::android;
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "illumos",
+    target_os = "solaris",
+    all(target_os = "android", feature = "android")
+)))]
+use crate::fallback;
+
+// 未指定命名空间时 as_uuid 使用的固定命名空间，不可更改
+pub const HARDWARE_ID_NAMESPACE: Uuid = uuid::uuid!("6295129c-7f98-4ada-a353-c754db3925e3");
+
+// 网卡类型；序列化为 "Ethernet"、"Wi-Fi"、"Unknown"，与早期版本的字符串相同
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NetworkInterfaceType {
+    Ethernet,
+    #[serde(rename = "Wi-Fi")]
+    WiFi,
+    #[default]
+    Unknown,
+}
+
+impl NetworkInterfaceType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            NetworkInterfaceType::Ethernet => "Ethernet",
+            NetworkInterfaceType::WiFi => "Wi-Fi",
+            NetworkInterfaceType::Unknown => "Unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for NetworkInterfaceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// 信息的来源：本机运行时采集，或从挂载的系统镜像中读取（HardwareInfo::collect_offline）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionMode {
+    #[default]
+    Live,
+    Offline,
+}
+
+impl CollectionMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CollectionMode::Live => "live",
+            CollectionMode::Offline => "offline",
+        }
+    }
+}
+
+impl std::fmt::Display for CollectionMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct NetworkInfo {
     name: String,
-    mac_address: String,
+    mac_address: MacAddress,
+    // 旧版本的序列化结果中没有此字段，反序列化为全 0 地址
+    #[serde(default)]
+    current_mac_address: MacAddress,
     is_up: bool,
-    interface_type: String,
+    interface_type: NetworkInterfaceType,
+    #[serde(default)]
+    is_primary: bool,
+}
+
+// 一块固定磁盘；is_root 为系统所在的磁盘
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskInfo {
+    name: String,
+    model: String,
+    serial: String,
+    firmware: Option<String>,
+    is_root: bool,
+}
+
+impl DiskInfo {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub fn serial(&self) -> &str {
+        &self.serial
+    }
+
+    pub fn firmware(&self) -> Option<&str> {
+        self.firmware.as_deref()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.is_root
+    }
+}
+
+// 采集选项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectOptions {
+    timeout: Duration,
+    wsl_host_identity: bool,
+    android_id: Option<String>,
+    parallel_probes: bool,
+    components: Option<ComponentSet>,
+    placeholder_rules: Vec<PlaceholderRule>,
+    progress: ProgressCallback,
+}
+
+impl CollectOptions {
+    // 单个外部命令的默认超时与允许的最大超时；其余默认值见 Default
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+    pub const MAX_TIMEOUT: Duration = Duration::from_secs(600);
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn wsl_host_identity(&self) -> bool {
+        self.wsl_host_identity
+    }
+
+    // 在 WSL 中经 cmd.exe 读取 Windows 宿主机的 MachineGuid，作为主板序列号缺失时的替代
+    pub fn with_wsl_host_identity(mut self, enabled: bool) -> Self {
+        self.wsl_host_identity = enabled;
+        self
+    }
+
+    pub fn android_id(&self) -> Option<&str> {
+        self.android_id.as_deref()
+    }
+
+    // Android 上由调用方传入 Settings.Secure.ANDROID_ID，采集为 machine_id；其他系统上忽略
+    pub fn with_android_id(mut self, id: &str) -> Self {
+        self.android_id = Some(id.trim().to_string()).filter(|id| !id.is_empty());
+        self
+    }
+
+    pub fn parallel_probes(&self) -> bool {
+        self.parallel_probes
+    }
+
+    // 默认开启：相互独立的外部命令（Windows 的 wmic/PowerShell、macOS 的 system_profiler 等）先并发执行，
+    // 各字段再按原顺序从其输出解析，结果与关闭时相同；每条命令仍受 timeout 限制
+    pub fn with_parallel_probes(mut self, enabled: bool) -> Self {
+        self.parallel_probes = enabled;
+        self
+    }
+
+    pub fn components(&self) -> Option<ComponentSet> {
+        self.components
+    }
+
+    // 只读取 components 哈希时用到的字段（见 HardwareInfo::probes_field），其余字段为空或 None；
+    // 默认读取全部字段
+    pub fn with_components(mut self, components: ComponentSet) -> Self {
+        self.components = Some(components);
+        self
+    }
+
+    pub fn extra_placeholder_patterns(&self) -> &[PlaceholderRule] {
+        &self.placeholder_rules
+    }
+
+    // 在内置占位值之外按缺失处理的值：作用于主板序列号、UUID、产品名称与厂商，系统与机箱序列号，
+    // 各磁盘序列号与内存条序列号；命中的字段与规则记入 CollectionReport::placeholder_matches
+    pub fn with_extra_placeholder_patterns(mut self, rules: Vec<PlaceholderRule>) -> Self {
+        self.placeholder_rules = rules;
+        self
+    }
+
+    // 每条可以提前执行的命令开始、结束或被跳过时调用，见 progress.rs；回调在执行命令的线程上运行，
+    // 应尽快返回，不影响采集结果
+    pub fn on_progress(mut self, callback: impl Fn(&ProgressEvent) + Send + Sync + 'static) -> Self {
+        self.progress = ProgressCallback::new(callback);
+        self
+    }
+}
+
+impl CollectOptions {
+    // 采集前检查设置：互相矛盾或无法生效的设置返回 OptionsError，没有效果的设置记为警告
+    pub fn validate(&self) -> Result<ValidatedOptions, OptionsError> {
+        if self.timeout.is_zero() {
+            return Err(OptionsError::ZeroTimeout);
+        }
+        if self.timeout > Self::MAX_TIMEOUT {
+            return Err(OptionsError::TimeoutTooLong { timeout: self.timeout, max: Self::MAX_TIMEOUT });
+        }
+        if self.android_id.as_deref().is_some_and(|id| id.chars().any(|c| c.is_whitespace() || c.is_control())) {
+            return Err(OptionsError::InvalidAndroidId);
+        }
+
+        let mut warnings = Vec::new();
+        if self.android_id.is_some() && !cfg!(all(target_os = "android", feature = "android")) {
+            warnings.push("android_id is only used on Android and is ignored here".to_string());
+        }
+        if self.wsl_host_identity && !cfg!(target_os = "linux") {
+            warnings.push("wsl_host_identity only applies under WSL and is ignored here".to_string());
+        }
+        if self.parallel_probes && cfg!(all(target_os = "windows", feature = "wmi-native")) {
+            warnings.push("parallel_probes has no effect with wmi-native, which runs no commands".to_string());
+        }
+        if self.components.is_some_and(|components| components.is_empty()) {
+            warnings.push("no component is selected; only the environment is detected".to_string());
+        }
+        Ok(ValidatedOptions { options: self.clone(), warnings })
+    }
+}
+
+// 通过检查的采集选项及其警告；Display 为实际生效配置的摘要，采集时写入日志与 CollectionReport::options
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatedOptions {
+    options: CollectOptions,
+    warnings: Vec<String>,
+}
+
+impl ValidatedOptions {
+    pub fn options(&self) -> &CollectOptions {
+        &self.options
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+}
+
+// ANDROID_ID 属于设备标识，摘要中只注明是否设置
+impl std::fmt::Display for ValidatedOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let on_off = |enabled: bool| if enabled { "on" } else { "off" };
+        let components = match self.options.components {
+            Some(components) => components.iter().map(|component| component.name()).collect::<Vec<_>>().join("+"),
+            None => "all".to_string(),
+        };
+        write!(
+            f,
+            "timeout={}s parallel_probes={} components={} wsl_host_identity={} android_id={} placeholder_rules={}",
+            self.options.timeout.as_secs_f64(),
+            on_off(self.options.parallel_probes),
+            components,
+            on_off(self.options.wsl_host_identity),
+            if self.options.android_id.is_some() { "set" } else { "unset" },
+            self.options.placeholder_rules.len()
+        )
+    }
+}
+
+impl Default for CollectOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Self::DEFAULT_TIMEOUT,
+            wsl_host_identity: false,
+            android_id: None,
+            parallel_probes: true,
+            components: None,
+            placeholder_rules: Vec::new(),
+            progress: ProgressCallback::default(),
+        }
+    }
+}
+
+// 宽松采集时未能读取的字段及对应错误（按采集顺序排列），以及采集时的运行环境
+#[derive(Debug, Default)]
+pub struct CollectionReport {
+    errors: Vec<(&'static str, HardwareError)>,
+    environment: Environment,
+    wsl_strategy: Option<WslStrategy>,
+    external_boot_disk: bool,
+    translated: bool,
+    architecture: String,
+    primary_interface: Option<String>,
+    interface_chain: Option<(String, Vec<String>)>,
+    placeholder_rules: Vec<PlaceholderRule>,
+    placeholder_matches: Vec<(&'static str, String)>,
+    options: String,
+}
+
+impl CollectionReport {
+    pub fn environment(&self) -> Environment {
+        self.environment
+    }
+
+    // 仅在 WSL 中有值
+    pub fn wsl_strategy(&self) -> Option<WslStrategy> {
+        self.wsl_strategy
+    }
+
+    // 从外接磁盘（USB/雷雳）启动：系统盘的型号与序列号随外接磁盘变化，不能代表这台机器；目前仅 macOS 检测
+    pub fn is_external_boot_disk(&self) -> bool {
+        self.external_boot_disk
+    }
+
+    // 本进程经 Rosetta 2 转译运行（Apple Silicon 上的 x86_64 构建）
+    pub fn is_translated(&self) -> bool {
+        self.translated
+    }
+
+    // 宿主机的 CPU 架构（Rust 的命名：x86_64、aarch64）：通常即本进程的架构，Rosetta 2 转译时为 aarch64；
+    // 仅供显示，不参与唯一码
+    pub fn architecture(&self) -> &str {
+        &self.architecture
+    }
+
+    // network_interfaces 中第一块网卡的名称；没有可用网卡时为 None
+    pub fn primary_interface(&self) -> Option<&str> {
+        self.primary_interface.as_deref()
+    }
+
+    // 默认路由在绑定、网桥或 team 接口上时，该接口与其下参与哈希的物理网卡（bond0 → eno1、eno2）；目前仅 Linux
+    pub fn interface_chain(&self) -> Option<(&str, &[String])> {
+        self.interface_chain.as_ref().map(|(name, lower)| (name.as_str(), lower.as_slice()))
+    }
+
+    pub fn errors(&self) -> &[(&'static str, HardwareError)] {
+        &self.errors
+    }
+
+    // 采集所用选项的摘要（ValidatedOptions 的 Display）；选项未通过检查时为空，错误记在 "options" 下
+    pub fn options(&self) -> &str {
+        &self.options
+    }
+
+    // 因 CollectOptions::with_extra_placeholder_patterns 的规则而按缺失处理的字段，及命中的规则
+    pub fn placeholder_matches(&self) -> &[(&'static str, String)] {
+        &self.placeholder_matches
+    }
+
+    pub fn into_errors(self) -> Vec<(&'static str, HardwareError)> {
+        self.errors
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    // 因权限不足而未能读取的字段，提升权限后可能可以读取
+    pub fn permission_denied(&self) -> impl Iterator<Item = &(&'static str, HardwareError)> {
+        self.errors.iter().filter(|(_, e)| matches!(e, HardwareError::PermissionDenied(_)))
+    }
+
+    // 其余失败：数据确实不存在、命令缺失或输出无法解析
+    pub fn unavailable(&self) -> impl Iterator<Item = &(&'static str, HardwareError)> {
+        self.errors.iter().filter(|(_, e)| !matches!(e, HardwareError::PermissionDenied(_)))
+    }
+
+    fn take(&mut self, field: &'static str, result: Result<String, HardwareError>) -> String {
+        self.take_optional(field, result).unwrap_or_default()
+    }
+
+    fn take_optional(&mut self, field: &'static str, result: Result<String, HardwareError>) -> Option<String> {
+        result.map_err(|e| self.record(field, e)).ok()
+    }
+
+    // 内置规则之后再按追加的占位值规则检查，命中时置空
+    fn screen(&mut self, field: &'static str, value: String) -> String {
+        self.screen_optional(field, Some(value)).unwrap_or_default()
+    }
+
+    fn screen_optional(&mut self, field: &'static str, value: Option<String>) -> Option<String> {
+        let value = value.filter(|value| !value.is_empty())?;
+        match self.placeholder_rules.iter().find(|rule| rule.matches(&value)) {
+            Some(rule) => {
+                debug!("{} \"{}\" matches the placeholder rule {}; treating it as missing", field, value, rule);
+                self.placeholder_matches.push((field, rule.to_string()));
+                None
+            }
+            None => Some(value),
+        }
+    }
+
+    fn record(&mut self, field: &'static str, error: HardwareError) {
+        debug!("Failed to collect {}: {}", field, error);
+        self.errors.push((field, error));
+    }
+}
+
+// CPU：品牌字符串、厂商、物理 ID 与物理核心数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CpuInfo {
+    brand: String,
+    vendor: String,
+    physical_id: String,
+    cores: usize,
+}
+
+impl CpuInfo {
+    pub fn brand(&self) -> &str {
+        &self.brand
+    }
+
+    pub fn vendor(&self) -> &str {
+        &self.vendor
+    }
+
+    pub fn physical_id(&self) -> &str {
+        &self.physical_id
+    }
+
+    pub fn cores(&self) -> usize {
+        self.cores
+    }
+}
+
+// 主板（SMBIOS 类型 2，UUID 为系统 UUID）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardInfo {
+    serial: String,
+    uuid: String,
+    // uuid 按另一种字节序的读法，见 BoardInfo::alternate_uuid
+    #[serde(default)]
+    alternate_uuid: Option<String>,
+    manufacturer: Option<String>,
+    product: String,
+    #[serde(default)]
+    device_tree: bool,
+}
+
+impl BoardInfo {
+    pub fn serial(&self) -> &str {
+        &self.serial
+    }
+
+    pub fn uuid(&self) -> &str {
+        &self.uuid
+    }
+
+    // UUID 来自 SMBIOS 时前三段的字节序有两种读法（规范 2.6 前后不同，工具之间也不一致），
+    // 这里是 uuid 的另一种读法；verify 也接受按这一读法生成的唯一码。macOS 等非 SMBIOS 来源为 None
+    pub fn alternate_uuid(&self) -> Option<&str> {
+        self.alternate_uuid.as_deref()
+    }
+
+    pub fn manufacturer(&self) -> Option<&str> {
+        self.manufacturer.as_deref()
+    }
+
+    pub fn product(&self) -> &str {
+        &self.product
+    }
+
+    // 没有 SMBIOS、由设备树描述的单板机（树莓派等 ARM SBC）：没有主板序列号与 UUID，以 SoC 序列号作为标识
+    pub fn is_device_tree(&self) -> bool {
+        self.device_tree
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BiosInfo {
+    vendor: Option<String>,
+    version: Option<String>,
+    release_date: Option<String>,
+}
+
+impl BiosInfo {
+    pub fn vendor(&self) -> Option<&str> {
+        self.vendor.as_deref()
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    pub fn release_date(&self) -> Option<&str> {
+        self.release_date.as_deref()
+    }
+}
+
+// 一块显卡；uuid 只有 NVIDIA 显卡（nvidia-smi 可用时）才有
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GpuInfo {
+    vendor: String,
+    model: String,
+    uuid: Option<String>,
+}
+
+impl GpuInfo {
+    pub fn vendor(&self) -> &str {
+        &self.vendor
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub fn uuid(&self) -> Option<&str> {
+        self.uuid.as_deref()
+    }
+}
+
+// TPM 是否存在，以及背书密钥公开部分的 SHA-256（小写十六进制）；读取背书密钥通常需要提升权限
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TpmInfo {
+    present: bool,
+    ek_pub_hash: Option<String>,
+}
+
+impl TpmInfo {
+    pub fn present(&self) -> bool {
+        self.present
+    }
+
+    pub fn ek_pub_hash(&self) -> Option<&str> {
+        self.ek_pub_hash.as_deref()
+    }
+}
+
+// 序列化结构：
+//   cpu { brand, vendor, physical_id, cores }、cpu_identity { vendor, family, model, stepping, features_hash }
+//   board { serial, uuid, alternate_uuid, manufacturer, product, device_tree }、bios { vendor, version, release_date }
+//   system_serial、chassis_serial、chassis_type（如 "Desktop"、"Laptop"）、board_asset_tag、chassis_asset_tag
+//   disks [{ name, model, serial, firmware, is_root }]、memory_serial、memory_modules []
+//   gpu_info [{ vendor, model, uuid }]、tpm { present, ek_pub_hash }、root_fs_uuid
+//   mac_address、network_interfaces [{ name, mac_address, current_mac_address, is_up, interface_type, is_primary }]
+//   os_info、virtualization、environment、live_boot、machine_identity { source, value }、machine_guid、machine_id
+//   collection_mode（"live" 或 "offline"）
+// 可选值缺失时为 null；哈希只经由访问器读取字段，结构调整不影响唯一码
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HardwareInfo {
+    cpu: CpuInfo,
+    board: BoardInfo,
+    bios: BiosInfo,
+    #[serde(default)]
+    system_serial: Option<String>,
+    #[serde(default)]
+    chassis_serial: Option<String>,
+    #[serde(default)]
+    chassis_type: Option<String>,
+    // 资产标签可由管理员随时修改，只用于展示与 CMDB 对账，不参与哈希
+    #[serde(default)]
+    board_asset_tag: Option<String>,
+    #[serde(default)]
+    chassis_asset_tag: Option<String>,
+    mac_address: String,
+    os_info: String,
+    // 由 memory_modules 派生（排序后的第一条），保留给原有的 JSON 使用方
+    memory_serial: Option<String>,
+    // 所有已安装内存条的序列号，已排序并去掉占位值
+    #[serde(default)]
+    memory_modules: Vec<String>,
+    cpu_identity: CpuIdentity,
+    // 按序列号排序；系统盘的各字段与原 disk_serial/disk_model/disk_firmware 相同
+    #[serde(default)]
+    disks: Vec<DiskInfo>,
+    // 按厂商、型号、UUID 排序；没有显卡时为空
+    #[serde(default)]
+    gpu_info: Vec<GpuInfo>,
+    // macOS 上为 None；没有 TPM 或无权读取时 present 为 false，不记为采集错误
+    #[serde(default)]
+    tpm: Option<TpmInfo>,
+    // 重新格式化系统分区后会改变，只能显式加入哈希
+    #[serde(default)]
+    root_fs_uuid: Option<String>,
+    network_interfaces: Vec<NetworkInfo>,
+    // 虚拟化平台名称；物理机为 None
+    #[serde(default)]
+    virtualization: Option<String>,
+    // 在容器内采集时默认拒绝生成唯一码，见 FingerprintBuilder::allow_container
+    #[serde(default)]
+    environment: Environment,
+    live_boot: bool,
+    #[serde(default)]
+    machine_identity: MachineIdentity,
+    machine_guid: Option<String>,
+    // 仅 Linux：systemd 的 machine-id，克隆镜像时若未重新生成会在多台机器间重复，因此不在默认哈希中
+    #[serde(default)]
+    machine_id: Option<String>,
+    // 旧版本的序列化结果中没有此字段，均为运行时采集
+    #[serde(default)]
+    collection_mode: CollectionMode,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct HardwareInfo {
-    cpu_info: String,
-    motherboard_serial: String,
-    disk_serial: String,
-    mac_address: String,
-    os_info: String,
-    memory_serial: String,
-    bios_version: String,
-    cpu_physical_id: String,
-    disk_model: String,
-    disk_firmware: String,
-    motherboard_uuid: String,
-    motherboard_manufacturer: String,
-    motherboard_product_name: String,
-    bios_vendor: String,
-    bios_release_date: String,
-    network_interfaces: Vec<NetworkInfo>,
-}
+impl NetworkInfo {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // 网卡的永久（出厂）地址，读不到时为当前地址；唯一码使用这一地址
+    pub fn mac_address(&self) -> MacAddress {
+        self.mac_address
+    }
+
+    // 网卡当前使用的地址，启用 MAC 随机化或 Wi-Fi 私有地址时与 mac_address 不同；仅供显示
+    pub fn current_mac_address(&self) -> MacAddress {
+        self.current_mac_address
+    }
+
+    // 采集时的运行状态，仅供显示：拔掉网线或关闭 Wi-Fi 不影响唯一码
+    pub fn is_up(&self) -> bool {
+        self.is_up
+    }
+
+    pub fn interface_type(&self) -> NetworkInterfaceType {
+        self.interface_type
+    }
+
+    // 主网卡（默认路由所在的网卡，聚合接口时为其全部下层网卡）；MacSelection::Primary 只哈希这些网卡，
+    // 与只保留一块网卡时的唯一码一致
+    pub fn is_primary(&self) -> bool {
+        self.is_primary
+    }
+}
+
+impl HardwareInfo {
+    // 可选字段：读取失败时为 None（序列化为 null），严格采集也不会因此失败，v2 哈希中不参与编码；
+    // disks 为其余磁盘的枚举，失败时只保留系统盘
+    pub const OPTIONAL_FIELDS: &'static [&'static str] = &[
+        "disks",
+        "memory_modules",
+        "bios_version",
+        "disk_firmware",
+        "motherboard_manufacturer",
+        "bios_vendor",
+        "bios_release_date",
+        "machine_guid",
+        "gpu_info",
+        "system_serial",
+        "chassis_serial",
+        "chassis_type",
+        "board_asset_tag",
+        "chassis_asset_tag",
+        "root_fs_uuid",
+    ];
+
+    // 离线镜像中无法读取的字段（固件、CPU 与硬件设备），collect_offline 的报告中记为 UnsupportedSystem
+    pub const OFFLINE_UNSUPPORTED_FIELDS: &'static [&'static str] = &[
+        "cpu_info",
+        "cpu_physical_id",
+        "motherboard_serial",
+        "motherboard_uuid",
+        "motherboard_product_name",
+        "motherboard_manufacturer",
+        "system_serial",
+        "chassis_serial",
+        "bios_vendor",
+        "bios_version",
+        "bios_release_date",
+        "disk_serial",
+        "disk_model",
+        "memory_modules",
+        "gpu_info",
+        "tpm",
+    ];
+
+    pub fn collect() -> Result<Self, HardwareError> {
+        Self::collect_with(&CollectOptions::default())
+    }
+
+    // 只采集 components 生成唯一码所需的字段，唯一码与完整采集相同；其余字段为空或 None，
+    // 不会执行 sudo dmidecode 等与唯一码无关的命令
+    pub fn collect_for(components: ComponentSet) -> Result<Self, HardwareError> {
+        Self::collect_with(&CollectOptions::default().with_components(components))
+    }
+
+    pub fn collect_with(options: &CollectOptions) -> Result<Self, HardwareError> {
+        let (info, report) = Self::collect_lenient_with(options);
+        Self::require_fields(info, report)
+    }
+
+    // 可选字段以外的字段读取失败时返回第一个错误
+    pub(crate) fn require_fields(info: Self, report: CollectionReport) -> Result<Self, HardwareError> {
+        match report
+            .into_errors()
+            .into_iter()
+            .find(|(field, _)| !Self::OPTIONAL_FIELDS.contains(field))
+        {
+            Some((_, e)) => Err(e),
+            None => Ok(info),
+        }
+    }
+
+    // 宽松采集：读取失败的字段置空并记入报告，由调用方（或唯一码生成时的检查）决定是否可用
+    pub fn collect_lenient() -> (Self, CollectionReport) {
+        Self::collect_lenient_with(&CollectOptions::default())
+    }
+
+    pub fn collect_lenient_with(options: &CollectOptions) -> (Self, CollectionReport) {
+        Self::collect_lenient_inner(&SystemCommandRunner::new(options.timeout()), options)
+    }
+
+    // 外部命令统一经 runner 执行，可替换为返回固定输出的实现
+    pub fn collect_lenient_with_runner(runner: &dyn CommandRunner) -> (Self, CollectionReport) {
+        Self::collect_lenient_inner(runner, &CollectOptions::default())
+    }
+
+    pub(crate) fn collect_lenient_inner(runner: &dyn CommandRunner, options: &CollectOptions) -> (Self, CollectionReport) {
+        let collector = Collector::new(runner);
+        let (ahead, skipped) = Self::probe_plan(options);
+        let progress = Self::progress_tracker(options, &ahead, &skipped);
+        collector.prefetch(&ahead, &progress);
+        Self::collect_from(&collector, options)
+    }
+
+    // 异步采集：probe_plan 中提前执行的命令已由调用方执行，其余命令与文件读取在当前线程完成
+    #[cfg(feature = "async")]
+    pub(crate) fn collect_lenient_prefetched(
+        runner: &dyn CommandRunner,
+        options: &CollectOptions,
+        prefetched: std::collections::HashMap<CommandLine, CommandResult>,
+    ) -> (Self, CollectionReport) {
+        let collector = Collector::new(runner);
+        collector.prefetched.borrow_mut().extend(prefetched);
+        Self::collect_from(&collector, options)
+    }
+
+    // 可以提前并发执行的命令，分为提前执行的与跳过的：按需采集时不需要的命令总是跳过；
+    // 未启用 parallel_probes 或选项未通过检查时全部跳过
+    pub(crate) fn probe_plan(options: &CollectOptions) -> (Vec<ProbeCommandLine>, Vec<ProbeCommandLine>) {
+        let run_ahead = options.parallel_probes() && options.validate().is_ok();
+        let (ahead, skipped): (Vec<_>, Vec<_>) = Collector::probe_commands().into_iter().partition(|(fields, _)| {
+            run_ahead && fields.iter().any(|field| Self::probes_field(options.components(), field))
+        });
+        let commands = |probes: Vec<ProbeCommand>| probes.into_iter().map(|(_, command)| command).collect();
+        (commands(ahead), commands(skipped))
+    }
+
+    // 跳过的命令立即报告，进度从它们开始计数
+    pub(crate) fn progress_tracker(
+        options: &CollectOptions,
+        ahead: &[ProbeCommandLine],
+        skipped: &[ProbeCommandLine],
+    ) -> ProgressTracker {
+        let progress = ProgressTracker::new(&options.progress, ahead.len() + skipped.len());
+        for (program, args) in skipped {
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            progress.skipped(&command_runner::command_line(program, &args));
+        }
+        progress
+    }
+
+    // 选项未通过检查时不读取任何硬件字段（只检测运行环境），错误记在 "options" 下，严格采集因此失败
+    fn collect_from(collector: &Collector, options: &CollectOptions) -> (Self, CollectionReport) {
+        match options.validate() {
+            Ok(validated) => {
+                info!("Collecting with {}", validated);
+                for warning in validated.warnings() {
+                    warn!("{}", warning);
+                }
+                let (info, mut report) = Self::collect_validated(collector, validated.options());
+                report.options = validated.to_string();
+                (info, report)
+            }
+            Err(e) => {
+                let nothing = CollectOptions::default().with_components(ComponentSet::empty());
+                let (info, mut report) = Self::collect_validated(collector, &nothing);
+                report.record("options", e.into());
+                (info, report)
+            }
+        }
+    }
+
+    #[cfg_attr(not(any(target_os = "linux", all(target_os = "android", feature = "android"))), allow(unused_variables))]
+    fn collect_validated(collector: &Collector, options: &CollectOptions) -> (Self, CollectionReport) {
+        let probe = |field: &str| Self::probes_field(options.components(), field);
+        let wants_network = probe("network_interfaces");
+        // 只刷新用到的网卡列表与 CPU 列表；System::new_all 会扫描全部进程，占去采集的大部分时间
+        let refresh = RefreshKind::new().with_cpu(CpuRefreshKind::new());
+        let sys = System::new_with_specifics(if wants_network { refresh.with_networks_list() } else { refresh });
+        let mut report = CollectionReport {
+            environment: collector.environment,
+            placeholder_rules: options.extra_placeholder_patterns().to_vec(),
+            ..Default::default()
+        };
+        if report.environment.is_container() {
+            warn!("Running inside a {}; hardware fields come from the host or are missing", report.environment);
+        }
+        let wsl = report.environment.wsl_version();
+        if let Some(version) = wsl {
+            warn!("Running under {}; DMI data is virtual and codes may collide across machines", version);
+        }
+
+        // Windows 的网卡名称不固定（"以太网"、"Ethernet 2"），改由 GetAdaptersAddresses 按类型筛选物理网卡
+        #[cfg(target_os = "windows")]
+        let mut network_interfaces = if wants_network { collector.get_network_adapters() } else { Ok(Vec::new()) }
+            .unwrap_or_else(|e| {
+                report.record("network_interfaces", e);
+                Vec::new()
+            });
+        // 接上雷雳扩展坞时 en0 可能是扩展坞的网卡，部分机型的内置网卡是 en1：改为只取内置端口
+        #[cfg(target_os = "macos")]
+        let mut network_interfaces = if wants_network { collector.get_builtin_interfaces() } else { Ok(Vec::new()) }
+            .unwrap_or_else(|e| {
+                debug!("Built-in network ports unavailable, falling back to en0: {}", e);
+                Vec::new()
+            });
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+        )))]
+        let mut network_interfaces = Vec::new();
+        // 网卡名称因机型而异（enp3s0、wlp2s0）：Linux 依次取默认路由所在的物理网卡与第一块有载波的物理网卡，
+        // macOS 在没有内置端口时取默认路由所在的网卡；都选不出时才按名称白名单。
+        // 值为 (默认路由所在接口, 选中的物理网卡)，二者不同说明默认路由在聚合接口上
+        #[cfg(target_os = "linux")]
+        let primary_interface = if wants_network { collector.sys.primary_interface() } else { None };
+        #[cfg(target_os = "macos")]
+        let primary_interface = if wants_network && network_interfaces.is_empty() {
+            collector.default_route_interface()
+        } else {
+            None
+        }
+        .map(|name| (name.clone(), vec![name]));
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+        )))]
+        let primary_interface: Option<(String, Vec<String>)> = None;
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+        )))]
+        let use_sysinfo = wants_network && network_interfaces.is_empty();
+        // 聚合接口的 MAC 继承自某一块下层网卡，主备切换后可能改变：改为哈希全部下层网卡的永久 MAC
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+        )))]
+        {
+            let aggregate = primary_interface.as_ref().filter(|(name, lower)| lower.as_slice() != [name.as_str()]);
+            if let Some((name, lower)) = aggregate {
+                debug!("Default route is on {}; using its lower devices {}", name, lower.join(", "));
+                report.interface_chain = Some((name.clone(), lower.clone()));
+            }
+        }
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+        )))]
+        for (name, network) in sys.networks() {
+            let is_primary = match &primary_interface {
+                Some((_, primary)) => primary.contains(name),
+                None => Self::is_primary_interface(name),
+            };
+            if use_sysinfo && (is_primary || Self::is_physical_interface(name)) {
+                let mac = MacAddress::from(network.mac_address().0);
+                if mac.is_unicast() {
+                    network_interfaces.push(NetworkInfo {
+                        name: name.to_string(),
+                        mac_address: Self::permanent_mac(name).unwrap_or(mac),
+                        current_mac_address: mac,
+                        is_up: collector.interface_is_up(name),
+                        interface_type: Self::detect_interface_type(name),
+                        is_primary,
+                    });
+                }
+            }
+        }
+
+        // sysinfo 在 BSD 上不提供 MAC，改由 getifaddrs 的链路层地址枚举以太网卡
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        let mut network_interfaces: Vec<NetworkInfo> = if wants_network { bsd::ethernet_interfaces() } else { Ok(Vec::new()) }
+            .unwrap_or_else(|e| {
+                report.record("network_interfaces", e);
+                Vec::new()
+            })
+            .into_iter()
+            .filter_map(|interface| {
+                let mac: MacAddress = interface.mac.parse().ok()?;
+                Some(NetworkInfo {
+                    interface_type: Self::detect_interface_type(&interface.name),
+                    name: interface.name,
+                    current_mac_address: mac,
+                    mac_address: mac,
+                    is_up: interface.is_up,
+                    is_primary: false,
+                })
+            })
+            .collect();
+
+        // sysinfo 不支持 illumos，由 dladm 列出物理链路及其 MAC 与状态
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        let mut network_interfaces: Vec<NetworkInfo> = match if wants_network { collector.illumos_links() } else { Ok(Vec::new()) } {
+            Ok(links) => links,
+            Err(e) => {
+                report.record("network_interfaces", e);
+                Vec::new()
+            }
+        };
+
+        // Android 上 sysinfo 枚举到的多为蜂窝与虚拟接口，只取 Wi-Fi 网卡
+        #[cfg(all(target_os = "android", feature = "android"))]
+        let mut network_interfaces: Vec<NetworkInfo> = match if wants_network { android::wlan_mac() } else { Ok(String::new()) } {
+            Ok(mac) => mac.parse().into_iter().map(|mac: MacAddress| NetworkInfo {
+                name: "wlan0".to_string(),
+                current_mac_address: mac,
+                mac_address: mac,
+                is_up: android::wlan_is_up(),
+                interface_type: NetworkInterfaceType::WiFi,
+                is_primary: true,
+            }).collect(),
+            Err(e) => {
+                report.record("network_interfaces", e);
+                Vec::new()
+            }
+        };
+
+        Self::order_interfaces(&mut network_interfaces, report.interface_chain.is_some());
+        // WSL2 的虚拟网卡 MAC 每次启动随机生成
+        if wsl == Some(WslVersion::Wsl2) {
+            network_interfaces.clear();
+        }
+        report.primary_interface =
+            network_interfaces.iter().find(|interface| interface.is_primary).map(|interface| interface.name.clone());
+
+        let live_boot = Self::detect_live_boot();
+        if live_boot {
+            warn!("Live/overlay root detected; disk components are excluded from the unique code");
+        }
+
+        let motherboard_serial = if probe("motherboard_serial") {
+            Self::screen_duplicate(
+                "motherboard_serial",
+                report.take("motherboard_serial", collector.get_motherboard_serial()),
+            )
+        } else {
+            String::new()
+        };
+        let motherboard_uuid = if probe("motherboard_uuid") {
+            Self::screen_duplicate("motherboard_uuid", report.take("motherboard_uuid", collector.get_motherboard_uuid()))
+        } else {
+            String::new()
+        };
+        let motherboard_serial = report.screen("motherboard_serial", motherboard_serial);
+        let motherboard_uuid = report.screen("motherboard_uuid", motherboard_uuid);
+        #[cfg(target_os = "windows")]
+        let machine_guid = if probe("machine_guid") {
+            report.take_optional("machine_guid", collector.get_machine_guid())
+        } else {
+            None
+        };
+        #[cfg(target_os = "linux")]
+        let machine_guid = match wsl {
+            Some(_) if options.wsl_host_identity() => {
+                let guid = report.take_optional("machine_guid", collector.get_wsl_host_machine_guid());
+                report.wsl_strategy = Some(if guid.is_some() { WslStrategy::HostMachineGuid } else { WslStrategy::GuestOnly });
+                guid
+            }
+            Some(_) => {
+                report.wsl_strategy = Some(WslStrategy::GuestOnly);
+                None
+            }
+            None => None,
+        };
+        #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+        let machine_guid = None;
+        #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        let machine_id = if probe("machine_id") { crate::machine_identity::read_machine_id() } else { None };
+        // Android 的 ANDROID_ID 只能经 Java API 读取，由调用方提供
+        #[cfg(all(target_os = "android", feature = "android"))]
+        let machine_id = options.android_id().map(str::to_string);
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            all(target_os = "android", feature = "android")
+        )))]
+        let machine_id = None;
+        let system_serial = if probe("system_serial") {
+            Self::screen_optional("system_serial", report.take_optional("system_serial", collector.get_system_serial()))
+        } else {
+            None
+        };
+        #[cfg(not(target_os = "macos"))]
+        let chassis_serial = if probe("chassis_serial") {
+            Self::screen_optional(
+                "chassis_serial",
+                report.take_optional("chassis_serial", collector.get_chassis_serial()),
+            )
+        } else {
+            None
+        };
+        #[cfg(not(target_os = "macos"))]
+        let chassis_type = if probe("chassis_type") {
+            report.take_optional("chassis_type", collector.get_chassis_type())
+        } else {
+            None
+        };
+        #[cfg(not(target_os = "macos"))]
+        let board_asset_tag = if probe("board_asset_tag") {
+            Self::screen_asset_tag(report.take_optional("board_asset_tag", collector.get_board_asset_tag()))
+        } else {
+            None
+        };
+        #[cfg(not(target_os = "macos"))]
+        let chassis_asset_tag = if probe("chassis_asset_tag") {
+            Self::screen_asset_tag(report.take_optional("chassis_asset_tag", collector.get_chassis_asset_tag()))
+        } else {
+            None
+        };
+        #[cfg(target_os = "macos")]
+        let (chassis_serial, chassis_type, board_asset_tag, chassis_asset_tag) = (None, None, None, None);
+        let system_serial = report.screen_optional("system_serial", system_serial);
+        let chassis_serial = report.screen_optional("chassis_serial", chassis_serial);
+        let virtualization = if probe("virtualization") { collector.detect_virtualization() } else { None };
+        if let Some(hypervisor) = &virtualization {
+            debug!("Running under {}", hypervisor);
+        }
+        let machine_identity = if probe("machine_identity") {
+            MachineIdentity::resolve(
+                virtualization.is_some(),
+                &motherboard_serial,
+                system_serial.as_deref(),
+                chassis_serial.as_deref(),
+                &motherboard_uuid,
+                machine_guid.as_deref(),
+            )
+        } else {
+            MachineIdentity::default()
+        };
+
+        // 系统盘取自磁盘枚举中的同一行，各字段来自同一块磁盘；枚举中没有系统盘或缺少某个字段时
+        // 沿用单盘时的读取方式（两者读取的是同一块磁盘，原有唯一码不变）
+        let wants_disks = probe("disks");
+        let enumerated = if wants_disks { collector.get_disks() } else { Ok(Vec::new()) }.unwrap_or_else(|e| {
+            report.record("disks", e);
+            Vec::new()
+        });
+        let root_name = if wants_disks { collector.primary_disk_name() } else { String::new() };
+        let (row, others) = Self::split_root_disk(enumerated, &root_name);
+        let row = row.unwrap_or_default();
+        let mut root_disk = DiskInfo {
+            name: root_name,
+            model: if !probe("disk_model") {
+                String::new()
+            } else if row.model.is_empty() {
+                report.take("disk_model", collector.get_disk_model())
+            } else {
+                row.model
+            },
+            serial: if !probe("disk_serial") {
+                String::new()
+            } else if row.serial.is_empty() {
+                Self::screen_placeholder("disk_serial", report.take("disk_serial", collector.get_disk_serial()))
+            } else {
+                Self::screen_placeholder("disk_serial", row.serial)
+            },
+            firmware: if !probe("disk_firmware") {
+                None
+            } else if row.firmware.is_none() {
+                report.take_optional("disk_firmware", collector.get_disk_firmware())
+            } else {
+                row.firmware
+            },
+            is_root: true,
+        };
+        root_disk.serial = report.screen("disk_serial", root_disk.serial);
+        let others = others
+            .into_iter()
+            .map(|disk| DiskInfo { serial: report.screen("disk_serial", Self::screen_placeholder("disk_serial", disk.serial)), ..disk })
+            .collect();
+        let disks = if wants_disks { Self::with_root_disk(root_disk, others) } else { Vec::new() };
+        #[cfg(target_os = "macos")]
+        if wants_disks {
+            report.external_boot_disk = collector.boot_disk().external;
+        }
+
+        let mut memory_modules: Vec<String> = if probe("memory_modules") { collector.get_memory_modules() } else { Ok(Vec::new()) }
+            .unwrap_or_else(|e| {
+                report.record("memory_modules", e);
+                Vec::new()
+            })
+            .into_iter()
+            .map(|serial| serial.trim().to_string())
+            .filter(|serial| !Self::is_placeholder_memory_serial(serial))
+            .filter_map(|serial| report.screen_optional("memory_modules", Some(serial)))
+            .collect();
+        memory_modules.sort();
+
+        let mut gpu_info = if probe("gpu_info") { collector.get_gpus() } else { Ok(Vec::new()) }.unwrap_or_else(|e| {
+            report.record("gpu_info", e);
+            Vec::new()
+        });
+        gpu_info.sort_by(|a, b| (&a.vendor, &a.model, &a.uuid).cmp(&(&b.vendor, &b.model, &b.uuid)));
+
+        let tpm = if probe("tpm") { collector.get_tpm() } else { None };
+        let root_fs_uuid = if probe("root_fs_uuid") {
+            report.take_optional("root_fs_uuid", collector.get_root_fs_uuid())
+        } else {
+            None
+        };
+
+        // Rosetta 2 下的 CPUID 是转译层模拟的 Intel 处理器，与 arm64 构建一样不记录，两种构建得到相同的输入
+        #[cfg(target_os = "macos")]
+        let translated = collector.is_translated();
+        #[cfg(not(target_os = "macos"))]
+        let translated = false;
+        report.translated = translated;
+        report.architecture = if translated { "aarch64" } else { std::env::consts::ARCH }.to_string();
+        let cpu_identity = if translated { CpuIdentity::default() } else { CpuIdentity::collect() };
+        let brand = if probe("cpu_info") { report.take("cpu_info", collector.get_cpu_info()) } else { String::new() };
+        let cpu = CpuInfo {
+            vendor: Self::cpu_vendor(&cpu_identity, &brand),
+            brand,
+            physical_id: if probe("cpu_physical_id") {
+                report.take("cpu_physical_id", collector.get_cpu_physical_id())
+            } else {
+                String::new()
+            },
+            cores: sys.physical_core_count().unwrap_or_else(|| sys.cpus().len()),
+        };
+        let manufacturer = if probe("motherboard_manufacturer") {
+            Self::screen_optional(
+                "motherboard_manufacturer",
+                report.take_optional("motherboard_manufacturer", collector.get_motherboard_manufacturer()),
+            )
+        } else {
+            None
+        };
+        let product = if probe("motherboard_product_name") {
+            Self::screen_placeholder(
+                "motherboard_product_name",
+                report.take("motherboard_product_name", collector.get_motherboard_product_name()),
+            )
+        } else {
+            String::new()
+        };
+        let board = BoardInfo {
+            alternate_uuid: Self::alternate_smbios_uuid(&motherboard_uuid),
+            serial: motherboard_serial,
+            uuid: motherboard_uuid,
+            manufacturer: report.screen_optional("motherboard_manufacturer", manufacturer),
+            product: report.screen("motherboard_product_name", product),
+            device_tree: Self::is_device_tree_board(),
+        };
+        let bios = BiosInfo {
+            vendor: if probe("bios_vendor") {
+                report.take_optional("bios_vendor", collector.get_bios_vendor())
+            } else {
+                None
+            },
+            version: if probe("bios_version") {
+                report.take_optional("bios_version", collector.get_bios_version())
+            } else {
+                None
+            },
+            release_date: if probe("bios_release_date") {
+                report.take_optional("bios_release_date", collector.get_bios_release_date())
+            } else {
+                None
+            },
+        };
+
+        let info = Self {
+            cpu,
+            board,
+            bios,
+            system_serial,
+            chassis_serial,
+            chassis_type,
+            board_asset_tag,
+            chassis_asset_tag,
+            mac_address: if wsl == Some(WslVersion::Wsl2) || !probe("mac_address") {
+                String::new()
+            } else {
+                // 各平台命令的写法不同（大小写、"-" 分隔），统一为 MacAddress 的规范形式
+                let mac = report.take("mac_address", collector.get_mac_address());
+                mac.parse::<MacAddress>().map_or(mac, |mac| mac.to_string())
+            },
+            os_info: format!("{} {}", sys.name().unwrap_or_default(), 
+                                    sys.os_version().unwrap_or_default()),
+            memory_serial: memory_modules.first().cloned(),
+            memory_modules,
+            cpu_identity,
+            disks,
+            gpu_info,
+            tpm,
+            root_fs_uuid,
+            network_interfaces,
+            virtualization,
+            environment: report.environment,
+            live_boot,
+            machine_identity,
+            machine_guid,
+            machine_id,
+            collection_mode: CollectionMode::Live,
+        };
+        (info, report)
+    }
+
+    // 从挂载在 root 的 Linux 系统镜像中读取：machine-id、udev 规则与 .link 文件中的网卡 MAC、
+    // fstab 中根分区的 UUID 与 os-release；DMI、CPU 与磁盘只能在运行时读取，记为不支持。
+    // Windows 镜像（注册表配置单元）不支持，root 不存在时返回 FileError
+    pub fn collect_offline(root: &Path) -> Result<(Self, CollectionReport), HardwareError> {
+        let image = OfflineRoot::new(root)?;
+        if image.is_windows() {
+            return Err(HardwareError::UnsupportedSystem(format!(
+                "{} is a Windows image; reading the registry hives offline is not supported",
+                image.root().display()
+            )));
+        }
+        let mut report = CollectionReport::default();
+        for field in Self::OFFLINE_UNSUPPORTED_FIELDS {
+            report.record(field, HardwareError::UnsupportedSystem("not recoverable from an offline image".to_string()));
+        }
+
+        let mut network_interfaces: Vec<NetworkInfo> = image
+            .interfaces()
+            .into_iter()
+            .map(|(name, mac_address)| NetworkInfo {
+                interface_type: Self::interface_type_from_name(&name),
+                name,
+                mac_address,
+                current_mac_address: mac_address,
+                is_up: false,
+                is_primary: false,
+            })
+            .collect();
+        network_interfaces.sort_by(|a, b| (&a.mac_address, &a.name).cmp(&(&b.mac_address, &b.name)));
+        network_interfaces.dedup_by(|a, b| a.mac_address == b.mac_address);
+        Self::mark_primary(&mut network_interfaces, false);
+        if network_interfaces.is_empty() {
+            report.record(
+                "network_interfaces",
+                HardwareError::FileError("no MAC address in the udev rules or systemd .link files".to_string()),
+            );
+        }
+        let mac_address = network_interfaces
+            .iter()
+            .find(|interface| interface.is_primary)
+            .map_or_else(String::new, |interface| interface.mac_address.to_string());
+        let machine_id = report.take_optional("machine_id", image.machine_id());
+        let root_fs_uuid = report.take_optional("root_fs_uuid", image.root_fs_uuid());
+
+        let info = Self {
+            cpu: CpuInfo::default(),
+            board: BoardInfo::default(),
+            bios: BiosInfo::default(),
+            system_serial: None,
+            chassis_serial: None,
+            chassis_type: None,
+            board_asset_tag: None,
+            chassis_asset_tag: None,
+            mac_address,
+            os_info: image.os_info().unwrap_or_default(),
+            memory_serial: None,
+            memory_modules: Vec::new(),
+            cpu_identity: CpuIdentity::default(),
+            disks: Vec::new(),
+            gpu_info: Vec::new(),
+            tpm: None,
+            root_fs_uuid,
+            network_interfaces,
+            virtualization: None,
+            environment: Environment::default(),
+            live_boot: false,
+            machine_identity: MachineIdentity::from_machine_id(machine_id.as_deref()),
+            machine_guid: None,
+            machine_id,
+            collection_mode: CollectionMode::Offline,
+        };
+        Ok((info, report))
+    }
+
+    // 字段访问器
+    pub fn cpu(&self) -> &CpuInfo {
+        &self.cpu
+    }
+
+    pub fn board(&self) -> &BoardInfo {
+        &self.board
+    }
+
+    pub fn bios(&self) -> &BiosInfo {
+        &self.bios
+    }
+
+    pub fn cpu_info(&self) -> &str {
+        &self.cpu.brand
+    }
+
+    pub fn motherboard_serial(&self) -> &str {
+        &self.board.serial
+    }
+
+    // 系统盘的序列号，与改为多块磁盘之前的字段相同
+    pub fn disk_serial(&self) -> &str {
+        self.root_disk().map_or("", DiskInfo::serial)
+    }
+
+    pub fn mac_address(&self) -> &str {
+        &self.mac_address
+    }
+
+    pub fn os_info(&self) -> &str {
+        &self.os_info
+    }
+
+    pub fn memory_serial(&self) -> Option<&str> {
+        self.memory_serial.as_deref()
+    }
+
+    pub fn memory_modules(&self) -> &[String] {
+        &self.memory_modules
+    }
+
+    pub fn bios_version(&self) -> Option<&str> {
+        self.bios.version.as_deref()
+    }
+
+    pub fn cpu_physical_id(&self) -> &str {
+        &self.cpu.physical_id
+    }
+
+    pub fn disk_model(&self) -> &str {
+        self.root_disk().map_or("", DiskInfo::model)
+    }
+
+    pub fn disk_firmware(&self) -> Option<&str> {
+        self.root_disk().and_then(DiskInfo::firmware)
+    }
+
+    pub fn disks(&self) -> &[DiskInfo] {
+        &self.disks
+    }
+
+    pub fn root_disk(&self) -> Option<&DiskInfo> {
+        self.disks.iter().find(|disk| disk.is_root)
+    }
+
+    pub fn gpu_info(&self) -> &[GpuInfo] {
+        &self.gpu_info
+    }
+
+    pub fn tpm(&self) -> Option<&TpmInfo> {
+        self.tpm.as_ref()
+    }
+
+    pub fn root_fs_uuid(&self) -> Option<&str> {
+        self.root_fs_uuid.as_deref()
+    }
+
+    pub fn motherboard_uuid(&self) -> &str {
+        &self.board.uuid
+    }
+
+    pub fn motherboard_manufacturer(&self) -> Option<&str> {
+        self.board.manufacturer.as_deref()
+    }
+
+    pub fn motherboard_product_name(&self) -> &str {
+        &self.board.product
+    }
+
+    pub fn bios_vendor(&self) -> Option<&str> {
+        self.bios.vendor.as_deref()
+    }
+
+    pub fn system_serial(&self) -> Option<&str> {
+        self.system_serial.as_deref()
+    }
+
+    pub fn chassis_serial(&self) -> Option<&str> {
+        self.chassis_serial.as_deref()
+    }
+
+    pub fn chassis_type(&self) -> Option<&str> {
+        self.chassis_type.as_deref()
+    }
+
+    pub fn board_asset_tag(&self) -> Option<&str> {
+        self.board_asset_tag.as_deref()
+    }
+
+    pub fn chassis_asset_tag(&self) -> Option<&str> {
+        self.chassis_asset_tag.as_deref()
+    }
+
+    pub fn bios_release_date(&self) -> Option<&str> {
+        self.bios.release_date.as_deref()
+    }
+
+    pub fn cpu_identity(&self) -> &CpuIdentity {
+        &self.cpu_identity
+    }
+
+    pub fn network_interfaces(&self) -> &[NetworkInfo] {
+        &self.network_interfaces
+    }
+
+    pub fn virtualization(&self) -> Option<&str> {
+        self.virtualization.as_deref()
+    }
+
+    pub fn environment(&self) -> Environment {
+        self.environment
+    }
+
+    pub fn live_boot(&self) -> bool {
+        self.live_boot
+    }
+
+    pub fn machine_identity(&self) -> &MachineIdentity {
+        &self.machine_identity
+    }
+
+    pub fn machine_guid(&self) -> Option<&str> {
+        self.machine_guid.as_deref()
+    }
+
+    pub fn machine_id(&self) -> Option<&str> {
+        self.machine_id.as_deref()
+    }
+
+    pub fn collection_mode(&self) -> CollectionMode {
+        self.collection_mode
+    }
+
+    // 按需采集时字段是否需要读取：只读取 components 哈希（及 MachineIdentity 回退链）用到的字段，
+    // 架构与运行环境始终检测；components 为 None 时读取全部字段
+    fn probes_field(components: Option<ComponentSet>, field: &str) -> bool {
+        let Some(components) = components else {
+            return true;
+        };
+        let any = |wanted: &[Component]| wanted.iter().any(|component| components.contains(*component));
+        match field {
+            "motherboard_serial" | "machine_guid" => any(&[Component::MotherboardSerial, Component::MachineIdentity]),
+            "motherboard_uuid" => any(&[Component::MotherboardUuid, Component::MachineIdentity]),
+            "system_serial" | "chassis_serial" | "virtualization" | "machine_identity" => {
+                any(&[Component::MachineIdentity])
+            }
+            "network_interfaces" => any(&[Component::MacAddress]),
+            // 系统盘的型号与序列号也从磁盘列表中读取
+            "disks" => any(&[Component::DiskModel, Component::DiskSerial]),
+            "disk_model" => any(&[Component::DiskModel]),
+            "disk_serial" => any(&[Component::DiskSerial]),
+            "memory_modules" => any(&[Component::MemorySerial]),
+            "bios_version" => any(&[Component::BiosVersion]),
+            "cpu_physical_id" => any(&[Component::CpuPhysicalId]),
+            "motherboard_product_name" => any(&[Component::MotherboardProductName]),
+            "gpu_info" => any(&[Component::Gpu]),
+            "tpm" => any(&[Component::TpmEkHash]),
+            "machine_id" => any(&[Component::MachineId]),
+            "root_fs_uuid" => any(&[Component::RootFsUuid]),
+            "architecture" | "environment" => true,
+            _ => false,
+        }
+    }
+
+    // 主板 UUID 来自 SMBIOS 的系统上，其另一种字节序的读法；与原值相同（对称的 UUID）时为 None
+    fn alternate_smbios_uuid(uuid: &str) -> Option<String> {
+        let smbios = cfg!(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris"
+        ));
+        if !smbios || uuid.is_empty() {
+            return None;
+        }
+        normalize::swap_uuid_byte_order(uuid).filter(|alternate| !alternate.eq_ignore_ascii_case(uuid))
+    }
+
+    // 从枚举的磁盘中取出名为 root 的一行（系统盘），其余磁盘的 is_root 均为 false
+    fn split_root_disk(disks: Vec<DiskInfo>, root: &str) -> (Option<DiskInfo>, Vec<DiskInfo>) {
+        let (mut row, others): (Vec<DiskInfo>, Vec<DiskInfo>) = disks.into_iter().partition(|disk| disk.name == root);
+        (row.pop(), others.into_iter().map(|disk| DiskInfo { is_root: false, ..disk }).collect())
+    }
+
+    // 系统盘与其余磁盘合并后按序列号排序；序列号与系统盘或之前的磁盘相同的（同一块磁盘以不同名称枚举）
+    // 只保留一项
+    fn with_root_disk(root: DiskInfo, others: Vec<DiskInfo>) -> Vec<DiskInfo> {
+        let mut seen: std::collections::HashSet<String> =
+            std::iter::once(root.serial.clone()).filter(|serial| !serial.is_empty()).collect();
+        let mut disks: Vec<DiskInfo> = others
+            .into_iter()
+            .filter(|disk| disk.serial.is_empty() || seen.insert(disk.serial.clone()))
+            .chain(std::iter::once(root))
+            .collect();
+        disks.sort_by(|a, b| (&a.serial, &a.name).cmp(&(&b.serial, &b.name)));
+        disks
+    }
+
+    // 已知被整批机器共用的值按缺失处理，避免大量机器生成相同唯一码
+    fn screen_duplicate(field: &str, value: String) -> String {
+        if denylist::is_known_duplicate(&value) {
+            warn!("{} \"{}\" is a known duplicated value; treating it as missing", field, value);
+            String::new()
+        } else {
+            Self::screen_placeholder(field, value)
+        }
+    }
+
+    // 厂商占位值（如 "To be filled by O.E.M."）不是真实标识，按缺失处理
+    fn screen_placeholder(field: &str, value: String) -> String {
+        if denylist::is_placeholder_serial(&value) {
+            debug!("{} \"{}\" is a vendor placeholder; treating it as missing", field, value);
+            String::new()
+        } else {
+            value
+        }
+    }
+
+    // CPUID/MIDR 给出的厂商；没有时（如 Apple Silicon）取品牌字符串的第一个词
+    fn cpu_vendor(identity: &CpuIdentity, brand: &str) -> String {
+        if !identity.vendor().is_empty() {
+            return identity.vendor().to_string();
+        }
+        brand.split_whitespace().next().unwrap_or_default().trim_end_matches("(R)").to_string()
+    }
+
+    // 空插槽与未烧录序列号的内存条：通用占位值、全 0（"00000000"）以及 "SerNum0" 这类按插槽编号的值
+    fn is_placeholder_memory_serial(serial: &str) -> bool {
+        let lower = serial.to_lowercase();
+        let numbered = |prefix: &str| {
+            lower
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()))
+        };
+        serial.is_empty()
+            || denylist::is_placeholder(serial)
+            || serial.chars().all(|c| c == '0')
+            || numbered("sernum")
+            || ["no dimm", "empty", "unknown"].contains(&lower.as_str())
+    }
+
+    // 未设置资产标签时固件填写的占位值（"Asset Tag"、"No Asset Tag"、"Asset-1234567890" 等）按未提供处理
+    #[cfg(not(target_os = "macos"))]
+    fn screen_asset_tag(tag: Option<String>) -> Option<String> {
+        tag.map(|tag| tag.trim().to_string()).filter(|tag| {
+            let lower = tag.to_lowercase();
+            !tag.is_empty()
+                && !denylist::is_placeholder(tag)
+                && !["asset tag", "asset-1234567890"].contains(&lower.as_str())
+                && !lower.starts_with("no asset")
+        })
+    }
+
+    fn screen_optional(field: &str, value: Option<String>) -> Option<String> {
+        value.filter(|value| {
+            let placeholder = denylist::is_placeholder_serial(value);
+            if placeholder {
+                debug!("{} \"{}\" is a vendor placeholder; treating it as missing", field, value);
+            }
+            !placeholder
+        })
+    }
+
+    // 按网卡描述识别虚拟网卡（Windows 上虚拟网卡的类型同样报告为以太网）
+    #[cfg(target_os = "windows")]
+    fn is_virtual_adapter(description: &str) -> bool {
+        const MARKERS: &[&str] = &[
+            "hyper-v", "vmware", "virtualbox", "virtual", "vpn", "tap-", "wireguard", "loopback", "bluetooth",
+            "wan miniport", "npcap", "docker", "zerotier",
+        ];
+        let description = description.to_lowercase();
+        MARKERS.iter().any(|marker| description.contains(marker))
+    }
+
+    // 按名称识别的虚拟接口：Docker 网桥与 veth 对（MAC 随守护进程重启而变化）、libvirt 网桥、TAP/TUN 与
+    // WireGuard、ZeroTier 等 VPN，以及 macOS 的网桥、utun 与 AWDL/低延迟 Wi-Fi 接口
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    pub(crate) fn is_virtual_interface(name: &str) -> bool {
+        const PREFIXES: &[&str] = &[
+            "docker", "veth", "br-", "virbr", "tap", "tun", "wg", "zt", "bridge", "utun", "awdl", "llw",
+        ];
+        PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+    }
+
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "illumos",
+        target_os = "solaris",
+        all(target_os = "android", feature = "android")
+    )))]
+    fn is_primary_interface(name: &str) -> bool {
+        matches!(name, "en0" | "eth0" | "enp0s1")
+    }
+
+    // 除主网卡外一并保留的物理网卡：Linux 上为有 /sys/class/net/<名称>/device 的接口（排除网桥、VPN、veth）；
+    // 其他系统上 sysinfo 无法区分，只保留主网卡
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "illumos",
+        target_os = "solaris",
+        all(target_os = "android", feature = "android")
+    )))]
+    pub(crate) fn is_physical_interface(name: &str) -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            linux::SysRoot::default().is_physical_interface(name)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = name;
+            false
+        }
+    }
+
+    // 保留全部物理网卡，按 MAC、名称排序后再标记主网卡：sysinfo 的网卡来自 HashMap，遍历顺序每次运行都可能不同
+    fn order_interfaces(interfaces: &mut [NetworkInfo], keep_all: bool) {
+        interfaces.sort_by(|a, b| (&a.mac_address, &a.name).cmp(&(&b.mac_address, &b.name)));
+        Self::mark_primary(interfaces, keep_all);
+    }
+
+    // 已排序的网卡中只保留一块主网卡（排序后的第一块，与只保留一块网卡时的结果一致）；
+    // 没有网卡标为主网卡（Windows、macOS 内置端口、BSD 等按类型筛选的平台）时取第一块厂商分配的地址，
+    // 跳过随机化或虚拟网卡使用的本地管理地址，都是本地管理地址时取第一块。
+    // 聚合接口的全部下层网卡都是主网卡
+    fn mark_primary(interfaces: &mut [NetworkInfo], keep_all: bool) {
+        let mut found = false;
+        for interface in interfaces.iter_mut() {
+            if interface.is_primary && found && !keep_all {
+                interface.is_primary = false;
+            }
+            found |= interface.is_primary;
+        }
+        if !found {
+            let universal = interfaces.iter().position(|interface| interface.mac_address.is_universal());
+            if let Some(first) = interfaces.get_mut(universal.unwrap_or(0)) {
+                first.is_primary = true;
+            }
+        }
+    }
+
+    // sysinfo 只提供当前地址；Linux 上另经 ethtool 读取永久地址
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "illumos",
+        target_os = "solaris",
+        all(target_os = "android", feature = "android")
+    )))]
+    fn permanent_mac(name: &str) -> Option<MacAddress> {
+        #[cfg(target_os = "linux")]
+        {
+            linux::permanent_mac(name)
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = name;
+            None
+        }
+    }
+
+    // Linux 按内核报告的链路类型区分（见 linux::SysRoot::interface_type），读不到 sysfs 时及其他系统上按名称前缀判断
+    #[cfg(not(any(target_os = "windows", all(target_os = "android", feature = "android"))))]
+    fn detect_interface_type(name: &str) -> NetworkInterfaceType {
+        #[cfg(target_os = "linux")]
+        if let Some(kind) = linux::SysRoot::default().interface_type(name) {
+            return kind;
+        }
+        Self::interface_type_from_name(name)
+    }
+
+    pub(crate) fn interface_type_from_name(name: &str) -> NetworkInterfaceType {
+        if name.starts_with("en") || name.starts_with("eth") {
+            NetworkInterfaceType::Ethernet
+        } else if name.starts_with("wl") || name.starts_with("wifi") {
+            NetworkInterfaceType::WiFi
+        } else {
+            NetworkInterfaceType::Unknown
+        }
+    }
+
+    #[deprecated(note = "use generate_unique_code_with(HashAlgorithm::Md5) instead")]
+    pub fn generate_unique_code(&self) -> Result<String, HardwareError> {
+        self.generate_unique_code_with(HashAlgorithm::Md5)
+    }
+
+    pub fn generate_unique_code_with(&self, algorithm: HashAlgorithm) -> Result<String, HardwareError> {
+        self.generate_unique_code_formatted(algorithm, OutputFormat::Grouped, None)
+    }
+
+    pub fn generate_unique_code_formatted(
+        &self,
+        algorithm: HashAlgorithm,
+        format: OutputFormat,
+        length: Option<usize>,
+    ) -> Result<String, HardwareError> {
+        Ok(format.encode(&self.unique_digest(algorithm)?, length))
+    }
+
+    // v1 无前缀（兼容旧唯一码），v2 带 "v2:" 前缀
+    pub fn generate_unique_code_versioned(&self, version: FingerprintVersion) -> Result<String, HardwareError> {
+        FingerprintBuilder::new().version(version).hash(self)
+    }
+
+    // 按策略放宽关键字段要求，同时返回实际参与哈希的组件
+    pub fn generate_unique_code_with_policy(
+        &self,
+        policy: CriticalFieldPolicy,
+    ) -> Result<(String, ComponentSet), HardwareError> {
+        let builder = FingerprintBuilder::new().policy(policy);
+        let code = builder.hash(self)?;
+        Ok((code, builder.used_components(self)))
+    }
+
+    // 仅由回退链得到的机器身份生成唯一码，主板序列号/UUID 缺失时可用；来源一并返回
+    pub fn generate_identity_code(&self) -> Result<(String, IdentitySource), HardwareError> {
+        let code = FingerprintBuilder::new()
+            .components(ComponentSet::empty().with(Component::MachineIdentity))
+            .policy(CriticalFieldPolicy::RequireAny(1))
+            .hash(self)?;
+        Ok((code, self.machine_identity.source()))
+    }
+
+    // v2 方案的规范哈希输入（默认组件集合），替代 v1 的拼接与 0xFF/":" 分隔
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        FingerprintBuilder::new().version(FingerprintVersion::V2).hash_input(self)
+    }
+
+    // 按唯一码的版本前缀重新计算并比较
+    pub fn verify(&self, code: &str) -> Result<bool, HardwareError> {
+        FingerprintBuilder::new().verify(self, code)
+    }
+
+    // codes 为 FingerprintBuilder::disk_codes 列出的唯一码（任一备用磁盘作为系统盘时的唯一码均可）
+    pub fn verify_any_alternate<S: AsRef<str>>(&self, codes: &[S]) -> Result<bool, HardwareError> {
+        FingerprintBuilder::new().verify_any_alternate(self, codes)
+    }
+
+    // 使用 HMAC-SHA256 按应用密钥生成唯一码，不同应用得到不同的标识
+    pub fn generate_unique_code_keyed(&self, key: &[u8]) -> Result<String, HardwareError> {
+        Ok(OutputFormat::Grouped.encode(&self.keyed_digest(key)?, None))
+    }
+
+    // 以 RFC 4122 v5 UUID 形式输出机器标识，输入与唯一码相同（见 FingerprintBuilder::hash_input）
+    pub fn as_uuid(&self, namespace: Uuid) -> Result<Uuid, HardwareError> {
+        FingerprintBuilder::new().uuid(self, namespace)
+    }
+
+    // 完整摘要，供调用方自行选择输出编码
+    pub fn unique_digest(&self, algorithm: HashAlgorithm) -> Result<Vec<u8>, HardwareError> {
+        FingerprintBuilder::new().algorithm(algorithm).digest(self)
+    }
+
+    pub fn keyed_digest(&self, key: &[u8]) -> Result<Vec<u8>, HardwareError> {
+        FingerprintBuilder::new().key(key).digest(self)
+    }
+
+    // Live/PXE 启动检测
+    fn detect_live_boot() -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            let mounts = Self::read_sys_file("/proc/mounts").unwrap_or_default();
+            let cmdline = Self::read_sys_file("/proc/cmdline").unwrap_or_default();
+            Self::is_live_root(&mounts, &cmdline)
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            false
+        }
+    }
+
+    // 没有 DMI 而有设备树
+    fn is_device_tree_board() -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            !Path::new("/sys/class/dmi/id").exists() && Path::new("/sys/firmware/devicetree/base").exists()
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            false
+        }
+    }
+
+    // 根文件系统为 squashfs/overlay/aufs 或 loop 设备，并且内核参数指定 live 启动；
+    // 容器与 snap 等的根文件系统同样可能是 overlay/loop，只看文件系统会误判
+    #[cfg(target_os = "linux")]
+    fn is_live_root(mounts: &str, cmdline: &str) -> bool {
+        let live_root = mounts
+            .lines()
+            .rev()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                Some((fields.next()?, fields.next()?, fields.next()?))
+            })
+            .find(|(_, mount_point, _)| *mount_point == "/")
+            .map(|(device, _, fs_type)| {
+                matches!(fs_type, "squashfs" | "overlay" | "aufs") || device.starts_with("/dev/loop")
+            })
+            .unwrap_or(false);
+
+        let live_cmdline = cmdline.split_whitespace().any(|arg| {
+            matches!(arg, "boot=live" | "boot=casper" | "rd.live.image")
+                || arg.starts_with("root=live:")
+        });
+
+        live_root && live_cmdline
+    }
+
+    // 当前进程是否以管理员/root 身份运行；只用于诊断，唯一码不依赖于此
+    // （需要提升权限才能读取的内存序列号不在默认字段中）
+    pub fn is_elevated() -> bool {
+        #[cfg(target_os = "windows")]
+        {
+            use winapi::shared::minwindef::{DWORD, FALSE};
+            use winapi::um::handleapi::CloseHandle;
+            use winapi::um::processthreadsapi::{GetCurrentProcess, OpenProcessToken};
+            use winapi::um::securitybaseapi::GetTokenInformation;
+            use winapi::um::winnt::{TokenElevation, HANDLE, TOKEN_ELEVATION, TOKEN_QUERY};
+
+            let mut token: HANDLE = std::ptr::null_mut();
+            let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+            let mut size: DWORD = 0;
+            // SAFETY: elevation 的大小与传入的长度一致，token 打开成功后关闭
+            unsafe {
+                if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == FALSE {
+                    return false;
+                }
+                let ok = GetTokenInformation(
+                    token,
+                    TokenElevation,
+                    (&mut elevation as *mut TOKEN_ELEVATION).cast(),
+                    std::mem::size_of::<TOKEN_ELEVATION>() as DWORD,
+                    &mut size,
+                );
+                CloseHandle(token);
+                ok != FALSE && elevation.TokenIsElevated != 0
+            }
+        }
+
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+        ))]
+        {
+            // SAFETY: geteuid 没有前置条件
+            unsafe { libc::geteuid() == 0 }
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+        )))]
+        {
+            false
+        }
+    }
+
+    // 读取 sysfs/procfs 文件，拒绝经符号链接解析到挂载点之外的路径（见 linux::SysRoot）
+    #[cfg(target_os = "linux")]
+    pub(crate) fn read_sys_file(path: &str) -> Result<String, HardwareError> {
+        linux::SysRoot::default().read_file(path)
+    }
+
+    // 列出 sysfs 目录下的条目名称（按名称排序，与 readdir 的顺序无关）
+    #[cfg(target_os = "linux")]
+    pub(crate) fn list_sys_dir(path: &str) -> Result<Vec<String>, HardwareError> {
+        linux::SysRoot::default().list_dir(path)
+    }
+
+    pub(crate) fn file_error(path: &str, e: std::io::Error) -> HardwareError {
+        match e.kind() {
+            std::io::ErrorKind::PermissionDenied => HardwareError::PermissionDenied(path.to_string()),
+            _ => HardwareError::FileError(format!("{}: {}", path, e)),
+        }
+    }
+}
+
+// 按顺序尝试的读取方式之一
+#[cfg(target_os = "windows")]
+type Probe<'a> = &'a dyn Fn() -> Result<String, HardwareError>;
+
+// 命令行（程序, 参数）及其执行结果，用于 Collector::prefetch
+pub(crate) type CommandLine = (String, Vec<String>);
+pub(crate) type CommandResult = Result<std::process::Output, HardwareError>;
+
+// 同时执行的命令数上限，避免一次启动十几个 wmic 进程
+pub(crate) const PREFETCH_CONCURRENCY: usize = 6;
+// 预先执行的命令及用到其输出的字段名，见 Collector::probe_commands
+// Win32_DiskDrive 按行查询的属性，Index 即 \\.\PhysicalDriveN 的编号
+#[cfg(target_os = "windows")]
+const DISK_DRIVE_PROPERTIES: &str = "FirmwareRevision,Index,Model,SerialNumber";
+
+type ProbeCommand = (&'static [&'static str], ProbeCommandLine);
+pub(crate) type ProbeCommandLine = (&'static str, Vec<String>);
+
+// 一次采集过程内共享的上下文；Android 与没有专门实现的系统上不执行任何命令
+#[cfg_attr(not(any(
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "illumos",
+    target_os = "solaris"
+)), allow(dead_code))]
+struct Collector<'a> {
+    runner: &'a dyn CommandRunner,
+    // prefetch 并发执行的命令输出，按命令行存放；run_text 取用一次后移除，之后的同一命令照常执行
+    prefetched: std::cell::RefCell<std::collections::HashMap<CommandLine, CommandResult>>,
+    // 进程内 WMI 连接，首次查询时建立，之后所有类共用；建立失败则为 None，改用命令方式
+    #[cfg(all(target_os = "windows", feature = "wmi-native"))]
+    wmi: std::cell::OnceCell<Option<wmi::WMIConnection>>,
+    // Win32_DiskDrive 的全部行（见 disk_drive）与系统盘编号，首次需要时查询一次
+    #[cfg(target_os = "windows")]
+    disk_drives: std::cell::OnceCell<Result<Vec<parsers::WmiRecord>, HardwareError>>,
+    #[cfg(target_os = "windows")]
+    system_disk: std::cell::OnceCell<Option<u32>>,
+    // 固件 SMBIOS 表（Windows 为 GetSystemFirmwareTable，Linux 为 /sys/firmware/dmi/tables），首次需要时读取一次
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    smbios: std::cell::OnceCell<Option<SmbiosTable>>,
+    #[cfg(target_os = "macos")]
+    profiler: MacProfilerCache,
+    // ioreg -rd1 -c IOPlatformExpertDevice 的输出，首次需要时执行一次；失败为 None
+    #[cfg(target_os = "macos")]
+    platform_expert: std::cell::OnceCell<Option<String>>,
+    // 启动卷所在的物理磁盘，首次需要时解析一次
+    #[cfg(target_os = "macos")]
+    boot_disk: std::cell::OnceCell<MacBootDisk>,
+    #[cfg(target_os = "macos")]
+    translated: std::cell::OnceCell<bool>,
+    // /proc/cpuinfo 与 /sys/class/dmi/id 各读取一次，CPU 型号、物理 ID 与各 DMI 字段都从缓存中解析
+    #[cfg(target_os = "linux")]
+    cpuinfo: std::cell::OnceCell<String>,
+    #[cfg(target_os = "linux")]
+    dmi: std::cell::OnceCell<std::collections::HashMap<String, String>>,
+    // 采集时读取的 sysfs/procfs（DMI、SMBIOS 表、cpuinfo、设备树、磁盘与网卡），测试中替换为模拟的目录树
+    #[cfg(target_os = "linux")]
+    sys: linux::SysRoot,
+    // 创建时检测的运行环境，测试中替换为模拟的容器或 WSL
+    environment: Environment,
+}
+
+// system_profiler 每种数据类型只执行一次（每次需 1 秒以上），各字段从缓存的输出中解析；
+// 失败也会缓存，避免同一命令反复等待超时
+#[cfg(any(test, target_os = "macos"))]
+#[derive(Default)]
+struct MacProfilerCache {
+    outputs: std::cell::RefCell<std::collections::HashMap<&'static str, Result<String, String>>>,
+}
+
+#[cfg(any(test, target_os = "macos"))]
+impl MacProfilerCache {
+    fn get(
+        &self,
+        data_type: &'static str,
+        run: impl FnOnce() -> Result<String, HardwareError>,
+    ) -> Result<String, HardwareError> {
+        let cached = self.outputs.borrow().get(data_type).cloned();
+        let output = match cached {
+            Some(output) => output,
+            None => {
+                let output = run().map_err(|e| e.to_string());
+                self.outputs.borrow_mut().insert(data_type, output.clone());
+                output
+            }
+        };
+        output.map_err(|e| HardwareError::CommandError(format!("system_profiler {}: {}", data_type, e)))
+    }
+}
+
+// 启动卷所在的物理磁盘；从外接（USB/雷雳）磁盘启动时 external 为 true
+#[cfg(target_os = "macos")]
+struct MacBootDisk {
+    name: String,
+    external: bool,
+}
+
+#[cfg_attr(not(any(
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "illumos",
+    target_os = "solaris"
+)), allow(dead_code))]
+impl<'a> Collector<'a> {
+    fn new(runner: &'a dyn CommandRunner) -> Self {
+        Self {
+            runner,
+            prefetched: std::cell::RefCell::default(),
+            #[cfg(all(target_os = "windows", feature = "wmi-native"))]
+            wmi: std::cell::OnceCell::new(),
+            #[cfg(target_os = "windows")]
+            disk_drives: std::cell::OnceCell::new(),
+            #[cfg(target_os = "windows")]
+            system_disk: std::cell::OnceCell::new(),
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            smbios: std::cell::OnceCell::new(),
+            #[cfg(target_os = "macos")]
+            profiler: MacProfilerCache::default(),
+            #[cfg(target_os = "macos")]
+            platform_expert: std::cell::OnceCell::new(),
+            #[cfg(target_os = "macos")]
+            boot_disk: std::cell::OnceCell::new(),
+            #[cfg(target_os = "macos")]
+            translated: std::cell::OnceCell::new(),
+            #[cfg(target_os = "linux")]
+            cpuinfo: std::cell::OnceCell::new(),
+            #[cfg(target_os = "linux")]
+            dmi: std::cell::OnceCell::new(),
+            #[cfg(target_os = "linux")]
+            sys: linux::SysRoot::default(),
+            environment: Environment::detect(),
+        }
+    }
+
+    // 执行命令并返回 stdout 文本，解析交给 parsers 中的纯函数
+    fn run_text(&self, program: &str, args: &[&str]) -> Result<String, HardwareError> {
+        let key = (program.to_string(), args.iter().map(|arg| arg.to_string()).collect());
+        let prefetched = self.prefetched.borrow_mut().remove(&key);
+        let output = match prefetched {
+            Some(output) => output?,
+            None => self.runner.run(program, args)?,
+        };
+        Ok(parsers::decode_output(&output.stdout, Self::console_codepage()))
+    }
+
+    // 以少量线程并发执行 commands，结果存入 prefetched；只共享 runner（Sync），Collector 的缓存不跨线程
+    fn prefetch(&self, commands: &[ProbeCommandLine], progress: &ProgressTracker) {
+        if commands.is_empty() {
+            return;
+        }
+        let runner = self.runner;
+        let next = std::sync::atomic::AtomicUsize::new(0);
+        let results: Vec<_> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..commands.len().min(PREFETCH_CONCURRENCY))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        while let Some((program, args)) =
+                            commands.get(next.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+                        {
+                            let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+                            let command = command_runner::command_line(program, &arg_refs);
+                            progress.started(&command);
+                            let started = std::time::Instant::now();
+                            let result = runner.run(program, &arg_refs);
+                            progress.finished(&command, started.elapsed(), &result);
+                            done.push(((program.to_string(), args.clone()), result));
+                        }
+                        done
+                    })
+                })
+                .collect();
+            workers.into_iter().flat_map(|worker| worker.join().unwrap_or_default()).collect()
+        });
+        self.prefetched.borrow_mut().extend(results);
+    }
+
+    // 采集时每次都会执行、彼此不依赖输出的命令；依赖其他命令结果的（macOS 启动盘的 diskutil info）
+    // 与只在回退时执行的命令不在其中。每条命令附带用到其输出的字段，按需采集时跳过不需要的命令
+    fn probe_commands() -> Vec<ProbeCommand> {
+        #[cfg(target_os = "windows")]
+        {
+            // 启用 wmi-native 时在进程内查询 WMI，不需要预先执行命令
+            if cfg!(feature = "wmi-native") {
+                return Vec::new();
+            }
+            const QUERIES: &[(&str, &str, &str)] = &[
+                ("motherboard_serial", "Win32_BaseBoard", "SerialNumber"),
+                ("motherboard_manufacturer", "Win32_BaseBoard", "Manufacturer"),
+                ("motherboard_product_name", "Win32_BaseBoard", "Product"),
+                ("motherboard_uuid", "Win32_ComputerSystemProduct", "UUID"),
+                ("system_serial", "Win32_ComputerSystemProduct", "IdentifyingNumber"),
+                ("virtualization", "Win32_ComputerSystem", "Manufacturer"),
+                ("virtualization", "Win32_ComputerSystem", "Model"),
+                ("chassis_serial", "Win32_SystemEnclosure", "SerialNumber"),
+                ("chassis_asset_tag", "Win32_SystemEnclosure", "SMBIOSAssetTag"),
+                ("chassis_type", "Win32_SystemEnclosure", "ChassisTypes"),
+                ("cpu_info", "Win32_Processor", "Name"),
+                ("cpu_physical_id", "Win32_Processor", "ProcessorId"),
+                ("bios_vendor", "Win32_BIOS", "Manufacturer"),
+                ("bios_version", "Win32_BIOS", "Version"),
+                ("bios_release_date", "Win32_BIOS", "ReleaseDate"),
+                ("memory_modules", "Win32_PhysicalMemory", "SerialNumber"),
+                ("gpu_info", "Win32_VideoController", "Name"),
+            ];
+            const DISK: &[&str] = &["disks", "disk_model", "disk_serial", "disk_firmware"];
+            // 与 wmi_rows、wmi_records 首先尝试的命令一致
+            let command = |class: &str, get_args: &[&str]| {
+                if cfg!(feature = "powershell-cim") {
+                    Self::cim_command(class, get_args[0])
+                } else {
+                    Self::wmic_command(class, get_args)
+                }
+            };
+            QUERIES
+                .iter()
+                .map(|(field, class, property)| (std::slice::from_ref(field), command(class, &[property])))
+                .chain(std::iter::once((DISK, command("Win32_DiskDrive", &[DISK_DRIVE_PROPERTIES, "/value"]))))
+                .collect()
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            const NETWORK: &[&str] = &["network_interfaces", "mac_address"];
+            const DISK: &[&str] = &["disks", "disk_model", "disk_serial", "disk_firmware"];
+            let commands: &[(&'static [&'static str], &'static str, &[&str])] = &[
+                (
+                    &["motherboard_serial", "motherboard_uuid", "system_serial", "motherboard_product_name", "bios_version"],
+                    "system_profiler",
+                    &["SPHardwareDataType"],
+                ),
+                (&["memory_modules"], "system_profiler", &["SPMemoryDataType"]),
+                (&["gpu_info"], "system_profiler", &["SPDisplaysDataType"]),
+                (&["disk_firmware"], "system_profiler", &["SPNVMeDataType"]),
+                (
+                    &["motherboard_serial", "motherboard_uuid", "system_serial"],
+                    "ioreg",
+                    &["-rd1", "-c", "IOPlatformExpertDevice"],
+                ),
+                (NETWORK, "ioreg", &["-r", "-c", "IOEthernetInterface"]),
+                (NETWORK, "networksetup", &["-listallhardwareports"]),
+                (NETWORK, "ifconfig", &[]),
+                (DISK, "diskutil", &["info", "-plist", "/"]),
+                (DISK, "diskutil", &["list", "physical"]),
+                (&["architecture"], "sysctl", &["-n", "sysctl.proc_translated"]),
+            ];
+            commands
+                .iter()
+                .map(|(fields, program, args)| (*fields, (*program, args.iter().map(|arg| arg.to_string()).collect())))
+                .collect()
+        }
+
+        // 其他系统的字段多来自文件与系统调用，命令很少且很快
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        {
+            Vec::new()
+        }
+    }
+
+    // 子进程输出使用的代码页：有控制台时为控制台输出代码页，否则为 OEM 代码页
+    #[cfg(target_os = "windows")]
+    fn console_codepage() -> Option<u32> {
+        // SAFETY: 两个函数都没有参数，只读取进程的代码页设置
+        let codepage = unsafe {
+            match winapi::um::consoleapi::GetConsoleOutputCP() {
+                0 => winapi::um::winnls::GetOEMCP(),
+                codepage => codepage,
+            }
+        };
+        Some(codepage)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn console_codepage() -> Option<u32> {
+        None
+    }
+
+    // 查询 WMI 类的某个属性：默认用 wmic，不可用（新版 Windows 11、Server Core 已移除）时改用
+    // PowerShell 的 Get-CimInstance；启用 powershell-cim 特性时顺序相反
+    #[cfg(target_os = "windows")]
+    fn wmi_rows(&self, class: &str, property: &str) -> Result<Vec<String>, HardwareError> {
+        #[cfg(feature = "wmi-native")]
+        match self.wmi_native_rows(class, property) {
+            Ok(rows) => return Ok(rows),
+            Err(e) => debug!("Native WMI query for {}.{} failed ({}); falling back to commands", class, property, e),
+        }
+
+        let rows = self.wmi_commands(
+            class,
+            &[property],
+            parsers::parse_wmic_rows,
+            |output| parsers::parse_cim_json(output, property),
+        );
+        // 两种命令都不可用时（Server Core、受限的 PowerShell）直接从 SMBIOS 表读取
+        let rows = rows.or_else(|e| match self.smbios_rows(class, property) {
+            Some(rows) => {
+                debug!("{}.{} failed ({}); using the SMBIOS table", class, property, e);
+                Ok(rows)
+            }
+            None => Err(e),
+        });
+        rows.map_err(|e| Self::wmi_access_error(class, e))
+    }
+
+    // 一次查询多个属性，每个实例一条记录，同一实例（如同一块磁盘）的属性不会与其他实例混在一起；
+    // properties 以逗号分隔，wmic 使用 /value 格式输出
+    #[cfg(target_os = "windows")]
+    fn wmi_records(&self, class: &str, properties: &str) -> Result<Vec<parsers::WmiRecord>, HardwareError> {
+        #[cfg(feature = "wmi-native")]
+        match self.wmi_native_records(class, properties) {
+            Ok(records) => return Ok(records),
+            Err(e) => debug!("Native WMI query for {}.{} failed ({}); falling back to commands", class, properties, e),
+        }
+
+        self.wmi_commands(
+            class,
+            &[properties, "/value"],
+            |output| Ok(parsers::parse_wmic_records(output)),
+            parsers::parse_cim_records,
+        )
+        .map_err(|e| Self::wmi_access_error(class, e))
+    }
+
+    // 按配置的顺序依次尝试 wmic 与 Get-CimInstance；get_args 为 wmic get 之后的参数，第一项即要查询的属性
+    #[cfg(target_os = "windows")]
+    fn wmi_commands<T>(
+        &self,
+        class: &str,
+        get_args: &[&str],
+        parse_wmic: impl Fn(&str) -> Result<T, HardwareError>,
+        parse_cim: impl Fn(&str) -> Result<T, HardwareError>,
+    ) -> Result<T, HardwareError> {
+        let wmic = || {
+            let (program, args) = Self::wmic_command(class, get_args);
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            parse_wmic(&self.run_text(program, &args)?)
+        };
+        let cim = || {
+            let (program, args) = Self::cim_command(class, get_args[0]);
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            parse_cim(&self.run_text(program, &args)?)
+        };
+
+        if cfg!(feature = "powershell-cim") {
+            cim().or_else(|e| {
+                debug!("Get-CimInstance {} failed ({}); falling back to wmic", class, e);
+                wmic()
+            })
+        } else {
+            wmic().or_else(|e| {
+                debug!("wmic {} failed ({}); falling back to Get-CimInstance", class, e);
+                cim()
+            })
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn wmic_command(class: &str, get_args: &[&str]) -> (&'static str, Vec<String>) {
+        let args = ["path", class, "get"].into_iter().chain(get_args.iter().copied());
+        ("wmic", args.map(str::to_string).collect())
+    }
+
+    #[cfg(target_os = "windows")]
+    fn cim_command(class: &str, properties: &str) -> (&'static str, Vec<String>) {
+        let script = parsers::cim_query_script(class, properties);
+        let args = ["-NoProfile", "-NonInteractive", "-Command", &script];
+        ("powershell", args.iter().map(|arg| arg.to_string()).collect())
+    }
+
+    // 组策略限制下部分类（如 Win32_PhysicalMemory）需要提升权限，与"没有数据"区分开
+    #[cfg(target_os = "windows")]
+    fn wmi_access_error(class: &str, e: HardwareError) -> HardwareError {
+        match e {
+            HardwareError::CommandError(message) if parsers::is_access_denied(&message) => {
+                HardwareError::PermissionDenied(class.to_string())
+            }
+            e => e,
+        }
+    }
+
+    // 与命令方式取相同的类与属性，并同样去掉首尾空白与空值，保证唯一码不变
+    #[cfg(all(target_os = "windows", feature = "wmi-native"))]
+    fn wmi_native_rows(&self, class: &str, property: &str) -> Result<Vec<String>, HardwareError> {
+        Ok(self
+            .wmi_native_records(class, property)?
+            .into_iter()
+            .filter_map(|mut record| record.remove(property))
+            .filter(|value| !value.is_empty())
+            .collect())
+    }
+
+    #[cfg(all(target_os = "windows", feature = "wmi-native"))]
+    fn wmi_native_records(&self, class: &str, properties: &str) -> Result<Vec<parsers::WmiRecord>, HardwareError> {
+        use std::collections::HashMap;
+        use wmi::{COMLibrary, Variant, WMIConnection};
+
+        let connection = self
+            .wmi
+            .get_or_init(|| {
+                COMLibrary::new()
+                    .and_then(WMIConnection::new)
+                    .map_err(|e| warn!("Failed to connect to WMI: {}", e))
+                    .ok()
+            })
+            .as_ref()
+            .ok_or_else(|| HardwareError::UnsupportedSystem("WMI connection unavailable".to_string()))?;
+
+        let instances: Vec<HashMap<String, Variant>> = connection
+            .raw_query(format!("SELECT {} FROM {}", properties, class))
+            .map_err(|e| HardwareError::CommandError(format!("WMI query {}.{}: {}", class, properties, e)))?;
+
+        Ok(instances
+            .into_iter()
+            .map(|instance| {
+                instance
+                    .into_iter()
+                    .filter_map(|(name, value)| {
+                        let value = match value {
+                            Variant::String(value) => value.trim().to_string(),
+                            Variant::I4(value) => value.to_string(),
+                            Variant::UI4(value) => value.to_string(),
+                            Variant::UI8(value) => value.to_string(),
+                            _ => return None,
+                        };
+                        Some((name, value))
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    // WMI 的这些属性本身即来自 SMBIOS，格式转换为与 wmic 相同（UUID 大写、日期为 DMTF 格式）；
+    // 没有对应 SMBIOS 字段的属性返回 None（Win32_BIOS.Version 来自 ACPI，与 SMBIOS 的 BIOS 版本不同）
+    #[cfg(target_os = "windows")]
+    fn smbios_rows(&self, class: &str, property: &str) -> Option<Vec<String>> {
+        let table = self.smbios()?;
+        let value = match (class, property) {
+            ("Win32_BaseBoard", "SerialNumber") => table.baseboard_serial(),
+            ("Win32_BaseBoard", "Manufacturer") => table.baseboard_manufacturer(),
+            ("Win32_BaseBoard", "Product") => table.baseboard_product(),
+            ("Win32_ComputerSystemProduct", "UUID") => table.system_uuid().map(|uuid| uuid.to_uppercase()),
+            ("Win32_ComputerSystemProduct", "IdentifyingNumber") => table.system_serial(),
+            ("Win32_SystemEnclosure", "SerialNumber") => table.chassis_serial(),
+            ("Win32_SystemEnclosure", "SMBIOSAssetTag") => table.chassis_asset_tag(),
+            ("Win32_SystemEnclosure", "ChassisTypes") => table.chassis_type().map(|kind| kind.to_string()),
+            ("Win32_BIOS", "Manufacturer") => table.bios_vendor(),
+            ("Win32_BIOS", "ReleaseDate") => table.bios_release_date().and_then(|date| smbios::dmtf_date(&date)),
+            ("Win32_Processor", "ProcessorId") => table.processor_id(),
+            ("Win32_Processor", "Name") => table.processor_version(),
+            ("Win32_PhysicalMemory", "SerialNumber") => return Some(table.memory_serials()),
+            _ => return None,
+        };
+        Some(value.into_iter().collect())
+    }
+
+    #[cfg(target_os = "macos")]
+    fn system_profiler(&self, data_type: &'static str) -> Result<String, HardwareError> {
+        self.profiler.get(data_type, || self.run_text("system_profiler", &[data_type]))
+    }
+
+    // IOPlatformExpertDevice 的属性（IOPlatformUUID、IOPlatformSerialNumber）：键名与系统语言无关，
+    // 比 system_profiler 快得多；读不到时由调用方改用 system_profiler
+    #[cfg(target_os = "macos")]
+    fn platform_expert_property(&self, key: &str) -> Option<String> {
+        let output = self.platform_expert.get_or_init(|| {
+            self.run_text("ioreg", &["-rd1", "-c", "IOPlatformExpertDevice"])
+                .map_err(|e| debug!("ioreg IOPlatformExpertDevice failed: {}", e))
+                .ok()
+        });
+        output.as_deref().and_then(|output| parsers::parse_ioreg_property(output, key))
+    }
+
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    fn smbios(&self) -> Option<&SmbiosTable> {
+        self.smbios
+            .get_or_init(|| {
+                self.read_smbios_table()
+                    .map_err(|e| debug!("Failed to read the SMBIOS table: {}", e))
+                    .ok()
+            })
+            .as_ref()
+    }
+
+    // GetSystemFirmwareTable('RSMB')：不需要管理员权限，也不启动任何进程
+    #[cfg(target_os = "windows")]
+    fn read_smbios_table(&self) -> Result<SmbiosTable, HardwareError> {
+        use winapi::um::sysinfoapi::GetSystemFirmwareTable;
+
+        const RSMB: u32 = u32::from_be_bytes(*b"RSMB");
+        // SAFETY: 缓冲区为空、长度为 0 时只返回所需的字节数
+        let size = unsafe { GetSystemFirmwareTable(RSMB, 0, std::ptr::null_mut(), 0) };
+        if size == 0 {
+            return Err(HardwareError::UnsupportedSystem(format!(
+                "GetSystemFirmwareTable('RSMB'): {}",
+                std::io::Error::last_os_error()
+            )));
+        }
+        let mut blob = vec![0u8; size as usize];
+        // SAFETY: blob 的长度即传入的 size
+        let written = unsafe { GetSystemFirmwareTable(RSMB, 0, blob.as_mut_ptr().cast(), size) };
+        if written == 0 || written > size {
+            return Err(HardwareError::UnsupportedSystem(format!(
+                "GetSystemFirmwareTable('RSMB'): {}",
+                std::io::Error::last_os_error()
+            )));
+        }
+        blob.truncate(written as usize);
+        SmbiosTable::from_raw_smbios_data(&blob)
+    }
+
+    // /sys/firmware/dmi/tables：入口点给出规范版本，DMI 为结构表本身；两者通常只有 root 可读
+    #[cfg(target_os = "linux")]
+    fn read_smbios_table(&self) -> Result<SmbiosTable, HardwareError> {
+        let entry_point = self.sys.read_bytes("/sys/firmware/dmi/tables/smbios_entry_point")?;
+        let table = self.sys.read_bytes("/sys/firmware/dmi/tables/DMI")?;
+        SmbiosTable::from_entry_point(&entry_point, table)
+    }
+
+    // /sys/class/dmi/id 下的字段，读取失败时改从 SMBIOS 表取同一字段
+    #[cfg(target_os = "linux")]
+    fn dmi_id(&self, name: &str, field: fn(&SmbiosTable) -> Option<String>) -> Result<String, HardwareError> {
+        let dmi = self.dmi.get_or_init(|| {
+            self.sys
+                .list_dir("/sys/class/dmi/id")
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|name| {
+                    let value = self.sys.read_file(&format!("/sys/class/dmi/id/{}", name)).ok()?;
+                    Some((name, value.trim().to_string()))
+                })
+                .collect()
+        });
+        match dmi.get(name) {
+            Some(value) => Ok(value.clone()),
+            // 不在缓存中（仅 root 可读的序列号、不存在的字段）：重新读取一次以得到具体错误
+            None => match self.sys.read_file(&format!("/sys/class/dmi/id/{}", name)) {
+                Ok(value) => Ok(value.trim().to_string()),
+                Err(e) => self.smbios().and_then(field).ok_or(e),
+            },
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn cpuinfo(&self) -> Result<&str, HardwareError> {
+        if let Some(cpuinfo) = self.cpuinfo.get() {
+            return Ok(cpuinfo);
+        }
+        let cpuinfo = self.sys.read_file("/proc/cpuinfo")?;
+        Ok(self.cpuinfo.get_or_init(|| cpuinfo))
+    }
+
+    // 单条数据的类（主板、BIOS 等）：取第一条，没有数据时为空字符串
+    #[cfg(target_os = "windows")]
+    fn wmi_value(&self, class: &str, property: &str) -> Result<String, HardwareError> {
+        Ok(self.wmi_rows(class, property)?.into_iter().next().unwrap_or_default())
+    }
+
+    // Win32_DiskDrive 中编号为 index 的一行；各属性都取自这一行，不会与其他磁盘的属性混在一起。
+    // 查询结果在首次需要时缓存；没有该编号的磁盘时为空记录
+    #[cfg(target_os = "windows")]
+    fn disk_drive(&self, index: u32) -> Result<parsers::WmiRecord, HardwareError> {
+        let drives = self
+            .disk_drives
+            .get_or_init(|| self.wmi_records("Win32_DiskDrive", DISK_DRIVE_PROPERTIES))
+            .as_ref()
+            .map_err(Clone::clone)?;
+        let index = index.to_string();
+        Ok(drives.iter().find(|drive| drive.get("Index") == Some(&index)).cloned().unwrap_or_default())
+    }
+
+    // HKLM\SOFTWARE\Microsoft\Cryptography\MachineGuid，规范化为小写、无花括号
+    #[cfg(target_os = "windows")]
+    fn get_machine_guid(&self) -> Result<String, HardwareError> {
+        use winapi::shared::winerror::{ERROR_ACCESS_DENIED, ERROR_SUCCESS};
+        use winapi::um::winreg::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ, RRF_SUBKEY_WOW6464KEY};
+
+        const PATH: &str = r"HKLM\SOFTWARE\Microsoft\Cryptography\MachineGuid";
+        let wide = |text: &str| text.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+        let subkey = wide(r"SOFTWARE\Microsoft\Cryptography");
+        let name = wide("MachineGuid");
+        let mut buffer = [0u16; 64];
+        let mut size = std::mem::size_of_val(&buffer) as u32;
+
+        // 32 位程序默认被重定向到 WOW6432Node，而 MachineGuid 只存在于 64 位视图
+        // SAFETY: 字符串以 NUL 结尾，size 为 buffer 的字节数
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_LOCAL_MACHINE,
+                subkey.as_ptr(),
+                name.as_ptr(),
+                RRF_RT_REG_SZ | RRF_SUBKEY_WOW6464KEY,
+                std::ptr::null_mut(),
+                buffer.as_mut_ptr().cast(),
+                &mut size,
+            )
+        } as u32;
+
+        match status {
+            ERROR_SUCCESS => {
+                let len = (size as usize / 2).min(buffer.len());
+                let guid = String::from_utf16_lossy(&buffer[..len]);
+                Ok(normalize::normalize_uuid(guid.trim_end_matches('\0')))
+            }
+            ERROR_ACCESS_DENIED => Err(HardwareError::PermissionDenied(PATH.to_string())),
+            code => Err(HardwareError::FileError(format!("{}: registry error {}", PATH, code))),
+        }
+    }
+
+    // 向 \\.\PhysicalDriveN 发送 IOCTL_STORAGE_QUERY_PROPERTY，不依赖 WMI
+    #[cfg(target_os = "windows")]
+    fn storage_descriptor(&self, index: u32) -> Result<Vec<u8>, HardwareError> {
+        use winapi::um::winioctl::{IOCTL_STORAGE_QUERY_PROPERTY, STORAGE_PROPERTY_QUERY};
+
+        // StorageDeviceProperty 与 PropertyStandardQuery 均为 0，全零即为所需的查询
+        let mut query = vec![0u8; std::mem::size_of::<STORAGE_PROPERTY_QUERY>()];
+        Self::device_query(&format!(r"\\.\PhysicalDrive{}", index), IOCTL_STORAGE_QUERY_PROPERTY, &mut query, 1024)
+    }
+
+    // 系统盘（%SystemDrive%）所在的物理磁盘编号，首次需要时解析一次
+    #[cfg(target_os = "windows")]
+    fn system_disk_index(&self) -> Option<u32> {
+        use winapi::um::winioctl::IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS;
+
+        *self.system_disk.get_or_init(|| {
+            let drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+            let extents =
+                Self::device_query(&format!(r"\\.\{}", drive), IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS, &mut [], 256)
+                    .map_err(|e| debug!("Failed to resolve the disk of {}: {}", drive, e))
+                    .ok()?;
+            parsers::parse_volume_disk_number(&extents)
+        })
+    }
+
+    // 解析不出系统盘时沿用第 0 块磁盘
+    #[cfg(target_os = "windows")]
+    fn system_disk(&self) -> u32 {
+        self.system_disk_index().unwrap_or(0)
+    }
+
+    // 以访问掩码 0 打开设备并发送查询类 IOCTL，普通用户也可查询；返回实际写入的输出
+    #[cfg(target_os = "windows")]
+    fn device_query(device: &str, code: u32, input: &mut [u8], output_len: usize) -> Result<Vec<u8>, HardwareError> {
+        use winapi::shared::minwindef::{DWORD, FALSE};
+        use winapi::um::fileapi::{CreateFileW, OPEN_EXISTING};
+        use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+        use winapi::um::ioapiset::DeviceIoControl;
+        use winapi::um::winnt::{FILE_SHARE_READ, FILE_SHARE_WRITE};
+
+        let path: Vec<u16> = device.encode_utf16().chain(Some(0)).collect();
+
+        // SAFETY: path 以 NUL 结尾，其余参数均为合法的常量或空指针
+        let handle = unsafe {
+            CreateFileW(
+                path.as_ptr(),
+                0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE,
+                std::ptr::null_mut(),
+                OPEN_EXISTING,
+                0,
+                std::ptr::null_mut(),
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(HardwareInfo::file_error(device, std::io::Error::last_os_error()));
+        }
+
+        let mut buffer = vec![0u8; output_len];
+        let mut returned: DWORD = 0;
+
+        // SAFETY: handle 有效，输入输出缓冲区的长度与实际大小一致，调用结束后关闭 handle
+        let ok = unsafe {
+            let ok = DeviceIoControl(
+                handle,
+                code,
+                input.as_mut_ptr().cast(),
+                input.len() as DWORD,
+                buffer.as_mut_ptr().cast(),
+                buffer.len() as DWORD,
+                &mut returned,
+                std::ptr::null_mut(),
+            );
+            CloseHandle(handle);
+            ok
+        };
+        if ok == FALSE {
+            return Err(HardwareError::CommandError(format!(
+                "{}: DeviceIoControl {:#x}: {}",
+                device,
+                code,
+                std::io::Error::last_os_error()
+            )));
+        }
+
+        buffer.truncate(returned as usize);
+        Ok(buffer)
+    }
+
+    // 所有固定磁盘（排除 USB、SD 卡与虚拟磁盘），编号间可能有空缺，因此逐个尝试；
+    // 各字段的读取方式与系统盘相同（见 disk_property、disk_serial）
+    #[cfg(target_os = "windows")]
+    fn get_disks(&self) -> Result<Vec<DiskInfo>, HardwareError> {
+        let root = self.system_disk_index();
+        Ok((0..16)
+            .filter(|index| {
+                self.storage_descriptor(*index).is_ok_and(|descriptor| parsers::is_fixed_storage(&descriptor))
+            })
+            .map(|index| DiskInfo {
+                name: format!("PhysicalDrive{}", index),
+                model: self.disk_property(index, "Model", parsers::STORAGE_PRODUCT_ID).unwrap_or_default(),
+                serial: self.disk_serial(index).unwrap_or_default(),
+                firmware: self
+                    .disk_property(index, "FirmwareRevision", parsers::STORAGE_PRODUCT_REVISION)
+                    .ok()
+                    .filter(|firmware| !firmware.is_empty()),
+                is_root: root == Some(index),
+            })
+            .collect())
+    }
+
+    #[cfg(target_os = "windows")]
+    fn primary_disk_name(&self) -> String {
+        format!("PhysicalDrive{}", self.system_disk())
+    }
+
+    // 第 index 块磁盘在 Win32_DiskDrive 中的属性；没有数据时（如 Server Core 上 wmic 与 PowerShell 均不可用）
+    // 改用同一块磁盘的设备描述符
+    #[cfg(target_os = "windows")]
+    fn disk_property(&self, index: u32, property: &str, field: usize) -> Result<String, HardwareError> {
+        let result = self.disk_drive(index).map(|mut drive| drive.remove(property).unwrap_or_default());
+        if matches!(&result, Ok(value) if !value.is_empty()) {
+            return result;
+        }
+        match self.storage_descriptor(index) {
+            Ok(descriptor) => parsers::parse_storage_descriptor_string(&descriptor, field).map_or(result, Ok),
+            Err(e) => {
+                debug!("IOCTL_STORAGE_QUERY_PROPERTY on PhysicalDrive{} failed: {}", index, e);
+                result
+            }
+        }
+    }
+
+    // Win32_DiskDrive 在部分 OEM 机器上为空或为十六进制编码，依次回退到 Win32_PhysicalMedia 与 IOCTL，
+    // 三者都按磁盘编号取同一块磁盘
+    #[cfg(target_os = "windows")]
+    fn disk_serial(&self, index: u32) -> Result<String, HardwareError> {
+        let sources: [(&str, Probe); 3] = [
+            ("Win32_DiskDrive", &|| Ok(self.disk_drive(index)?.remove("SerialNumber").unwrap_or_default())),
+            ("Win32_PhysicalMedia", &|| {
+                Ok(self
+                    .wmi_records("Win32_PhysicalMedia", "SerialNumber,Tag")?
+                    .into_iter()
+                    .find(|media| media.get("Tag").and_then(|tag| parsers::physical_drive_index(tag)) == Some(index))
+                    .and_then(|mut media| media.remove("SerialNumber"))
+                    .unwrap_or_default())
+            }),
+            ("IOCTL_STORAGE_QUERY_PROPERTY", &|| {
+                let descriptor = self.storage_descriptor(index)?;
+                Ok(parsers::parse_storage_descriptor_string(&descriptor, parsers::STORAGE_SERIAL_NUMBER)
+                    .unwrap_or_default())
+            }),
+        ];
+        for (source, read) in sources {
+            match read() {
+                Ok(serial) => {
+                    let serial = parsers::normalize_disk_serial(&serial);
+                    if !serial.is_empty() {
+                        return Ok(serial);
+                    }
+                    debug!("{} returned no serial for PhysicalDrive{}", source, index);
+                }
+                Err(e) => debug!("{} failed: {}", source, e),
+            }
+        }
+        Err(HardwareError::MissingField("disk_serial".to_string()))
+    }
+
+    // 以太网与无线网卡，排除隧道、回环以及 Hyper-V/VMware/VPN 等虚拟网卡
+    #[cfg(target_os = "windows")]
+    fn get_network_adapters(&self) -> Result<Vec<NetworkInfo>, HardwareError> {
+        use winapi::shared::ifdef::IfOperStatusUp;
+        use winapi::shared::ipifcons::{IF_TYPE_ETHERNET_CSMACD, IF_TYPE_IEEE80211};
+        use winapi::shared::netioapi::{GetIfEntry2, MIB_IF_ROW2};
+        use winapi::shared::winerror::{ERROR_BUFFER_OVERFLOW, ERROR_SUCCESS, NO_ERROR};
+        use winapi::shared::ws2def::AF_UNSPEC;
+        use winapi::um::iphlpapi::GetAdaptersAddresses;
+        use winapi::um::iptypes::{
+            GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER, GAA_FLAG_SKIP_MULTICAST, GAA_FLAG_SKIP_UNICAST,
+            IP_ADAPTER_ADDRESSES,
+        };
+
+        let flags = GAA_FLAG_SKIP_UNICAST | GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER;
+        let mut size: u32 = 16 * 1024;
+        // u64 保证缓冲区满足 IP_ADAPTER_ADDRESSES 的对齐要求
+        let mut buffer: Vec<u64>;
+        loop {
+            buffer = vec![0; size as usize / 8 + 1];
+            // SAFETY: size 不超过 buffer 的字节数，函数只写入 buffer
+            let status = unsafe {
+                GetAdaptersAddresses(AF_UNSPEC as u32, flags, std::ptr::null_mut(), buffer.as_mut_ptr().cast(), &mut size)
+            };
+            match status {
+                ERROR_SUCCESS => break,
+                ERROR_BUFFER_OVERFLOW => continue,
+                code => return Err(HardwareError::CommandError(format!("GetAdaptersAddresses: error {}", code))),
+            }
+        }
+
+        let wide = |ptr: *const u16| {
+            if ptr.is_null() {
+                return String::new();
+            }
+            // SAFETY: 系统返回的字符串以 NUL 结尾，在 buffer 释放前有效
+            let len = (0..).take_while(|i| unsafe { *ptr.add(*i) } != 0).count();
+            String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(ptr, len) })
+        };
+
+        let mut adapters = Vec::new();
+        let mut current = buffer.as_ptr() as *const IP_ADAPTER_ADDRESSES;
+        while !current.is_null() {
+            // SAFETY: 链表节点都位于 buffer 内
+            let adapter = unsafe { &*current };
+            current = adapter.Next;
+
+            let mac = &adapter.PhysicalAddress[..(adapter.PhysicalAddressLength as usize).min(adapter.PhysicalAddress.len())];
+            let description = wide(adapter.Description);
+            if !matches!(adapter.IfType, IF_TYPE_ETHERNET_CSMACD | IF_TYPE_IEEE80211)
+                || mac.len() != 6
+                || mac.iter().all(|b| *b == 0)
+                || HardwareInfo::is_virtual_adapter(&description)
+            {
+                continue;
+            }
+
+            // 当前地址可被驱动的"网络地址"设置或随机硬件地址改写，永久地址由 GetIfEntry2 读取
+            // SAFETY: MIB_IF_ROW2 全 0 是合法值，GetIfEntry2 按 InterfaceLuid 查找并只写入 row
+            let mut row: MIB_IF_ROW2 = unsafe { std::mem::zeroed() };
+            row.InterfaceLuid = adapter.Luid;
+            let permanent = (unsafe { GetIfEntry2(&mut row) } == NO_ERROR && row.PhysicalAddressLength == 6)
+                .then(|| MacAddress::from(row.PermanentPhysicalAddress[..6].try_into().unwrap_or([0; 6])))
+                .filter(|address| !address.is_zero());
+            let mac = MacAddress::from(<[u8; 6]>::try_from(mac).unwrap_or([0; 6]));
+            adapters.push(NetworkInfo {
+                name: wide(adapter.FriendlyName),
+                mac_address: permanent.unwrap_or(mac),
+                current_mac_address: mac,
+                is_up: adapter.OperStatus == IfOperStatusUp,
+                interface_type: if adapter.IfType == IF_TYPE_IEEE80211 {
+                    NetworkInterfaceType::WiFi
+                } else {
+                    NetworkInterfaceType::Ethernet
+                },
+                is_primary: false,
+            });
+        }
+        // 枚举顺序随连接先后变化，按 MAC 排序保证结果稳定
+        adapters.sort_by_key(|adapter| adapter.mac_address);
+        Ok(adapters)
+    }
+
+    #[cfg(target_os = "windows")]
+    fn get_cpu_physical_id(&self) -> Result<String, HardwareError> {
+        self.wmi_value("Win32_Processor", "ProcessorId")
+    }
+
+    #[cfg(target_os = "windows")]
+    fn get_disk_model(&self) -> Result<String, HardwareError> {
+        self.disk_property(self.system_disk(), "Model", parsers::STORAGE_PRODUCT_ID)
+    }
+
+    #[cfg(target_os = "windows")]
+    fn get_disk_firmware(&self) -> Result<String, HardwareError> {
+        self.disk_property(self.system_disk(), "FirmwareRevision", parsers::STORAGE_PRODUCT_REVISION)
+    }
+
+    // ARM 上没有 "physical id"，改用 SoC 序列号（树莓派的 Serial 行，其他单板机的设备树 serial-number）
+    #[cfg(target_os = "linux")]
+    fn get_cpu_physical_id(&self) -> Result<String, HardwareError> {
+        let cpuinfo = self.cpuinfo()?;
+        parsers::parse_cpuinfo_field(cpuinfo, "physical id").or_else(|e| {
+            parsers::parse_cpuinfo_serial(cpuinfo)
+                .or_else(|| self.device_tree_attribute("serial-number").filter(|serial| !parsers::is_zero_serial(serial)))
+                .ok_or(e)
+        })
+    }
+
+    // /proc/device-tree 是指向此处的符号链接，直接读取 sysfs 中的路径
+    #[cfg(target_os = "linux")]
+    fn device_tree_attribute(&self, name: &str) -> Option<String> {
+        self.sys.attribute(&format!("/sys/firmware/devicetree/base/{}", name))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn get_disk_model(&self) -> Result<String, HardwareError> {
+        match self.sys.system_disk().and_then(|disk| self.sys.disk_model(&disk)) {
+            Some(model) => Ok(model),
+            None => parsers::parse_first_line(&self.run_text("lsblk", &["-no", "MODEL"])?),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn get_disk_firmware(&self) -> Result<String, HardwareError> {
+        let disk = self.primary_disk_name();
+        self.sys
+            .disk_firmware(&disk)
+            .ok_or_else(|| HardwareError::FileError(format!("/sys/block/{}: no firmware revision", disk)))
+    }
+
+    // 所有固定磁盘，各字段的来源与根磁盘相同
+    #[cfg(target_os = "linux")]
+    fn get_disks(&self) -> Result<Vec<DiskInfo>, HardwareError> {
+        let root = self.sys.system_disk();
+        Ok(self
+            .sys
+            .fixed_disks()?
+            .into_iter()
+            .map(|disk| DiskInfo {
+                model: self.sys.disk_model(&disk).unwrap_or_default(),
+                serial: self.sys.disk_serial(&disk).unwrap_or_default(),
+                firmware: self.sys.disk_firmware(&disk),
+                is_root: root.as_deref() == Some(disk.as_str()),
+                name: disk,
+            })
+            .collect())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn primary_disk_name(&self) -> String {
+        self.sys.system_disk().unwrap_or_else(|| "sda".to_string())
+    }
+
+    #[cfg(target_os = "macos")]
+    fn get_cpu_physical_id(&self) -> Result<String, HardwareError> {
+        self.cpu_brand_string()
+    }
+
+    // Rosetta 2 转译运行的 x86_64 进程中 sysctl.proc_translated 为 1；原生进程为 0，Intel Mac 上没有这个键
+    #[cfg(target_os = "macos")]
+    fn is_translated(&self) -> bool {
+        *self.translated.get_or_init(|| {
+            self.run_text("sysctl", &["-n", "sysctl.proc_translated"])
+                .is_ok_and(|output| output.trim() == "1")
+        })
+    }
+
+    // 转译运行时子进程也被转译，部分 macOS 版本此时报告虚拟的 "VirtualApple @ 2.50GHz processor"；
+    // 经 arch -arm64 原生执行 sysctl，得到与 arm64 构建相同的品牌字符串
+    #[cfg(target_os = "macos")]
+    fn cpu_brand_string(&self) -> Result<String, HardwareError> {
+        let output = if self.is_translated() {
+            self.run_text("arch", &["-arm64", "/usr/sbin/sysctl", "-n", "machdep.cpu.brand_string"])?
+        } else {
+            self.run_text("sysctl", &["-n", "machdep.cpu.brand_string"])?
+        };
+        Ok(output.trim().to_string())
+    }
+
+    // 启动卷所在的物理磁盘：APFS 卷位于合成磁盘（disk3）上，沿 APFSPhysicalStores 找到物理存储（disk0s2）
+    // 所在的磁盘；不是物理磁盘（如 Core Storage 的 Fusion Drive 逻辑卷）或解析不出时取第一块内置磁盘
+    #[cfg(target_os = "macos")]
+    fn boot_disk(&self) -> &MacBootDisk {
+        self.boot_disk.get_or_init(|| {
+            let physical = self.run_text("diskutil", &["list", "physical"]).unwrap_or_default();
+            let internal = parsers::parse_diskutil_internal_disks(&physical);
+            let volume = self.run_text("diskutil", &["info", "-plist", "/"]).unwrap_or_default();
+            let disk = parsers::parse_plist_value(&volume, "APFSPhysicalStore")
+                .or_else(|| parsers::parse_plist_value(&volume, "ParentWholeDisk"))
+                .map(|device| parsers::diskutil_whole_disk(&device).to_string());
+            match disk {
+                Some(name) if internal.contains(&name) => MacBootDisk { name, external: false },
+                Some(name) if physical.contains(&format!("/dev/{} (external", name)) => {
+                    warn!("booted from the external disk {}", name);
+                    MacBootDisk { name, external: true }
+                }
+                _ => MacBootDisk {
+                    name: internal.into_iter().next().unwrap_or_else(|| "disk0".to_string()),
+                    external: false,
+                },
+            }
+        })
+    }
+
+    // networksetup 的 "Ethernet Address" 是网卡的硬件地址，不是 Wi-Fi 按网络生成的私有地址；
+    // 内置端口以 IOEthernetInterface 的 IOBuiltin 为准，ioreg 不可用时按端口名称判断（"Wi-Fi"、"Ethernet 1"）。
+    // 按设备编号排序（en0、en1、…、en10），与是否接着扩展坞无关
+    #[cfg(target_os = "macos")]
+    fn get_builtin_interfaces(&self) -> Result<Vec<NetworkInfo>, HardwareError> {
+        let ports = parsers::parse_hardware_ports(&self.run_text("networksetup", &["-listallhardwareports"])?);
+        let builtin = self
+            .run_text("ioreg", &["-r", "-c", "IOEthernetInterface"])
+            .map(|output| parsers::parse_ioreg_builtin_interfaces(&output))
+            .ok()
+            .filter(|builtin| !builtin.is_empty());
+        let current = self
+            .run_text("ifconfig", &[])
+            .map(|output| parsers::parse_interface_ethers(&output, "ether "))
+            .unwrap_or_default();
+        let mut interfaces: Vec<NetworkInfo> = ports
+            .into_iter()
+            .filter(|(port, device, _)| match &builtin {
+                Some(builtin) => builtin.contains(device),
+                None => matches!(port.as_str(), "Wi-Fi" | "AirPort" | "Ethernet") || port.starts_with("Ethernet "),
+            })
+            .filter_map(|(port, device, mac)| {
+                let mac: MacAddress = mac.parse().ok()?;
+                Some(NetworkInfo {
+                    interface_type: if matches!(port.as_str(), "Wi-Fi" | "AirPort") {
+                        NetworkInterfaceType::WiFi
+                    } else {
+                        NetworkInterfaceType::Ethernet
+                    },
+                    is_up: self.interface_is_up(&device),
+                    current_mac_address: current
+                        .iter()
+                        .find(|(name, _)| *name == device)
+                        .and_then(|(_, current)| current.parse().ok())
+                        .unwrap_or(mac),
+                    name: device,
+                    mac_address: mac,
+                    is_primary: false,
+                })
+            })
+            .collect();
+        interfaces.sort_by(|a, b| (a.name.len(), &a.name).cmp(&(b.name.len(), &b.name)));
+        Ok(interfaces)
+    }
+
+    // 网卡的运行状态（Linux 为 operstate，macOS 为 ifconfig 的 "status: active"），仅供显示，不参与唯一码；
+    // 没有来源的系统上视为已启用
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "illumos",
+        target_os = "solaris",
+        all(target_os = "android", feature = "android")
+    )))]
+    fn interface_is_up(&self, name: &str) -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            self.sys.attribute(&format!("/sys/class/net/{}/operstate", name)).as_deref() == Some("up")
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            self.run_text("ifconfig", &[name])
+                .is_ok_and(|output| output.lines().any(|line| line.trim() == "status: active"))
+        }
+
+        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+        {
+            let _ = name;
+            true
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn default_route_interface(&self) -> Option<String> {
+        let output = self.run_text("route", &["-n", "get", "default"]).ok()?;
+        parsers::parse_route_get_interface(&output).filter(|name| !HardwareInfo::is_virtual_interface(name))
+    }
+
+    #[cfg(target_os = "macos")]
+    fn get_disk_model(&self) -> Result<String, HardwareError> {
+        let output = self.run_text("diskutil", &["info", "-plist", &self.boot_disk().name])?;
+        parsers::parse_plist_value(&output, "MediaName")
+            .ok_or_else(|| HardwareError::ParseError("no MediaName in diskutil info".to_string()))
+    }
+
+    // NVMe 与 SATA（较早的 Intel Mac）磁盘分别列在两种数据类型中；Fusion Drive 两者都有，
+    // 按 BSD Name 取启动磁盘的那一项
+    #[cfg(target_os = "macos")]
+    fn get_disk_firmware(&self) -> Result<String, HardwareError> {
+        let disk = self.boot_disk().name.as_str();
+        let mut last_error = None;
+        for data_type in ["SPNVMeDataType", "SPSerialATADataType"] {
+            match self.system_profiler(data_type) {
+                Ok(output) => {
+                    let labels = ["Revision", "Firmware Version"];
+                    if let Some(firmware) = parsers::parse_profiler_disk_field(&output, disk, &labels) {
+                        return Ok(firmware);
+                    }
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| HardwareError::MissingField(format!("firmware of {}", disk))))
+    }
+
+    // diskutil list physical 中的内置磁盘（外接的 USB/雷雳磁盘标记为 external）
+    #[cfg(target_os = "macos")]
+    fn get_disks(&self) -> Result<Vec<DiskInfo>, HardwareError> {
+        let disks = parsers::parse_diskutil_internal_disks(&self.run_text("diskutil", &["list", "physical"])?);
+        let boot_disk = &self.boot_disk().name;
+        Ok(disks
+            .into_iter()
+            .map(|disk| {
+                let info = self.run_text("diskutil", &["info", &disk]).unwrap_or_default();
+                DiskInfo {
+                    model: parsers::parse_labeled_value(&info, "Device / Media Name").unwrap_or_default(),
+                    serial: parsers::parse_labeled_value(&info, "Serial Number").unwrap_or_default(),
+                    firmware: None,
+                    is_root: disk == *boot_disk,
+                    name: disk,
+                }
+            })
+            .collect())
+    }
+
+    #[cfg(target_os = "macos")]
+    fn primary_disk_name(&self) -> String {
+        self.boot_disk().name.clone()
+    }
+
+    // kenv -q 在变量不存在时不输出任何内容，与空值一样视为缺失
+    #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+    fn bsd_value(&self, field: bsd::Field) -> Result<String, HardwareError> {
+        let output = match bsd::source(field) {
+            #[cfg(target_os = "freebsd")]
+            Some(bsd::Source::Kenv(name)) => self.run_text("kenv", &["-q", name])?,
+            Some(bsd::Source::Sysctl(name)) => self.run_text("sysctl", &["-n", name])?,
+            None => {
+                return Err(HardwareError::UnsupportedSystem(format!(
+                    "{:?} is not exposed on {}",
+                    field,
+                    std::env::consts::OS
+                )))
+            }
+        };
+        let value = output.trim().to_string();
+        if value.is_empty() {
+            return Err(HardwareError::MissingField(format!("{:?}", field)));
+        }
+        Ok(value)
+    }
+
+    // 固定磁盘及其型号、序列号、固件版本：FreeBSD 由 diskinfo -v 提供，OpenBSD 与 NetBSD 的型号取自启动信息，
+    // OpenBSD 以磁盘标签的 DUID 作为序列号（NetBSD 没有普通用户可读的序列号来源）
+    #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+    fn bsd_disks(&self) -> Result<Vec<DiskInfo>, HardwareError> {
+        #[cfg(target_os = "freebsd")]
+        let names = self.run_text("sysctl", &["-n", "kern.disks"])?;
+        #[cfg(not(target_os = "freebsd"))]
+        let names = self.run_text("sysctl", &["-n", "hw.disknames"])?;
+        let dmesg = std::fs::read_to_string("/var/run/dmesg.boot").unwrap_or_default();
+
+        let disks: Vec<DiskInfo> = bsd::parse_disknames(&names)
+            .into_iter()
+            .map(|(name, duid)| {
+                let (model, firmware) = bsd::parse_dmesg_disk(&dmesg, &name).unwrap_or_default();
+                #[cfg(target_os = "freebsd")]
+                let (model, serial) = {
+                    let _ = duid;
+                    let info = self.run_text("diskinfo", &["-v", &name]).unwrap_or_default();
+                    (bsd::parse_diskinfo(&info, "Disk descr.").unwrap_or(model), bsd::parse_diskinfo(&info, "Disk ident."))
+                };
+                #[cfg(not(target_os = "freebsd"))]
+                let serial = duid;
+                DiskInfo {
+                    serial: serial.map(|serial| parsers::normalize_disk_serial(&serial)).unwrap_or_default(),
+                    model,
+                    firmware,
+                    is_root: false,
+                    name,
+                }
+            })
+            .collect();
+        if disks.is_empty() {
+            return Err(HardwareError::MissingField("disks".to_string()));
+        }
+        Ok(disks)
+    }
+
+    // 没有可靠的根磁盘映射，按名称排序后的第一块固定磁盘视为系统盘
+    #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+    fn bsd_root_disk(&self) -> Result<DiskInfo, HardwareError> {
+        self.bsd_disks()?
+            .into_iter()
+            .next()
+            .map(|disk| DiskInfo { is_root: true, ..disk })
+            .ok_or_else(|| HardwareError::MissingField("disk".to_string()))
+    }
+
+    // 与 macOS 一样没有单独的物理 ID，使用型号
+    #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+    fn get_cpu_physical_id(&self) -> Result<String, HardwareError> {
+        self.bsd_value(bsd::Field::CpuModel)
+    }
+
+    #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+    fn get_disk_model(&self) -> Result<String, HardwareError> {
+        Ok(self.bsd_root_disk()?.model)
+    }
+
+    #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+    fn get_disk_firmware(&self) -> Result<String, HardwareError> {
+        self.bsd_root_disk()?
+            .firmware
+            .ok_or_else(|| HardwareError::MissingField("disk_firmware".to_string()))
+    }
+
+    #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+    fn get_disks(&self) -> Result<Vec<DiskInfo>, HardwareError> {
+        let root = self.primary_disk_name();
+        Ok(self
+            .bsd_disks()?
+            .into_iter()
+            .map(|disk| DiskInfo { is_root: disk.name == root, ..disk })
+            .collect())
+    }
+
+    #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+    fn primary_disk_name(&self) -> String {
+        self.bsd_root_disk().map(|disk| disk.name).unwrap_or_default()
+    }
+
+    // 依次尝试的系统属性中第一个有值的
+    #[cfg(all(target_os = "android", feature = "android"))]
+    fn android_property(&self, names: &[&str]) -> Result<String, HardwareError> {
+        names
+            .iter()
+            .find_map(|name| android::property(name))
+            .ok_or_else(|| HardwareError::MissingField(names.join(", ")))
+    }
+
+    // SoC 名称（ro.hardware，如 "qcom"），CPU 本身没有可读的物理 ID
+    #[cfg(all(target_os = "android", feature = "android"))]
+    fn get_cpu_physical_id(&self) -> Result<String, HardwareError> {
+        self.android_property(&["ro.hardware"])
+    }
+
+    #[cfg(all(target_os = "android", feature = "android"))]
+    fn get_disk_model(&self) -> Result<String, HardwareError> {
+        android::unsupported("disk model")
+    }
+
+    #[cfg(all(target_os = "android", feature = "android"))]
+    fn get_disk_firmware(&self) -> Result<String, HardwareError> {
+        android::unsupported("disk firmware")
+    }
+
+    #[cfg(all(target_os = "android", feature = "android"))]
+    fn get_disks(&self) -> Result<Vec<DiskInfo>, HardwareError> {
+        android::unsupported("disk enumeration")
+    }
+
+    #[cfg(all(target_os = "android", feature = "android"))]
+    fn get_chassis_type(&self) -> Result<String, HardwareError> {
+        android::unsupported("chassis type")
+    }
+
+    #[cfg(all(target_os = "android", feature = "android"))]
+    fn primary_disk_name(&self) -> String {
+        String::new()
+    }
+
+    // smbios -t <类型> 中的一个字段，空值视为缺失
+    #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+    fn illumos_smbios(&self, kind: &str, label: &str) -> Result<String, HardwareError> {
+        let output = self.run_text("smbios", &["-t", kind])?;
+        illumos::smbios_field(&output, label).ok_or_else(|| HardwareError::MissingField(format!("{} {}", kind, label)))
+    }
+
+    // 不可移除的磁盘（diskinfo 的 RMV 为 no），序列号来自 diskinfo -P；diskinfo 不提供固件版本
+    #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+    fn illumos_disks(&self) -> Result<Vec<DiskInfo>, HardwareError> {
+        let disks = illumos::diskinfo_fixed_disks(&self.run_text("diskinfo", &["-H", "-p"])?);
+        let physical = self.run_text("diskinfo", &["-H", "-p", "-P"]).unwrap_or_default();
+        if disks.is_empty() {
+            return Err(HardwareError::MissingField("disks".to_string()));
+        }
+        Ok(disks
+            .into_iter()
+            .map(|(name, model)| DiskInfo {
+                serial: illumos::diskinfo_serial(&physical, &name)
+                    .map(|serial| parsers::normalize_disk_serial(&serial))
+                    .unwrap_or_default(),
+                model,
+                firmware: None,
+                is_root: false,
+                name,
+            })
+            .collect())
+    }
+
+    // 与 BSD 相同，按名称排序后的第一块固定磁盘视为系统盘
+    #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+    fn illumos_root_disk(&self) -> Result<DiskInfo, HardwareError> {
+        self.illumos_disks()?
+            .into_iter()
+            .next()
+            .map(|disk| DiskInfo { is_root: true, ..disk })
+            .ok_or_else(|| HardwareError::MissingField("disk".to_string()))
+    }
+
+    #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+    fn illumos_links(&self) -> Result<Vec<NetworkInfo>, HardwareError> {
+        let macs = self.run_text("dladm", &["show-phys", "-m", "-p", "-o", "link,address"])?;
+        let states = self.run_text("dladm", &["show-phys", "-p", "-o", "link,state"]).unwrap_or_default();
+        let up = illumos::dladm_up_links(&states);
+        Ok(illumos::dladm_macs(&macs)
+            .into_iter()
+            .filter_map(|(link, mac)| {
+                let mac: MacAddress = mac.parse().ok()?;
+                Some(NetworkInfo {
+                    interface_type: HardwareInfo::detect_interface_type(&link),
+                    is_up: up.contains(&link),
+                    current_mac_address: mac,
+                    mac_address: mac,
+                    name: link,
+                    is_primary: false,
+                })
+            })
+            .collect())
+    }
+
+    // kstat 的 brand，与 BSD 一样没有单独的物理 ID
+    #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+    fn get_cpu_physical_id(&self) -> Result<String, HardwareError> {
+        self.get_cpu_info()
+    }
+
+    #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+    fn get_disk_model(&self) -> Result<String, HardwareError> {
+        Ok(self.illumos_root_disk()?.model)
+    }
+
+    #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+    fn get_disk_firmware(&self) -> Result<String, HardwareError> {
+        Err(HardwareError::UnsupportedSystem(format!("disk firmware on {}", std::env::consts::OS)))
+    }
+
+    #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+    fn get_disks(&self) -> Result<Vec<DiskInfo>, HardwareError> {
+        let root = self.primary_disk_name();
+        Ok(self
+            .illumos_disks()?
+            .into_iter()
+            .map(|disk| DiskInfo { is_root: disk.name == root, ..disk })
+            .collect())
+    }
+
+    #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+    fn primary_disk_name(&self) -> String {
+        self.illumos_root_disk().map(|disk| disk.name).unwrap_or_default()
+    }
+
+    // "0x3 (desktop)" 转换为与其他系统相同的名称（"Desktop"）
+    #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+    fn get_chassis_type(&self) -> Result<String, HardwareError> {
+        let output = self.run_text("smbios", &["-t", "SMB_TYPE_CHASSIS"])?;
+        illumos::smbios_chassis_code(&output)
+            .and_then(|code| smbios::chassis_type_name(&code))
+            .map(str::to_string)
+            .ok_or_else(|| HardwareError::MissingField("chassis_type".to_string()))
+    }
+
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "illumos",
+        target_os = "solaris",
+        all(target_os = "android", feature = "android")
+)))]
+    fn get_cpu_physical_id(&self) -> Result<String, HardwareError> {
+        fallback::unsupported()
+    }
+
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "illumos",
+        target_os = "solaris",
+        all(target_os = "android", feature = "android")
+)))]
+    fn get_disk_model(&self) -> Result<String, HardwareError> {
+        fallback::unsupported()
+    }
+
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "illumos",
+        target_os = "solaris",
+        all(target_os = "android", feature = "android")
+)))]
+    fn get_disk_firmware(&self) -> Result<String, HardwareError> {
+        fallback::unsupported()
+    }
+
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "illumos",
+        target_os = "solaris",
+        all(target_os = "android", feature = "android")
+)))]
+    fn get_disks(&self) -> Result<Vec<DiskInfo>, HardwareError> {
+        fallback::unsupported()
+    }
+
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "illumos",
+        target_os = "solaris",
+        all(target_os = "android", feature = "android")
+)))]
+    fn get_chassis_type(&self) -> Result<String, HardwareError> {
+        fallback::unsupported()
+    }
+
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "illumos",
+        target_os = "solaris",
+        all(target_os = "android", feature = "android")
+)))]
+    fn primary_disk_name(&self) -> String {
+        String::new()
+    }
+
+    fn get_motherboard_uuid(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.wmi_value("Win32_ComputerSystemProduct", "UUID")
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            // 内核已按规范版本处理字节序；只差字节序时两种读法都记在 BoardInfo 中（见 alternate_uuid），
+            // 其他不一致说明固件或解析有问题，记录下来便于排查
+            let uuid = self.dmi_id("product_uuid", SmbiosTable::system_uuid)?;
+            if let Some(table_uuid) = self.smbios().and_then(SmbiosTable::system_uuid) {
+                if !table_uuid.eq_ignore_ascii_case(&uuid) {
+                    let swapped = normalize::swap_uuid_byte_order(&uuid)
+                        .is_some_and(|swapped| swapped.eq_ignore_ascii_case(&table_uuid));
+                    if swapped {
+                        debug!("product_uuid {} and the SMBIOS table UUID {} differ in byte order", uuid, table_uuid);
+                    } else {
+                        warn!("product_uuid {} differs from the SMBIOS table UUID {}", uuid, table_uuid);
+                    }
+                }
+            }
+            Ok(uuid)
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            if let Some(uuid) = self.platform_expert_property("IOPlatformUUID") {
+                return Ok(uuid);
+            }
+            let output = self.system_profiler("SPHardwareDataType")?;
+            parsers::parse_labeled_value(&output, "Hardware UUID")
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            self.bsd_value(bsd::Field::SystemUuid)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            android::unsupported("system UUID")
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            self.illumos_smbios("SMB_TYPE_SYSTEM", "UUID")
+        }
+    }
+
+    // CPU 信息获取
+    fn get_cpu_info(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.wmi_value("Win32_Processor", "Name")
+        }
+
+        // ARM 上没有 "model name"，依次取 "Hardware" 行与设备树 compatible 中的 SoC（最后一项，如 "brcm,bcm2711"）
+        #[cfg(target_os = "linux")]
+        {
+            let cpuinfo = self.cpuinfo()?;
+            parsers::parse_cpuinfo_field(cpuinfo, "model name").or_else(|e| {
+                parsers::parse_cpuinfo_field(cpuinfo, "Hardware").or_else(|_| {
+                    self.device_tree_attribute("compatible")
+                        .and_then(|compatible| parsers::parse_device_tree_strings(&compatible).pop())
+                        .ok_or(e)
+                })
+            })
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            self.cpu_brand_string()
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            self.bsd_value(bsd::Field::CpuModel)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            self.android_property(&["ro.soc.model", "ro.hardware"])
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            let output = self.run_text("kstat", &["-p", "cpu_info:0:cpu_info0:brand"])?;
+            illumos::kstat_value(&output).ok_or_else(|| HardwareError::MissingField("cpu_info".to_string()))
+        }
+    }
+
+    // 主板序列号获取
+    fn get_motherboard_serial(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.wmi_value("Win32_BaseBoard", "SerialNumber")
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.dmi_id("board_serial", SmbiosTable::baseboard_serial)
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            if let Some(serial) = self.platform_expert_property("IOPlatformSerialNumber") {
+                return Ok(serial);
+            }
+            let output = self.system_profiler("SPHardwareDataType")?;
+            parsers::parse_labeled_value(&output, "Serial Number")
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            self.bsd_value(bsd::Field::BoardSerial)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            android::serial_number()
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            self.illumos_smbios("SMB_TYPE_BASEBOARD", "Serial Number")
+        }
+    }
+
+    // 系统（产品）序列号：品牌机上主板序列号为空时通常仍有值
+    fn get_system_serial(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.wmi_value("Win32_ComputerSystemProduct", "IdentifyingNumber")
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.dmi_id("product_serial", SmbiosTable::system_serial)
+        }
+
+        // 与主板序列号相同，均为 "Serial Number (system)"
+        #[cfg(target_os = "macos")]
+        {
+            if let Some(serial) = self.platform_expert_property("IOPlatformSerialNumber") {
+                return Ok(serial);
+            }
+            let output = self.system_profiler("SPHardwareDataType")?;
+            parsers::parse_labeled_value(&output, "Serial Number")
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            self.bsd_value(bsd::Field::SystemSerial)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            android::unsupported("system serial")
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            self.illumos_smbios("SMB_TYPE_SYSTEM", "Serial Number")
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn get_chassis_serial(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.wmi_value("Win32_SystemEnclosure", "SerialNumber")
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.dmi_id("chassis_serial", SmbiosTable::chassis_serial)
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            self.bsd_value(bsd::Field::ChassisSerial)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            android::unsupported("chassis serial")
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            self.illumos_smbios("SMB_TYPE_CHASSIS", "Serial Number")
+        }
+    }
+
+    // Win32_BaseBoard 没有资产标签属性（Tag 是 WMI 的实例键，固定为 "Base Board"），直接读 SMBIOS 表
+    #[cfg(not(target_os = "macos"))]
+    fn get_board_asset_tag(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.smbios()
+                .map(|table| table.baseboard_asset_tag().unwrap_or_default())
+                .ok_or_else(|| HardwareError::UnsupportedSystem("SMBIOS table is not available".to_string()))
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.dmi_id("board_asset_tag", SmbiosTable::baseboard_asset_tag)
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            self.bsd_value(bsd::Field::BoardAssetTag)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            android::unsupported("board asset tag")
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            self.illumos_smbios("SMB_TYPE_BASEBOARD", "Asset Tag")
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn get_chassis_asset_tag(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.wmi_value("Win32_SystemEnclosure", "SMBIOSAssetTag")
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.dmi_id("chassis_asset_tag", SmbiosTable::chassis_asset_tag)
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            self.bsd_value(bsd::Field::ChassisAssetTag)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            android::unsupported("chassis asset tag")
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            self.illumos_smbios("SMB_TYPE_CHASSIS", "Asset Tag")
+        }
+    }
+
+    // 机箱类型，解码为名称（Desktop、Laptop、Rack Mount Chassis 等）
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    fn get_chassis_type(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        let raw = self.wmi_value("Win32_SystemEnclosure", "ChassisTypes")?;
+
+        #[cfg(target_os = "linux")]
+        let raw = self.dmi_id("chassis_type", |table| table.chassis_type().map(|kind| kind.to_string()))?;
+
+        smbios::chassis_type_name(&raw)
+            .map(str::to_string)
+            .ok_or_else(|| HardwareError::ParseError(format!("unknown chassis type '{}'", raw)))
+    }
+
+    // FreeBSD 的 kenv 中已是名称（"Desktop"、"Laptop"）
+    #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+    fn get_chassis_type(&self) -> Result<String, HardwareError> {
+        self.bsd_value(bsd::Field::ChassisType)
+    }
+
+    // 磁盘序列号获取
+    fn get_disk_serial(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.disk_serial(self.system_disk())
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            let disk = self.sys.system_disk();
+            match disk.as_deref().and_then(|disk| self.sys.disk_serial(disk)) {
+                Some(serial) => {
+                    // 与 udev 的结果交叉核对，不一致时只记录，仍以 sysfs 为准
+                    if let Some(udev) = disk.as_deref().and_then(|disk| self.sys.udev_serial_short(disk)) {
+                        if udev != serial {
+                            debug!("Disk serial {} differs from udev ID_SERIAL_SHORT {}", serial, udev);
+                        }
+                    }
+                    Ok(serial)
+                }
+                None => {
+                    let device = format!("--name=/dev/{}", disk.as_deref().unwrap_or("sda"));
+                    let output = self.run_text("udevadm", &["info", "--query=property", &device])?;
+                    parsers::parse_udev_property(&output, "ID_SERIAL")
+                }
+            }
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            let output = self.run_text("diskutil", &["info", &self.boot_disk().name])?;
+            parsers::parse_labeled_value(&output, "Serial Number")
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            Ok(self.bsd_root_disk()?.serial)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            android::unsupported("disk serial")
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            Ok(self.illumos_root_disk()?.serial)
+        }
+    }
+
+    // MAC 地址获取
+    fn get_mac_address(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            // 物理网卡中 MAC 最小的一块，不受连接顺序与 Hyper-V 虚拟交换机影响
+            Ok(self
+                .get_network_adapters()?
+                .into_iter()
+                .map(|adapter| adapter.mac_address)
+                .min()
+                .map(|mac| mac.to_string())
+                .unwrap_or_default())
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            // 按 ifindex 顺序取第一块物理以太网卡，跳过 docker0、veth 等虚拟接口
+            match self.sys.first_ether() {
+                Some(mac) => Ok(mac),
+                None => {
+                    let (name, mac) = Self::first_ether(&self.run_text("ip", &["link", "show"])?, "link/ether ")?;
+                    Ok(linux::permanent_mac(&name).map_or(mac, |mac| mac.to_string()))
+                }
+            }
+        }
+
+        // 启用 Wi-Fi 私有地址时 ifconfig 显示按网络生成的地址，硬件地址以 networksetup 为准
+        #[cfg(target_os = "macos")]
+        {
+            let (name, mac) = Self::first_ether(&self.run_text("ifconfig", &[])?, "ether ")?;
+            let ports = self
+                .run_text("networksetup", &["-listallhardwareports"])
+                .map(|output| parsers::parse_hardware_ports(&output))
+                .unwrap_or_default();
+            Ok(ports.into_iter().find(|(_, device, _)| *device == name).map_or(mac, |(_, _, permanent)| permanent))
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            // 名称排序后的第一块以太网卡
+            Ok(bsd::ethernet_interfaces()?.into_iter().next().map(|interface| interface.mac).unwrap_or_default())
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            android::wlan_mac()
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            let output = self.run_text("dladm", &["show-phys", "-m", "-p", "-o", "link,address"])?;
+            Ok(illumos::dladm_macs(&output).into_iter().next().map(|(_, mac)| mac).unwrap_or_default())
+        }
+    }
+
+    // ip link show / ifconfig 中第一个不属于虚拟接口的 (接口名, MAC)
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn first_ether(output: &str, marker: &str) -> Result<(String, String), HardwareError> {
+        parsers::parse_interface_ethers(output, marker)
+            .into_iter()
+            .find(|(name, _)| !HardwareInfo::is_virtual_interface(name))
+            .ok_or_else(|| HardwareError::ParseError(format!("no '{}' line of a physical interface in output", marker.trim())))
+    }
+
+    // BIOS 版本获取
+    fn get_bios_version(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.wmi_value("Win32_BIOS", "Version")
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.dmi_id("bios_version", SmbiosTable::bios_version)
+        }
+
+        // Intel Mac 为 "Boot ROM Version"；Apple Silicon 上没有这一行，改为 "System Firmware Version"（iBoot 版本）
+        #[cfg(target_os = "macos")]
+        {
+            let output = self.system_profiler("SPHardwareDataType")?;
+            parsers::parse_labeled_value(&output, "Boot ROM Version")
+                .or_else(|_| parsers::parse_labeled_value(&output, "System Firmware Version"))
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            self.bsd_value(bsd::Field::BiosVersion)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            self.android_property(&["ro.bootloader"])
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            self.illumos_smbios("SMB_TYPE_BIOS", "Version String")
+        }
+    }
+
+    // 主板制造商获取
+    fn get_motherboard_manufacturer(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.wmi_value("Win32_BaseBoard", "Manufacturer")
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.dmi_id("board_vendor", SmbiosTable::baseboard_manufacturer)
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            Ok(String::from("Apple Inc."))
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            self.bsd_value(bsd::Field::BoardVendor)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            self.android_property(&["ro.product.manufacturer"])
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            self.illumos_smbios("SMB_TYPE_BASEBOARD", "Manufacturer")
+        }
+    }
+
+    // 主板产品名称获取
+    fn get_motherboard_product_name(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.wmi_value("Win32_BaseBoard", "Product")
+        }
+
+        // 单板机取设备树的 model（"Raspberry Pi 4 Model B Rev 1.4"）
+        #[cfg(target_os = "linux")]
+        {
+            self.dmi_id("board_name", SmbiosTable::baseboard_product)
+                .or_else(|e| self.device_tree_attribute("model").ok_or(e))
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            let output = self.system_profiler("SPHardwareDataType")?;
+            parsers::parse_labeled_value(&output, "Model Identifier")
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            self.bsd_value(bsd::Field::BoardProduct)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            self.android_property(&["ro.product.board"])
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            self.illumos_smbios("SMB_TYPE_BASEBOARD", "Product")
+        }
+    }
+
+    // BIOS 供应商获取
+    fn get_bios_vendor(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.wmi_value("Win32_BIOS", "Manufacturer")
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.dmi_id("bios_vendor", SmbiosTable::bios_vendor)
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            Ok(String::from("Apple Inc."))
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            self.bsd_value(bsd::Field::BiosVendor)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            android::unsupported("BIOS vendor")
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            self.illumos_smbios("SMB_TYPE_BIOS", "Vendor")
+        }
+    }
+
+    // BIOS 发布日期获取
+    fn get_bios_release_date(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.wmi_value("Win32_BIOS", "ReleaseDate")
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.dmi_id("bios_date", SmbiosTable::bios_release_date)
+        }
+
+        // system_profiler 与 ioreg 都不提供固件的发布日期
+        #[cfg(target_os = "macos")]
+        {
+            Err(HardwareError::UnsupportedSystem("BIOS release date on macOS".to_string()))
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            self.bsd_value(bsd::Field::BiosDate)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            android::unsupported("BIOS release date")
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            self.illumos_smbios("SMB_TYPE_BIOS", "Release Date")
+        }
+    }
+
+    // 内存序列号获取
+    fn get_memory_modules(&self) -> Result<Vec<String>, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            self.wmi_rows("Win32_PhysicalMemory", "SerialNumber")
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            // 优先直接解析 SMBIOS 表，读不到时再用 dmidecode（同样需要 root 权限）
+            if let Some(table) = self.smbios() {
+                return Ok(table.memory_serials());
+            }
+            let output = self.run_text("sudo", &["-n", "dmidecode", "-t", "memory"]).map_err(|e| match e {
+                HardwareError::CommandError(message) if parsers::is_access_denied(&message) => {
+                    HardwareError::PermissionDenied("dmidecode -t memory".to_string())
+                }
+                e => e,
+            })?;
+            Ok(parsers::parse_labeled_values(&output, "Serial Number:"))
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            let output = self.system_profiler("SPMemoryDataType")?;
+            Ok(parsers::parse_labeled_values(&output, "Serial Number:"))
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            Err(HardwareError::UnsupportedSystem(format!("memory serials on {}", std::env::consts::OS)))
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            android::unsupported("memory serials")
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            Err(HardwareError::UnsupportedSystem(format!("memory serials on {}", std::env::consts::OS)))
+        }
+    }
+
+    // 显卡：型号与厂商，NVIDIA 显卡另取 nvidia-smi 的 GPU UUID；没有显卡时返回空列表
+    fn get_gpus(&self) -> Result<Vec<GpuInfo>, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            // 未装驱动时的基本显示适配器与远程桌面的虚拟适配器随驱动和会话出现、消失，不算作显卡
+            let mut nvidia = self.nvidia_gpus();
+            Ok(self
+                .wmi_rows("Win32_VideoController", "Name")?
+                .into_iter()
+                .filter(|name| !name.starts_with("Microsoft Basic Display") && !name.contains("Remote Display"))
+                .map(|model| {
+                    let uuid = nvidia
+                        .iter()
+                        .position(|(_, name, _)| *name == model)
+                        .map(|index| nvidia.remove(index).2);
+                    GpuInfo { vendor: parsers::gpu_vendor(&model), model, uuid }
+                })
+                .collect())
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            // 只取 PCI 显卡（有 vendor 属性），排除 simpledrm 等固件帧缓冲；同一设备可能有多个 card 节点
+            let mut nvidia = self.nvidia_gpus();
+            let mut addresses = Vec::new();
+            let mut gpus = Vec::new();
+            let cards = HardwareInfo::list_sys_dir("/sys/class/drm").unwrap_or_default();
+            for card in cards.iter().filter(|card| card.strip_prefix("card").is_some_and(|n| n.bytes().all(|b| b.is_ascii_digit()))) {
+                let device = format!("/sys/class/drm/{}/device", card);
+                let Some(vendor_id) = self.sys.attribute(&format!("{}/vendor", device)) else {
+                    continue;
+                };
+                let Some(address) = std::fs::canonicalize(&device)
+                    .ok()
+                    .and_then(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+                else {
+                    continue;
+                };
+                if addresses.contains(&address) {
+                    continue;
+                }
+
+                // lspci 给出可读的名称；没有安装 pciutils 时退回 sysfs 中的厂商与设备 ID
+                let (vendor, model) = self
+                    .run_text("lspci", &["-mm", "-s", &address])
+                    .ok()
+                    .and_then(|output| parsers::parse_lspci_mm(&output))
+                    .map(|(vendor, model)| (parsers::gpu_vendor(&vendor), model))
+                    .unwrap_or_else(|| {
+                        let device_id = self.sys.attribute(&format!("{}/device", device)).unwrap_or_default();
+                        (parsers::pci_vendor_name(&vendor_id), device_id.trim_start_matches("0x").to_string())
+                    });
+                let uuid = nvidia
+                    .iter()
+                    .position(|(bus, _, _)| parsers::pci_bus_address(bus) == parsers::pci_bus_address(&address))
+                    .map(|index| nvidia.remove(index).2);
+                gpus.push(GpuInfo { vendor, model, uuid });
+                addresses.push(address);
+            }
+
+            // 未加载 nvidia-drm 的计算卡没有 DRM 节点，只能从 nvidia-smi 得到
+            gpus.extend(nvidia.into_iter().map(|(_, model, uuid)| GpuInfo {
+                vendor: "NVIDIA".to_string(),
+                model,
+                uuid: Some(uuid),
+            }));
+            Ok(gpus)
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            let output = self.system_profiler("SPDisplaysDataType")?;
+            Ok(parsers::parse_profiler_displays(&output)
+                .into_iter()
+                .map(|(vendor, model)| GpuInfo {
+                    vendor: parsers::gpu_vendor(if vendor.is_empty() { &model } else { &vendor }),
+                    model,
+                    uuid: None,
+                })
+                .collect())
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            Err(HardwareError::UnsupportedSystem(format!("GPU enumeration on {}", std::env::consts::OS)))
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            android::unsupported("GPU enumeration")
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            Err(HardwareError::UnsupportedSystem(format!("GPU enumeration on {}", std::env::consts::OS)))
+        }
+    }
+
+    // 根文件系统的 UUID（Windows 上为系统卷的卷序列号）；重新格式化后会改变
+    fn get_root_fs_uuid(&self) -> Result<String, HardwareError> {
+        #[cfg(target_os = "windows")]
+        {
+            use winapi::um::fileapi::GetVolumeInformationW;
+
+            let drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+            let root: Vec<u16> = format!("{}\\", drive).encode_utf16().chain(std::iter::once(0)).collect();
+            let mut serial = 0u32;
+            // SAFETY: root 以 NUL 结尾；不需要的输出缓冲区传空指针，长度为 0
+            let ok = unsafe {
+                GetVolumeInformationW(
+                    root.as_ptr(),
+                    std::ptr::null_mut(),
+                    0,
+                    &mut serial,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                    0,
+                )
+            };
+            if ok == 0 {
+                return Err(HardwareError::Io(std::io::Error::last_os_error()));
+            }
+            Ok(format!("{:04X}-{:04X}", serial >> 16, serial & 0xFFFF))
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(uuid) = self
+                .run_text("findmnt", &["-no", "UUID", "/"])
+                .ok()
+                .map(|output| output.trim().to_string())
+                .filter(|uuid| !uuid.is_empty())
+            {
+                return Ok(uuid);
+            }
+
+            // 没有 findmnt 或根为 overlay 时自行解析：overlay 取 upperdir 所在的文件系统，
+            // btrfs 子卷的挂载源即底层设备；再在 /dev/disk/by-uuid 中反查该设备
+            let mountinfo = HardwareInfo::read_sys_file("/proc/self/mountinfo")?;
+            let missing = || HardwareError::MissingField("root_fs_uuid".to_string());
+            let (fs_type, mut source, options) = parsers::parse_mount_for(&mountinfo, "/").ok_or_else(missing)?;
+            if fs_type == "overlay" {
+                let upper = options.split(',').find_map(|option| option.strip_prefix("upperdir=")).ok_or_else(missing)?;
+                source = parsers::parse_mount_for(&mountinfo, upper).ok_or_else(missing)?.1;
+            }
+            let device = std::fs::canonicalize(&source).map_err(|_| missing())?;
+            HardwareInfo::list_sys_dir("/dev/disk/by-uuid")?
+                .into_iter()
+                .find(|uuid| std::fs::canonicalize(format!("/dev/disk/by-uuid/{}", uuid)).is_ok_and(|path| path == device))
+                .ok_or_else(missing)
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            let output = self.run_text("diskutil", &["info", "/"])?;
+            parsers::parse_labeled_value(&output, "Volume UUID")
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            Err(HardwareError::UnsupportedSystem(format!("root filesystem UUID on {}", std::env::consts::OS)))
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            fallback::unsupported()
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            android::unsupported("root filesystem UUID")
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            Err(HardwareError::UnsupportedSystem(format!("root filesystem UUID on {}", std::env::consts::OS)))
+        }
+    }
+
+    // 虚拟化平台（KVM、VMware、Hyper-V 等）；物理机或无法判断时为 None
+    fn detect_virtualization(&self) -> Option<String> {
+        #[cfg(target_os = "windows")]
+        {
+            let manufacturer = self.wmi_value("Win32_ComputerSystem", "Manufacturer").unwrap_or_default();
+            let model = self.wmi_value("Win32_ComputerSystem", "Model").unwrap_or_default();
+            parsers::hypervisor_name(&format!("{} {}", manufacturer, model)).map(str::to_string)
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            // 未知厂商的 CPUID 签名原样保留
+            if let Some(signature) = CpuIdentity::hypervisor_signature() {
+                return Some(parsers::hypervisor_name(&signature).map_or(signature, str::to_string));
+            }
+            ["/sys/hypervisor/type", "/sys/class/dmi/id/sys_vendor", "/sys/class/dmi/id/product_name"]
+                .iter()
+                .filter_map(|path| HardwareInfo::read_sys_file(path).ok())
+                .find_map(|value| parsers::hypervisor_name(&value))
+                .map(str::to_string)
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            let sysctl = |name: &str| self.run_text("sysctl", &["-n", name]).unwrap_or_default();
+            let in_vm = sysctl("kern.hv_vmm_present").trim() == "1"
+                || sysctl("machdep.cpu.features").split_whitespace().any(|feature| feature == "VMM");
+            in_vm.then(|| parsers::hypervisor_name(&sysctl("hw.model")).unwrap_or("unknown").to_string())
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            if let Some(signature) = CpuIdentity::hypervisor_signature() {
+                return Some(parsers::hypervisor_name(&signature).map_or(signature, str::to_string));
+            }
+            [bsd::Field::BoardVendor, bsd::Field::BoardProduct]
+                .into_iter()
+                .filter_map(|field| self.bsd_value(field).ok())
+                .find_map(|value| parsers::hypervisor_name(&value))
+                .map(str::to_string)
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            None
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            // 模拟器（基于 QEMU）设置 ro.kernel.qemu
+            (android::property("ro.kernel.qemu").as_deref() == Some("1")).then(|| "QEMU".to_string())
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            if let Some(signature) = CpuIdentity::hypervisor_signature() {
+                return Some(parsers::hypervisor_name(&signature).map_or(signature, str::to_string));
+            }
+            ["Manufacturer", "Product"]
+                .into_iter()
+                .filter_map(|label| self.illumos_smbios("SMB_TYPE_SYSTEM", label).ok())
+                .find_map(|value| parsers::hypervisor_name(&value))
+                .map(str::to_string)
+        }
+    }
+
+    // WSL 中经互操作调用 Windows 的 reg.exe 读取宿主机的 MachineGuid
+    #[cfg(target_os = "linux")]
+    fn get_wsl_host_machine_guid(&self) -> Result<String, HardwareError> {
+        let output = self.run_text(
+            "cmd.exe",
+            &["/c", "reg", "query", r"HKLM\SOFTWARE\Microsoft\Cryptography", "/v", "MachineGuid"],
+        )?;
+        parsers::parse_reg_query_value(&output, "MachineGuid")
+            .ok_or_else(|| HardwareError::ParseError("no MachineGuid in reg query output".to_string()))
+    }
+
+    // TPM 状态；读不到任何信息时视为没有 TPM
+    fn get_tpm(&self) -> Option<TpmInfo> {
+        #[cfg(target_os = "windows")]
+        {
+            // 两条命令都需要管理员权限；背书密钥读不到时再用 Win32_Tpm 判断是否存在
+            let powershell = |script: &str| {
+                self.run_text("powershell", &["-NoProfile", "-NonInteractive", "-Command", script])
+                    .map(|output| output.trim().to_string())
+                    .inspect_err(|e| debug!("TPM query failed ({})", e))
+                    .ok()
+                    .filter(|output| !output.is_empty())
+            };
+            let ek_pub_hash = powershell("(Get-TpmEndorsementKeyInfo -HashAlgorithm Sha256).PublicKeyHash")
+                .filter(|hash| hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit()))
+                .map(|hash| hash.to_ascii_lowercase());
+            let present = ek_pub_hash.is_some()
+                || powershell("Get-CimInstance -Namespace root/cimv2/Security/MicrosoftTpm -ClassName Win32_Tpm").is_some();
+            Some(TpmInfo { present, ek_pub_hash })
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            // 0x81010001 为 TCG 规定的 RSA 背书密钥持久句柄；需要 tpm2-tools 且能访问 /dev/tpmrm0
+            let present = std::path::Path::new("/sys/class/tpm/tpm0").exists();
+            let ek_pub_hash = if present {
+                self.run_text("tpm2_readpublic", &["-c", "0x81010001"])
+                    .inspect_err(|e| debug!("tpm2_readpublic failed ({})", e))
+                    .ok()
+                    .and_then(|output| parsers::parse_tpm2_name_sha256(&output))
+            } else {
+                None
+            };
+            Some(TpmInfo { present, ek_pub_hash })
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            None
+        }
+
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            None
+        }
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "illumos",
+            target_os = "solaris",
+            all(target_os = "android", feature = "android")
+    )))]
+        {
+            None
+        }
+
+        #[cfg(all(target_os = "android", feature = "android"))]
+        {
+            None
+        }
+
+        #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+        {
+            None
+        }
+    }
+
+    // nvidia-smi 列出的 (PCI 地址, 名称, UUID)；没有 NVIDIA 驱动时为空
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    fn nvidia_gpus(&self) -> Vec<(String, String, String)> {
+        let output = self
+            .run_text("nvidia-smi", &["--query-gpu=pci.bus_id,name,uuid", "--format=csv,noheader"])
+            .unwrap_or_default();
+        parsers::parse_csv_rows(&output)
+            .into_iter()
+            .filter_map(|row| match <[String; 3]>::try_from(row) {
+                Ok([bus, name, uuid]) if !uuid.is_empty() => Some((bus, name, uuid)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::fingerprint::MacSelection;
+    use crate::progress::ProgressStatus;
+    use crate::warning::{Warning, WarningId};
+
+    // 固定的合成采集结果（台式机：两块磁盘、两块网卡），各模块的测试共用
+    pub(crate) fn sample() -> HardwareInfo {
+        sample_with(|_| {})
+    }
+
+    // 先修改 JSON 再反序列化，用于构造私有字段不同的采集结果
+    pub(crate) fn sample_with(edit: impl FnOnce(&mut serde_json::Value)) -> HardwareInfo {
+        let mut value = serde_json::from_str(include_str!("../tests/fixtures/hardware_info.json")).unwrap();
+        edit(&mut value);
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn screening_report(rules: Vec<PlaceholderRule>) -> CollectionReport {
+        CollectionReport { placeholder_rules: rules, ..Default::default() }
+    }
+
+    #[test]
+    fn extra_placeholder_rules_clear_the_field_and_are_reported() {
+        let mut report = screening_report(vec![PlaceholderRule::case_insensitive("SN-OEM-DUMMY")]);
+        assert_eq!(report.screen("motherboard_serial", "sn-oem-dummy".to_string()), "");
+        assert_eq!(report.screen("disk_serial", "S4GNNF0N123456".to_string()), "S4GNNF0N123456");
+        assert_eq!(report.placeholder_matches(), [("motherboard_serial", "case-insensitive:\"sn-oem-dummy\"".to_string())]);
+        assert!(report.is_complete());
+    }
+
+    #[test]
+    fn built_in_placeholders_are_cleared_before_the_extra_rules() {
+        let mut report = screening_report(vec![PlaceholderRule::exact("To be filled by O.E.M.")]);
+        let value = HardwareInfo::screen_placeholder("motherboard_serial", "To be filled by O.E.M.".to_string());
+        assert_eq!(report.screen("motherboard_serial", value), "");
+        // 内置规则已经清空，不记为追加规则命中
+        assert!(report.placeholder_matches().is_empty());
+    }
+
+    // 采集时序列号、UUID 与型号中单独的 "0" 按未填写清空
+    #[test]
+    fn a_zero_serial_is_cleared_when_collected() {
+        assert_eq!(HardwareInfo::screen_placeholder("motherboard_serial", "0".to_string()), "");
+        assert_eq!(HardwareInfo::screen_duplicate("motherboard_uuid", " 0 ".to_string()), "");
+        assert_eq!(HardwareInfo::screen_optional("system_serial", Some("0".to_string())), None);
+        assert_eq!(HardwareInfo::screen_placeholder("disk_serial", "0x5000c500a1b2c3d4".to_string()), "0x5000c500a1b2c3d4");
+    }
+
+    // 读到占位值不是采集错误（宽松采集的报告中没有它），是否还能生成唯一码由关键字段检查决定
+    #[test]
+    fn placeholder_serial_is_missing_but_not_a_collection_error() {
+        let info = sample_with(|value| value["board"]["serial"] = "Default string".into());
+        let info = HardwareInfo::require_fields(info, CollectionReport::default()).unwrap();
+        assert!(matches!(
+            FingerprintBuilder::new().hash(&info),
+            Err(HardwareError::MissingField(field)) if field == "motherboard_serial"
+        ));
+    }
+
+    #[test]
+    fn contradictory_options_are_rejected() {
+        let cases = [
+            (CollectOptions::new().with_timeout(Duration::ZERO), OptionsError::ZeroTimeout),
+            (
+                CollectOptions::new().with_timeout(Duration::from_secs(3600)),
+                OptionsError::TimeoutTooLong { timeout: Duration::from_secs(3600), max: CollectOptions::MAX_TIMEOUT },
+            ),
+            (CollectOptions::new().with_android_id("a b"), OptionsError::InvalidAndroidId),
+        ];
+        for (options, expected) in cases {
+            assert_eq!(options.validate(), Err(expected));
+        }
+    }
+
+    #[test]
+    fn options_without_effect_are_warnings() {
+        let validated = CollectOptions::new()
+            .with_android_id("9774d56d682e549c")
+            .with_components(ComponentSet::empty())
+            .validate()
+            .unwrap();
+        assert!(validated.warnings().iter().any(|warning| warning.contains("no component is selected")));
+        assert_eq!(
+            validated.warnings().iter().any(|warning| warning.starts_with("android_id")),
+            !cfg!(all(target_os = "android", feature = "android"))
+        );
+        assert!(CollectOptions::default().validate().unwrap().warnings().iter().all(|warning| !warning.contains("component")));
+    }
+
+    #[test]
+    fn options_summary_names_the_effective_settings() {
+        let summary = CollectOptions::new()
+            .with_timeout(Duration::from_millis(1500))
+            .with_parallel_probes(false)
+            .with_components(ComponentSet::empty().with(Component::MotherboardUuid).with(Component::DiskSerial))
+            .with_android_id("9774d56d682e549c")
+            .validate()
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            summary,
+            "timeout=1.5s parallel_probes=off components=motherboard_uuid+disk_serial wsl_host_identity=off \
+             android_id=set placeholder_rules=0"
+        );
+        assert!(!summary.contains("9774d56d682e549c"));
+    }
+
+    #[test]
+    fn invalid_options_fail_strict_collection_without_probing() {
+        let options = CollectOptions::new().with_timeout(Duration::ZERO);
+        assert!(matches!(
+            HardwareInfo::collect_with(&options),
+            Err(HardwareError::InvalidOptions(OptionsError::ZeroTimeout))
+        ));
+        let (info, report) = HardwareInfo::collect_lenient_with(&options);
+        assert_eq!(report.errors().len(), 1);
+        assert_eq!(report.errors()[0].0, "options");
+        assert!(report.options().is_empty());
+        assert!(info.motherboard_uuid().is_empty() && info.disks().is_empty());
+    }
 
-impl HardwareInfo {
-    pub fn collect() -> Result<Self, Box<dyn Error>> {
-        let mut sys = System::new_all();
-        sys.refresh_all();
+    // ioreg 失败、其余命令在 delay 之后输出 program 的名字
+    struct ScriptedRunner {
+        delay: Duration,
+    }
 
-        let mut network_interfaces = Vec::new();
-        for (name, network) in sys.networks() {
-            if Self::is_primary_interface(name) {
-                let mac = network.mac_address().to_string();
-                if !mac.is_empty() && mac != "00:00:00:00:00:00" {
-                    network_interfaces.push(NetworkInfo {
-                        name: name.to_string(),
-                        mac_address: mac,
-                        is_up: true,
-                        interface_type: Self::detect_interface_type(name),
-                    });
-                }
+    impl CommandRunner for ScriptedRunner {
+        fn run(&self, program: &str, _args: &[&str]) -> Result<std::process::Output, HardwareError> {
+            std::thread::sleep(self.delay);
+            match program {
+                "ioreg" => Err(HardwareError::CommandError("ioreg: not found".to_string())),
+                _ => Ok(std::process::Output {
+                    status: std::process::ExitStatus::default(),
+                    stdout: program.as_bytes().to_vec(),
+                    stderr: Vec::new(),
+                }),
             }
         }
+    }
+
+    fn recorded(options: CollectOptions) -> (CollectOptions, std::sync::Arc<std::sync::Mutex<Vec<ProgressEvent>>>) {
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = std::sync::Arc::clone(&events);
+        (options.on_progress(move |event| sink.lock().unwrap().push(event.clone())), events)
+    }
+
+    #[test]
+    fn prefetch_reports_every_command_once_and_reaches_the_total() {
+        let (options, events) = recorded(CollectOptions::new());
+        let runner = ScriptedRunner { delay: Duration::from_millis(20) };
+        let command = |program: &'static str, args: &[&str]| (program, args.iter().map(|arg| arg.to_string()).collect());
+        let ahead: Vec<ProbeCommandLine> =
+            vec![command("system_profiler", &["SPHardwareDataType"]), command("ioreg", &["-rd1"]), command("sysctl", &[])];
+        let skipped = vec![command("diskutil", &["list", "physical"])];
+        let progress = HardwareInfo::progress_tracker(&options, &ahead, &skipped);
+        let collector = Collector::new(&runner);
+        collector.prefetch(&ahead, &progress);
 
-        if network_interfaces.len() > 1 {
-            network_interfaces.truncate(1);
+        let events = events.lock().unwrap().clone();
+        assert_eq!(events[0], ProgressEvent::new("diskutil list physical", ProgressStatus::Skipped, 1, 4));
+        for line in ["system_profiler SPHardwareDataType", "ioreg -rd1", "sysctl"] {
+            let statuses: Vec<&ProgressStatus> =
+                events.iter().filter(|event| event.command() == line).map(ProgressEvent::status).collect();
+            assert_eq!(statuses.len(), 2, "{}", line);
+            assert_eq!(statuses[0], &ProgressStatus::Started);
+            match statuses[1] {
+                ProgressStatus::Failed { elapsed, error } => {
+                    assert_eq!(line, "ioreg -rd1");
+                    assert_eq!(error, "Failed to execute command: ioreg: not found");
+                    assert!(*elapsed >= Duration::from_millis(20));
+                }
+                ProgressStatus::Finished(elapsed) => assert!(*elapsed >= Duration::from_millis(20)),
+                other => panic!("{}: unexpected {:?}", line, other),
+            }
         }
+        let done: Vec<usize> = events.iter().filter(|event| event.status() != &ProgressStatus::Started).map(ProgressEvent::done).collect();
+        assert_eq!(done, [1, 2, 3, 4]);
+        assert!(events.last().unwrap().is_last());
+        // 报告不影响预先执行的结果
+        assert_eq!(collector.run_text("sysctl", &[]).unwrap(), "sysctl");
+    }
 
-        network_interfaces.sort_by(|a, b| a.mac_address.cmp(&b.mac_address));
+    #[test]
+    fn commands_are_skipped_without_parallel_probes() {
+        let options = CollectOptions::new().with_parallel_probes(false);
+        let (ahead, skipped) = HardwareInfo::probe_plan(&options);
+        assert!(ahead.is_empty());
+        assert_eq!(skipped.len(), Collector::probe_commands().len());
 
-        Ok(Self {
-            cpu_info: Self::get_cpu_info()?,
-            motherboard_serial: Self::get_motherboard_serial()?,
-            disk_serial: Self::get_disk_serial()?,
-            mac_address: Self::get_mac_address()?,
-            os_info: format!("{} {}", sys.name().unwrap_or_default(), 
-                                    sys.os_version().unwrap_or_default()),
-            memory_serial: Self::get_memory_serial()?,
-            bios_version: Self::get_bios_version()?,
-            cpu_physical_id: Self::get_cpu_physical_id()?,
-            disk_model: Self::get_disk_model()?,
-            disk_firmware: Self::get_disk_firmware()?,
-            motherboard_uuid: Self::get_motherboard_uuid()?,
-            motherboard_manufacturer: Self::get_motherboard_manufacturer()?,
-            motherboard_product_name: Self::get_motherboard_product_name()?,
-            bios_vendor: Self::get_bios_vendor()?,
-            bios_release_date: Self::get_bios_release_date()?,
-            network_interfaces,
-        })
+        let (ahead, skipped) = HardwareInfo::probe_plan(&CollectOptions::new().with_components(ComponentSet::empty()));
+        assert!(ahead.is_empty());
+        assert_eq!(skipped.len(), Collector::probe_commands().len());
     }
 
-    fn is_primary_interface(name: &str) -> bool {
-        match name {
-            "en0" | "eth0" | "enp0s1" => true,
-            _ => false
-        }
+    #[test]
+    fn progress_callback_does_not_change_the_options() {
+        let (options, _) = recorded(CollectOptions::new().with_timeout(Duration::from_secs(2)));
+        assert_eq!(options, CollectOptions::new().with_timeout(Duration::from_secs(2)));
+        assert_eq!(format!("{:?}", options), format!("{:?}", CollectOptions::new().with_timeout(Duration::from_secs(2))));
     }
 
-    fn detect_interface_type(name: &str) -> String {
-        if name.starts_with("en") || name.starts_with("eth") {
-            "Ethernet".to_string()
-        } else if name.starts_with("wl") || name.starts_with("wifi") {
-            "Wi-Fi".to_string()
-        } else {
-            "Unknown".to_string()
+    // 固定的采集结果与密钥 → 固定的唯一码；摘要即以该密钥对 FingerprintBuilder::hash_input 计算的 HMAC-SHA256
+    #[test]
+    fn keyed_code_matches_the_fixed_vector() {
+        use hmac::{Hmac, Mac};
+
+        let info = sample();
+        let key = b"com.example.product-a";
+        assert_eq!(info.generate_unique_code_keyed(key).unwrap(), "60ce-5595-d036-aaca");
+
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(key).unwrap();
+        mac.update(&FingerprintBuilder::new().hash_input(&info));
+        assert_eq!(info.keyed_digest(key).unwrap(), mac.finalize().into_bytes().to_vec());
+    }
+
+    #[test]
+    fn keyed_codes_differ_per_key_and_from_the_plain_code() {
+        let info = sample();
+        let a = info.generate_unique_code_keyed(b"com.example.product-a").unwrap();
+        let b = info.generate_unique_code_keyed(b"com.example.product-b").unwrap();
+        assert_ne!(a, b);
+        assert_ne!(a, info.generate_unique_code_with(HashAlgorithm::Sha256).unwrap());
+        assert_eq!(a, info.generate_unique_code_keyed(b"com.example.product-a").unwrap());
+    }
+
+    fn disk(name: &str, serial: &str) -> DiskInfo {
+        DiskInfo { name: name.to_string(), model: "model".to_string(), serial: serial.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn root_disk_row_is_split_from_the_enumeration() {
+        let enumerated = vec![disk("sda", "B"), DiskInfo { is_root: true, ..disk("nvme0n1", "A") }];
+        let (row, others) = HardwareInfo::split_root_disk(enumerated, "nvme0n1");
+        assert_eq!(row.map(|row| row.serial), Some("A".to_string()));
+        assert_eq!(others, [disk("sda", "B")]);
+    }
+
+    #[test]
+    fn disk_enumerated_under_another_name_is_not_duplicated() {
+        let root = DiskInfo { is_root: true, ..disk("disk0", "A") };
+        // 同一块磁盘以 disk0 与 disk2 两个名称出现，数据盘 B 也出现了两次
+        let others = vec![disk("disk2", "A"), disk("disk1", "B"), disk("disk3", "B"), disk("disk4", "")];
+        let disks = HardwareInfo::with_root_disk(root.clone(), others);
+        assert_eq!(disks, [disk("disk4", ""), root, disk("disk1", "B")]);
+        assert_eq!(disks.iter().filter(|disk| disk.is_root()).count(), 1);
+    }
+
+    #[test]
+    fn profiler_runs_each_data_type_once_and_caches_failures() {
+        let cache = MacProfilerCache::default();
+        let runs = std::cell::Cell::new(0);
+        let hardware = || {
+            runs.set(runs.get() + 1);
+            Ok("      Model Identifier: Mac14,2\n".to_string())
+        };
+        let memory = || {
+            runs.set(runs.get() + 1);
+            Err(HardwareError::CommandError("system_profiler SPMemoryDataType: timed out after 30s".to_string()))
+        };
+        for _ in 0..3 {
+            assert_eq!(cache.get("SPHardwareDataType", hardware).unwrap(), "      Model Identifier: Mac14,2\n");
+            let error = cache.get("SPMemoryDataType", memory).unwrap_err();
+            assert!(error.to_string().contains("SPMemoryDataType"), "{}", error);
         }
+        assert_eq!(runs.get(), 2);
     }
 
-    pub fn generate_unique_code(&self) -> Result<String, Box<dyn Error>> {
-        if self.motherboard_serial.is_empty() || self.motherboard_uuid.is_empty() {
-            return Err("Critical hardware information missing".into());
+    fn nic(name: &str, mac: &str, is_primary: bool) -> NetworkInfo {
+        let mac: MacAddress = mac.parse().unwrap();
+        NetworkInfo {
+            name: name.to_string(),
+            mac_address: mac,
+            current_mac_address: mac,
+            is_up: true,
+            interface_type: NetworkInterfaceType::Ethernet,
+            is_primary,
         }
+    }
 
-        let mut hasher = Md5::new();
+    fn with_interfaces(interfaces: &[NetworkInfo]) -> HardwareInfo {
+        sample_with(|value| value["network_interfaces"] = serde_json::to_value(interfaces).unwrap())
+    }
 
-        hasher.update(self.motherboard_serial.as_bytes());
-        hasher.update(self.motherboard_uuid.as_bytes());
+    // 白名单同时命中两块网卡（eth0、en0）：无论枚举顺序如何，都先排序再只保留一块主网卡
+    #[test]
+    fn interfaces_are_sorted_before_the_primary_is_kept() {
+        let forward = || vec![nic("eth0", "d8:9e:f3:12:34:56", true), nic("en0", "a4:34:d9:65:43:21", true)];
+        let mut first = forward();
+        let mut second: Vec<NetworkInfo> = forward().into_iter().rev().collect();
+        HardwareInfo::order_interfaces(&mut first, false);
+        HardwareInfo::order_interfaces(&mut second, false);
+        assert_eq!(serde_json::to_value(&first).unwrap(), serde_json::to_value(&second).unwrap());
+        let primary: Vec<&str> = first.iter().filter(|nic| nic.is_primary()).map(NetworkInfo::name).collect();
+        assert_eq!(primary, ["en0"]);
 
-        for interface in &self.network_interfaces {
-            if interface.is_up && !interface.mac_address.is_empty() {
-                hasher.update(interface.mac_address.as_bytes());
-            }
+        let builder = FingerprintBuilder::new().macs(MacSelection::Primary);
+        assert_eq!(
+            builder.hash(&with_interfaces(&first)).unwrap(),
+            builder.hash(&with_interfaces(&second)).unwrap()
+        );
+    }
+
+    // is_up 只供显示：拔掉网线或 ip link set down 之后唯一码不变
+    #[test]
+    fn interface_state_does_not_affect_the_code() {
+        let set_up = |up: bool| {
+            sample_with(|value| {
+                for interface in value["network_interfaces"].as_array_mut().unwrap() {
+                    interface["is_up"] = up.into();
+                }
+            })
+        };
+        for builder in [FingerprintBuilder::new(), FingerprintBuilder::new().macs(MacSelection::Primary)] {
+            assert_eq!(builder.hash(&set_up(false)).unwrap(), builder.hash(&set_up(true)).unwrap());
         }
+    }
 
-        let secondary_info = format!("{}:{}:{}",
-            self.cpu_physical_id,
-            self.motherboard_product_name,
-            self.disk_model
+    // 哈希永久地址：NetworkManager 每次连接随机生成的当前地址不影响唯一码，两者都输出
+    #[test]
+    fn randomized_current_mac_does_not_affect_the_code() {
+        let randomized = sample_with(|value| {
+            value["network_interfaces"][1]["current_mac_address"] = "5a:1e:7c:33:90:0d".into();
+        });
+        assert_eq!(
+            FingerprintBuilder::new().hash(&randomized).unwrap(),
+            FingerprintBuilder::new().hash(&sample()).unwrap()
         );
-        hasher.update([0xFF]);
-        hasher.update(secondary_info.as_bytes());
+        let wifi = &randomized.network_interfaces()[1];
+        assert_eq!(wifi.mac_address().to_string(), "a4:34:d9:65:43:21");
+        assert!(wifi.current_mac_address().is_locally_administered());
+        let json = serde_json::to_value(wifi).unwrap();
+        assert_eq!(json["mac_address"], "a4:34:d9:65:43:21");
+        assert_eq!(json["current_mac_address"], "5a:1e:7c:33:90:0d");
 
-        let result = hasher.finalize();
-        let hash = hex::encode(result);
+        // 旧版本的结果没有当前地址
+        let old: NetworkInfo = serde_json::from_value(serde_json::json!({
+            "name": "eth0", "mac_address": "d8:9e:f3:12:34:56", "is_up": true, "interface_type": "Ethernet"
+        }))
+        .unwrap();
+        assert!(old.current_mac_address().is_zero());
+    }
 
-        Ok(format!("{}-{}-{}-{}", 
-            &hash[0..4], 
-            &hash[4..8], 
-            &hash[8..12], 
-            &hash[12..16]
-        ))
+    // 接上扩展坞、插入 USB 网卡或拔掉全部网卡，不含 MAC 的唯一码都不变；网卡仍在采集结果中
+    #[test]
+    fn no_mac_code_ignores_added_and_removed_interfaces() {
+        let no_mac = FingerprintBuilder::new().with_mac(false);
+        let code = no_mac.hash(&sample()).unwrap();
+        let docked = {
+            let mut interfaces = sample().network_interfaces;
+            interfaces.push(nic("enx00e04c680a51", "00:e0:4c:68:0a:51", false));
+            with_interfaces(&interfaces)
+        };
+        assert_eq!(docked.network_interfaces().len(), 3);
+        assert_eq!(no_mac.hash(&docked).unwrap(), code);
+        assert_eq!(no_mac.hash(&with_interfaces(&[])).unwrap(), code);
+        assert_ne!(FingerprintBuilder::new().hash(&docked).unwrap(), FingerprintBuilder::new().hash(&sample()).unwrap());
     }
 
-    #[cfg(target_os = "windows")]
-    fn get_cpu_physical_id() -> Result<String, Box<dyn Error>> {
-        use std::process::Command;
-        let output = Command::new("wmic")
-            .args(&["cpu", "get", "processorid"])
-            .output()?;
-        let id = String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .nth(1)
-            .unwrap_or("")
-            .trim()
-            .to_string();
-        Ok(id)
+    // sysinfo 每次运行的枚举顺序不同：六种顺序保留全部网卡，哈希输入完全相同
+    #[test]
+    fn every_enumeration_order_hashes_the_same_interfaces() {
+        let nics = [
+            ("eno1", "d8:9e:f3:12:34:56", true),
+            ("enp3s0", "b4:96:91:aa:bb:cc", false),
+            ("wlp2s0", "a4:34:d9:65:43:21", false),
+        ];
+        let orders = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+        let inputs: Vec<Vec<u8>> = orders
+            .iter()
+            .map(|order| {
+                let mut interfaces: Vec<NetworkInfo> =
+                    order.iter().map(|&i| nic(nics[i].0, nics[i].1, nics[i].2)).collect();
+                HardwareInfo::order_interfaces(&mut interfaces, false);
+                let info = with_interfaces(&interfaces);
+                assert_eq!(info.network_interfaces().len(), 3);
+                FingerprintBuilder::new().hash_input(&info)
+            })
+            .collect();
+        assert!(inputs.iter().all(|input| *input == inputs[0]));
+        let input = String::from_utf8_lossy(&inputs[0]);
+        for (_, mac, _) in nics {
+            assert!(input.contains(mac), "{}", input);
+        }
     }
 
-    #[cfg(target_os = "windows")]
-    fn get_disk_model() -> Result<String, Box<dyn Error>> {
-        use std::process::Command;
-        let output = Command::new("wmic")
-            .args(&["diskdrive", "get", "model"])
-            .output()?;
-        let model = String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .nth(1)
-            .unwrap_or("")
-            .trim()
-            .to_string();
-        Ok(model)
+    #[test]
+    fn offline_image_yields_the_os_identifiers() {
+        let image = crate::offline::tests::FakeImage::debian();
+        let (info, report) = HardwareInfo::collect_offline(image.path()).unwrap();
+        assert_eq!(info.collection_mode(), CollectionMode::Offline);
+        assert_eq!(info.machine_id(), Some("4c4c4544004a3810803fb7c04f513532"));
+        assert_eq!(info.machine_identity().source(), IdentitySource::MachineId);
+        assert_eq!(info.root_fs_uuid(), Some("9f8e7d6c-5b4a-3928-1706-f5e4d3c2b1a0"));
+        assert_eq!(info.os_info(), "Debian GNU/Linux 12");
+        let interfaces: Vec<(&str, NetworkInterfaceType, bool)> = info
+            .network_interfaces()
+            .iter()
+            .map(|interface| (interface.name(), interface.interface_type(), interface.is_primary()))
+            .collect();
+        assert_eq!(
+            interfaces,
+            [("wlan0", NetworkInterfaceType::WiFi, true), ("eth0", NetworkInterfaceType::Ethernet, false)]
+        );
+        assert_eq!(info.mac_address(), "a4:34:d9:65:43:21");
+
+        // DMI 等字段记为不支持，默认方案因缺少主板信息而无法生成唯一码
+        let (field, error) = &report.errors()[0];
+        assert_eq!(*field, "cpu_info");
+        assert!(matches!(error, HardwareError::UnsupportedSystem(_)), "{:?}", error);
+        assert_eq!(report.errors().len(), HardwareInfo::OFFLINE_UNSUPPORTED_FIELDS.len());
+        let default = FingerprintBuilder::new().code(&info, OutputFormat::Grouped, None);
+        assert!(matches!(default, Err(HardwareError::MissingField(_))), "{:?}", default);
+        let offline = FingerprintBuilder::new()
+            .components(ComponentSet::empty().with(Component::MacAddress).with(Component::MachineId))
+            .policy(CriticalFieldPolicy::RequireAny(1));
+        assert!(offline.code(&info, OutputFormat::Grouped, None).is_ok());
+
+        let ids: Vec<WarningId> = crate::warning::warnings(&info, Some(&report)).iter().map(Warning::id).collect();
+        assert_eq!(ids, [WarningId::OfflineImage]);
+        assert_eq!(serde_json::to_value(&info).unwrap()["collection_mode"], "offline");
+        assert_eq!(sample().collection_mode(), CollectionMode::Live);
     }
 
-    #[cfg(target_os = "windows")]
-    fn get_disk_firmware() -> Result<String, Box<dyn Error>> {
-        use std::process::Command;
-        let output = Command::new("wmic")
-            .args(&["diskdrive", "get", "firmwarerevision"])
-            .output()?;
-        let firmware = String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .nth(1)
-            .unwrap_or("")
-            .trim()
-            .to_string();
-        Ok(firmware)
+    #[test]
+    fn offline_windows_images_are_unsupported() {
+        let image = crate::offline::tests::FakeImage::new();
+        image.write("/Windows/System32/config/SYSTEM", "regf");
+        let error = HardwareInfo::collect_offline(image.path()).unwrap_err();
+        assert!(
+            matches!(&error, HardwareError::UnsupportedSystem(message) if message.contains("Windows")),
+            "{:?}",
+            error
+        );
+
+        // 没有任何标识的目录：字段缺失记入报告，不是错误
+        let empty = crate::offline::tests::FakeImage::new();
+        let (info, report) = HardwareInfo::collect_offline(empty.path()).unwrap();
+        assert!(info.network_interfaces().is_empty() && info.machine_id().is_none());
+        assert_eq!(info.machine_identity().source(), IdentitySource::Unavailable);
+        let missing: Vec<&str> = report
+            .errors()
+            .iter()
+            .map(|(field, _)| *field)
+            .filter(|field| !HardwareInfo::OFFLINE_UNSUPPORTED_FIELDS.contains(field))
+            .collect();
+        assert_eq!(missing, ["network_interfaces", "machine_id", "root_fs_uuid"]);
     }
 
-    #[cfg(target_os = "linux")]
-    fn get_cpu_physical_id() -> Result<String, Box<dyn Error>> {
-        use std::fs;
-        let id = fs::read_to_string("/proc/cpuinfo")?
-            .lines()
-            .find(|line| line.starts_with("physical id"))
-            .unwrap_or("")
-            .split(':')
-            .nth(1)
-            .unwrap_or("")
-            .trim()
-            .to_string();
-        Ok(id)
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    #[test]
+    fn alternate_smbios_uuid_is_the_other_byte_order() {
+        assert_eq!(
+            HardwareInfo::alternate_smbios_uuid("4c4c4544-0058-4a10-8038-b7c04f513532").as_deref(),
+            Some("44454c4c-5800-104a-8038-b7c04f513532")
+        );
+        assert_eq!(
+            HardwareInfo::alternate_smbios_uuid("4C4C4544-0058-4A10-8038-B7C04F513532").as_deref(),
+            Some("44454C4C-5800-104A-8038-B7C04F513532")
+        );
+        // 前三段对称时两种读法相同，不另记
+        assert_eq!(HardwareInfo::alternate_smbios_uuid("01010101-0202-0303-0405-060708090a0b"), None);
+        assert_eq!(HardwareInfo::alternate_smbios_uuid(""), None);
+        assert_eq!(HardwareInfo::alternate_smbios_uuid("not-a-uuid"), None);
     }
 
     #[cfg(target_os = "linux")]
-    fn get_disk_model() -> Result<String, Box<dyn Error>> {
-        use std::process::Command;
-        let output = Command::new("lsblk")
-            .args(&["-no", "MODEL"])
-            .output()?;
-        let model = String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .next()
-            .unwrap_or("")
-            .trim()
-            .to_string();
-        Ok(model)
+    #[test]
+    fn missing_sysfs_file_is_a_file_error() {
+        let error = HardwareInfo::read_sys_file("/sys/class/dmi/id/hardware-id-no-such-file").unwrap_err();
+        assert!(matches!(&error, HardwareError::FileError(message) if message.contains("hardware-id-no-such-file")), "{:?}", error);
+        // /sys 之外的文件不读取
+        assert!(matches!(HardwareInfo::read_sys_file("/etc/hostname"), Err(HardwareError::FileError(_))));
     }
 
     #[cfg(target_os = "linux")]
-    fn get_disk_firmware() -> Result<String, Box<dyn Error>> {
-        use std::fs;
-        let firmware = fs::read_to_string("/sys/class/block/sda/device/firmware_rev")?
-            .trim()
-            .to_string();
-        Ok(firmware)
-    }
+    mod mock_collection {
+        use super::*;
+        use crate::command_runner::MockRunner;
 
-    #[cfg(target_os = "macos")]
-    fn get_cpu_physical_id() -> Result<String, Box<dyn Error>> {
-        use std::process::Command;
-        let output = Command::new("sysctl")
-            .args(&["-n", "machdep.cpu.brand_string"])
-            .output()?;
-        let id = String::from_utf8_lossy(&output.stdout)
-            .trim()
-            .to_string();
-        Ok(id)
-    }
+        const DMIDECODE_MEMORY: &str = include_str!("../tests/fixtures/linux/dmidecode_memory.txt");
+        const FINDMNT: &str = "findmnt -no UUID /";
+        const DMIDECODE: &str = "sudo -n dmidecode -t memory";
 
-    #[cfg(target_os = "macos")]
-    fn get_disk_model() -> Result<String, Box<dyn Error>> {
-        use std::process::Command;
-        let output = Command::new("diskutil")
-            .args(&["info", "disk0"])
-            .output()?;
-        let model = String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .find(|line| line.contains("Device / Media Name"))
-            .unwrap_or("")
-            .split(':')
-            .nth(1)
-            .unwrap_or("")
-            .trim()
-            .to_string();
-        Ok(model)
-    }
+        // 不读取本机的 SMBIOS 表，内存序列号只能来自 dmidecode 的输出
+        fn collect(runner: &MockRunner, options: &CollectOptions) -> (HardwareInfo, CollectionReport) {
+            let collector = Collector::new(runner);
+            collector.smbios.set(None).ok();
+            HardwareInfo::collect_from(&collector, options)
+        }
 
-    #[cfg(target_os = "macos")]
-    fn get_disk_firmware() -> Result<String, Box<dyn Error>> {
-        use std::process::Command;
-        let output = Command::new("system_profiler")
-            .args(&["SPNVMeDataType"])
-            .output()?;
-        let firmware = String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .find(|line| line.contains("Firmware Version"))
-            .unwrap_or("")
-            .split(':')
-            .nth(1)
-            .unwrap_or("")
-            .trim()
-            .to_string();
-        Ok(firmware)
-    }
+        fn root_and_memory() -> CollectOptions {
+            CollectOptions::new()
+                .with_components(ComponentSet::empty().with(Component::RootFsUuid).with(Component::MemorySerial))
+        }
 
-    fn get_motherboard_uuid() -> Result<String, Box<dyn Error>> {
-        #[cfg(target_os = "windows")]
-        {
-            use std::process::Command;
-            let output = Command::new("wmic")
-                .args(&["csproduct", "get", "uuid"])
-                .output()?;
-            let uuid = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(uuid)
+        fn errors(report: &CollectionReport) -> Vec<&'static str> {
+            report.errors().iter().map(|(field, _)| *field).collect()
         }
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::fs;
-            let uuid = fs::read_to_string("/sys/class/dmi/id/product_uuid")?
-                .trim()
-                .to_string();
-            Ok(uuid)
+        #[test]
+        fn canned_command_output_is_parsed_into_the_fields() {
+            let runner = MockRunner::new()
+                .with_stdout(FINDMNT, "6f1d2c4e-8a7b-4c3d-9e0f-112233445566\n")
+                .with_stdout(DMIDECODE, DMIDECODE_MEMORY);
+            let (info, report) = collect(&runner, &root_and_memory());
+            assert_eq!(info.root_fs_uuid(), Some("6f1d2c4e-8a7b-4c3d-9e0f-112233445566"));
+            assert_eq!(info.memory_modules(), ["41A2C0F3", "41A2C1D8"]);
+            assert!(errors(&report).is_empty(), "{:?}", report.errors());
+            // 只执行所选组件需要的命令
+            assert_eq!(runner.calls(), [DMIDECODE, FINDMNT]);
         }
 
-        #[cfg(target_os = "macos")]
-        {
-            use std::process::Command;
-            let output = Command::new("system_profiler")
-                .args(&["SPHardwareDataType"])
-                .output()?;
-            let uuid = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .find(|line| line.contains("Hardware UUID"))
-                .unwrap_or("")
-                .split(':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(uuid)
+        #[test]
+        fn a_failing_command_is_reported_against_its_field() {
+            let runner = MockRunner::new()
+                .with_stdout(FINDMNT, "6f1d2c4e-8a7b-4c3d-9e0f-112233445566\n")
+                .with_failure(DMIDECODE, "sudo: a password is required");
+            let (info, report) = collect(&runner, &root_and_memory());
+            assert!(info.memory_modules().is_empty());
+            assert_eq!(info.root_fs_uuid(), Some("6f1d2c4e-8a7b-4c3d-9e0f-112233445566"));
+            let (field, error) = &report.errors()[0];
+            assert_eq!(*field, "memory_modules");
+            assert!(matches!(error, HardwareError::PermissionDenied(_)), "{:?}", error);
         }
-    }
 
-    // CPU 信息获取
-    fn get_cpu_info() -> Result<String, Box<dyn Error>> {
-        #[cfg(target_os = "windows")]
-        {
-            use std::process::Command;
-            let output = Command::new("wmic")
-                .args(&["cpu", "get", "name"])
-                .output()?;
-            let info = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(info)
+        const REG_QUERY: &str = r"cmd.exe /c reg query HKLM\SOFTWARE\Microsoft\Cryptography /v MachineGuid";
+        const REG_QUERY_OUTPUT: &str = "\r\nHKEY_LOCAL_MACHINE\\SOFTWARE\\Microsoft\\Cryptography\r\n    \
+                                        MachineGuid    REG_SZ    6d1a3f2e-8b4c-4d5e-9f6a-7b8c9d0e1f2a\r\n\r\n";
+
+        fn collect_under(
+            environment: Environment,
+            runner: &MockRunner,
+            options: &CollectOptions,
+        ) -> (HardwareInfo, CollectionReport) {
+            let mut collector = Collector::new(runner);
+            collector.environment = environment;
+            let (info, report) = HardwareInfo::collect_from(&collector, options);
+            assert_eq!(info.environment(), environment);
+            assert_eq!(report.environment(), environment);
+            (info, report)
         }
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::fs;
-            let info = fs::read_to_string("/proc/cpuinfo")?
-                .lines()
-                .find(|line| line.starts_with("model name"))
-                .unwrap_or("")
-                .split(':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(info)
+        fn root_and_mac() -> CollectOptions {
+            CollectOptions::new()
+                .with_components(ComponentSet::empty().with(Component::RootFsUuid).with(Component::MacAddress))
         }
 
-        #[cfg(target_os = "macos")]
-        {
-            use std::process::Command;
-            let output = Command::new("sysctl")
-                .args(&["-n", "machdep.cpu.brand_string"])
-                .output()?;
-            let info = String::from_utf8_lossy(&output.stdout)
-                .trim()
-                .to_string();
-            Ok(info)
+        fn ran_reg_query(runner: &MockRunner) -> bool {
+            runner.calls().iter().any(|call| call == REG_QUERY)
         }
-    }
 
-    // 主板序列号获取
-    fn get_motherboard_serial() -> Result<String, Box<dyn Error>> {
-        #[cfg(target_os = "windows")]
-        {
-            use std::process::Command;
-            let output = Command::new("wmic")
-                .args(&["baseboard", "get", "serialnumber"])
-                .output()?;
-            let serial = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(serial)
+        #[test]
+        fn wsl_reads_the_host_machine_guid_only_when_asked() {
+            let runner = MockRunner::new().with_stdout(REG_QUERY, REG_QUERY_OUTPUT);
+            let options = root_and_mac().with_wsl_host_identity(true);
+            let (info, report) = collect_under(Environment::Wsl(WslVersion::Wsl2), &runner, &options);
+            assert_eq!(info.machine_guid(), Some("6d1a3f2e-8b4c-4d5e-9f6a-7b8c9d0e1f2a"));
+            assert_eq!(report.wsl_strategy(), Some(WslStrategy::HostMachineGuid));
+            // WSL2 的虚拟网卡 MAC 每次启动随机生成，不参与
+            assert!(info.network_interfaces().is_empty());
+            assert!(ran_reg_query(&runner), "{:?}", runner.calls());
+
+            let runner = MockRunner::new().with_stdout(REG_QUERY, REG_QUERY_OUTPUT);
+            let (info, report) = collect_under(Environment::Wsl(WslVersion::Wsl1), &runner, &root_and_mac());
+            assert_eq!(info.machine_guid(), None);
+            assert_eq!(report.wsl_strategy(), Some(WslStrategy::GuestOnly));
+            assert!(!ran_reg_query(&runner), "{:?}", runner.calls());
         }
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::fs;
-            let serial = fs::read_to_string("/sys/class/dmi/id/board_serial")?
-                .trim()
-                .to_string();
-            Ok(serial)
+        #[test]
+        fn an_unreachable_wsl_host_falls_back_to_the_guest() {
+            let runner = MockRunner::new().with_failure(REG_QUERY, "cmd.exe: Exec format error");
+            let options = root_and_mac().with_wsl_host_identity(true);
+            let (info, report) = collect_under(Environment::Wsl(WslVersion::Wsl2), &runner, &options);
+            assert_eq!(info.machine_guid(), None);
+            assert_eq!(report.wsl_strategy(), Some(WslStrategy::GuestOnly));
+            assert!(errors(&report).contains(&"machine_guid"), "{:?}", report.errors());
+
+            // 不在 WSL 中时不考虑宿主机
+            let runner = MockRunner::new().with_stdout(REG_QUERY, REG_QUERY_OUTPUT);
+            let (_, report) = collect_under(Environment::Native, &runner, &options);
+            assert_eq!(report.wsl_strategy(), None);
+            assert!(!ran_reg_query(&runner), "{:?}", runner.calls());
         }
 
-        #[cfg(target_os = "macos")]
-        {
-            use std::process::Command;
-            let output = Command::new("system_profiler")
-                .args(&["SPHardwareDataType"])
-                .output()?;
-            let serial = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .find(|line| line.contains("Serial Number"))
-                .unwrap_or("")
-                .split(':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(serial)
+        #[test]
+        fn invalid_options_run_no_commands() {
+            let runner = MockRunner::new().with_stdout(FINDMNT, "6f1d2c4e-8a7b-4c3d-9e0f-112233445566\n");
+            let (info, report) = collect(&runner, &root_and_memory().with_timeout(Duration::ZERO));
+            assert_eq!(info.root_fs_uuid(), None);
+            assert_eq!(errors(&report), ["options"]);
+            assert!(runner.calls().is_empty(), "{:?}", runner.calls());
         }
-    }
 
-    // 磁盘序列号获取
-    fn get_disk_serial() -> Result<String, Box<dyn Error>> {
-        #[cfg(target_os = "windows")]
-        {
-            use std::process::Command;
-            let output = Command::new("wmic")
-                .args(&["diskdrive", "get", "serialnumber"])
-                .output()?;
-            let serial = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(serial)
+        // hash、verify 只采集默认组件：不执行与唯一码无关的命令，哈希输入与完整采集相同
+        #[test]
+        fn collecting_for_the_fingerprint_skips_unhashed_commands() {
+            let runner = || {
+                MockRunner::new()
+                    .with_stdout(FINDMNT, "6f1d2c4e-8a7b-4c3d-9e0f-112233445566\n")
+                    .with_stdout(DMIDECODE, DMIDECODE_MEMORY)
+            };
+            let (full_runner, hashed_runner) = (runner(), runner());
+            let (full, _) = collect(&full_runner, &CollectOptions::new());
+            let (hashed, _) = collect(&hashed_runner, &CollectOptions::new().with_components(ComponentSet::default()));
+            let ran = |runner: &MockRunner, command: &str| runner.calls().iter().any(|call| call == command);
+            assert!(ran(&full_runner, DMIDECODE) && ran(&full_runner, FINDMNT), "{:?}", full_runner.calls());
+            assert!(!ran(&hashed_runner, DMIDECODE) && !ran(&hashed_runner, FINDMNT), "{:?}", hashed_runner.calls());
+            assert!(hashed.memory_modules().is_empty());
+            assert_eq!(hashed.root_fs_uuid(), None);
+
+            let fingerprint = FingerprintBuilder::new();
+            assert_eq!(fingerprint.hash_input(&hashed), fingerprint.hash_input(&full));
+            // 关键字段读不到（如在容器中）时两者都无法生成唯一码
+            assert_eq!(fingerprint.hash(&hashed).ok(), fingerprint.hash(&full).ok());
+            if let Ok(code) = fingerprint.hash(&full) {
+                assert!(fingerprint.verify(&hashed, &code).unwrap());
+            }
         }
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::process::Command;
-            let output = Command::new("udevadm")
-                .args(&["info", "--query=property", "--name=/dev/sda"])
-                .output()?;
-            let serial = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .find(|line| line.starts_with("ID_SERIAL="))
-                .unwrap_or("")
-                .split('=')
-                .nth(1)
-                .unwrap_or("")
-                .to_string();
-            Ok(serial)
+        const LSBLK: &str = "lsblk -no MODEL";
+
+        // 模拟的 /sys 中没有任何磁盘：硬盘型号只能来自 lsblk（非可选字段），磁盘列表（可选字段）为空
+        fn collect_without_sys(runner: &MockRunner, options: &CollectOptions) -> (HardwareInfo, CollectionReport) {
+            let mut collector = Collector::new(runner);
+            collector.smbios.set(None).ok();
+            collector.sys = crate::linux::tests::FakeSys::new().sys();
+            HardwareInfo::collect_from(&collector, options)
         }
 
-        #[cfg(target_os = "macos")]
-        {
-            use std::process::Command;
-            let output = Command::new("diskutil")
-                .args(&["info", "disk0"])
-                .output()?;
-            let serial = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .find(|line| line.contains("Serial Number"))
-                .unwrap_or("")
-                .split(':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(serial)
+        fn disk_root_and_memory() -> CollectOptions {
+            CollectOptions::new().with_components(
+                ComponentSet::empty().with(Component::DiskModel).with(Component::RootFsUuid).with(Component::MemorySerial),
+            )
         }
-    }
 
-    // MAC 地址获取
-    fn get_mac_address() -> Result<String, Box<dyn Error>> {
-        #[cfg(target_os = "windows")]
-        {
-            use std::process::Command;
-            let output = Command::new("getmac")
-                .output()?;
-            let mac = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .next()
-                .unwrap_or("")
-                .split_whitespace()
-                .next()
-                .unwrap_or("")
-                .to_string();
-            Ok(mac)
+        // 宽松采集总是返回已读到的字段；严格采集只在可选字段以外的字段失败时返回错误
+        #[test]
+        fn strict_collection_fails_only_on_required_fields() {
+            let runner = MockRunner::new()
+                .with_stdout(LSBLK, "Samsung SSD 970 EVO Plus 1TB\n")
+                .with_stdout(FINDMNT, "6f1d2c4e-8a7b-4c3d-9e0f-112233445566\n")
+                .with_failure(DMIDECODE, "sudo: a password is required");
+            let (info, report) = collect_without_sys(&runner, &disk_root_and_memory());
+            assert_eq!(errors(&report), ["disks", "memory_modules"]);
+            let strict = HardwareInfo::require_fields(info, report).unwrap();
+            assert_eq!(strict.disk_model(), "Samsung SSD 970 EVO Plus 1TB");
+
+            let runner = MockRunner::new()
+                .with_failure(LSBLK, "lsblk: failed to access sysfs directory")
+                .with_stdout(FINDMNT, "6f1d2c4e-8a7b-4c3d-9e0f-112233445566\n")
+                .with_failure(DMIDECODE, "sudo: a password is required");
+            let (info, report) = collect_without_sys(&runner, &disk_root_and_memory());
+            assert_eq!(info.disk_model(), "");
+            assert_eq!(info.root_fs_uuid(), Some("6f1d2c4e-8a7b-4c3d-9e0f-112233445566"));
+            assert_eq!(errors(&report), ["disks", "disk_model", "memory_modules"]);
+            match HardwareInfo::require_fields(info, report) {
+                Err(HardwareError::CommandError(message)) => assert!(message.starts_with(LSBLK), "{}", message),
+                other => panic!("expected the lsblk error, got {:?}", other),
+            }
         }
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::process::Command;
-            let output = Command::new("ip")
-                .args(&["link", "show"])
-                .output()?;
-            let mac = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .find(|line| line.contains("link/ether"))
-                .and_then(|line| line.split_whitespace().nth(1))
-                .unwrap_or("")
-                .to_string();
-            Ok(mac)
+        // 超时的命令只让它的字段失败，其余命令照常执行
+        #[test]
+        fn a_timed_out_command_fails_only_its_field() {
+            let runner = MockRunner::new()
+                .with_timeout(LSBLK, Duration::from_secs(5))
+                .with_stdout(FINDMNT, "6f1d2c4e-8a7b-4c3d-9e0f-112233445566\n")
+                .with_stdout(DMIDECODE, DMIDECODE_MEMORY);
+            let (info, report) = collect_without_sys(&runner, &disk_root_and_memory());
+            assert_eq!(info.root_fs_uuid(), Some("6f1d2c4e-8a7b-4c3d-9e0f-112233445566"));
+            assert_eq!(info.memory_modules(), ["41A2C0F3", "41A2C1D8"]);
+            assert_eq!(errors(&report), ["disks", "disk_model"]);
+            let timed_out = "lsblk -no MODEL: timed out after 5s";
+            assert!(report.errors()[1].1.to_string().contains(timed_out), "{:?}", report.errors());
+            match HardwareInfo::require_fields(info, report) {
+                Err(HardwareError::CommandError(message)) => assert!(message.contains(timed_out), "{}", message),
+                other => panic!("expected the timeout, got {:?}", other),
+            }
         }
 
-        #[cfg(target_os = "macos")]
-        {
-            use std::process::Command;
-            let output = Command::new("ifconfig")
-                .output()?;
-            let mac = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .find(|line| line.contains("ether"))
-                .and_then(|line| line.split_whitespace().nth(1))
-                .unwrap_or("")
-                .to_string();
-            Ok(mac)
+        // 与 collect_lenient_inner 相同：先按 probe_plan 提前执行命令，再逐个字段采集
+        fn collect_planned(runner: &MockRunner, options: &CollectOptions) -> (serde_json::Value, Vec<&'static str>) {
+            let collector = Collector::new(runner);
+            collector.smbios.set(None).ok();
+            let (ahead, skipped) = HardwareInfo::probe_plan(options);
+            collector.prefetch(&ahead, &HardwareInfo::progress_tracker(options, &ahead, &skipped));
+            let (info, report) = HardwareInfo::collect_from(&collector, options);
+            (serde_json::to_value(&info).unwrap(), errors(&report))
+        }
+
+        #[test]
+        fn parallel_and_sequential_probes_collect_the_same_values() {
+            let runner = || {
+                MockRunner::new()
+                    .with_stdout(FINDMNT, "6f1d2c4e-8a7b-4c3d-9e0f-112233445566\n")
+                    .with_stdout(DMIDECODE, DMIDECODE_MEMORY)
+                    .with_timeout(LSBLK, Duration::from_secs(5))
+            };
+            let options = CollectOptions::new().with_components(ComponentSet::all());
+            let parallel = collect_planned(&runner(), &options.clone().with_parallel_probes(true));
+            let sequential = collect_planned(&runner(), &options.with_parallel_probes(false));
+            assert_eq!(parallel, sequential);
         }
     }
 
-    // BIOS 版本获取
-    fn get_bios_version() -> Result<String, Box<dyn Error>> {
-        #[cfg(target_os = "windows")]
-        {
-            use std::process::Command;
-            let output = Command::new("wmic")
-                .args(&["bios", "get", "version"])
-                .output()?;
-            let version = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(version)
+    // 主网卡的选择：路由表与 /sys/class/net 来自模拟的目录树
+    #[cfg(target_os = "linux")]
+    mod interface_selection {
+        use super::*;
+        use crate::linux::tests::FakeSys;
+
+        const PROC_NET_ROUTE: &str = include_str!("../tests/fixtures/linux/proc_net_route.txt");
+        const ROUTE_HEADER: &str = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n";
+
+        fn default_route(interface: &str) -> String {
+            format!("{}{}\t00000000\t0100A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0\n", ROUTE_HEADER, interface)
         }
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::fs;
-            let version = fs::read_to_string("/sys/class/dmi/id/bios_version")?
-                .trim()
-                .to_string();
-            Ok(version)
+        // 有线与 Wi-Fi 各一块，外加 Docker 网桥
+        fn laptop() -> FakeSys {
+            let fake = FakeSys::new();
+            fake.interface("lo", 1, "00:00:00:00:00:00", false, false)
+                .file("/sys/class/net/lo/type", "772\n")
+                .interface("enp0s31f6", 2, "8c:16:45:5a:6b:7c", true, false)
+                .interface("wlp3s0", 3, "3c:a9:f4:10:20:30", true, true)
+                .interface("docker0", 4, "02:42:8e:11:22:33", false, false);
+            fake
         }
 
-        #[cfg(target_os = "macos")]
-        {
-            use std::process::Command;
-            let output = Command::new("system_profiler")
-                .args(&["SPHardwareDataType"])
-                .output()?;
-            let version = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .find(|line| line.contains("Boot ROM Version"))
-                .unwrap_or("")
-                .split(':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(version)
+        fn primary(fake: &FakeSys) -> Option<(String, Vec<String>)> {
+            fake.sys().primary_interface()
         }
-    }
 
-    // 主板制造商获取
-    fn get_motherboard_manufacturer() -> Result<String, Box<dyn Error>> {
-        #[cfg(target_os = "windows")]
-        {
-            use std::process::Command;
-            let output = Command::new("wmic")
-                .args(&["baseboard", "get", "manufacturer"])
-                .output()?;
-            let manufacturer = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(manufacturer)
+        fn only(name: &str) -> Option<(String, Vec<String>)> {
+            Some((name.to_string(), vec![name.to_string()]))
         }
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::fs;
-            let manufacturer = fs::read_to_string("/sys/class/dmi/id/board_vendor")?
-                .trim()
-                .to_string();
-            Ok(manufacturer)
+        // 有线与 Wi-Fi 都有默认路由时取 Metric 较小的有线网卡；拔掉网线后只剩 Wi-Fi 的默认路由
+        #[test]
+        fn the_default_route_picks_the_primary_interface() {
+            let fake = laptop();
+            fake.file("/proc/net/route", PROC_NET_ROUTE);
+            assert_eq!(primary(&fake), only("enp0s31f6"));
+            fake.file("/proc/net/route", &default_route("wlp3s0"));
+            assert_eq!(primary(&fake), only("wlp3s0"));
         }
 
-        #[cfg(target_os = "macos")]
-        {
-            Ok(String::from("Apple Inc."))
+        // 没有默认路由时取第一块有载波的物理网卡，都没有载波时取有线网卡
+        #[test]
+        fn without_a_default_route_carrier_then_type_decide() {
+            let fake = laptop();
+            fake.file("/proc/net/route", ROUTE_HEADER);
+            assert_eq!(primary(&fake), only("enp0s31f6"));
+            fake.file("/sys/class/net/wlp3s0/carrier", "1\n");
+            assert_eq!(primary(&fake), only("wlp3s0"));
+            assert_eq!(primary(&FakeSys::new()), None);
         }
-    }
 
-    // 主板产品名称获取
-    fn get_motherboard_product_name() -> Result<String, Box<dyn Error>> {
-        #[cfg(target_os = "windows")]
-        {
-            use std::process::Command;
-            let output = Command::new("wmic")
-                .args(&["baseboard", "get", "product"])
-                .output()?;
-            let product = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(product)
+        // Docker 网桥、veth 对与用户网桥的 MAC 随守护进程重启而变化：接口列表中有没有它们、
+        // 默认路由是否在 docker0 上（--net=host 的容器），选出的网卡都相同
+        #[test]
+        fn docker_interfaces_do_not_change_the_selection() {
+            let plain = laptop();
+            plain.file("/proc/net/route", ROUTE_HEADER).file("/sys/class/net/enp0s31f6/carrier", "1\n");
+            let docker = laptop();
+            docker
+                .file("/proc/net/route", &default_route("docker0"))
+                .file("/sys/class/net/enp0s31f6/carrier", "1\n")
+                .file("/sys/class/net/docker0/carrier", "1\n")
+                .interface("veth1a2b3c4", 5, "76:3e:9d:00:11:22", false, false)
+                .file("/sys/class/net/veth1a2b3c4/carrier", "1\n")
+                .interface("br-5f3a9c0d1e2b", 6, "02:42:c0:a8:00:01", false, false)
+                .dir("/sys/class/net/br-5f3a9c0d1e2b/brif/veth1a2b3c4");
+            assert_eq!(primary(&plain), only("enp0s31f6"));
+            assert_eq!(primary(&docker), primary(&plain));
+            assert!(docker.sys().lower_devices("br-5f3a9c0d1e2b").is_empty());
         }
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::fs;
-            let product = fs::read_to_string("/sys/class/dmi/id/board_name")?
-                .trim()
-                .to_string();
-            Ok(product)
+        #[test]
+        fn interface_state_comes_from_operstate() {
+            let fake = laptop();
+            fake.file("/sys/class/net/enp0s31f6/operstate", "up\n")
+                .file("/sys/class/net/wlp3s0/operstate", "down\n")
+                .file("/sys/class/net/docker0/operstate", "unknown\n");
+            let runner = crate::command_runner::MockRunner::new();
+            let mut collector = Collector::new(&runner);
+            collector.sys = fake.sys();
+            assert!(collector.interface_is_up("enp0s31f6"));
+            assert!(!collector.interface_is_up("wlp3s0"));
+            assert!(!collector.interface_is_up("docker0"));
+            assert!(!collector.interface_is_up("eth9"));
         }
 
-        #[cfg(target_os = "macos")]
-        {
-            use std::process::Command;
-            let output = Command::new("system_profiler")
-                .args(&["SPHardwareDataType"])
-                .output()?;
-            let product = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .find(|line| line.contains("Model Identifier"))
-                .unwrap_or("")
-                .split(':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(product)
+        // 没有永久地址（回环、不存在或名称过长的接口）时为 None，由调用方改用当前地址
+        #[test]
+        fn missing_permanent_address_falls_back() {
+            assert_eq!(HardwareInfo::permanent_mac("lo"), None);
+            assert_eq!(HardwareInfo::permanent_mac("nosuchif0"), None);
+            assert_eq!(HardwareInfo::permanent_mac("an-interface-name-longer-than-ifnamsiz"), None);
+        }
+
+        // 链路类型与 wireless/phy80211 目录决定类型，名称不影响：eth1 可以是 Wi-Fi，enx… 是 USB 有线网卡
+        #[test]
+        fn interface_type_comes_from_the_kernel() {
+            let fake = laptop();
+            fake.interface("eth1", 5, "00:90:4c:0d:f4:3e", true, false)
+                .dir("/sys/class/net/eth1/phy80211")
+                .interface("enx00e04c680a51", 6, "00:e0:4c:68:0a:51", true, false);
+            let sys = fake.sys();
+            assert_eq!(sys.interface_type("wlp3s0"), Some(NetworkInterfaceType::WiFi));
+            assert_eq!(sys.interface_type("eth1"), Some(NetworkInterfaceType::WiFi));
+            assert_eq!(sys.interface_type("enx00e04c680a51"), Some(NetworkInterfaceType::Ethernet));
+            assert_eq!(sys.interface_type("lo"), Some(NetworkInterfaceType::Unknown));
+            assert_eq!(sys.interface_type("wlan9"), None);
+        }
+
+        // 只有 Wi-Fi 的笔记本：未联网时同样选出 Wi-Fi 网卡，而不是没有网卡
+        #[test]
+        fn wifi_only_laptops_use_the_wifi_interface() {
+            let fake = FakeSys::new();
+            fake.interface("wlp2s0", 2, "3c:a9:f4:10:20:30", true, true)
+                .interface("docker0", 3, "02:42:8e:11:22:33", false, false);
+            assert_eq!(primary(&fake), only("wlp2s0"));
+        }
+
+        // 服务器：eno1、eno2 组成 active-backup 的 bond0，另有一块 enp3s0
+        fn server() -> FakeSys {
+            let fake = FakeSys::new();
+            fake.interface("eno1", 2, "3c:ec:ef:10:20:30", true, false)
+                .interface("eno2", 3, "3c:ec:ef:10:20:31", true, false)
+                .interface("enp3s0", 4, "b4:96:91:aa:bb:cc", true, false)
+                .interface("bond0", 5, "3c:ec:ef:10:20:30", false, false)
+                .file("/sys/class/net/bond0/bonding/mode", "active-backup 1\n")
+                .file("/sys/class/net/bond0/bonding/slaves", "eno2 eno1\n")
+                .link("/sys/class/net/bond0/lower_eno1", "/sys/class/net/eno1")
+                .link("/sys/class/net/bond0/lower_eno2", "/sys/class/net/eno2");
+            fake
+        }
+
+        fn chain(name: &str, lower: &[&str]) -> Option<(String, Vec<String>)> {
+            Some((name.to_string(), lower.iter().map(|lower| lower.to_string()).collect()))
+        }
+
+        // bond0 的 MAC 来自当前的主用网卡，切换后改变：改为全部下层网卡
+        #[test]
+        fn a_bond_resolves_to_its_slaves() {
+            let fake = server();
+            fake.file("/proc/net/route", &default_route("bond0"));
+            assert_eq!(primary(&fake), chain("bond0", &["eno1", "eno2"]));
+        }
+
+        #[test]
+        fn a_bridge_resolves_to_its_ports() {
+            // 建在一块网卡上的网桥（虚拟机宿主常见），虚拟机的 tap 接口不是物理网卡
+            let fake = server();
+            fake.interface("br0", 6, "b4:96:91:aa:bb:cc", false, false)
+                .dir("/sys/class/net/br0/brif/enp3s0")
+                .interface("vnet0", 7, "fe:54:00:12:34:56", false, false)
+                .dir("/sys/class/net/br0/brif/vnet0")
+                .file("/proc/net/route", &default_route("br0"));
+            assert_eq!(primary(&fake), chain("br0", &["enp3s0"]));
+
+            // 建在 bond 上的网桥展开两层
+            let fake = server();
+            fake.interface("br1", 6, "3c:ec:ef:10:20:30", false, false)
+                .dir("/sys/class/net/br1/brif/bond0")
+                .file("/proc/net/route", &default_route("br1"));
+            assert_eq!(primary(&fake), chain("br1", &["eno1", "eno2"]));
+        }
+
+        // 聚合接口的每块下层网卡都是主网卡
+        #[test]
+        fn every_slave_stays_primary() {
+            let mut interfaces =
+                vec![nic("eno2", "3c:ec:ef:10:20:31", true), nic("enp3s0", "b4:96:91:aa:bb:cc", false), nic("eno1", "3c:ec:ef:10:20:30", true)];
+            HardwareInfo::order_interfaces(&mut interfaces, true);
+            let primary: Vec<&str> = interfaces.iter().filter(|nic| nic.is_primary()).map(NetworkInfo::name).collect();
+            assert_eq!(primary, ["eno1", "eno2"]);
+        }
+
+        #[test]
+        fn virtual_interfaces_are_recognized_by_name() {
+            for name in ["docker0", "veth1a2b3c4", "br-5f3a9c0d1e2b", "virbr0", "tap0", "tun0", "wg0", "ztly5abcde"] {
+                assert!(HardwareInfo::is_virtual_interface(name), "{}", name);
+            }
+            for name in ["eno1", "enp3s0", "wlp2s0", "eth0", "bond0"] {
+                assert!(!HardwareInfo::is_virtual_interface(name), "{}", name);
+            }
         }
     }
 
-    // BIOS 供应商获取
-    fn get_bios_vendor() -> Result<String, Box<dyn Error>> {
-        #[cfg(target_os = "windows")]
-        {
-            use std::process::Command;
-            let output = Command::new("wmic")
-                .args(&["bios", "get", "manufacturer"])
-                .output()?;
-            let vendor = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(vendor)
+    // 单板机：没有 DMI，CPU 与主板信息来自 cpuinfo 与设备树
+    #[cfg(target_os = "linux")]
+    mod device_tree {
+        use super::*;
+        use crate::command_runner::MockRunner;
+        use crate::linux::tests::FakeSys;
+
+        const CPUINFO_RPI: &str = include_str!("../tests/fixtures/linux/cpuinfo_rpi.txt");
+        const CPUINFO_ROCK5: &str = include_str!("../tests/fixtures/linux/cpuinfo_rock5.txt");
+        const CPUINFO_X86: &str = include_str!("../tests/fixtures/linux/cpuinfo_x86.txt");
+        const RPI4: [(&str, &str); 3] = [
+            ("model", include_str!("../tests/fixtures/linux/device_tree/rpi4/model")),
+            ("serial-number", include_str!("../tests/fixtures/linux/device_tree/rpi4/serial-number")),
+            ("compatible", include_str!("../tests/fixtures/linux/device_tree/rpi4/compatible")),
+        ];
+        const ROCK5B: [(&str, &str); 3] = [
+            ("model", include_str!("../tests/fixtures/linux/device_tree/rock5b/model")),
+            ("serial-number", include_str!("../tests/fixtures/linux/device_tree/rock5b/serial-number")),
+            ("compatible", include_str!("../tests/fixtures/linux/device_tree/rock5b/compatible")),
+        ];
+
+        fn board(cpuinfo: &str, device_tree: &[(&str, &str)]) -> FakeSys {
+            let fake = FakeSys::new();
+            fake.file("/proc/cpuinfo", cpuinfo);
+            for (name, contents) in device_tree {
+                fake.file(&format!("/sys/firmware/devicetree/base/{}", name), contents);
+            }
+            fake
         }
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::fs;
-            let vendor = fs::read_to_string("/sys/class/dmi/id/bios_vendor")?
-                .trim()
-                .to_string();
-            Ok(vendor)
+        // (CPU 型号, CPU 物理 ID, 主板型号)
+        fn identity(fake: &FakeSys) -> (String, String, String) {
+            let runner = MockRunner::new();
+            let mut collector = Collector::new(&runner);
+            collector.sys = fake.sys();
+            let field = |result: Result<String, HardwareError>| result.unwrap_or_else(|e| format!("error: {}", e));
+            (
+                field(collector.get_cpu_info()),
+                field(collector.get_cpu_physical_id()),
+                field(collector.get_motherboard_product_name()),
+            )
         }
 
-        #[cfg(target_os = "macos")]
-        {
-            Ok(String::from("Apple Inc."))
+        #[test]
+        fn raspberry_pi_uses_the_cpuinfo_serial_and_device_tree_model() {
+            let (cpu, physical_id, product) = identity(&board(CPUINFO_RPI, &RPI4));
+            assert_eq!(cpu, "BCM2835");
+            assert_eq!(physical_id, "10000000a3b4c5d6");
+            assert_eq!(product, "Raspberry Pi 4 Model B Rev 1.4");
+        }
+
+        // Rock 5B 的 cpuinfo 既没有 Hardware 也没有 Serial 行
+        #[test]
+        fn other_boards_fall_back_to_the_device_tree() {
+            let (cpu, physical_id, product) = identity(&board(CPUINFO_ROCK5, &ROCK5B));
+            assert_eq!(cpu, "rockchip,rk3588");
+            assert_eq!(physical_id, "fd4e9b1c2a3d5e6f");
+            assert_eq!(product, "Radxa ROCK 5B");
+
+            // 没有设备树时与原来一样报告 cpuinfo 中缺少的字段
+            let (cpu, physical_id, _) = identity(&board(CPUINFO_ROCK5, &[]));
+            assert!(cpu.starts_with("error: "), "{}", cpu);
+            assert!(physical_id.starts_with("error: "), "{}", physical_id);
+        }
+
+        #[test]
+        fn x86_cpuinfo_and_dmi_take_precedence() {
+            let fake = board(CPUINFO_X86, &ROCK5B);
+            fake.file("/sys/class/dmi/id/board_name", "0NNNCT\n");
+            let (cpu, physical_id, product) = identity(&fake);
+            assert_eq!(cpu, parsers::parse_cpuinfo_field(CPUINFO_X86, "model name").unwrap());
+            assert_eq!(physical_id, parsers::parse_cpuinfo_field(CPUINFO_X86, "physical id").unwrap());
+            assert_eq!(product, "0NNNCT");
         }
     }
 
-    // BIOS 发布日期获取
-    fn get_bios_release_date() -> Result<String, Box<dyn Error>> {
-        #[cfg(target_os = "windows")]
-        {
-            use std::process::Command;
-            let output = Command::new("wmic")
-                .args(&["bios", "get", "releasedate"])
-                .output()?;
-            let date = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(date)
+    #[cfg(target_os = "linux")]
+    mod live_boot {
+        use super::*;
+
+        const LIVE_MOUNTS: &str = include_str!("../tests/fixtures/linux/mounts_live.txt");
+        const CONTAINER_MOUNTS: &str = include_str!("../tests/fixtures/linux/mounts_container.txt");
+        const INSTALLED_MOUNTS: &str = include_str!("../tests/fixtures/linux/mounts_installed.txt");
+        const LIVE_CMDLINE: &str = include_str!("../tests/fixtures/linux/cmdline_live.txt");
+        const INSTALLED_CMDLINE: &str = include_str!("../tests/fixtures/linux/cmdline_installed.txt");
+
+        #[test]
+        fn overlay_root_with_live_cmdline_is_live() {
+            assert!(HardwareInfo::is_live_root(LIVE_MOUNTS, LIVE_CMDLINE));
+            assert!(HardwareInfo::is_live_root(LIVE_MOUNTS, "BOOT_IMAGE=/images/pxeboot/vmlinuz rd.live.image quiet"));
+            assert!(HardwareInfo::is_live_root(LIVE_MOUNTS, "root=live:CDLABEL=Fedora-WS-Live rd.live.image"));
         }
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::fs;
-            let date = fs::read_to_string("/sys/class/dmi/id/bios_date")?
-                .trim()
-                .to_string();
-            Ok(date)
+        #[test]
+        fn container_overlay_root_is_not_live() {
+            assert!(!HardwareInfo::is_live_root(CONTAINER_MOUNTS, INSTALLED_CMDLINE));
         }
 
-        #[cfg(target_os = "macos")]
-        {
-            use std::process::Command;
-            let output = Command::new("system_profiler")
-                .args(&["SPHardwareDataType"])
-                .output()?;
-            let date = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .find(|line| line.contains("Boot ROM Version"))
-                .unwrap_or("")
-                .split(':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(date)
+        #[test]
+        fn installed_system_is_not_live() {
+            assert!(!HardwareInfo::is_live_root(INSTALLED_MOUNTS, INSTALLED_CMDLINE));
+            // 内核参数残留 live 标记但根文件系统在真实磁盘上
+            assert!(!HardwareInfo::is_live_root(INSTALLED_MOUNTS, LIVE_CMDLINE));
         }
     }
 
-    // 内存序列号获取
-    fn get_memory_serial() -> Result<String, Box<dyn Error>> {
-        #[cfg(target_os = "windows")]
-        {
-            use std::process::Command;
-            let output = Command::new("wmic")
-                .args(&["memorychip", "get", "serialnumber"])
-                .output()?;
-            let serial = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(serial)
+    // macOS 的采集路径，命令输出来自 MockRunner
+    #[cfg(target_os = "macos")]
+    mod mac_collection {
+        use super::*;
+        use crate::command_runner::MockRunner;
+
+        const SP_HARDWARE: &str = include_str!("../tests/fixtures/macos/system_profiler_hardware.txt");
+        const SP_HARDWARE_DE: &str = include_str!("../tests/fixtures/macos/system_profiler_hardware_de.txt");
+        const IOREG_PLATFORM_APPLE_SILICON: &str = include_str!("../tests/fixtures/macos/ioreg_platform_apple_silicon.txt");
+        const SP_SATA: &str = include_str!("../tests/fixtures/macos/system_profiler_sata.txt");
+        const SP_FUSION: &str = include_str!("../tests/fixtures/macos/system_profiler_fusion.txt");
+        const DISKUTIL_LIST_PHYSICAL: &str = include_str!("../tests/fixtures/macos/diskutil_list_physical.txt");
+        const DISKUTIL_INFO_ROOT: &str = include_str!("../tests/fixtures/macos/diskutil_info_root.plist");
+        const DISKUTIL_INFO_ROOT_EXTERNAL: &str = include_str!("../tests/fixtures/macos/diskutil_info_root_external.plist");
+        const DISKUTIL_INFO_DISK0: &str = include_str!("../tests/fixtures/macos/diskutil_info_disk0.plist");
+        const DISKUTIL_INFO_DISK4: &str = include_str!("../tests/fixtures/macos/diskutil_info_disk4.plist");
+        const NETWORKSETUP_PORTS: &str = include_str!("../tests/fixtures/macos/networksetup_ports.txt");
+        const NETWORKSETUP_PORTS_DOCK: &str = include_str!("../tests/fixtures/macos/networksetup_ports_dock.txt");
+        const IOREG_ETHERNET: &str = include_str!("../tests/fixtures/macos/ioreg_ethernet.txt");
+        const IOREG_ETHERNET_DOCK: &str = include_str!("../tests/fixtures/macos/ioreg_ethernet_dock.txt");
+        const SP_HARDWARE_BOOTROM: &str = include_str!("../tests/fixtures/macos/system_profiler_hardware_bootrom.txt");
+        const SP_HARDWARE_M2: &str = include_str!("../tests/fixtures/macos/system_profiler_hardware_m2.txt");
+        const SP_HARDWARE_COMMAND: &str = "system_profiler SPHardwareDataType";
+        const SP_NVME_COMMAND: &str = "system_profiler SPNVMeDataType";
+        const SP_SATA_COMMAND: &str = "system_profiler SPSerialATADataType";
+        const IOREG_PLATFORM_COMMAND: &str = "ioreg -rd1 -c IOPlatformExpertDevice";
+
+        fn collect(runner: &MockRunner, components: ComponentSet) -> (HardwareInfo, CollectionReport) {
+            collect_with(runner, &CollectOptions::new().with_components(components))
         }
 
-        #[cfg(target_os = "linux")]
-        {
-            use std::process::Command;
-            // 使用 dmidecode 命令获取内存信息（需要 root 权限）
-            let output = Command::new("sudo")
-                .args(&["dmidecode", "-t", "memory"])
-                .output()?;
-            let serial = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .find(|line| line.contains("Serial Number:"))
-                .unwrap_or("")
-                .split(':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(serial)
+        fn collect_with(runner: &MockRunner, options: &CollectOptions) -> (HardwareInfo, CollectionReport) {
+            let mut collector = Collector::new(runner);
+            collector.environment = Environment::Native;
+            HardwareInfo::collect_from(&collector, options)
         }
 
-        #[cfg(target_os = "macos")]
-        {
-            use std::process::Command;
-            let output = Command::new("system_profiler")
-                .args(&["SPMemoryDataType"])
-                .output()?;
-            let serial = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .find(|line| line.contains("Serial Number:"))
-                .unwrap_or("")
-                .split(':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            Ok(serial)
-        }
-    }
-} 
\ No newline at end of file
+        fn runs_of(runner: &MockRunner, command: &str) -> usize {
+            runner.calls().iter().filter(|call| *call == command).count()
+        }
+
+        #[test]
+        fn hardware_overview_is_profiled_once_for_every_field() {
+            // ioreg 不可用，序列号与 UUID 也从 system_profiler 中读取
+            let runner = MockRunner::new().with_stdout(SP_HARDWARE_COMMAND, SP_HARDWARE);
+            let components = ComponentSet::empty()
+                .with(Component::MotherboardSerial)
+                .with(Component::MotherboardUuid)
+                .with(Component::MotherboardProductName)
+                .with(Component::BiosVersion);
+            let (info, _) = collect(&runner, components);
+            assert_eq!(info.motherboard_serial(), "C02XL0GHJHD2");
+            assert_eq!(info.board().uuid(), "8A3C6F1E-2B4D-5E6F-9A0B-1C2D3E4F5A6B");
+            assert_eq!(info.board().product(), "MacBookPro15,2");
+            assert_eq!(info.bios_version(), Some("1916.40.8.0.0 (iBridge: 20.16.3045.0.0,0)"));
+            assert_eq!(runs_of(&runner, SP_HARDWARE_COMMAND), 1, "{:?}", runner.calls());
+        }
+
+        // 德语系统上按英文标签读不到序列号与 UUID；ioreg 的键名不随语言变化
+        #[test]
+        fn ioreg_identity_does_not_depend_on_the_locale() {
+            let components = ComponentSet::empty().with(Component::MotherboardSerial).with(Component::MotherboardUuid);
+            let options = CollectOptions::new().with_components(components).with_parallel_probes(false);
+            let runner = MockRunner::new().with_stdout(SP_HARDWARE_COMMAND, SP_HARDWARE_DE);
+            let (info, report) = collect_with(&runner, &options);
+            assert_eq!(info.motherboard_serial(), "");
+            assert!(!report.errors().is_empty());
+
+            let runner = MockRunner::new()
+                .with_stdout(SP_HARDWARE_COMMAND, SP_HARDWARE_DE)
+                .with_stdout(IOREG_PLATFORM_COMMAND, IOREG_PLATFORM_APPLE_SILICON);
+            let (info, _) = collect_with(&runner, &options);
+            assert_eq!(info.motherboard_serial(), "K7VY2Q1PMR");
+            assert_eq!(info.board().uuid(), "5D3B2E1A-7C4F-5A9B-8E6D-0F1A2B3C4D5E");
+            assert_eq!(runs_of(&runner, SP_HARDWARE_COMMAND), 0, "{:?}", runner.calls());
+        }
+
+        // 没有 NVMe 磁盘的 Intel Mac 上 SPNVMeDataType 为空，固件版本取自 SATA；diskutil 不可用时启动盘为 disk0
+        #[test]
+        fn sata_firmware_is_read_when_there_is_no_nvme_disk() {
+            let runner = MockRunner::new().with_stdout(SP_NVME_COMMAND, "").with_stdout(SP_SATA_COMMAND, SP_SATA);
+            assert_eq!(Collector::new(&runner).get_disk_firmware().unwrap(), "BXW1SA0Q");
+
+            let runner = MockRunner::new()
+                .with_failure(SP_NVME_COMMAND, "SPNVMeDataType: no such data type")
+                .with_stdout(SP_SATA_COMMAND, SP_FUSION);
+            assert_eq!(Collector::new(&runner).get_disk_firmware().unwrap(), "CXS4LA0Q");
+
+            let runner = MockRunner::new().with_stdout(SP_NVME_COMMAND, "").with_stdout(SP_SATA_COMMAND, "");
+            assert!(Collector::new(&runner).get_disk_firmware().is_err());
+        }
+
+        fn diskutil(root: &str) -> MockRunner {
+            MockRunner::new()
+                .with_stdout("diskutil list physical", DISKUTIL_LIST_PHYSICAL)
+                .with_stdout("diskutil info -plist /", root)
+                .with_stdout("diskutil info -plist disk0", DISKUTIL_INFO_DISK0)
+                .with_stdout("diskutil info -plist disk4", DISKUTIL_INFO_DISK4)
+        }
+
+        #[test]
+        fn boot_disk_is_the_physical_store_of_the_root_volume() {
+            let components = ComponentSet::empty().with(Component::DiskModel);
+            let (info, report) = collect(&diskutil(DISKUTIL_INFO_ROOT), components);
+            assert_eq!(info.root_disk().map(DiskInfo::name), Some("disk0"));
+            assert_eq!(info.disk_model(), "APPLE SSD AP0512Q");
+            assert!(!report.is_external_boot_disk());
+
+            // 从外接磁盘启动时照常读取，并在报告中标记
+            let (info, report) = collect(&diskutil(DISKUTIL_INFO_ROOT_EXTERNAL), components);
+            assert_eq!(info.root_disk().map(DiskInfo::name), Some("disk4"));
+            assert_eq!(info.disk_model(), "Samsung PSSD T7 Media");
+            assert!(report.is_external_boot_disk());
+        }
+
+        fn mac_selection(ports: &str, ioreg: Option<&str>) -> (String, Vec<String>) {
+            let mut runner = MockRunner::new().with_stdout("networksetup -listallhardwareports", ports);
+            if let Some(ioreg) = ioreg {
+                runner = runner.with_stdout("ioreg -r -c IOEthernetInterface", ioreg);
+            }
+            let (info, _) = collect(&runner, ComponentSet::empty().with(Component::MacAddress));
+            let names = info.network_interfaces().iter().map(|interface| interface.name().to_string()).collect();
+            (info.mac_address().to_string(), names)
+        }
+
+        // 接上、拔下扩展坞，参与唯一码的 MAC 不变；ioreg 不可用时按端口名称排除扩展坞
+        #[test]
+        fn dock_does_not_change_the_mac_address() {
+            let undocked = mac_selection(NETWORKSETUP_PORTS, Some(IOREG_ETHERNET));
+            assert_eq!(undocked.1, ["en0", "en1"]);
+            assert!(!undocked.0.is_empty());
+            assert_eq!(mac_selection(NETWORKSETUP_PORTS_DOCK, Some(IOREG_ETHERNET_DOCK)), undocked);
+            assert_eq!(mac_selection(NETWORKSETUP_PORTS, None), undocked);
+            assert_eq!(mac_selection(NETWORKSETUP_PORTS_DOCK, None), undocked);
+        }
+
+        // Mac 的固件没有发布日期，不再重复版本号
+        #[test]
+        fn firmware_version_on_intel_and_m_series_macs() {
+            for (overview, version) in [
+                (SP_HARDWARE_BOOTROM, "199.0.0.0.0"),
+                (SP_HARDWARE, "1916.40.8.0.0 (iBridge: 20.16.3045.0.0,0)"),
+                (SP_HARDWARE_M2, "10151.101.3"),
+            ] {
+                let runner = MockRunner::new().with_stdout(SP_HARDWARE_COMMAND, overview);
+                let collector = Collector::new(&runner);
+                assert_eq!(collector.get_bios_version().unwrap(), version);
+                assert_eq!(collector.get_bios_vendor().unwrap(), "Apple Inc.");
+                let error = collector.get_bios_release_date().unwrap_err();
+                assert!(matches!(error, HardwareError::UnsupportedSystem(_)), "{:?}", error);
+            }
+        }
+
+        const PROC_TRANSLATED: &str = "sysctl -n sysctl.proc_translated";
+        const BRAND_STRING: &str = "sysctl -n machdep.cpu.brand_string";
+        const NATIVE_BRAND_STRING: &str = "arch -arm64 /usr/sbin/sysctl -n machdep.cpu.brand_string";
+
+        // 同一台 M2 上的 arm64 构建与 Rosetta 2 下的 x86_64 构建：参与哈希的 CPU 字段相同，报告中区分两者
+        #[test]
+        fn translated_and_native_processes_hash_the_same_cpu() {
+            let components = ComponentSet::empty().with(Component::CpuPhysicalId);
+            let native = MockRunner::new().with_stdout(PROC_TRANSLATED, "0\n").with_stdout(BRAND_STRING, "Apple M2\n");
+            let (native_info, native_report) = collect(&native, components);
+            assert_eq!(native_info.cpu_physical_id(), "Apple M2");
+            assert!(!native_report.is_translated());
+            assert_eq!(native_report.architecture(), std::env::consts::ARCH);
+
+            let translated = MockRunner::new()
+                .with_stdout(PROC_TRANSLATED, "1\n")
+                .with_stdout(BRAND_STRING, "VirtualApple @ 2.50GHz processor\n")
+                .with_stdout(NATIVE_BRAND_STRING, "Apple M2\n");
+            let (translated_info, translated_report) = collect(&translated, components);
+            assert_eq!(translated_info.cpu_physical_id(), native_info.cpu_physical_id());
+            assert!(translated_report.is_translated());
+            assert_eq!(translated_report.architecture(), "aarch64");
+            assert!(!translated.calls().iter().any(|call| call == BRAND_STRING), "{:?}", translated.calls());
+
+            // Intel Mac 上没有 sysctl.proc_translated
+            let intel = MockRunner::new()
+                .with_failure(PROC_TRANSLATED, "sysctl: unknown oid 'sysctl.proc_translated'")
+                .with_stdout(BRAND_STRING, "Intel(R) Core(TM) i7-8569U CPU @ 2.80GHz\n");
+            let (info, report) = collect(&intel, components);
+            assert_eq!(info.cpu_physical_id(), "Intel(R) Core(TM) i7-8569U CPU @ 2.80GHz");
+            assert!(!report.is_translated());
+        }
+    }
+}
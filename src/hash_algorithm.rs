@@ -0,0 +1,68 @@
+use md5::Md5;
+use sha2::{Digest, Sha256, Sha512};
+
+// 唯一码使用的摘要算法，默认 MD5 以兼容已有部署
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    #[default]
+    Md5,
+    Sha256,
+    Sha512,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    pub fn digest(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Md5 => Md5::digest(data).to_vec(),
+            HashAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+            HashAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+            HashAlgorithm::Blake3 => blake3::hash(data).as_bytes().to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware_info::tests::sample;
+
+    const ALGORITHMS: [HashAlgorithm; 4] =
+        [HashAlgorithm::Md5, HashAlgorithm::Sha256, HashAlgorithm::Sha512, HashAlgorithm::Blake3];
+
+    // 各算法公布的 "abc" 测试向量
+    #[test]
+    fn digests_match_the_published_vectors() {
+        let vectors = [
+            "900150983cd24fb0d6963f7d28e17f72",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+             2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85",
+        ];
+        for (algorithm, expected) in ALGORITHMS.into_iter().zip(vectors) {
+            assert_eq!(hex::encode(algorithm.digest(b"abc")), expected, "{:?}", algorithm);
+        }
+    }
+
+    // 固定的合成采集结果的唯一码；这些值变化意味着已部署的唯一码全部失效
+    #[test]
+    fn codes_for_the_fixture_are_stable() {
+        let info = sample();
+        let codes = ["1a6b-8bdd-c72f-61ec", "3a56-af5e-79c2-4c5a", "62f4-3b09-c95b-6a07", "e66e-9a62-26d6-74a4"];
+        for (algorithm, expected) in ALGORITHMS.into_iter().zip(codes) {
+            assert_eq!(info.generate_unique_code_with(algorithm).unwrap(), expected, "{:?}", algorithm);
+        }
+    }
+
+    // 分组格式只取摘要的前 16 个十六进制字符，与摘要长度无关
+    #[test]
+    fn grouped_code_is_the_digest_prefix_for_every_length() {
+        let info = sample();
+        for algorithm in ALGORITHMS {
+            let digest = hex::encode(info.unique_digest(algorithm).unwrap());
+            let code = info.generate_unique_code_with(algorithm).unwrap();
+            assert_eq!(code.replace('-', ""), digest[..16], "{:?}", algorithm);
+        }
+    }
+}
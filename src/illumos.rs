@@ -0,0 +1,177 @@
+// illumos（SmartOS、OmniOS 等）与 Solaris 的输出解析：smbios(1M)、kstat(1M)、diskinfo(1M)、dladm(1M)
+// 各函数只解析文本，命令由 Collector 执行；测试时在所有平台上编译
+
+// smbios -t <类型> 中的 "  Label: value" 行；值中可能含 ":"（UUID 之外的版本串等），只按第一个 ":" 切分
+pub(crate) fn smbios_field(output: &str, label: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.trim_start().split_once(':')?;
+            (name == label).then(|| value.trim().to_string())
+        })
+        .filter(|value| !value.is_empty())
+}
+
+// "Chassis Type: 0x3 (desktop)"：取十六进制编号，交给 smbios::chassis_type_name 得到与其他系统相同的名称
+pub(crate) fn smbios_chassis_code(output: &str) -> Option<String> {
+    let value = smbios_field(output, "Chassis Type")?;
+    let hex = value.split_whitespace().next()?.strip_prefix("0x")?;
+    u8::from_str_radix(hex, 16).ok().map(|code| (code & 0x7F).to_string())
+}
+
+// kstat -p 的 "module:instance:name:statistic<TAB>value"
+pub(crate) fn kstat_value(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.split_once('\t'))
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// diskinfo -Hp：TYPE、DISK、VID、PID、SIZE、RMV、SSD 以制表符分隔；只保留不可移除的磁盘，返回 (名称, 型号)
+pub(crate) fn diskinfo_fixed_disks(output: &str) -> Vec<(String, String)> {
+    let mut disks: Vec<(String, String)> = output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').map(str::trim).collect();
+            match fields.as_slice() {
+                [_, disk, vendor, product, _, removable, ..] if *removable == "no" => {
+                    // SATA 盘的 VID 固定为 "ATA"，型号只取 PID
+                    let model = match *vendor {
+                        "ATA" | "" => product.to_string(),
+                        vendor => format!("{} {}", vendor, product),
+                    };
+                    Some((disk.to_string(), model))
+                }
+                _ => None,
+            }
+        })
+        .collect();
+    disks.sort();
+    disks
+}
+
+// diskinfo -HpP：DISK、VID、PID、SERIAL、FLT、LOC、LOCATION；返回 disk 的序列号
+pub(crate) fn diskinfo_serial(output: &str, disk: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split('\t').map(str::trim).collect();
+        match fields.as_slice() {
+            [name, _, _, serial, ..] if *name == disk && !serial.is_empty() && *serial != "-" => {
+                Some(serial.to_string())
+            }
+            _ => None,
+        }
+    })
+}
+
+// dladm show-phys -m -p -o link,address：冒号以反斜杠转义（"e1000g0:0\:c\:29\:a\:b\:c"），
+// 各字节没有前导 0；返回 (链路名, 规范化的 MAC)，排除全 0 地址，按链路名排序
+pub(crate) fn dladm_macs(output: &str) -> Vec<(String, String)> {
+    let mut links: Vec<(String, String)> = output
+        .lines()
+        .filter_map(|line| {
+            let (link, address) = line.split_once(':')?;
+            let octets: Vec<u8> = address
+                .split("\\:")
+                .map(|octet| u8::from_str_radix(octet.trim(), 16))
+                .collect::<Result<_, _>>()
+                .ok()?;
+            if octets.len() != 6 || octets.iter().all(|b| *b == 0) {
+                return None;
+            }
+            let mac = octets.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":");
+            Some((link.to_string(), mac))
+        })
+        .collect();
+    links.sort();
+    links.dedup_by(|a, b| a.0 == b.0);
+    links
+}
+
+// dladm show-phys -p -o link,state 中处于 up 状态的链路
+pub(crate) fn dladm_up_links(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(_, state)| state.trim() == "up")
+        .map(|(link, _)| link.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Supermicro X11SCL-F 上 OmniOS 的命令输出
+    const SMBIOS_SYSTEM: &str = include_str!("../tests/fixtures/illumos/smbios_system.txt");
+    const SMBIOS_BASEBOARD: &str = include_str!("../tests/fixtures/illumos/smbios_baseboard.txt");
+    const SMBIOS_BIOS: &str = include_str!("../tests/fixtures/illumos/smbios_bios.txt");
+    const SMBIOS_CHASSIS: &str = include_str!("../tests/fixtures/illumos/smbios_chassis.txt");
+    const KSTAT_BRAND: &str = include_str!("../tests/fixtures/illumos/kstat_brand.txt");
+    const DISKINFO: &str = include_str!("../tests/fixtures/illumos/diskinfo.txt");
+    const DISKINFO_PHYSICAL: &str = include_str!("../tests/fixtures/illumos/diskinfo_physical.txt");
+    const DLADM_MACS: &str = include_str!("../tests/fixtures/illumos/dladm_show_phys_m.txt");
+    const DLADM_STATES: &str = include_str!("../tests/fixtures/illumos/dladm_show_phys_state.txt");
+
+    #[test]
+    fn smbios_fields_match_the_whole_label() {
+        assert_eq!(smbios_field(SMBIOS_SYSTEM, "UUID").as_deref(), Some("00000000-0000-0000-0000-ac1f6b4c2d3e"));
+        assert_eq!(smbios_field(SMBIOS_SYSTEM, "Serial Number").as_deref(), Some("S291834X8A21047"));
+        assert_eq!(smbios_field(SMBIOS_BASEBOARD, "Serial Number").as_deref(), Some("ZM19AS018722"));
+        assert_eq!(smbios_field(SMBIOS_BASEBOARD, "Product").as_deref(), Some("X11SCL-F"));
+        assert_eq!(smbios_field(SMBIOS_BIOS, "Vendor").as_deref(), Some("American Megatrends Inc."));
+        assert_eq!(smbios_field(SMBIOS_BIOS, "Version String").as_deref(), Some("2.1"));
+        assert_eq!(smbios_field(SMBIOS_BIOS, "Release Date").as_deref(), Some("11/20/2019"));
+        // 空值与不存在的字段都没有值；"Version" 不匹配 "Version String"
+        assert_eq!(smbios_field(SMBIOS_BASEBOARD, "Asset Tag"), None);
+        assert_eq!(smbios_field(SMBIOS_BIOS, "Version"), None);
+        assert_eq!(smbios_field("", "UUID"), None);
+    }
+
+    #[test]
+    fn chassis_type_is_the_masked_code() {
+        assert_eq!(smbios_chassis_code(SMBIOS_CHASSIS).as_deref(), Some("23"));
+        assert_eq!(crate::smbios::chassis_type_name("23"), Some("Rack Mount Chassis"));
+        assert_eq!(smbios_chassis_code("  Chassis Type: 0x83 (desktop)\n").as_deref(), Some("3"));
+        assert_eq!(smbios_chassis_code("  Chassis Type: desktop\n"), None);
+        assert_eq!(smbios_chassis_code(SMBIOS_SYSTEM), None);
+    }
+
+    #[test]
+    fn kstat_value_follows_the_tab() {
+        assert_eq!(kstat_value(KSTAT_BRAND).as_deref(), Some("Intel(r) Xeon(r) E-2236 CPU @ 3.40GHz"));
+        assert_eq!(kstat_value("cpu_info:0:cpu_info0:brand\t\n"), None);
+        assert_eq!(kstat_value(""), None);
+    }
+
+    #[test]
+    fn diskinfo_keeps_fixed_disks_with_their_serials() {
+        assert_eq!(
+            diskinfo_fixed_disks(DISKINFO),
+            [
+                ("c1t0d0".to_string(), "INTEL SSDSC2KB48".to_string()),
+                ("c1t1d0".to_string(), "ST4000NM0035-1V4".to_string()),
+                ("c3t0d0".to_string(), "SAMSUNG MZQLB960HAJR-00007".to_string()),
+            ]
+        );
+        assert_eq!(diskinfo_serial(DISKINFO_PHYSICAL, "c1t0d0").as_deref(), Some("PHYS8350012A480BGN"));
+        assert_eq!(diskinfo_serial(DISKINFO_PHYSICAL, "c3t0d0").as_deref(), Some("S437NE0M801234"));
+        // "-" 表示没有序列号
+        assert_eq!(diskinfo_serial(DISKINFO_PHYSICAL, "c2t0d0"), None);
+        assert_eq!(diskinfo_serial(DISKINFO_PHYSICAL, "c9t0d0"), None);
+        assert!(diskinfo_fixed_disks("").is_empty());
+    }
+
+    #[test]
+    fn dladm_macs_are_unescaped_and_padded() {
+        let macs = dladm_macs(DLADM_MACS);
+        let macs: Vec<(&str, &str)> = macs.iter().map(|(link, mac)| (link.as_str(), mac.as_str())).collect();
+        // 全 0 地址（未配置的 vioif0）不返回
+        assert_eq!(
+            macs,
+            [("igb0", "ac:1f:6b:4c:2d:3e"), ("igb1", "ac:1f:6b:4c:2d:3f"), ("ixgbe0", "00:1b:21:0a:0b:0c")]
+        );
+        assert_eq!(dladm_up_links(DLADM_STATES), ["igb0", "vioif0"]);
+        assert!(dladm_macs("igb0:zz\\:1f\n").is_empty());
+    }
+}
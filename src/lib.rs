@@ -0,0 +1,62 @@
+mod cached_id;
+pub mod command_runner;
+pub mod conformance;
+pub mod cpu_identity;
+pub mod denylist;
+pub mod environment;
+pub mod error;
+pub mod export;
+pub mod fingerprint;
+pub mod hardware_info;
+pub mod hash_algorithm;
+pub mod mac_address;
+pub mod machine_identity;
+pub mod normalize;
+pub mod output_format;
+pub mod progress;
+pub mod snapshot_cache;
+pub mod warning;
+#[cfg(all(target_os = "android", feature = "android"))]
+mod android;
+#[cfg(feature = "async")]
+mod async_collect;
+#[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+mod bsd;
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "illumos",
+    target_os = "solaris",
+    all(target_os = "android", feature = "android")
+)))]
+mod fallback;
+#[cfg(any(test, target_os = "illumos", target_os = "solaris"))]
+mod illumos;
+#[cfg(target_os = "linux")]
+mod linux;
+mod offline;
+mod parsers;
+#[cfg(any(test, target_os = "windows", target_os = "linux", target_os = "illumos", target_os = "solaris"))]
+mod smbios;
+mod state_file;
+
+pub use cached_id::{id, id_uncached, set_id_runner};
+pub use command_runner::{CommandRunner, SystemCommandRunner};
+pub use cpu_identity::CpuIdentity;
+pub use denylist::PlaceholderRule;
+pub use environment::{ContainerKind, Environment, WslStrategy, WslVersion};
+pub use error::{HardwareError, OptionsError};
+pub use export::{Export, ExportFormat};
+pub use fingerprint::{Component, ComponentSet, CriticalFieldPolicy, DiskCode, DiskCodeKind, DiskSelection, FingerprintBuilder, FingerprintVersion, MacSelection, NO_MAC_MARKER};
+pub use hardware_info::{BiosInfo, BoardInfo, CollectOptions, CollectionMode, CollectionReport, ValidatedOptions, CpuInfo, DiskInfo, GpuInfo, HardwareInfo, NetworkInfo, NetworkInterfaceType, TpmInfo, HARDWARE_ID_NAMESPACE};
+pub use hash_algorithm::HashAlgorithm;
+pub use mac_address::MacAddress;
+pub use machine_identity::{IdentitySource, MachineIdentity};
+pub use output_format::OutputFormat;
+pub use progress::{ProgressEvent, ProgressStatus};
+pub use snapshot_cache::SnapshotCache;
+pub use warning::{Acknowledgement, Acknowledgements, Warning, WarningId};
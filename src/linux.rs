@@ -0,0 +1,660 @@
+// Linux 上的采集辅助：经 ethtool ioctl 读取网卡的永久 MAC，以及按 sysfs 选出系统盘与主网卡
+// NetworkManager 与 systemd-networkd 的 MAC 随机化只改变当前地址（/sys/class/net/<名称>/address）
+
+use std::ffi::CString;
+use std::fs;
+use std::path::PathBuf;
+use crate::error::HardwareError;
+use crate::hardware_info::{HardwareInfo, NetworkInterfaceType};
+use crate::mac_address::MacAddress;
+use crate::parsers;
+
+const ETHTOOL_GPERMADDR: u32 = 0x20;
+// 以太网地址为 6 字节，其余链路层类型（InfiniBand 等）不超过 32 字节
+const MAX_ADDR_LEN: usize = 32;
+
+// struct ethtool_perm_addr，data 为变长数组，这里按最大长度预留
+#[repr(C)]
+struct EthtoolPermAddr {
+    cmd: u32,
+    size: u32,
+    data: [u8; MAX_ADDR_LEN],
+}
+
+// 不支持该 ioctl 的驱动（部分 USB 网卡、虚拟网卡）或返回全 0 时为 None，由调用方改用当前地址
+pub(crate) fn permanent_mac(interface: &str) -> Option<MacAddress> {
+    let name = CString::new(interface).ok()?;
+    if name.as_bytes_with_nul().len() > libc::IFNAMSIZ {
+        return None;
+    }
+    let mut request = EthtoolPermAddr { cmd: ETHTOOL_GPERMADDR, size: MAX_ADDR_LEN as u32, data: [0; MAX_ADDR_LEN] };
+    // SAFETY: ifreq 全 0 是合法值；名称长度已检查不超过 IFNAMSIZ（含 NUL）
+    let mut ifreq: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, src) in ifreq.ifr_name.iter_mut().zip(name.as_bytes()) {
+        *dst = *src as libc::c_char;
+    }
+    ifreq.ifr_ifru.ifru_data = (&mut request as *mut EthtoolPermAddr).cast();
+
+    // SAFETY: 参数均为有效值，返回的描述符在下面关闭
+    let socket = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if socket < 0 {
+        return None;
+    }
+    // SAFETY: ifr_data 指向的 request 在调用期间有效，内核最多写入 request.size 字节
+    let status = unsafe { libc::ioctl(socket, libc::SIOCETHTOOL as _, &mut ifreq) };
+    // SAFETY: socket 为上面打开的描述符，只关闭一次
+    unsafe { libc::close(socket) };
+
+    let address = request.data.get(..request.size as usize)?;
+    if status != 0 {
+        return None;
+    }
+    let address = MacAddress::new(address.try_into().ok()?);
+    (!address.is_zero()).then_some(address)
+}
+
+// sysfs 与 procfs 所在的根目录：采集时为 "/"，测试中为按相同布局建立的临时目录树
+#[derive(Debug, Clone)]
+pub(crate) struct SysRoot {
+    root: PathBuf,
+}
+
+impl Default for SysRoot {
+    fn default() -> Self {
+        Self { root: PathBuf::from("/") }
+    }
+}
+
+impl SysRoot {
+    #[cfg(test)]
+    pub(crate) fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, path: &str) -> PathBuf {
+        self.root.join(path.trim_start_matches('/'))
+    }
+
+    // 读取 sysfs/procfs 文件，拒绝经符号链接解析到挂载点之外的路径；错误信息中为不含根目录的路径
+    pub(crate) fn read_bytes(&self, path: &str) -> Result<Vec<u8>, HardwareError> {
+        let mount = if path.starts_with("/proc/") { "/proc" } else { "/sys" };
+        let resolved = fs::canonicalize(self.path(path)).map_err(|e| HardwareInfo::file_error(path, e))?;
+        let mount = fs::canonicalize(self.path(mount)).unwrap_or_else(|_| PathBuf::from(mount));
+        if !resolved.starts_with(&mount) {
+            return Err(HardwareError::FileError(format!(
+                "Refusing to read {}: resolves to {} outside {}",
+                path,
+                resolved.display(),
+                mount.display()
+            )));
+        }
+        fs::read(&resolved).map_err(|e| HardwareInfo::file_error(path, e))
+    }
+
+    pub(crate) fn read_file(&self, path: &str) -> Result<String, HardwareError> {
+        String::from_utf8(self.read_bytes(path)?).map_err(|e| HardwareError::FileError(format!("{}: {}", path, e)))
+    }
+
+    // 目录下的条目名称（按名称排序，与 readdir 的顺序无关）
+    pub(crate) fn list_dir(&self, path: &str) -> Result<Vec<String>, HardwareError> {
+        let mut names: Vec<String> = fs::read_dir(self.path(path))
+            .map_err(|e| HardwareInfo::file_error(path, e))?
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    pub(crate) fn exists(&self, path: &str) -> bool {
+        self.path(path).exists()
+    }
+
+    // 去掉首尾的空白与 NUL（model、NVMe serial 等属性以空格补齐到固定长度），空值视为没有
+    pub(crate) fn attribute(&self, path: &str) -> Option<String> {
+        let value = self.read_file(path).ok()?;
+        Some(value.trim_matches(|c: char| c.is_whitespace() || c == '\0').to_string()).filter(|value| !value.is_empty())
+    }
+
+    // 符号链接解析后的最后一级名称（/sys/dev/block/8:2 → sda2）
+    fn link_name(&self, path: &str) -> Option<String> {
+        fs::canonicalize(self.path(path)).ok()?.file_name()?.to_str().map(str::to_string)
+    }
+
+    // 挂载 / 的磁盘；根文件系统不在块设备上（overlay、tmpfs 等）时取 /sys/block 中第一块固定磁盘
+    pub(crate) fn system_disk(&self) -> Option<String> {
+        if let Some(disk) = self.root_disk() {
+            return Some(disk);
+        }
+        self.list_dir("/sys/block").ok()?.into_iter().find(|disk| self.is_fixed_disk(disk))
+    }
+
+    // /proc/self/mountinfo 中 / 的设备号经 /sys/dev/block 找到块设备（btrfs 等匿名设备号改用挂载源），
+    // 再归结到整块磁盘
+    fn root_disk(&self) -> Option<String> {
+        let mountinfo = self.read_file("/proc/self/mountinfo").ok()?;
+        let (device, source) = parsers::parse_root_mount(&mountinfo)?;
+        let name = self
+            .link_name(&format!("/sys/dev/block/{}", device))
+            .or_else(|| source.strip_prefix("/dev/").map(str::to_string))?;
+        self.whole_disk(&name, 0)
+    }
+
+    // 分区（有 partition 文件）取上级目录的磁盘；dm/LVM/LUKS 设备沿 slaves 逐层向下；
+    // 限制层数以防异常的 sysfs 出现循环
+    fn whole_disk(&self, name: &str, depth: usize) -> Option<String> {
+        if depth > 8 {
+            return None;
+        }
+        let path = fs::canonicalize(self.path(&format!("/sys/class/block/{}", name))).ok()?;
+        if path.join("partition").exists() {
+            return path.parent()?.file_name()?.to_str().map(str::to_string);
+        }
+        let slaves = self.list_dir(&format!("/sys/block/{}/slaves", name)).unwrap_or_default();
+        match slaves.first() {
+            Some(slave) => self.whole_disk(slave, depth + 1),
+            None => path.join("device").exists().then(|| name.to_string()),
+        }
+    }
+
+    // /sys/block 中的全部固定磁盘，按名称排序
+    pub(crate) fn fixed_disks(&self) -> Result<Vec<String>, HardwareError> {
+        Ok(self.list_dir("/sys/block")?.into_iter().filter(|disk| self.is_fixed_disk(disk)).collect())
+    }
+
+    // 有 device 目录（排除 loop、dm、zram 等虚拟设备）、不可移除且不经 USB 连接的磁盘；
+    // MMC 只取板载 eMMC（type 为 "MMC"），SD 卡即使 removable 为 0 也可随时插拔，排除在外，
+    // eMMC 的 boot0/boot1/rpmb 硬件分区也不算独立的磁盘
+    fn is_fixed_disk(&self, disk: &str) -> bool {
+        let Ok(device) = fs::canonicalize(self.path(&format!("/sys/block/{}/device", disk))) else {
+            return false;
+        };
+        if parsers::is_mmc_disk(disk)
+            && (parsers::is_mmc_hardware_partition(disk)
+                || self.disk_attribute(disk, "device/type").as_deref() != Some("MMC"))
+        {
+            return false;
+        }
+        self.disk_attribute(disk, "removable").as_deref() != Some("1")
+            && !device.components().any(|component| component.as_os_str().to_string_lossy().starts_with("usb"))
+    }
+
+    // eMMC/SD 的型号为 CID 中的产品名（device/name）
+    pub(crate) fn disk_model(&self, disk: &str) -> Option<String> {
+        if parsers::is_mmc_disk(disk) {
+            return self.disk_attribute(disk, "device/name");
+        }
+        self.nvme_attribute(disk, "model").or_else(|| self.disk_attribute(disk, "device/model"))
+    }
+
+    // NVMe 控制器与部分 SCSI 设备提供 serial，virtio 在块设备目录下，SATA 盘的序列号在 VPD 0x80 页中，
+    // 再不行用 wwid；eMMC/SD 由出厂写入的 CID 得到（见 parsers::mmc_serial_from_cid）
+    pub(crate) fn disk_serial(&self, disk: &str) -> Option<String> {
+        if parsers::is_mmc_disk(disk) {
+            return self.disk_attribute(disk, "device/cid").and_then(|cid| parsers::mmc_serial_from_cid(&cid));
+        }
+        self.nvme_attribute(disk, "serial")
+            .or_else(|| self.disk_attribute(disk, "device/serial"))
+            .or_else(|| self.disk_attribute(disk, "serial"))
+            .or_else(|| {
+                let page = self.read_bytes(&format!("/sys/block/{}/device/vpd_pg80", disk)).ok()?;
+                parsers::parse_vpd_serial(&page)
+            })
+            .or_else(|| self.disk_attribute(disk, "wwid"))
+    }
+
+    // eMMC/SD 的 rev 是 CID 中的产品版本而非固件版本，不作为固件
+    pub(crate) fn disk_firmware(&self, disk: &str) -> Option<String> {
+        if parsers::is_mmc_disk(disk) {
+            return None;
+        }
+        self.nvme_attribute(disk, "firmware_rev").or_else(|| {
+            ["device/firmware_rev", "device/rev"]
+                .iter()
+                .find_map(|attribute| self.disk_attribute(disk, attribute))
+        })
+    }
+
+    // udev 数据库中的 ID_SERIAL_SHORT（/run/udev/data/b<主:次>），不需要 udevadm
+    pub(crate) fn udev_serial_short(&self, disk: &str) -> Option<String> {
+        let device = self.disk_attribute(disk, "dev")?;
+        let data = fs::read_to_string(self.path(&format!("/run/udev/data/b{}", device))).ok()?;
+        parsers::parse_udev_property(&data, "E:ID_SERIAL_SHORT").ok().map(|serial| serial.trim().to_string())
+    }
+
+    // /sys/block/<disk>/<attribute>
+    fn disk_attribute(&self, disk: &str, attribute: &str) -> Option<String> {
+        self.attribute(&format!("/sys/block/{}/{}", disk, attribute))
+    }
+
+    // NVMe 的序列号、型号与固件版本属于控制器：nvme0n1 → /sys/class/nvme/nvme0/<attribute>
+    fn nvme_attribute(&self, disk: &str, attribute: &str) -> Option<String> {
+        let controller = parsers::parse_nvme_controller(disk)?;
+        self.attribute(&format!("/sys/class/nvme/{}/{}", controller, attribute))
+    }
+
+    // 有 device 目录的网卡是物理网卡（排除网桥、VPN、veth）
+    pub(crate) fn is_physical_interface(&self, name: &str) -> bool {
+        self.exists(&format!("/sys/class/net/{}/device", name))
+    }
+
+    // 按内核报告的链路类型（type 1 为以太网）区分，Wi-Fi 网卡另有 wireless 或 phy80211 目录；读不到时为 None
+    pub(crate) fn interface_type(&self, name: &str) -> Option<NetworkInterfaceType> {
+        let kind = self.read_file(&format!("/sys/class/net/{}/type", name)).ok()?;
+        let wireless = ["wireless", "phy80211"].iter().any(|dir| self.exists(&format!("/sys/class/net/{}/{}", name, dir)));
+        Some(match (kind.trim(), wireless) {
+            ("1", true) => NetworkInterfaceType::WiFi,
+            ("1", false) => NetworkInterfaceType::Ethernet,
+            _ => NetworkInterfaceType::Unknown,
+        })
+    }
+
+    // 默认路由所在的物理网卡，默认路由在 bond/网桥/team 上时取其下的全部物理网卡；
+    // 没有默认路由或其不是物理网卡时，取名称排序后第一块有载波的物理网卡；都没有（未联网）时
+    // 取第一块物理以太网卡，只有 Wi-Fi 的笔记本取 Wi-Fi 网卡。返回 (默认路由所在接口, 选中的物理网卡)
+    pub(crate) fn primary_interface(&self) -> Option<(String, Vec<String>)> {
+        let default_route = self
+            .read_file("/proc/net/route")
+            .ok()
+            .and_then(|route| parsers::parse_default_route_interface(&route));
+        if let Some(name) = default_route {
+            if self.is_physical_interface(&name) {
+                return Some((name.clone(), vec![name]));
+            }
+            let lower = self.lower_devices(&name);
+            if !lower.is_empty() {
+                return Some((name, lower));
+            }
+        }
+        let physical: Vec<String> = self
+            .list_dir("/sys/class/net")
+            .ok()?
+            .into_iter()
+            .filter(|name| self.is_physical_interface(name))
+            .collect();
+        let has_carrier =
+            |name: &&String| self.attribute(&format!("/sys/class/net/{}/carrier", name)).as_deref() == Some("1");
+        let of_type = |kind: NetworkInterfaceType| {
+            move |name: &&String| {
+                self.interface_type(name).unwrap_or_else(|| HardwareInfo::interface_type_from_name(name)) == kind
+            }
+        };
+        physical
+            .iter()
+            .find(has_carrier)
+            .or_else(|| physical.iter().find(of_type(NetworkInterfaceType::Ethernet)))
+            .or_else(|| physical.iter().find(of_type(NetworkInterfaceType::WiFi)))
+            .map(|name| (name.clone(), vec![name.clone()]))
+    }
+
+    // 按 ifindex 顺序第一个以太网类型（type 1）的物理网卡的永久 MAC（读不到时为当前地址）；
+    // 没有物理网卡时（容器中的 veth 对端）取第一个不属于虚拟接口名称的以太网接口
+    pub(crate) fn first_ether(&self) -> Option<String> {
+        let attribute = |interface: &str, name: &str| self.attribute(&format!("/sys/class/net/{}/{}", interface, name));
+        let mut interfaces: Vec<(u32, String)> = self
+            .list_dir("/sys/class/net")
+            .ok()?
+            .into_iter()
+            .filter(|interface| attribute(interface, "type").as_deref() == Some("1"))
+            .filter(|interface| !HardwareInfo::is_virtual_interface(interface))
+            .filter_map(|interface| Some((attribute(&interface, "ifindex")?.parse().ok()?, interface)))
+            .collect();
+        interfaces.sort();
+        let physical = interfaces.iter().find(|(_, interface)| self.is_physical_interface(interface));
+        let (_, interface) = physical.or_else(|| interfaces.first())?;
+        permanent_mac(interface).map(|mac| mac.to_string()).or_else(|| attribute(interface, "address"))
+    }
+
+    // 绑定（bonding/slaves）、网桥（brif/）与 team 等聚合接口（lower_<名称> 链接）下的物理网卡，排序去重；
+    // 下层设备本身也是聚合接口时（网桥建在 bond 之上）继续展开，不是聚合接口时为空
+    pub(crate) fn lower_devices(&self, interface: &str) -> Vec<String> {
+        let mut devices = Vec::new();
+        self.collect_lower_devices(interface, 0, &mut devices);
+        devices.sort();
+        devices.dedup();
+        devices
+    }
+
+    fn collect_lower_devices(&self, interface: &str, depth: usize, devices: &mut Vec<String>) {
+        // 层级通常不超过两层（br0 → bond0 → eno1），限制深度以防异常的环
+        if depth > 4 {
+            return;
+        }
+        let base = format!("/sys/class/net/{}", interface);
+        let mut lower: Vec<String> = fs::read_to_string(self.path(&format!("{}/bonding/slaves", base)))
+            .map(|slaves| slaves.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+        lower.extend(self.list_dir(&format!("{}/brif", base)).unwrap_or_default());
+        lower.extend(
+            self.list_dir(&base)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|entry| entry.strip_prefix("lower_").map(str::to_string)),
+        );
+        for name in lower {
+            if self.is_physical_interface(&name) {
+                devices.push(name);
+            } else {
+                self.collect_lower_devices(&name, depth + 1, devices);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    // 临时目录中按 sysfs/procfs 布局建立的目录树；sysfs 中的符号链接指向目录树内的绝对路径
+    pub(crate) struct FakeSys {
+        root: PathBuf,
+    }
+
+    impl FakeSys {
+        pub(crate) fn new() -> Self {
+            let root = std::env::temp_dir().join(format!("hardware_id-sysfs-{}", Uuid::new_v4().simple()));
+            fs::create_dir_all(root.join("sys")).unwrap();
+            fs::create_dir_all(root.join("proc")).unwrap();
+            Self { root }
+        }
+
+        pub(crate) fn sys(&self) -> SysRoot {
+            SysRoot::new(&self.root)
+        }
+
+        pub(crate) fn file(&self, path: &str, contents: &str) -> &Self {
+            let path = self.root.join(path.trim_start_matches('/'));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+            self
+        }
+
+        pub(crate) fn dir(&self, path: &str) -> &Self {
+            fs::create_dir_all(self.root.join(path.trim_start_matches('/'))).unwrap();
+            self
+        }
+
+        pub(crate) fn link(&self, path: &str, target: &str) -> &Self {
+            let path = self.root.join(path.trim_start_matches('/'));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::os::unix::fs::symlink(self.root.join(target.trim_start_matches('/')), path).unwrap();
+            self
+        }
+
+        // 一块磁盘：device 为 /sys/devices 下的物理设备，块设备目录在其下的 block/<name>，
+        // 并建立 /sys/block、/sys/class/block 与 /sys/dev/block 中的链接
+        pub(crate) fn disk(&self, name: &str, device: &str, dev: &str) -> &Self {
+            let device = format!("/sys/devices/{}", device);
+            let block = format!("{}/block/{}", device, name);
+            self.dir(&device)
+                .file(&format!("{}/dev", block), &format!("{}\n", dev))
+                .file(&format!("{}/removable", block), "0\n")
+                .link(&format!("{}/device", block), &device)
+                .link(&format!("/sys/block/{}", name), &block)
+                .link(&format!("/sys/class/block/{}", name), &block)
+                .link(&format!("/sys/dev/block/{}", dev), &block)
+        }
+
+        pub(crate) fn partition(&self, disk: &str, name: &str, dev: &str) -> &Self {
+            let block = fs::canonicalize(self.root.join(format!("sys/block/{}", disk))).unwrap();
+            let partition = block.join(name);
+            let partition = partition.strip_prefix(&self.root).unwrap().to_str().unwrap().to_string();
+            self.file(&format!("{}/partition", partition), "1\n")
+                .file(&format!("{}/dev", partition), &format!("{}\n", dev))
+                .link(&format!("/sys/class/block/{}", name), &partition)
+                .link(&format!("/sys/dev/block/{}", dev), &partition)
+        }
+
+        // / 挂载在设备号为 dev 的块设备上
+        pub(crate) fn root_on(&self, dev: &str, source: &str) -> &Self {
+            self.file(
+                "/proc/self/mountinfo",
+                &format!(
+                    "22 28 0:21 / /proc rw,nosuid,nodev,noexec,relatime shared:12 - proc proc rw\n\
+                     28 1 {} / / rw,relatime shared:1 - ext4 {} rw\n",
+                    dev, source
+                ),
+            )
+        }
+
+        // 一块网卡：physical 时有 device 目录，wireless 时有 wireless 目录
+        pub(crate) fn interface(&self, name: &str, index: u32, mac: &str, physical: bool, wireless: bool) -> &Self {
+            let base = format!("/sys/class/net/{}", name);
+            self.file(&format!("{}/type", base), "1\n")
+                .file(&format!("{}/ifindex", base), &format!("{}\n", index))
+                .file(&format!("{}/address", base), &format!("{}\n", mac))
+                .file(&format!("{}/carrier", base), "0\n");
+            if physical {
+                self.dir(&format!("{}/device", base));
+            }
+            if wireless {
+                self.dir(&format!("{}/wireless", base));
+            }
+            self
+        }
+    }
+
+    impl Drop for FakeSys {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    const SATA: &str = "pci0000:00/0000:00:17.0/ata1/host0/target0:0:0/0:0:0:0";
+    const USB: &str = "pci0000:00/0000:00:14.0/usb2/2-1/2-1:1.0/host6/target6:0:0/6:0:0:0";
+
+    #[test]
+    fn disk_attributes_are_read_from_sysfs() {
+        let fake = FakeSys::new();
+        fake.disk("sda", SATA, "8:0")
+            .file(&format!("/sys/devices/{}/model", SATA), "ST2000DM008-2FR102  \n")
+            .file(&format!("/sys/devices/{}/rev", SATA), "0001\n")
+            .file("/sys/block/sda/wwid", "t10.ATA     ST2000DM008-2FR102                      ZFL1ABCD\n")
+            .disk("sdb", USB, "8:16")
+            .file(&format!("/sys/devices/{}/model", USB), "Ultra Fit\n")
+            .disk("sdc", "pci0000:00/0000:00:17.0/ata2/host1/target1:0:0/1:0:0:0", "8:32")
+            .file("/sys/block/sdc/removable", "1\n")
+            .dir("/sys/devices/virtual/block/loop0")
+            .link("/sys/block/loop0", "/sys/devices/virtual/block/loop0");
+        let sys = fake.sys();
+        // USB 连接、可移除与没有 device 的虚拟设备都不是固定磁盘
+        assert_eq!(sys.fixed_disks().unwrap(), ["sda"]);
+        assert_eq!(sys.disk_model("sda").as_deref(), Some("ST2000DM008-2FR102"));
+        assert_eq!(sys.disk_firmware("sda").as_deref(), Some("0001"));
+        // 没有 serial 与 VPD 页时用 wwid
+        assert_eq!(
+            sys.disk_serial("sda").as_deref(),
+            Some("t10.ATA     ST2000DM008-2FR102                      ZFL1ABCD")
+        );
+        // / 不在块设备上时取第一块固定磁盘
+        fake.file("/proc/self/mountinfo", "28 1 0:31 / / rw,relatime shared:1 - overlay overlay rw\n");
+        assert_eq!(sys.system_disk().as_deref(), Some("sda"));
+    }
+
+    const NVME: &str = "pci0000:00/0000:00:1d.0/0000:3d:00.0/nvme/nvme0/nvme0n1";
+    const EMMC: &str = "platform/fe310000.mmc/mmc_host/mmc0/mmc0:0001";
+
+    // 与 / 所在分区的布局无关，都归结到同一块整盘
+    #[test]
+    fn root_partition_resolves_to_its_whole_disk() {
+        let fake = FakeSys::new();
+        fake.disk("sda", SATA, "8:0").partition("sda", "sda2", "8:2").root_on("8:2", "/dev/sda2");
+        assert_eq!(fake.sys().system_disk().as_deref(), Some("sda"));
+
+        let fake = FakeSys::new();
+        fake.disk("nvme0n1", NVME, "259:0")
+            .partition("nvme0n1", "nvme0n1p3", "259:3")
+            .root_on("259:3", "/dev/nvme0n1p3");
+        assert_eq!(fake.sys().system_disk().as_deref(), Some("nvme0n1"));
+    }
+
+    #[test]
+    fn root_on_emmc_skips_the_boot_partitions() {
+        let fake = FakeSys::new();
+        fake.disk("mmcblk0boot0", EMMC, "179:8")
+            .disk("mmcblk0", EMMC, "179:0")
+            .partition("mmcblk0", "mmcblk0p1", "179:1")
+            .file(&format!("/sys/devices/{}/type", EMMC), "MMC\n")
+            .file(&format!("/sys/devices/{}/name", EMMC), "DG4016\n")
+            .file(&format!("/sys/devices/{}/cid", EMMC), "450100444734303136c0a1b2c3d4e5f6\n")
+            .root_on("179:1", "/dev/mmcblk0p1");
+        let sys = fake.sys();
+        assert_eq!(sys.system_disk().as_deref(), Some("mmcblk0"));
+        assert_eq!(sys.fixed_disks().unwrap(), ["mmcblk0"]);
+        assert_eq!(sys.disk_model("mmcblk0").as_deref(), Some("DG4016"));
+        assert_eq!(sys.disk_serial("mmcblk0").as_deref(), Some("mmc-450100444734303136c0a1b2c3d4e5"));
+        assert_eq!(sys.disk_firmware("mmcblk0"), None);
+
+        // SD 卡（type 为 "SD"）不是固定磁盘
+        fake.file(&format!("/sys/devices/{}/type", EMMC), "SD\n");
+        assert!(sys.fixed_disks().unwrap().is_empty());
+    }
+
+    #[test]
+    fn root_on_device_mapper_follows_the_slaves() {
+        let fake = FakeSys::new();
+        fake.disk("sda", SATA, "8:0")
+            .disk("nvme0n1", NVME, "259:0")
+            .partition("nvme0n1", "nvme0n1p3", "259:3")
+            .dir("/sys/devices/virtual/block/dm-0/slaves/nvme0n1p3")
+            .file("/sys/devices/virtual/block/dm-0/dev", "253:0\n")
+            .link("/sys/block/dm-0", "/sys/devices/virtual/block/dm-0")
+            .link("/sys/class/block/dm-0", "/sys/devices/virtual/block/dm-0")
+            .link("/sys/dev/block/253:0", "/sys/devices/virtual/block/dm-0")
+            .root_on("253:0", "/dev/mapper/vg-root");
+        let sys = fake.sys();
+        assert_eq!(sys.system_disk().as_deref(), Some("nvme0n1"));
+        assert_eq!(sys.fixed_disks().unwrap(), ["nvme0n1", "sda"]);
+
+        // btrfs 等匿名设备号在 /sys/dev/block 中找不到时按挂载源的名称查找
+        let fake = FakeSys::new();
+        fake.disk("sda", SATA, "8:0")
+            .disk("nvme0n1", NVME, "259:0")
+            .partition("nvme0n1", "nvme0n1p3", "259:3")
+            .root_on("0:33", "/dev/nvme0n1p3");
+        assert_eq!(fake.sys().system_disk().as_deref(), Some("nvme0n1"));
+    }
+
+    // 控制器属性按 NVMe Identify 的定长字段以空格补齐：serial 20 字节，model 40 字节
+    const NVME_DRIVES: [[(&str, &str); 3]; 2] = [
+        [
+            ("serial", include_str!("../tests/fixtures/linux/nvme/samsung_970_evo_plus/serial")),
+            ("model", include_str!("../tests/fixtures/linux/nvme/samsung_970_evo_plus/model")),
+            ("firmware_rev", include_str!("../tests/fixtures/linux/nvme/samsung_970_evo_plus/firmware_rev")),
+        ],
+        [
+            ("serial", include_str!("../tests/fixtures/linux/nvme/wd_sn550/serial")),
+            ("model", include_str!("../tests/fixtures/linux/nvme/wd_sn550/model")),
+            ("firmware_rev", include_str!("../tests/fixtures/linux/nvme/wd_sn550/firmware_rev")),
+        ],
+    ];
+
+    fn nvme_drive(attributes: &[(&str, &str)]) -> FakeSys {
+        let fake = FakeSys::new();
+        fake.disk("nvme0n1", NVME, "259:0")
+            .partition("nvme0n1", "nvme0n1p2", "259:2")
+            .root_on("259:2", "/dev/nvme0n1p2");
+        for (attribute, contents) in attributes {
+            let controller = "/sys/devices/pci0000:00/0000:00:1d.0/0000:3d:00.0/nvme/nvme0";
+            fake.file(&format!("{}/{}", controller, attribute), contents)
+                .link(&format!("/sys/class/nvme/nvme0/{}", attribute), &format!("{}/{}", controller, attribute));
+        }
+        fake
+    }
+
+    #[test]
+    fn nvme_identity_comes_from_the_controller_trimmed() {
+        let expected = [
+            ("S4EWNX0R123456K", "Samsung SSD 970 EVO Plus 1TB", "2B2QEXM7"),
+            ("21160N800123", "WDC WDS500G2B0C-00PXH0", "211070WD"),
+        ];
+        for (attributes, (serial, model, firmware)) in NVME_DRIVES.iter().zip(expected) {
+            let fake = nvme_drive(attributes);
+            let sys = fake.sys();
+            let disk = sys.system_disk().unwrap();
+            assert_eq!(disk, "nvme0n1");
+            assert_eq!(sys.disk_serial(&disk).as_deref(), Some(serial));
+            assert_eq!(sys.disk_model(&disk).as_deref(), Some(model));
+            assert_eq!(sys.disk_firmware(&disk).as_deref(), Some(firmware));
+        }
+    }
+
+    #[test]
+    fn udev_serial_short_matches_the_nvme_serial() {
+        let fake = nvme_drive(&NVME_DRIVES[0]);
+        fake.file("/run/udev/data/b259:0", include_str!("../tests/fixtures/linux/udev_data_nvme.txt"));
+        let sys = fake.sys();
+        assert_eq!(sys.udev_serial_short("nvme0n1"), sys.disk_serial("nvme0n1"));
+        assert_eq!(sys.udev_serial_short("sda"), None);
+    }
+
+    // 两种 eMMC 与一张 SD 卡的 CID 属性（mmc_host 下卡设备目录中的 cid、name、manfid、type）
+    macro_rules! mmc_fixture {
+        ($part:literal) => {
+            [
+                ("cid", include_str!(concat!("../tests/fixtures/linux/mmc/", $part, "/cid"))),
+                ("name", include_str!(concat!("../tests/fixtures/linux/mmc/", $part, "/name"))),
+                ("manfid", include_str!(concat!("../tests/fixtures/linux/mmc/", $part, "/manfid"))),
+                ("type", include_str!(concat!("../tests/fixtures/linux/mmc/", $part, "/type"))),
+            ]
+        };
+    }
+    const EMMC_PARTS: [[(&str, &str); 4]; 2] = [mmc_fixture!("samsung_bjtd4r"), mmc_fixture!("sandisk_dg4016")];
+    const SD_CARD: [(&str, &str); 4] = mmc_fixture!("sandisk_sd_sc64g");
+
+    fn mmc_card(fake: &FakeSys, disk: &str, host: &str, dev: &str, attributes: &[(&str, &str)]) {
+        let device = format!("platform/soc/mmc_host/{0}/{0}:0001", host);
+        fake.disk(disk, &device, dev);
+        for (attribute, contents) in attributes {
+            fake.file(&format!("/sys/devices/{}/{}", device, attribute), contents);
+        }
+    }
+
+    #[test]
+    fn emmc_identity_comes_from_the_cid_and_sd_cards_are_skipped() {
+        let expected = [
+            ("mmc-150100424a544434520a9c1d2e3f4b", "BJTD4R"),
+            ("mmc-450100444734303136085c3a7b916e", "DG4016"),
+        ];
+        for (attributes, (serial, model)) in EMMC_PARTS.iter().zip(expected) {
+            let fake = FakeSys::new();
+            mmc_card(&fake, "mmcblk0", "mmc0", "179:0", attributes);
+            // removable 为 0 的 SD 卡同样按 type 排除，插拔不改变系统盘
+            mmc_card(&fake, "mmcblk1", "mmc1", "179:32", &SD_CARD);
+            fake.file("/proc/self/mountinfo", "28 1 0:31 / / rw,relatime shared:1 - overlay overlay rw\n");
+            let sys = fake.sys();
+            assert_eq!(sys.fixed_disks().unwrap(), ["mmcblk0"]);
+            assert_eq!(sys.system_disk().as_deref(), Some("mmcblk0"));
+            assert_eq!(sys.disk_serial("mmcblk0").as_deref(), Some(serial));
+            assert_eq!(sys.disk_model("mmcblk0").as_deref(), Some(model));
+            assert_eq!(sys.disk_firmware("mmcblk0"), None);
+        }
+    }
+
+    #[test]
+    fn first_ether_is_the_lowest_ifindex_physical_ethernet() {
+        let fake = FakeSys::new();
+        fake.interface("docker0", 2, "02:42:ac:11:00:01", false, false)
+            .interface("wlp3s0", 3, "3c:a9:f4:10:20:30", true, true)
+            .interface("enp0s31f6", 4, "8c:16:45:5a:6b:7c", true, false)
+            .interface("veth1a2b3c4", 5, "76:3e:9d:00:11:22", false, false);
+        // Wi-Fi 的链路类型同样为 1，按 ifindex 取第一块物理网卡
+        assert_eq!(fake.sys().first_ether().as_deref(), Some("3c:a9:f4:10:20:30"));
+
+        // 容器中只有 veth 对端时取第一个不属于虚拟接口名称的以太网接口
+        let fake = FakeSys::new();
+        fake.interface("docker0", 2, "02:42:ac:11:00:01", false, false)
+            .interface("ens3", 3, "52:54:00:12:34:56", false, false);
+        assert_eq!(fake.sys().first_ether().as_deref(), Some("52:54:00:12:34:56"));
+        assert_eq!(FakeSys::new().sys().first_ether(), None);
+    }
+
+    #[test]
+    fn files_outside_the_mount_are_refused() {
+        let fake = FakeSys::new();
+        fake.file("/etc/hostname", "host\n").link("/sys/class/dmi/id/product_name", "/etc/hostname");
+        let error = fake.sys().read_file("/sys/class/dmi/id/product_name").unwrap_err();
+        assert!(matches!(&error, HardwareError::FileError(message) if message.starts_with("Refusing to read")), "{error:?}");
+        assert!(matches!(fake.sys().read_file("/sys/class/dmi/id/missing"), Err(HardwareError::FileError(_))));
+    }
+}
@@ -0,0 +1,207 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Serialize, Deserialize};
+
+// 48 位 MAC 地址；解析各平台与工具的写法，显示与序列化统一为小写、":" 分隔（aa:bb:cc:dd:ee:ff），
+// 因此同一地址的不同写法得到相同的哈希输入
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct MacAddress([u8; 6]);
+
+impl MacAddress {
+    pub fn new(octets: [u8; 6]) -> Self {
+        Self(octets)
+    }
+
+    pub fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|b| *b == 0)
+    }
+
+    // 第一个字节的最低位：组播/广播地址，不属于某块网卡
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+
+    // 第一个字节的次低位：本地管理的地址，MAC 随机化、Wi-Fi 私有地址与多数虚拟网卡使用这类地址
+    pub fn is_locally_administered(&self) -> bool {
+        self.0[0] & 0x02 != 0
+    }
+
+    // 可作为网卡地址：非全 0、非组播
+    pub fn is_unicast(&self) -> bool {
+        !self.is_zero() && !self.is_multicast()
+    }
+
+    // 厂商分配（全局唯一）的单播地址，选择主网卡时优先
+    pub fn is_universal(&self) -> bool {
+        self.is_unicast() && !self.is_locally_administered()
+    }
+}
+
+impl From<[u8; 6]> for MacAddress {
+    fn from(octets: [u8; 6]) -> Self {
+        Self(octets)
+    }
+}
+
+// 支持 aa:bb:cc:dd:ee:ff、AA-BB-CC-DD-EE-FF（Windows getmac）、aabb.ccdd.eeff（Cisco）、aabbccddeeff，
+// 以及省略前导 0 的 0:c:29:a:b:c；前后空白与大小写不影响结果
+impl FromStr for MacAddress {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let trimmed = value.trim();
+        let invalid = || format!("invalid MAC address '{}'", trimmed);
+        let hex: String = if trimmed.contains([':', '-']) {
+            let octets: Vec<&str> = trimmed.split([':', '-']).collect();
+            if octets.len() != 6 || octets.iter().any(|octet| octet.is_empty() || octet.len() > 2) {
+                return Err(invalid());
+            }
+            octets.iter().map(|octet| format!("{:0>2}", octet)).collect()
+        } else if trimmed.contains('.') {
+            let groups: Vec<&str> = trimmed.split('.').collect();
+            if groups.len() != 3 || groups.iter().any(|group| group.len() != 4) {
+                return Err(invalid());
+            }
+            groups.concat()
+        } else {
+            trimmed.to_string()
+        };
+        if hex.len() != 12 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let mut octets = [0u8; 6];
+        for (i, octet) in octets.iter_mut().enumerate() {
+            *octet = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+        }
+        Ok(Self(octets))
+    }
+}
+
+impl TryFrom<String> for MacAddress {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<MacAddress> for String {
+    fn from(mac: MacAddress) -> Self {
+        mac.to_string()
+    }
+}
+
+impl fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(f, "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}", a, b, c, d, e, g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::FingerprintBuilder;
+    use crate::hardware_info::tests::{sample, sample_with};
+
+    fn mac(value: &str) -> MacAddress {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn every_written_form_is_the_same_address() {
+        let expected = MacAddress::new([0xd8, 0x9e, 0xf3, 0x02, 0x34, 0x56]);
+        for written in [
+            "d8:9e:f3:02:34:56",
+            "D8:9E:F3:02:34:56",
+            "D8-9E-F3-02-34-56",
+            "d89e.f302.3456",
+            "d89ef3023456",
+            "d8:9e:f3:2:34:56",
+            "  d8:9e:f3:02:34:56\n",
+        ] {
+            assert_eq!(mac(written), expected, "{:?}", written);
+        }
+        assert_eq!(expected.to_string(), "d8:9e:f3:02:34:56");
+    }
+
+    // 按各种写法格式化后解析回原地址；地址由固定的线性同余序列生成，覆盖各字节的高低位
+    #[test]
+    fn formatting_and_parsing_round_trip() {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut octets = || {
+            let mut octets = [0u8; 6];
+            for octet in octets.iter_mut() {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                *octet = (state >> 56) as u8;
+            }
+            octets
+        };
+        for octets in (0..500).map(|_| octets()).chain([[0; 6], [0xff; 6]]) {
+            let address = MacAddress::new(octets);
+            let canonical = address.to_string();
+            let hex: String = octets.iter().map(|b| format!("{:02X}", b)).collect();
+            for written in [
+                canonical.clone(),
+                canonical.to_uppercase().replace(':', "-"),
+                format!("{}.{}.{}", &hex[..4], &hex[4..8], &hex[8..]),
+                hex.clone(),
+            ] {
+                assert_eq!(mac(&written), address, "{}", written);
+            }
+            assert_eq!(mac(&canonical).to_string(), canonical);
+            assert_eq!(serde_json::from_value::<MacAddress>(serde_json::to_value(address).unwrap()).unwrap(), address);
+        }
+    }
+
+    #[test]
+    fn malformed_addresses_are_rejected() {
+        for written in [
+            "",
+            "d8:9e:f3:02:34",
+            "d8:9e:f3:02:34:56:78",
+            "d8:9e:f3::34:56",
+            "d8:9e:f3:002:34:56",
+            "g8:9e:f3:02:34:56",
+            "d89e.f302.345",
+            "d89ef302345",
+            "d8:9e:f3:02:34:5g",
+        ] {
+            let error = written.parse::<MacAddress>().unwrap_err();
+            assert!(error.starts_with("invalid MAC address"), "{}", error);
+        }
+        assert!(serde_json::from_value::<MacAddress>("not a mac".into()).is_err());
+    }
+
+    #[test]
+    fn address_kinds() {
+        assert!(mac("00:00:00:00:00:00").is_zero());
+        assert!(!mac("00:00:00:00:00:00").is_unicast());
+        // 组播与广播
+        assert!(mac("01:00:5e:00:00:fb").is_multicast());
+        assert!(!mac("ff:ff:ff:ff:ff:ff").is_unicast());
+        // 随机化与 Docker 的地址是本地管理的
+        assert!(mac("5a:1e:7c:33:90:0d").is_locally_administered());
+        assert!(mac("02:42:ac:11:00:02").is_unicast());
+        assert!(!mac("02:42:ac:11:00:02").is_universal());
+        assert!(mac("d8:9e:f3:12:34:56").is_universal());
+    }
+
+    // 同一块网卡的不同写法（Windows 的 getmac 与 Linux 的 sysfs）得到相同的唯一码
+    #[test]
+    fn equivalent_representations_hash_identically() {
+        let uppercase = sample_with(|value| {
+            value["network_interfaces"][0]["mac_address"] = "D8-9E-F3-12-34-56".into();
+            value["network_interfaces"][1]["mac_address"] = "a434.d965.4321".into();
+        });
+        assert_eq!(uppercase.network_interfaces()[0].mac_address(), mac("d8:9e:f3:12:34:56"));
+        let builder = FingerprintBuilder::new();
+        assert_eq!(builder.hash_input(&uppercase), builder.hash_input(&sample()));
+        assert_eq!(builder.hash(&uppercase).unwrap(), builder.hash(&sample()).unwrap());
+    }
+}
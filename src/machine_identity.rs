@@ -0,0 +1,232 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use log::{debug, warn};
+use crate::denylist;
+use crate::state_file;
+#[cfg(target_os = "linux")]
+use crate::hardware_info::HardwareInfo;
+
+// 机器身份的来源，按回退顺序排列，越靠前可信度越高
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentitySource {
+    BoardSerial,
+    SystemSerial,
+    ChassisSerial,
+    ProductUuid,
+    HypervisorUuid,
+    MachineGuid,
+    MachineId,
+    Persisted,
+    #[default]
+    Unavailable,
+}
+
+impl IdentitySource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            IdentitySource::BoardSerial => "board_serial",
+            IdentitySource::SystemSerial => "system_serial",
+            IdentitySource::ChassisSerial => "chassis_serial",
+            IdentitySource::ProductUuid => "product_uuid",
+            IdentitySource::HypervisorUuid => "hypervisor_uuid",
+            IdentitySource::MachineGuid => "machine_guid",
+            IdentitySource::MachineId => "machine_id",
+            IdentitySource::Persisted => "persisted",
+            IdentitySource::Unavailable => "unavailable",
+        }
+    }
+
+    // 来自固件/虚拟化层，而非操作系统安装或本地文件
+    pub fn is_hardware_backed(&self) -> bool {
+        matches!(
+            self,
+            IdentitySource::BoardSerial
+                | IdentitySource::SystemSerial
+                | IdentitySource::ChassisSerial
+                | IdentitySource::ProductUuid
+                | IdentitySource::HypervisorUuid
+        )
+    }
+}
+
+impl fmt::Display for IdentitySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// 回退链：主板序列号 → 系统序列号 → 机箱序列号 → 产品 UUID → 虚拟化层提供的 UUID → MachineGuid（Windows）→ machine-id
+// → 本地持久化的随机 ID
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MachineIdentity {
+    source: IdentitySource,
+    value: String,
+}
+
+impl MachineIdentity {
+    pub fn source(&self) -> IdentitySource {
+        self.source
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    // 虚拟机的系统/机箱序列号常为空或随模板复制，主板序列号不可用时先取产品 UUID（即 VM UUID）
+    pub fn resolve(
+        virtualized: bool,
+        board_serial: &str,
+        system_serial: Option<&str>,
+        chassis_serial: Option<&str>,
+        product_uuid: &str,
+        machine_guid: Option<&str>,
+    ) -> Self {
+        let mut candidates: [(IdentitySource, &dyn Fn() -> Option<String>); 8] = [
+            (IdentitySource::BoardSerial, &|| Some(board_serial.to_string())),
+            (IdentitySource::SystemSerial, &|| system_serial.map(str::to_string)),
+            (IdentitySource::ChassisSerial, &|| chassis_serial.map(str::to_string)),
+            (IdentitySource::ProductUuid, &|| Some(product_uuid.to_string())),
+            (IdentitySource::HypervisorUuid, &Self::hypervisor_uuid),
+            (IdentitySource::MachineGuid, &|| machine_guid.map(str::to_string)),
+            (IdentitySource::MachineId, &Self::machine_id),
+            (IdentitySource::Persisted, &Self::persisted_id),
+        ];
+        if virtualized {
+            candidates[1..4].rotate_right(1);
+        }
+
+        for (source, read) in candidates {
+            if let Some(value) = read().filter(|value| Self::is_usable(value)) {
+                debug!("Machine identity taken from {}", source);
+                return Self { source, value: value.trim().to_string() };
+            }
+        }
+        warn!("No machine identity source is available");
+        Self::default()
+    }
+
+    // 离线镜像中没有固件信息，回退链只剩 machine-id；不可用时为 Unavailable
+    pub(crate) fn from_machine_id(machine_id: Option<&str>) -> Self {
+        match machine_id.filter(|value| Self::is_usable(value)) {
+            Some(value) => Self { source: IdentitySource::MachineId, value: value.trim().to_string() },
+            None => Self::default(),
+        }
+    }
+
+    fn is_usable(value: &str) -> bool {
+        !value.trim().is_empty() && !denylist::is_placeholder_serial(value) && !denylist::is_known_duplicate(value)
+    }
+
+    // Xen 通过 /sys/hypervisor/uuid 提供，VMware 把 UUID 写在产品序列号里（"VMware-56 4d ... -.. .."）
+    fn hypervisor_uuid() -> Option<String> {
+        #[cfg(target_os = "linux")]
+        {
+            if let Ok(uuid) = HardwareInfo::read_sys_file("/sys/hypervisor/uuid") {
+                return Some(uuid.trim().to_string());
+            }
+            let serial = HardwareInfo::read_sys_file("/sys/class/dmi/id/product_serial").ok()?;
+            let hex: String = serial
+                .trim()
+                .strip_prefix("VMware-")?
+                .chars()
+                .filter(char::is_ascii_hexdigit)
+                .collect();
+            Uuid::try_parse(&hex).ok().map(|uuid| uuid.to_string())
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            None
+        }
+    }
+
+    fn machine_id() -> Option<String> {
+        #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+        {
+            read_machine_id().or_else(|| std::fs::read_to_string("/etc/hostid").ok().map(|id| id.trim().to_string()))
+        }
+
+        #[cfg(not(any(target_os = "linux", target_os = "freebsd", target_os = "openbsd", target_os = "netbsd")))]
+        {
+            None
+        }
+    }
+
+    // 首次使用时生成随机 UUID 并写入本地文件，之后一直复用；文件不跟随符号链接、仅所有者可读写
+    fn persisted_id() -> Option<String> {
+        Self::persisted_id_at(&Self::persisted_id_path()?)
+    }
+
+    // 两个进程同时首次运行时只有一个能创建文件，另一个读回已写入的 ID
+    fn persisted_id_at(path: &Path) -> Option<String> {
+        if let Ok(id) = state_file::read(path) {
+            if !id.trim().is_empty() {
+                return Some(id.trim().to_string());
+            }
+        }
+
+        let id = Uuid::new_v4().to_string();
+        match state_file::create_new(path, id.as_bytes()) {
+            Ok(()) => Some(id),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                state_file::read(path).ok().map(|id| id.trim().to_string()).filter(|id| !id.is_empty())
+            }
+            Err(e) => {
+                warn!("Failed to persist machine identity to {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    fn persisted_id_path() -> Option<PathBuf> {
+        #[cfg(target_os = "windows")]
+        let base = std::env::var_os("PROGRAMDATA").map(PathBuf::from);
+
+        #[cfg(target_os = "macos")]
+        let base = std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"));
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let base = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+
+        base.map(|base| base.join("hardware_id").join("machine-id"))
+    }
+}
+
+// systemd 的 machine-id，优先 /etc/machine-id，其次 D-Bus 的副本
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
+pub(crate) fn read_machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .find_map(|id| parse_machine_id(&id))
+}
+
+// 空值与全 0（镜像中尚未初始化）视为缺失
+pub(crate) fn parse_machine_id(contents: &str) -> Option<String> {
+    let id = contents.trim();
+    (!id.is_empty() && !id.chars().all(|c| c == '0')).then(|| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_first_runs_agree_on_the_persisted_id() {
+        let dir = std::env::temp_dir().join(format!("hardware_id-persisted-{}", Uuid::new_v4().simple()));
+        let path = dir.join("hardware_id").join("machine-id");
+        let ids: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8).map(|_| scope.spawn(|| MachineIdentity::persisted_id_at(&path))).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        assert!(ids[0].is_some());
+        assert!(ids.iter().all(|id| *id == ids[0]), "{ids:?}");
+        assert_eq!(MachineIdentity::persisted_id_at(&path), ids[0]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,34 +1,992 @@
-use std::error::Error;
-use log::{info, error};
-mod hardware_info;
-use hardware_info::HardwareInfo;
-
-fn main() -> Result<(), Box<dyn Error>> {
-    env_logger::init();
-    
-    info!("Starting hardware ID collection...");
-    
-    let hardware_id = match HardwareInfo::collect() {
-        Ok(id) => id,
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime};
+use clap::{Args, Parser, Subcommand};
+use log::{info, warn, error};
+use hardware_id::{CollectOptions, CollectionReport, Component, ComponentSet, CriticalFieldPolicy, DiskCode, Export, ExportFormat, FingerprintBuilder, HardwareError, FingerprintVersion, HardwareInfo, IdentitySource, MacSelection, OutputFormat, ProgressEvent, SnapshotCache, HARDWARE_ID_NAMESPACE};
+use hardware_id::conformance;
+use hardware_id::warning::{self, Acknowledgements, Warning, WarningId};
+use sysinfo::{System, SystemExt};
+use uuid::Uuid;
+
+const EXIT_STATUS: &str = "\
+Exit status:
+  0 success (verify: the code matches, diff: no changes, conformance check: every case matches),
+  1 verify/diff/conformance check mismatch,
+  2 invalid arguments or other errors; collection errors with a known cause use
+  3 critical hardware information missing, 4 permission denied,
+  5 unsupported system or environment (container); any status of 2 or more is an error";
+
+#[derive(Parser)]
+#[command(
+    name = "hardware_id",
+    version,
+    about = "Collects hardware information and prints the unique code.",
+    after_help = EXIT_STATUS
+)]
+struct Cli {
+    // 不带子命令时与 collect 相同，保持原有的无参数用法
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    options: Options,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "Print the collected hardware information and the unique code (the default)")]
+    Collect(CollectArgs),
+    #[command(about = "Print only the unique code (or the UUID with --uuid); implies --quiet unless --verbose is given")]
+    Hash,
+    #[command(about = "Collect again and compare with CODE; exit status 0 if it matches, 1 if not")]
+    Verify {
+        #[arg(help = "Code printed by an earlier run; v2: and nomac: prefixes select the scheme to compare with")]
+        code: String,
+    },
+    #[command(about = "Compare the current hardware with a saved snapshot; exit status 0 if unchanged, 1 if not")]
+    Diff {
+        #[arg(help = "Output of collect --format (.json, .yaml/.yml or .toml), a bare HardwareInfo JSON, \
+                      or the --cache snapshot")]
+        snapshot: PathBuf,
+    },
+    #[command(
+        subcommand,
+        about = "Write or re-verify the conformance kit (fixtures with the expected hash inputs and codes) \
+                 for other implementations of the code"
+    )]
+    Conformance(ConformanceCommand),
+    #[command(subcommand, about = "Acknowledge warnings so that they are no longer printed on this machine")]
+    Warn(WarnCommand),
+    #[command(
+        about = "Read a mounted Linux system image (machine-id, MAC addresses from the udev rules and systemd \
+                 .link files, root filesystem UUID) and print its code; firmware and hardware fields are unavailable"
+    )]
+    Offline(OfflineArgs),
+}
+
+#[derive(Subcommand)]
+enum ConformanceCommand {
+    #[command(about = "Write the kit into DIR (README.md, manifest.json and fixtures/)")]
+    Generate {
+        #[arg(long, value_name = "DIR")]
+        output: PathBuf,
+    },
+    #[command(about = "Recompute every case in DIR with this build; exit status 0 if all match, 1 if not")]
+    Check {
+        #[arg(long, value_name = "DIR")]
+        dir: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum WarnCommand {
+    #[command(about = "Stop printing the warning ID on stderr for the current user on this machine \
+                       (collect --format still lists it)")]
+    Ack {
+        #[arg(value_name = "ID", help = "Warning ID, e.g. container; warn list shows them all")]
+        id: WarningId,
+    },
+    #[command(about = "List the warning IDs and when each was acknowledged")]
+    List,
+}
+
+#[derive(Args, Default)]
+struct CollectArgs {
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        help = "Print the hardware information as json, yaml or toml, wrapped with the tool version, \
+                schema version and collection time, instead of the text dump and the code"
+    )]
+    format: Option<ExportFormat>,
+    #[arg(long, requires = "format", help = "Print JSON on a single line (other formats are unaffected)")]
+    compact: bool,
+    #[arg(long, requires = "format", help = "Include the host name in the output")]
+    hostname: bool,
+}
+
+#[derive(Args)]
+struct OfflineArgs {
+    #[arg(long, value_name = "DIR", help = "Mount point of the image's root filesystem")]
+    root: PathBuf,
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        help = "Print the information read from the image as json, yaml or toml (collection_mode is offline) \
+                instead of the code"
+    )]
+    format: Option<ExportFormat>,
+    #[arg(long, requires = "format", help = "Print JSON on a single line (other formats are unaffected)")]
+    compact: bool,
+}
+
+#[derive(Args)]
+struct Options {
+    #[arg(
+        long,
+        global = true,
+        value_parser = parse_key,
+        value_name = "KEY",
+        help = "Derive an application-specific code with HMAC-SHA256; the key is used as text, \
+                or decoded from hex when written as hex:<digits>"
+    )]
+    key: Option<Key>,
+    #[arg(long, global = true, value_name = "FORMAT", help = "Code encoding: grouped (default), hex, base32, base58")]
+    output_format: Option<OutputFormat>,
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        value_parser = parse_length,
+        help = "Characters to keep (grouped defaults to 16, others to full length)"
+    )]
+    length: Option<usize>,
+    #[arg(
+        long,
+        global = true,
+        value_name = "VERSION",
+        help = "Fingerprint scheme: v1 (default, no prefix) or v2 (codes start with v2:)"
+    )]
+    scheme: Option<FingerprintVersion>,
+    #[arg(
+        long,
+        global = true,
+        value_name = "FIELDS",
+        value_delimiter = ',',
+        conflicts_with = "exclude_fields",
+        help = "Hash only these comma-separated fields, e.g. motherboard_uuid,disk_serial \
+                (order does not matter)"
+    )]
+    fields: Vec<Component>,
+    #[arg(
+        long,
+        global = true,
+        value_name = "FIELDS",
+        value_delimiter = ',',
+        help = "Leave these comma-separated fields out of the default set, e.g. mac_address"
+    )]
+    exclude_fields: Vec<Component>,
+    #[arg(
+        long,
+        global = true,
+        help = "Leave the network MAC addresses out of the code (for docks and USB network adapters); \
+                codes then carry the nomac: marker"
+    )]
+    no_mac: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "Hash only the primary network interface's MAC instead of every physical interface \
+                (matches codes generated before all interfaces were kept)"
+    )]
+    primary_mac: bool,
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "uuid",
+        help = "On machines whose internal disks each hold a system, also print the codes computed with each \
+                other internal disk as the system disk (at most 4, labeled alternate); verify accepts any of them"
+    )]
+    multi_disk_codes: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "If the motherboard serial/UUID is missing, derive the code from the fallback identity \
+                (system/chassis serial, product UUID, hypervisor UUID, machine-id, persisted ID)"
+    )]
+    fallback: bool,
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "Show whether the process is elevated, the detected hypervisor, and the fields that could \
+                not be collected and why"
+    )]
+    verbose: bool,
+    #[arg(
+        short,
+        long,
+        global = true,
+        conflicts_with = "verbose",
+        help = "Print only the code on stdout (verify and diff print nothing); no warnings or log output, \
+                and errors are a single \"error: <kind>: <message>\" line on stderr"
+    )]
+    quiet: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "Generate a code even when running inside a container (the code then reflects the host or \
+                the container, not a stable machine)"
+    )]
+    allow_container: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "Under WSL, read the Windows host's MachineGuid through cmd.exe and use it in place of the \
+                missing motherboard serial"
+    )]
+    wsl_host_identity: bool,
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECS",
+        value_parser = parse_timeout,
+        help = "Give up on a hardware query command after SECS seconds (default 5)"
+    )]
+    timeout: Option<Duration>,
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["key", "output_format", "length", "scheme", "fallback"],
+        help = "Print the identity as a UUIDv5 instead of the unique code"
+    )]
+    uuid: bool,
+    #[arg(
+        long,
+        global = true,
+        value_name = "UUID",
+        requires = "uuid",
+        help = "Namespace for --uuid (defaults to the built-in namespace)"
+    )]
+    namespace: Option<Uuid>,
+    #[arg(
+        long,
+        global = true,
+        help = "Reuse the hardware information collected by an earlier run (stored in the user's cache \
+                directory, readable only by the user)"
+    )]
+    cache: bool,
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECS",
+        requires = "cache",
+        value_parser = parse_cache_ttl,
+        help = "How long a cached snapshot stays valid (default 86400, one day)"
+    )]
+    cache_ttl: Option<Duration>,
+    #[arg(long, global = true, requires = "cache", help = "Collect again and overwrite the cached snapshot")]
+    refresh: bool,
+    #[arg(long, global = true, help = "Neither read nor write the cache (overrides --cache)")]
+    no_cache: bool,
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "quiet",
+        help = "Report the hardware query commands on stderr as they run (a single updating line on a terminal)"
+    )]
+    progress: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "Print no warnings on stderr; collect --format still lists every warning under warnings"
+    )]
+    no_warnings: bool,
+}
+
+// HMAC 密钥；单独的类型避免 clap 把 Vec<u8> 当作多个参数值
+#[derive(Clone)]
+struct Key(Vec<u8>);
+
+fn main() -> ExitCode {
+    // 先解析参数：--help/--version 与参数错误（退出码 2）不触发任何硬件采集
+    let mut cli = Cli::parse();
+    let command = cli.command.unwrap_or(Command::Collect(CollectArgs::default()));
+    if matches!(command, Command::Hash) && !cli.options.verbose {
+        cli.options.quiet = true;
+    }
+    let options = cli.options;
+    // 安静模式下默认不输出日志（仍可用 RUST_LOG 打开），stderr 只有错误那一行
+    let default_filter = if options.quiet { "off" } else { "error" };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter)).init();
+
+    match run(&command, &options) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) if options.quiet => {
+            eprintln!("error: {}: {}", e.kind(), e);
+            exit_code(&e)
+        }
         Err(e) => {
-            error!("Failed to collect hardware info: {}", e);
-            return Err(e.into());
+            eprintln!("Error: {}", e);
+            if matches!(e, HardwareError::UnsupportedEnvironment(_)) {
+                eprintln!("Use --allow-container to generate a code anyway.");
+            }
+            exit_code(&e)
+        }
+    }
+}
+
+// 返回 false 表示 verify/diff/conformance check 的结果不一致
+fn run(command: &Command, options: &Options) -> Result<bool, HardwareError> {
+    match command {
+        Command::Collect(CollectArgs { format: Some(format), compact, hostname }) => {
+            let hostname = if *hostname { System::new().host_name() } else { None };
+            let (hardware_id, warnings) = collect(options, None)?;
+            let codes = if options.multi_disk_codes { disk_codes(options, &hardware_id)? } else { Vec::new() };
+            let export = Export::new(hardware_id, hostname).with_codes(codes).with_warnings(warnings);
+            println!("{}", export.serialize(*format, *compact)?.trim_end());
+            Ok(true)
+        }
+        // 安静模式下与 hash 相同
+        Command::Collect(CollectArgs { format: None, .. }) if options.quiet => run(&Command::Hash, options),
+        Command::Collect(CollectArgs { format: None, .. }) => {
+            let (hardware_id, _) = collect(options, None)?;
+            println!("收集到的硬件信息：");
+            println!("{:#?}", hardware_id);
+            if options.uuid {
+                println!("\n生成的 UUID: {}", uuid(options, builder(options), &hardware_id)?);
+                return Ok(true);
+            }
+            let (unique_code, source) = code(options, builder(options), &hardware_id)?;
+            if let Some(source) = source {
+                println!("\n身份来源: {}", source);
+            }
+            let disk_component =
+                |component: &Component| matches!(component, Component::DiskModel | Component::DiskSerial);
+            if hardware_id.live_boot() && components(options).iter().any(|component| disk_component(&component)) {
+                println!("\n磁盘组件: 已排除（live 启动，磁盘信息来自启动介质）");
+            }
+            println!("\n生成的唯一码: {}", unique_code);
+            if options.multi_disk_codes {
+                for alternate in disk_codes(options, &hardware_id)?.iter().skip(1) {
+                    println!("备用唯一码（系统盘为 {}）: {}", alternate.disk(), alternate.code());
+                }
+            }
+            Ok(true)
+        }
+        Command::Hash => {
+            let (hardware_id, _) = collect(options, Some(hashed_components(options)))?;
+            if options.uuid {
+                println!("{}", uuid(options, builder(options), &hardware_id)?);
+                return Ok(true);
+            }
+            let (unique_code, source) = code(options, builder(options), &hardware_id)?;
+            if let (Some(source), true) = (source, options.verbose) {
+                eprintln!("identity source: {}", source);
+            }
+            println!("{}", unique_code);
+            // 备用唯一码每行一个，标明以哪块磁盘作为系统盘
+            if options.multi_disk_codes {
+                for alternate in disk_codes(options, &hardware_id)?.iter().skip(1) {
+                    println!("{} alternate:{}", alternate.code(), alternate.disk());
+                }
+            }
+            Ok(true)
+        }
+        Command::Verify { code: expected } => {
+            let expected = expected.trim();
+            let (hardware_id, _) = collect(options, Some(hashed_components(options)))?;
+            let matches = if options.uuid {
+                verify_uuid(options, &hardware_id, expected)?
+            } else {
+                verify(options, &hardware_id, expected)?
+            };
+            if !options.quiet {
+                println!("{}", if matches { "match" } else { "mismatch" });
+                // live 启动时磁盘组件被排除，在已安装的系统上生成的唯一码必然不一致
+                if !matches && hardware_id.live_boot() {
+                    eprintln!(
+                        "note: this is a live boot session; the disk fields are excluded here, \
+                         so a code generated on the installed system will not match"
+                    );
+                }
+            }
+            Ok(matches)
+        }
+        Command::Diff { snapshot } => {
+            let (saved, written) = read_snapshot(snapshot)?;
+            let skew = written.and_then(|written| hardware_id::export::future_skew(written, SystemTime::now()));
+            if let Some(skew) = skew {
+                let message = format!(
+                    "{} was written {} in the future; the clock of the machine that wrote it may be wrong",
+                    snapshot.display(),
+                    humantime::format_duration(skew)
+                );
+                print_warnings(options, &[Warning::new(WarningId::FutureSnapshot, message)]);
+            }
+            let (hardware_id, _) = collect(options, None)?;
+            let changes = diff(&saved, &hardware_id);
+            if options.quiet {
+                return Ok(changes.is_empty());
+            }
+            for change in &changes {
+                println!("{}", change);
+            }
+            if changes.is_empty() {
+                println!("no changes");
+            }
+            let codes =
+                (code_unlogged(options, builder(options), &saved), code_unlogged(options, builder(options), &hardware_id));
+            if let (Ok((saved_code, _)), Ok((current_code, _))) = codes {
+                println!("unique code: {}", if saved_code == current_code { "unchanged" } else { "changed" });
+            }
+            Ok(changes.is_empty())
+        }
+        Command::Conformance(ConformanceCommand::Generate { output }) => {
+            let count = conformance::generate(output)?;
+            if !options.quiet {
+                println!("wrote {} cases to {}", count, output.display());
+            }
+            Ok(true)
+        }
+        Command::Conformance(ConformanceCommand::Check { dir }) => {
+            let mismatches = conformance::check(dir)?;
+            if !options.quiet {
+                for mismatch in &mismatches {
+                    println!("{}", mismatch);
+                }
+                if mismatches.is_empty() {
+                    println!("all cases match");
+                }
+            }
+            Ok(mismatches.is_empty())
         }
+        Command::Warn(WarnCommand::Ack { id }) => {
+            let acknowledged_at = acknowledgements()?.acknowledge(*id)?;
+            if !options.quiet {
+                println!("{} acknowledged at {}", id, humantime::format_rfc3339_seconds(acknowledged_at));
+            }
+            Ok(true)
+        }
+        Command::Offline(OfflineArgs { root, format, compact }) => {
+            let (hardware_id, report) = HardwareInfo::collect_offline(root)?;
+            let warnings = warning::warnings(&hardware_id, Some(&report));
+            print_warnings(options, &warnings);
+            if options.verbose && !options.quiet {
+                print_report(&report);
+            }
+            if let Some(format) = format {
+                let export = Export::new(hardware_id, None).with_warnings(warnings);
+                println!("{}", export.serialize(*format, *compact)?.trim_end());
+            } else if options.uuid {
+                println!("{}", uuid(options, offline_builder(options), &hardware_id)?);
+            } else {
+                println!("{}", code(options, offline_builder(options), &hardware_id)?.0);
+            }
+            Ok(true)
+        }
+        Command::Warn(WarnCommand::List) => {
+            let acknowledged = acknowledgements()?.load();
+            for id in WarningId::ALL {
+                match acknowledged.iter().find(|acknowledgement| acknowledgement.id() == id) {
+                    Some(acknowledgement) => println!(
+                        "{} acknowledged at {}",
+                        id,
+                        humantime::format_rfc3339_seconds(acknowledgement.acknowledged_at())
+                    ),
+                    None => println!("{}", id),
+                }
+            }
+            Ok(true)
+        }
+    }
+}
+
+// 采集（或读取缓存）并输出提示，一并返回全部提示；个别字段读取失败不影响，能否生成唯一码由后续的关键字段检查决定
+// lazy 为 Some 时只采集生成唯一码所需的字段（只输出唯一码的 hash、verify）
+fn collect(options: &Options, lazy: Option<ComponentSet>) -> Result<(HardwareInfo, Vec<Warning>), HardwareError> {
+    info!("Starting hardware ID collection...");
+
+    let mut collect_options = CollectOptions::new();
+    if let Some(timeout) = options.timeout {
+        collect_options = collect_options.with_timeout(timeout);
+    }
+    collect_options = collect_options.with_wsl_host_identity(options.wsl_host_identity);
+    if let Some(components) = lazy {
+        collect_options = collect_options.with_components(components);
+    }
+    if options.progress {
+        let terminal = std::io::stderr().is_terminal();
+        collect_options = collect_options.on_progress(move |event| eprint!("{}", render_progress(event, terminal)));
+    }
+    // 设置有误时不采集，按参数错误退出
+    let validated = collect_options.validate()?;
+
+    let cache = if options.cache && !options.no_cache {
+        let cache = SnapshotCache::new(options.scheme.unwrap_or_default());
+        if cache.is_none() {
+            warn!("No cache directory found; collecting without the cache");
+        }
+        cache.map(|cache| {
+            cache
+                .with_ttl(options.cache_ttl.unwrap_or(SnapshotCache::DEFAULT_TTL))
+                .with_options(&collect_options)
+        })
+    } else {
+        None
     };
-    
-    info!("Hardware information collected successfully");
-    println!("收集到的硬件信息：");
-    println!("{:#?}", hardware_id);
-    
-    let unique_code = match hardware_id.generate_unique_code() {
-        Ok(code) => code,
+    let cached = cache.as_ref().filter(|_| !options.refresh).and_then(SnapshotCache::load);
+
+    // 使用缓存时没有采集报告，依赖报告的提示与详细信息随之省略
+    let (hardware_id, report) = match cached {
+        Some(hardware_id) => {
+            info!("Using the cached hardware information");
+            (hardware_id, None)
+        }
+        None => {
+            let (hardware_id, report) = HardwareInfo::collect_lenient_with(&collect_options);
+            if report.is_complete() {
+                info!("Hardware information collected successfully");
+            }
+            // 有字段未能读取（如命令超时）时不写入缓存，避免在 TTL 内一直复用不完整的结果
+            if let Some(cache) = cache.as_ref().filter(|_| report.is_complete()) {
+                if let Err(e) = cache.store(&hardware_id) {
+                    warn!("Failed to write the cache {}: {}", cache.path().display(), e);
+                }
+            }
+            (hardware_id, Some(report))
+        }
+    };
+    let warnings = warning::warnings(&hardware_id, report.as_ref());
+    if options.quiet {
+        return Ok((hardware_id, warnings));
+    }
+    print_warnings(options, &warnings);
+    if options.verbose {
+        // 同一台机器提升权限前后结果不同时，据此判断是否为权限导致
+        eprintln!("elevated: {}", HardwareInfo::is_elevated());
+        eprintln!("virtualization: {}", hardware_id.virtualization().unwrap_or("none"));
+        // 两种 SMBIOS 字节序的读法都列出，便于对照其他工具读出的 UUID
+        if let Some(alternate) = hardware_id.board().alternate_uuid() {
+            eprintln!("motherboard uuid: {} (other byte order: {})", hardware_id.motherboard_uuid(), alternate);
+        }
+        let fields: Vec<&str> = components(options).iter().map(|component| component.name()).collect();
+        eprintln!("fields: {}", fields.join(", "));
+        eprintln!("options: {}", validated);
+        match &report {
+            Some(report) => print_report(report),
+            None => {
+                eprintln!("environment: {}", hardware_id.environment());
+                if let Some(cache) = &cache {
+                    eprintln!("cached: {}", cache.path().display());
+                }
+            }
+        }
+    }
+    Ok((hardware_id, warnings))
+}
+
+// 安静模式与 --no-warnings 时不输出；本机确认过的提示不输出，--verbose 时注明确认时间
+fn print_warnings(options: &Options, warnings: &[Warning]) {
+    if options.quiet || options.no_warnings {
+        return;
+    }
+    let acknowledged = Acknowledgements::new().map(|acknowledgements| acknowledgements.load()).unwrap_or_default();
+    for warning in warnings {
+        match acknowledged.iter().find(|acknowledgement| acknowledgement.id() == warning.id()) {
+            None => eprintln!("warning: {}", warning),
+            Some(acknowledgement) if options.verbose => eprintln!(
+                "acknowledged warning {} (since {})",
+                warning.id(),
+                humantime::format_rfc3339_seconds(acknowledgement.acknowledged_at())
+            ),
+            Some(_) => {}
+        }
+    }
+}
+
+fn print_report(report: &CollectionReport) {
+    eprintln!("environment: {}", report.environment());
+    if report.is_translated() {
+        eprintln!("architecture: {} (this process runs under Rosetta 2)", report.architecture());
+    } else {
+        eprintln!("architecture: {}", report.architecture());
+    }
+    match report.interface_chain() {
+        Some((name, lower)) => eprintln!("network interface: {} → {}", name, lower.join(", ")),
+        None => eprintln!("network interface: {}", report.primary_interface().unwrap_or("none")),
+    }
+    if let Some(strategy) = report.wsl_strategy() {
+        eprintln!("wsl strategy: {}", strategy);
+    }
+    for (field, e) in report.permission_denied() {
+        eprintln!("permission denied: {}: {}", field, e);
+    }
+    for (field, e) in report.unavailable() {
+        eprintln!("unavailable: {}: {}", field, e);
+    }
+    for (field, rule) in report.placeholder_matches() {
+        eprintln!("placeholder: {}: matched {}", field, rule);
+    }
+}
+
+// --fields / --exclude-fields 选出的组件，都未指定时为默认集合
+fn components(options: &Options) -> ComponentSet {
+    if options.fields.is_empty() {
+        options.exclude_fields.iter().fold(ComponentSet::default(), |set, component| set.without(*component))
+    } else {
+        options.fields.iter().copied().collect()
+    }
+}
+
+// 唯一码可能用到的组件：--fallback 时还需要机器身份的回退链
+fn hashed_components(options: &Options) -> ComponentSet {
+    let components = components(options);
+    if options.fallback {
+        components.with(Component::MachineIdentity)
+    } else {
+        components
+    }
+}
+
+fn builder(options: &Options) -> FingerprintBuilder {
+    // 先设置组件再应用 --no-mac，保证 MAC 被排除且唯一码带上 nomac: 标记；不带 --no-mac 时不调用
+    // with_mac，否则会把 --fields/--exclude-fields 排除的 MAC 重新加入
+    let mut builder = FingerprintBuilder::new()
+        .version(options.scheme.unwrap_or_default())
+        .components(components(options))
+        .macs(if options.primary_mac { MacSelection::Primary } else { MacSelection::All })
+        .allow_container(options.allow_container);
+    if options.no_mac {
+        builder = builder.with_mac(false);
+    }
+    if let Some(Key(key)) = &options.key {
+        builder = builder.key(key);
+    }
+    builder
+}
+
+// 离线镜像没有主板信息：至少一个选中的组件有值即可；未指定 --fields/--exclude-fields 时用 MAC 与 machine-id
+fn offline_builder(options: &Options) -> FingerprintBuilder {
+    let builder = builder(options).policy(CriticalFieldPolicy::RequireAny(1));
+    if !options.fields.is_empty() || !options.exclude_fields.is_empty() {
+        return builder;
+    }
+    let builder = builder.components(ComponentSet::empty().with(Component::MacAddress).with(Component::MachineId));
+    // 与 builder 相同，--no-mac 在设置组件之后应用
+    if options.no_mac {
+        builder.with_mac(false)
+    } else {
+        builder
+    }
+}
+
+// 唯一码，以及 --fallback 生效时所用机器身份的来源
+fn code(
+    options: &Options,
+    builder: FingerprintBuilder,
+    hardware_id: &HardwareInfo,
+) -> Result<(String, Option<IdentitySource>), HardwareError> {
+    match code_unlogged(options, builder, hardware_id) {
+        Ok((code, None)) => {
+            info!("Unique code generated successfully");
+            Ok((code, None))
+        }
+        Ok((code, Some(source))) => {
+            warn!("Critical hardware information missing; using the fallback machine identity ({})", source);
+            Ok((code, Some(source)))
+        }
         Err(e) => {
             error!("Failed to generate unique code: {}", e);
-            return Err(e.into());
+            Err(e)
         }
+    }
+}
+
+// 与 code 相同但不输出日志：diff 只附带说明唯一码是否变化，快照或本机无法生成唯一码时不算错误
+fn code_unlogged(
+    options: &Options,
+    builder: FingerprintBuilder,
+    hardware_id: &HardwareInfo,
+) -> Result<(String, Option<IdentitySource>), HardwareError> {
+    let format = options.output_format.unwrap_or_default();
+    match builder.code(hardware_id, format, options.length) {
+        // 主板信息缺失时改用回退链得到的机器身份，并注明来源
+        Err(HardwareError::MissingField(_)) if options.fallback => {
+            let code = fallback(builder).code(hardware_id, format, options.length)?;
+            Ok((code, Some(hardware_id.machine_identity().source())))
+        }
+        result => result.map(|code| (code, None)),
+    }
+}
+
+// --fallback 时主板信息缺失所改用的方案：只用机器身份
+fn fallback(builder: FingerprintBuilder) -> FingerprintBuilder {
+    builder.components(ComponentSet::empty().with(Component::MachineIdentity)).policy(CriticalFieldPolicy::RequireAny(1))
+}
+
+// 方案、是否排除 MAC 与另一种字节序的主板 UUID 由 FingerprintBuilder::verify 处理；
+// --multi-disk-codes 时以其他内置磁盘作为系统盘生成的唯一码同样视为一致
+fn verify(options: &Options, hardware_id: &HardwareInfo, expected: &str) -> Result<bool, HardwareError> {
+    let verify = |builder: FingerprintBuilder| match options.multi_disk_codes {
+        true => builder.verify_any_alternate(hardware_id, &[expected]),
+        false => builder.verify(hardware_id, expected),
+    };
+    match verify(builder(options)) {
+        Err(HardwareError::MissingField(_)) if options.fallback => verify(fallback(builder(options))),
+        result => result,
+    }
+    .inspect_err(|e| error!("Failed to generate unique code: {}", e))
+}
+
+// 另一种 SMBIOS 字节序的主板 UUID 生成的 UUID 同样视为一致
+fn verify_uuid(options: &Options, hardware_id: &HardwareInfo, expected: &str) -> Result<bool, HardwareError> {
+    let matches = |builder: FingerprintBuilder| -> Result<bool, HardwareError> {
+        Ok(uuid(options, builder, hardware_id)?.to_string().eq_ignore_ascii_case(expected))
+    };
+    if matches(builder(options))? {
+        return Ok(true);
+    }
+    Ok(hardware_id.board().alternate_uuid().is_some() && matches(builder(options).alternate_uuid(true))?)
+}
+
+// 实际系统盘与各备用磁盘作为系统盘时的唯一码；--fallback 改用机器身份时与磁盘无关，只有一项
+fn disk_codes(options: &Options, hardware_id: &HardwareInfo) -> Result<Vec<DiskCode>, HardwareError> {
+    let format = options.output_format.unwrap_or_default();
+    builder(options).disk_codes(hardware_id, format, options.length).or_else(|e| match e {
+        HardwareError::MissingField(_) if options.fallback => Ok(Vec::new()),
+        e => Err(e),
+    })
+}
+
+fn uuid(options: &Options, builder: FingerprintBuilder, hardware_id: &HardwareInfo) -> Result<Uuid, HardwareError> {
+    let namespace = options.namespace.unwrap_or(HARDWARE_ID_NAMESPACE);
+    builder.uuid(hardware_id, namespace).inspect_err(|e| error!("Failed to generate UUID: {}", e))
+}
+
+fn acknowledgements() -> Result<Acknowledgements, HardwareError> {
+    Acknowledgements::new().ok_or_else(|| HardwareError::FileError("no state directory found (set HOME)".to_string()))
+}
+
+// 终端上每个事件覆盖同一行，最后一个事件后换行；否则每个事件一行
+fn render_progress(event: &ProgressEvent, terminal: bool) -> String {
+    match terminal {
+        true if event.is_last() => format!("\r\x1b[2K{}\n", event),
+        true => format!("\r\x1b[2K{}", event),
+        false => format!("{}\n", event),
+    }
+}
+
+// 快照可以是 HardwareInfo 本身，也可以包在 "info" 下（collect --format 的输出、--cache 写入的文件）；
+// .yaml/.yml/.toml 按 collect --format 的输出读取。一并返回快照中记录的写入时间（HardwareInfo 本身没有）
+fn read_snapshot(path: &Path) -> Result<(HardwareInfo, Option<SystemTime>), HardwareError> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| HardwareError::FileError(format!("{}: {}", path.display(), e)))?;
+    let format = path.extension().and_then(|extension| extension.to_str()?.parse::<ExportFormat>().ok());
+    if let Some(format @ (ExportFormat::Yaml | ExportFormat::Toml)) = format {
+        let export = Export::deserialize(format, &contents)?;
+        let written = export.collected_at();
+        return Ok((export.into_info(), Some(written)));
+    }
+    let invalid = |e: serde_json::Error| HardwareError::ParseError(format!("{}: {}", path.display(), e));
+    let mut value: serde_json::Value = serde_json::from_str(&contents).map_err(invalid)?;
+    // collect --format json 记为 collected_at，--cache 记为 created_at；无法解析的时间不影响比较
+    let written = ["collected_at", "created_at"]
+        .iter()
+        .find_map(|key| value.get(*key)?.as_str())
+        .and_then(|text| humantime::parse_rfc3339(text).ok());
+    if let Some(info) = value.get_mut("info") {
+        value = info.take();
+    }
+    Ok((serde_json::from_value(value).map_err(invalid)?, written))
+}
+
+// 按序列化后的字段逐项比较，每项变化为一行 "路径: 旧值 → 新值"
+fn diff(saved: &HardwareInfo, current: &HardwareInfo) -> Vec<String> {
+    let to_value = |info: &HardwareInfo| serde_json::to_value(info).unwrap_or_default();
+    let mut changes = Vec::new();
+    diff_values("", &to_value(saved), &to_value(current), &mut changes);
+    changes
+}
+
+fn diff_values(path: &str, saved: &serde_json::Value, current: &serde_json::Value, changes: &mut Vec<String>) {
+    use serde_json::Value;
+    match (saved, current) {
+        (Value::Object(saved), Value::Object(current)) => {
+            let added = current.keys().filter(|key| !saved.contains_key(*key));
+            for key in saved.keys().chain(added) {
+                let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                let (saved, current) = (saved.get(key), current.get(key));
+                diff_values(&path, saved.unwrap_or(&Value::Null), current.unwrap_or(&Value::Null), changes);
+            }
+        }
+        // 数量不同（如增减网卡）时整个列表作为一项
+        (Value::Array(saved), Value::Array(current)) if saved.len() == current.len() => {
+            for (i, (saved, current)) in saved.iter().zip(current).enumerate() {
+                diff_values(&format!("{}[{}]", path, i), saved, current, changes);
+            }
+        }
+        _ if saved != current => changes.push(format!("{}: {} → {}", path, saved, current)),
+        _ => {}
+    }
+}
+
+// 退出码见 HardwareError::exit_code
+fn exit_code(error: &HardwareError) -> ExitCode {
+    ExitCode::from(error.exit_code())
+}
+
+// "hex:" 之后的十六进制按字节解码，其他按 UTF-8 字节使用；不带前缀的十六进制同样是文本
+fn parse_key(value: &str) -> Result<Key, String> {
+    let key = match value.strip_prefix("hex:") {
+        Some(digits) => hex::decode(digits).map_err(|e| format!("invalid hex key: {}", e))?,
+        None => value.as_bytes().to_vec(),
     };
-    
-    info!("Unique code generated successfully");
-    println!("\n生成的唯一码: {}", unique_code);
-    Ok(())
-} 
\ No newline at end of file
+    if key.is_empty() {
+        return Err("must not be empty".to_string());
+    }
+    Ok(Key(key))
+}
+
+fn parse_length(value: &str) -> Result<usize, String> {
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|length| *length > 0)
+        .ok_or_else(|| "expected a positive integer".to_string())
+}
+
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| *secs > 0.0)
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| "expected a positive number of seconds".to_string())
+}
+
+fn parse_cache_ttl(value: &str) -> Result<Duration, String> {
+    value.parse::<u64>().map(Duration::from_secs).map_err(|_| "expected a number of seconds".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_timeout_accepts_fractional_seconds() {
+        assert_eq!(parse_timeout("1.5"), Ok(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn parse_timeout_rejects_out_of_range_values() {
+        for value in ["0", "-1", "NaN", "inf", "1e30", "abc"] {
+            assert!(parse_timeout(value).is_err(), "{value}");
+        }
+    }
+
+    use hardware_id::ProgressStatus;
+
+    // hardware_id_agent 使用 FingerprintBuilder::new()，与不带选项的 hash 必须生成相同的唯一码
+    #[test]
+    fn default_options_build_the_same_code_as_the_agent() {
+        let info: HardwareInfo = serde_json::from_str(include_str!("../tests/fixtures/hardware_info.json")).unwrap();
+        let cli = Cli::try_parse_from(["hardware_id", "hash"]).unwrap();
+        assert_eq!(code(&cli.options, builder(&cli.options), &info).unwrap().0, FingerprintBuilder::new().hash(&info).unwrap());
+        assert_eq!(hashed_components(&cli.options), ComponentSet::default());
+    }
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("hardware_id").chain(args.iter().copied()))
+    }
+
+    fn hash_with(args: &[&str]) -> String {
+        let info: HardwareInfo = serde_json::from_str(include_str!("../tests/fixtures/hardware_info.json")).unwrap();
+        let cli = parse(args).unwrap();
+        code(&cli.options, builder(&cli.options), &info).unwrap().0
+    }
+
+    #[test]
+    fn fields_select_the_hashed_components() {
+        let expected = ComponentSet::empty().with(Component::MotherboardUuid).with(Component::DiskSerial);
+        let cli = parse(&["hash", "--fields", "motherboard_uuid,disk_serial"]).unwrap();
+        assert_eq!(components(&cli.options), expected);
+        // 可重复给出，名称不区分大小写，- 与 _ 等价
+        let cli = parse(&["hash", "--fields", "Motherboard-UUID", "--fields", "disk_serial"]).unwrap();
+        assert_eq!(components(&cli.options), expected);
+        assert_eq!(hashed_components(&cli.options), expected);
+    }
+
+    #[test]
+    fn exclude_fields_start_from_the_default_set() {
+        let cli = parse(&["hash", "--exclude-fields", "mac_address,disk_model"]).unwrap();
+        let expected = ComponentSet::default().without(Component::MacAddress).without(Component::DiskModel);
+        assert_eq!(components(&cli.options), expected);
+        // 排除默认集合以外的字段不影响
+        let cli = parse(&["hash", "--exclude-fields", "gpu"]).unwrap();
+        assert_eq!(components(&cli.options), ComponentSet::default());
+    }
+
+    #[test]
+    fn field_order_does_not_change_the_code() {
+        let fields = |list: &'static str| hash_with(&["hash", "--fields", list]);
+        assert_eq!(
+            fields("motherboard_serial,motherboard_uuid,disk_serial"),
+            fields("disk_serial,motherboard_serial,motherboard_uuid")
+        );
+        assert_ne!(hash_with(&["hash"]), hash_with(&["hash", "--exclude-fields", "mac_address"]));
+        assert_ne!(fields("motherboard_serial,motherboard_uuid"), fields("motherboard_serial,motherboard_uuid,disk_serial"));
+        // --fields 未列出 MAC 时同样不参与
+        assert_ne!(fields("motherboard_serial,motherboard_uuid"), fields("motherboard_serial,motherboard_uuid,mac_address"));
+    }
+
+    #[test]
+    fn unknown_fields_list_the_valid_names() {
+        for flag in ["--fields", "--exclude-fields"] {
+            let error = parse(&["hash", flag, "motherboard_uuid,serial"]).err().unwrap();
+            assert_eq!(error.kind(), clap::error::ErrorKind::ValueValidation);
+            let message = error.to_string();
+            assert!(message.contains("unknown field 'serial'"), "{}", message);
+            assert!(Component::ALL.iter().all(|component| message.contains(component.name())), "{}", message);
+        }
+    }
+
+    #[test]
+    fn fields_and_exclude_fields_conflict() {
+        let error = parse(&["hash", "--fields", "motherboard_uuid", "--exclude-fields", "mac_address"]).err().unwrap();
+        assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn parse_key_decodes_hex_only_with_the_prefix() {
+        assert_eq!(parse_key("hex:00ff").unwrap().0, [0x00, 0xff]);
+        assert_eq!(parse_key("hex:00FF").unwrap().0, [0x00, 0xff]);
+        assert_eq!(parse_key("00ff").unwrap().0, b"00ff");
+        assert_eq!(parse_key("product-a").unwrap().0, b"product-a");
+        assert!(parse_key("").is_err());
+        assert!(parse_key("hex:").is_err());
+        assert!(parse_key("hex:0f0").err().unwrap().contains("invalid hex key"));
+        assert!(parse_key("hex:product-a").is_err());
+        let error = parse(&["hash", "--key", "hex:zz"]).err().unwrap();
+        assert_eq!(error.kind(), clap::error::ErrorKind::ValueValidation);
+        // 同一密钥的两种写法生成同一个唯一码；不带前缀的十六进制是另一个密钥
+        assert_eq!(hash_with(&["hash", "--key", "hex:6b6579"]), hash_with(&["hash", "--key", "key"]));
+        assert_ne!(hash_with(&["hash", "--key", "6b6579"]), hash_with(&["hash", "--key", "key"]));
+    }
+
+    #[test]
+    fn progress_is_one_updating_line_on_a_terminal() {
+        let events = progress_events();
+        let rendered: String = events.iter().map(|event| render_progress(event, true)).collect();
+        assert_eq!(rendered.matches('\n').count(), 1);
+        assert!(rendered.ends_with("[2/2] ioreg -rd1 -c IOPlatformExpertDevice: failed after 0.0s: ioreg: not found\n"));
+        assert!(rendered.starts_with("\r\x1b[2K[0/2] system_profiler SPHardwareDataType: started"));
+    }
+
+    #[test]
+    fn progress_is_plain_lines_otherwise() {
+        let rendered: Vec<String> = progress_events().iter().map(|event| render_progress(event, false)).collect();
+        assert_eq!(
+            rendered,
+            [
+                "[0/2] system_profiler SPHardwareDataType: started\n",
+                "[1/2] system_profiler SPHardwareDataType: done in 0.0s\n",
+                "[1/2] ioreg -rd1 -c IOPlatformExpertDevice: started\n",
+                "[2/2] ioreg -rd1 -c IOPlatformExpertDevice: failed after 0.0s: ioreg: not found\n",
+            ]
+        );
+    }
+
+    fn progress_events() -> Vec<ProgressEvent> {
+        let (profiler, ioreg) = ("system_profiler SPHardwareDataType", "ioreg -rd1 -c IOPlatformExpertDevice");
+        let failed = ProgressStatus::Failed { elapsed: Duration::from_millis(20), error: "ioreg: not found".to_string() };
+        vec![
+            ProgressEvent::new(profiler, ProgressStatus::Started, 0, 2),
+            ProgressEvent::new(profiler, ProgressStatus::Finished(Duration::from_millis(40)), 1, 2),
+            ProgressEvent::new(ioreg, ProgressStatus::Started, 1, 2),
+            ProgressEvent::new(ioreg, failed, 2, 2),
+        ]
+    }
+}
@@ -0,0 +1,124 @@
+// 哈希前的字段规范化：同一台机器在不同工具/平台下的不同写法得到相同的值
+
+// 去掉首尾空白，内部连续空白折叠为一个空格
+pub fn normalize_text(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// UUID：小写，去掉花括号
+pub fn normalize_uuid(value: &str) -> String {
+    normalize_text(value)
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .to_lowercase()
+}
+
+// SMBIOS UUID 的另一种字节序读法：前三段（4、2、2 字节）逐段反转，其余不变；保留原有的大小写。
+// 2.6 之前的规范未规定字节序，不同工具可能按不同的字节序显示同一个 UUID；无法解析时为 None
+pub fn swap_uuid_byte_order(value: &str) -> Option<String> {
+    let uuid = uuid::Uuid::parse_str(value.trim().trim_start_matches('{').trim_end_matches('}')).ok()?;
+    let swapped = uuid::Uuid::from_bytes_le(*uuid.as_bytes()).hyphenated().to_string();
+    Some(if value.chars().any(|c| c.is_ascii_uppercase()) { swapped.to_uppercase() } else { swapped })
+}
+
+// MAC：按 MacAddress 解析后的规范形式（小写、":" 分隔）；无法解析时小写并把 "-" 换成 ":"
+pub fn normalize_mac(value: &str) -> String {
+    match value.parse::<crate::mac_address::MacAddress>() {
+        Ok(mac) => mac.to_string(),
+        Err(_) => normalize_text(value).replace('-', ":").to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::{FingerprintBuilder, FingerprintVersion};
+    use crate::hardware_info::tests::sample_with;
+
+    #[test]
+    fn text_is_trimmed_and_whitespace_collapsed() {
+        let cases = [
+            ("Dell Inc.", "Dell Inc."),
+            // SMBIOS 字符串常以空格补齐到固定长度
+            ("Dell Inc.                       ", "Dell Inc."),
+            ("  LENOVO\t20L8S02D00 ", "LENOVO 20L8S02D00"),
+            ("Micro-Star\u{a0}International\r\n", "Micro-Star International"),
+            ("PM981a  NVMe   SAMSUNG", "PM981a NVMe SAMSUNG"),
+            // 大小写保持不变：厂商与型号的大小写属于值本身
+            ("ThinkPad", "ThinkPad"),
+            ("   ", ""),
+            ("", ""),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize_text(input), expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn uuids_are_lowercased_without_braces() {
+        let expected = "4c4c4544-0058-4a10-8038-b7c04f513532";
+        let cases = [
+            "4c4c4544-0058-4a10-8038-b7c04f513532",
+            "4C4C4544-0058-4A10-8038-B7C04F513532",
+            "{4C4C4544-0058-4A10-8038-B7C04F513532}",
+            "  4C4C4544-0058-4a10-8038-b7c04f513532\n",
+        ];
+        for input in cases {
+            assert_eq!(normalize_uuid(input), expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn macs_use_lowercase_colon_form() {
+        let cases = [
+            ("d8:9e:f3:12:34:56", "d8:9e:f3:12:34:56"),
+            ("D8-9E-F3-12-34-56", "d8:9e:f3:12:34:56"),
+            (" D8:9E:F3:12:34:56 ", "d8:9e:f3:12:34:56"),
+            // 无法解析的值只做大小写与分隔符转换
+            ("D8-9E-F3", "d8:9e:f3"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize_mac(input), expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn uuid_byte_order_swaps_the_first_three_groups() {
+        let cases = [
+            ("4c4c4544-0058-4a10-8038-b7c04f513532", Some("44454c4c-5800-104a-8038-b7c04f513532")),
+            ("4C4C4544-0058-4A10-8038-B7C04F513532", Some("44454C4C-5800-104A-8038-B7C04F513532")),
+            ("{4c4c4544-0058-4a10-8038-b7c04f513532}", Some("44454c4c-5800-104a-8038-b7c04f513532")),
+            ("Not Settable", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(swap_uuid_byte_order(input).as_deref(), expected, "{:?}", input);
+            if let Some(swapped) = expected {
+                assert_eq!(normalize_uuid(&swap_uuid_byte_order(swapped).unwrap()), normalize_uuid(input));
+            }
+        }
+    }
+
+    // 同一台机器由不同工具读出的不同写法，v2 唯一码相同
+    #[test]
+    fn surface_forms_give_the_same_v2_code() {
+        let builder = FingerprintBuilder::new().version(FingerprintVersion::V2);
+        let code = |uuid: &str, product: &str| {
+            builder
+                .hash(&sample_with(|value| {
+                    value["board"]["uuid"] = uuid.into();
+                    value["board"]["product"] = product.into();
+                }))
+                .unwrap()
+        };
+        let expected = code("4c4c4544-0058-4a10-8038-b7c04f513532", "0NNNCT");
+        let forms = [
+            ("4C4C4544-0058-4A10-8038-B7C04F513532", "0NNNCT"),
+            ("{4C4C4544-0058-4A10-8038-B7C04F513532}", "0NNNCT   "),
+            ("4c4c4544-0058-4a10-8038-b7c04f513532", " 0NNNCT"),
+        ];
+        for (uuid, product) in forms {
+            assert_eq!(code(uuid, product), expected, "{:?} {:?}", uuid, product);
+        }
+        assert_ne!(code("4c4c4544-0058-4a10-8038-b7c04f513532", "0nnnct"), expected);
+    }
+}
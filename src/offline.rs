@@ -0,0 +1,338 @@
+// 离线采集：从挂载的 Linux 系统镜像（磁盘映像、备份）中读取不依赖运行中内核的标识：
+// machine-id、udev 持久化网卡规则与 systemd .link 文件中的 MAC、/etc/fstab 中根分区的 UUID、os-release
+// DMI、CPU 与磁盘信息只能在运行时读取，由 HardwareInfo::collect_offline 记为不支持。
+// Windows 镜像的标识在注册表配置单元（Windows/System32/config/SYSTEM）中，目前没有可用的解析器，按不支持处理
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::error::HardwareError;
+use crate::hardware_info::HardwareInfo;
+use crate::mac_address::MacAddress;
+use crate::machine_identity;
+
+// 常见的几种大小写写法；镜像挂载后的文件系统（ntfs-3g 等）可能区分大小写
+const WINDOWS_HIVES: &[&str] = &[
+    "Windows/System32/config/SYSTEM",
+    "Windows/system32/config/SYSTEM",
+    "WINDOWS/system32/config/SYSTEM",
+    "WINDOWS/System32/config/SYSTEM",
+];
+
+// 镜像的根目录；读取的文件经符号链接解析后必须仍在根目录之下
+#[derive(Debug, Clone)]
+pub(crate) struct OfflineRoot {
+    root: PathBuf,
+}
+
+impl OfflineRoot {
+    // 根目录不存在或不是目录时返回 FileError
+    pub(crate) fn new(root: &Path) -> Result<Self, HardwareError> {
+        let root = fs::canonicalize(root).map_err(|e| HardwareInfo::file_error(&root.display().to_string(), e))?;
+        if !root.is_dir() {
+            return Err(HardwareError::FileError(format!("{}: not a directory", root.display())));
+        }
+        Ok(Self { root })
+    }
+
+    pub(crate) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path(&self, path: &str) -> PathBuf {
+        self.root.join(path.trim_start_matches('/'))
+    }
+
+    // 拒绝经符号链接解析到根目录之外的路径（镜像中的绝对链接指向的是本机的文件）；错误信息中为镜像内的路径
+    pub(crate) fn read_file(&self, path: &str) -> Result<String, HardwareError> {
+        let resolved = fs::canonicalize(self.path(path)).map_err(|e| HardwareInfo::file_error(path, e))?;
+        if !resolved.starts_with(&self.root) {
+            return Err(HardwareError::FileError(format!(
+                "Refusing to read {}: resolves to {} outside {}",
+                path,
+                resolved.display(),
+                self.root.display()
+            )));
+        }
+        let bytes = fs::read(&resolved).map_err(|e| HardwareInfo::file_error(path, e))?;
+        String::from_utf8(bytes).map_err(|e| HardwareError::FileError(format!("{}: {}", path, e)))
+    }
+
+    // 目录下以 extension 结尾的文件（按名称排序）；目录不存在时为空
+    fn list_dir(&self, path: &str, extension: &str) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(self.path(path)) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| name.ends_with(extension))
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub(crate) fn is_windows(&self) -> bool {
+        WINDOWS_HIVES.iter().any(|hive| self.path(hive).is_file())
+    }
+
+    // 优先 /etc/machine-id，其次 D-Bus 的副本；与运行时的读取规则相同
+    pub(crate) fn machine_id(&self) -> Result<String, HardwareError> {
+        ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+            .iter()
+            .filter_map(|path| self.read_file(path).ok())
+            .find_map(|id| machine_identity::parse_machine_id(&id))
+            .ok_or_else(|| HardwareError::FileError("no initialized machine-id in /etc or /var/lib/dbus".to_string()))
+    }
+
+    // udev 持久化规则（/etc/udev/rules.d/*.rules）与 systemd .link 文件（/etc/systemd/network/*.link）中
+    // 按 MAC 命名的网卡：(名称, MAC)，未排序，可能重复
+    pub(crate) fn interfaces(&self) -> Vec<(String, MacAddress)> {
+        let mut interfaces = Vec::new();
+        for name in self.list_dir("/etc/udev/rules.d", ".rules") {
+            if let Ok(rules) = self.read_file(&format!("/etc/udev/rules.d/{}", name)) {
+                interfaces.extend(parse_persistent_net_rules(&rules));
+            }
+        }
+        for name in self.list_dir("/etc/systemd/network", ".link") {
+            if let Ok(link) = self.read_file(&format!("/etc/systemd/network/{}", name)) {
+                let stem = name.trim_end_matches(".link");
+                interfaces.extend(parse_link_file(&link, stem));
+            }
+        }
+        interfaces
+    }
+
+    pub(crate) fn root_fs_uuid(&self) -> Result<String, HardwareError> {
+        let fstab = self.read_file("/etc/fstab")?;
+        parse_fstab_root_uuid(&fstab)
+            .ok_or_else(|| HardwareError::ParseError("/etc/fstab: no UUID= entry for /".to_string()))
+    }
+
+    // 与运行时的 os_info 相同，为 "名称 版本号"；/etc/os-release 不存在时读 /usr/lib/os-release
+    pub(crate) fn os_info(&self) -> Option<String> {
+        let release = self.read_file("/etc/os-release").or_else(|_| self.read_file("/usr/lib/os-release")).ok()?;
+        let name = parse_os_release_value(&release, "NAME")?;
+        Some(match parse_os_release_value(&release, "VERSION_ID") {
+            Some(version) => format!("{} {}", name, version),
+            None => name,
+        })
+    }
+}
+
+// 70-persistent-net.rules 的一行：SUBSYSTEM=="net", ..., ATTR{address}=="00:11:22:33:44:55", ..., NAME="eth0"
+// 地址为通配符（"?*"）或没有 NAME 的规则跳过
+fn parse_persistent_net_rules(rules: &str) -> Vec<(String, MacAddress)> {
+    rules
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut address = None;
+            let mut name = None;
+            for pair in line.split(',') {
+                let pair = pair.trim();
+                if let Some(value) = pair.strip_prefix("ATTR{address}==") {
+                    address = unquote(value).parse::<MacAddress>().ok();
+                } else if let Some(value) = pair.strip_prefix("NAME=").filter(|value| !value.starts_with('=')) {
+                    name = Some(unquote(value).to_string());
+                }
+            }
+            Some((name.filter(|name| !name.is_empty())?, address?))
+        })
+        .collect()
+}
+
+// .link 文件：[Match] 的 MACAddress=/PermanentMACAddress=（可列出多个），[Link] 的 Name=；
+// 没有 Name= 时以文件名（不含 .link）代替
+fn parse_link_file(link: &str, stem: &str) -> Vec<(String, MacAddress)> {
+    let mut section = "";
+    let mut addresses = Vec::new();
+    let mut name = None;
+    for line in link.lines().map(str::trim) {
+        if line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            section = header;
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match (section, key.trim()) {
+            ("Match", "MACAddress" | "PermanentMACAddress") => {
+                addresses.extend(value.split_whitespace().filter_map(|address| address.parse::<MacAddress>().ok()));
+            }
+            ("Link", "Name") if !value.trim().is_empty() => name = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    let name = name.unwrap_or_else(|| stem.to_string());
+    addresses.into_iter().map(|address| (name.clone(), address)).collect()
+}
+
+// 挂载点为 / 的一行的 UUID=（可加引号）；以设备名或 LABEL= 挂载时为 None
+fn parse_fstab_root_uuid(fstab: &str) -> Option<String> {
+    fstab.lines().map(str::trim).filter(|line| !line.starts_with('#')).find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.get(1) != Some(&"/") {
+            return None;
+        }
+        let uuid = unquote(fields[0].strip_prefix("UUID=")?);
+        (!uuid.is_empty()).then(|| uuid.to_string())
+    })
+}
+
+fn parse_os_release_value(release: &str, key: &str) -> Option<String> {
+    release.lines().find_map(|line| {
+        let value = unquote(line.trim().strip_prefix(key)?.strip_prefix('=')?);
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    // 按 Linux 系统的布局建立的临时镜像目录树
+    pub(crate) struct FakeImage {
+        root: PathBuf,
+    }
+
+    impl FakeImage {
+        pub(crate) fn new() -> Self {
+            let root = std::env::temp_dir().join(format!("hardware_id-offline-{}", Uuid::new_v4().simple()));
+            fs::create_dir_all(&root).unwrap();
+            Self { root }
+        }
+
+        pub(crate) fn path(&self) -> &Path {
+            &self.root
+        }
+
+        pub(crate) fn write(&self, path: &str, contents: &str) -> &Self {
+            let path = self.root.join(path.trim_start_matches('/'));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+            self
+        }
+
+        // 典型的 Debian 系镜像：udev 规则命名 eth0，.link 文件命名 wlan0
+        pub(crate) fn debian() -> Self {
+            let image = Self::new();
+            image
+                .write("/etc/machine-id", "4c4c4544004a3810803fb7c04f513532\n")
+                .write(
+                    "/etc/udev/rules.d/70-persistent-net.rules",
+                    "# This file was automatically generated by the udev rule generator\n\n\
+                     # PCI device 0x8086:0x100e (e1000)\n\
+                     SUBSYSTEM==\"net\", ACTION==\"add\", DRIVERS==\"?*\", ATTR{address}==\"D8:9E:F3:12:34:56\", \
+                     ATTR{dev_id}==\"0x0\", ATTR{type}==\"1\", KERNEL==\"eth*\", NAME=\"eth0\"\n",
+                )
+                .write(
+                    "/etc/systemd/network/10-wlan.link",
+                    "[Match]\nMACAddress=a4:34:d9:65:43:21\n\n[Link]\nName=wlan0\n",
+                )
+                .write(
+                    "/etc/fstab",
+                    "# <file system> <mount point> <type> <options> <dump> <pass>\n\
+                     UUID=0a1b2c3d-4e5f-6071-8293-a4b5c6d7e8f9 /boot ext4 defaults 0 2\n\
+                     UUID=\"9f8e7d6c-5b4a-3928-1706-f5e4d3c2b1a0\" / ext4 errors=remount-ro 0 1\n",
+                )
+                .write("/etc/os-release", "PRETTY_NAME=\"Debian GNU/Linux 12 (bookworm)\"\nNAME=\"Debian GNU/Linux\"\nVERSION_ID=\"12\"\n");
+            image
+        }
+    }
+
+    impl Drop for FakeImage {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn reads_the_identifiers_of_an_image() {
+        let image = FakeImage::debian();
+        let root = OfflineRoot::new(image.path()).unwrap();
+        assert!(!root.is_windows());
+        assert_eq!(root.machine_id().unwrap(), "4c4c4544004a3810803fb7c04f513532");
+        assert_eq!(
+            root.interfaces(),
+            [
+                ("eth0".to_string(), "d8:9e:f3:12:34:56".parse().unwrap()),
+                ("wlan0".to_string(), "a4:34:d9:65:43:21".parse().unwrap()),
+            ]
+        );
+        assert_eq!(root.root_fs_uuid().unwrap(), "9f8e7d6c-5b4a-3928-1706-f5e4d3c2b1a0");
+        assert_eq!(root.os_info().as_deref(), Some("Debian GNU/Linux 12"));
+    }
+
+    // 镜像刚安装尚未初始化时 machine-id 为空或全 0，改读 D-Bus 的副本
+    #[test]
+    fn an_uninitialized_machine_id_is_missing() {
+        let image = FakeImage::new();
+        image.write("/etc/machine-id", "00000000000000000000000000000000\n");
+        let root = OfflineRoot::new(image.path()).unwrap();
+        assert!(root.machine_id().is_err());
+        image.write("/var/lib/dbus/machine-id", "0123456789abcdef0123456789abcdef\n");
+        assert_eq!(root.machine_id().unwrap(), "0123456789abcdef0123456789abcdef");
+    }
+
+    #[test]
+    fn link_files_without_a_name_use_the_file_name() {
+        let link = "# comment\n[Match]\nMACAddress=00:11:22:33:44:55 00-11-22-33-44-66\n[Link]\nMTUBytes=9000\n";
+        let interfaces = parse_link_file(link, "50-uplink");
+        assert_eq!(interfaces.len(), 2);
+        assert!(interfaces.iter().all(|(name, _)| name == "50-uplink"));
+        assert_eq!(interfaces[1].1.to_string(), "00:11:22:33:44:66");
+        // [Link] 之外的 Name= 与 [Match] 之外的地址不算
+        assert_eq!(parse_link_file("[Network]\nMACAddress=00:11:22:33:44:55\n", "x"), []);
+    }
+
+    #[test]
+    fn wildcard_rules_are_skipped() {
+        let rules = "SUBSYSTEM==\"net\", ATTR{address}==\"?*\", NAME=\"eth0\"\n\
+                     SUBSYSTEM==\"net\", ATTR{address}==\"00:11:22:33:44:55\", NAME==\"eth1\"\n";
+        assert_eq!(parse_persistent_net_rules(rules), []);
+    }
+
+    #[test]
+    fn the_root_is_mounted_by_uuid_only() {
+        assert_eq!(parse_fstab_root_uuid("/dev/sda1 / ext4 defaults 0 1\n"), None);
+        assert_eq!(parse_fstab_root_uuid("LABEL=root / ext4 defaults 0 1\n"), None);
+        assert_eq!(parse_fstab_root_uuid("#UUID=abcd / ext4 defaults 0 1\n"), None);
+        assert_eq!(parse_fstab_root_uuid("UUID=ABCD-1234 / vfat defaults 0 1\n").as_deref(), Some("ABCD-1234"));
+    }
+
+    // 镜像中指向绝对路径的链接指向的是本机文件，不能读取
+    #[cfg(unix)]
+    #[test]
+    fn links_out_of_the_image_are_refused() {
+        let image = FakeImage::new();
+        let outside = FakeImage::new();
+        outside.write("/machine-id", "0123456789abcdef0123456789abcdef\n");
+        fs::create_dir_all(image.path().join("etc")).unwrap();
+        std::os::unix::fs::symlink(outside.path().join("machine-id"), image.path().join("etc/machine-id")).unwrap();
+        let root = OfflineRoot::new(image.path()).unwrap();
+        assert!(root.read_file("/etc/machine-id").unwrap_err().to_string().contains("outside"));
+        assert!(root.machine_id().is_err());
+    }
+
+    #[test]
+    fn windows_images_are_detected() {
+        let image = FakeImage::new();
+        image.write("/Windows/System32/config/SYSTEM", "regf");
+        assert!(OfflineRoot::new(image.path()).unwrap().is_windows());
+        let missing = std::env::temp_dir().join(format!("hardware_id-offline-{}", Uuid::new_v4().simple()));
+        assert!(matches!(OfflineRoot::new(&missing), Err(HardwareError::FileError(_))));
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+use std::str::FromStr;
+
+// 唯一码的输出编码，默认保持 xxxx-xxxx-xxxx-xxxx 短格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Grouped,
+    Hex,
+    Base32,
+    Base58,
+}
+
+impl OutputFormat {
+    pub const DEFAULT_GROUPED_LENGTH: usize = 16;
+
+    // length 为编码后保留的字符数；Grouped 默认 16，其余格式默认完整输出
+    pub fn encode(&self, digest: &[u8], length: Option<usize>) -> String {
+        match self {
+            OutputFormat::Grouped => {
+                let hash = hex::encode(digest);
+                let length = length.unwrap_or(Self::DEFAULT_GROUPED_LENGTH);
+                truncate(&hash, Some(length))
+                    .as_bytes()
+                    .chunks(4)
+                    .map(|group| String::from_utf8_lossy(group).into_owned())
+                    .collect::<Vec<_>>()
+                    .join("-")
+            }
+            OutputFormat::Hex => truncate(&hex::encode(digest), length),
+            OutputFormat::Base32 => {
+                truncate(&base32::encode(base32::Alphabet::Crockford, digest), length)
+            }
+            OutputFormat::Base58 => truncate(&bs58::encode(digest).into_string(), length),
+        }
+    }
+}
+
+fn truncate(encoded: &str, length: Option<usize>) -> String {
+    match length {
+        Some(length) => encoded.chars().take(length).collect(),
+        None => encoded.to_string(),
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "grouped" => Ok(OutputFormat::Grouped),
+            "hex" => Ok(OutputFormat::Hex),
+            "base32" => Ok(OutputFormat::Base32),
+            "base58" => Ok(OutputFormat::Base58),
+            other => Err(format!(
+                "unknown output format '{}' (expected grouped, hex, base32 or base58)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            OutputFormat::Grouped => "grouped",
+            OutputFormat::Hex => "hex",
+            OutputFormat::Base32 => "base32",
+            OutputFormat::Base58 => "base58",
+        };
+        f.write_str(name)
+    }
+}
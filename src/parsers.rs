@@ -0,0 +1,1109 @@
+// 平台命令输出的纯解析函数，不执行任何命令，便于用固定输出验证
+// 每个平台只用到其中一部分，各项只在用到它的平台上编译；测试时全部编译
+
+#[cfg(any(test, target_os = "windows"))]
+use std::collections::HashMap;
+use encoding_rs::Encoding;
+#[cfg(any(test, target_os = "windows", target_os = "linux", target_os = "macos"))]
+use crate::error::HardwareError;
+
+// 命令输出解码：UTF-16（带 BOM 或高字节全为 0）→ UTF-8（去掉 BOM）→ 控制台代码页（GBK、Shift-JIS 等）
+// 解码必须在解析与哈希之前完成，否则不同系统语言下相同的硬件会得到不同的值
+pub(crate) fn decode_output(bytes: &[u8], codepage: Option<u32>) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return decode_utf16le(utf16);
+    }
+    if bytes.len() >= 4 && bytes.len().is_multiple_of(2) && bytes.iter().skip(1).step_by(2).take(16).all(|b| *b == 0) {
+        return decode_utf16le(bytes);
+    }
+
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.to_string();
+    }
+
+    match codepage.and_then(codepage_encoding) {
+        Some(encoding) => encoding.decode_without_bom_handling(bytes).0.into_owned(),
+        None => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+fn decode_utf16le(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+    String::from_utf16_lossy(&units)
+}
+
+// Windows 代码页编号到编码的映射，只覆盖常见的 OEM/ANSI 代码页
+fn codepage_encoding(codepage: u32) -> Option<&'static Encoding> {
+    let label = match codepage {
+        437 | 850 | 1252 => "windows-1252",
+        866 => "ibm866",
+        874 => "windows-874",
+        932 => "shift_jis",
+        936 => "gbk",
+        949 => "euc-kr",
+        950 => "big5",
+        1250..=1258 => return Encoding::for_label(format!("windows-{}", codepage).as_bytes()),
+        54936 => "gb18030",
+        _ => return None,
+    };
+    Encoding::for_label(label.as_bytes())
+}
+
+// wmic 表格输出：首个非空行为列名，其后每个非空行是一条数据（中间可能夹有空行），去掉首尾空白
+#[cfg(any(test, target_os = "windows"))]
+pub(crate) fn parse_wmic_rows(output: &str) -> Result<Vec<String>, HardwareError> {
+    let mut lines = output.lines().map(str::trim).filter(|line| !line.is_empty());
+    lines
+        .next()
+        .ok_or_else(|| HardwareError::ParseError("wmic output has no header".to_string()))?;
+    Ok(lines.map(str::to_string).collect())
+}
+
+// 一个 WMI 实例的若干属性，键为属性名
+#[cfg(any(test, target_os = "windows"))]
+pub(crate) type WmiRecord = HashMap<String, String>;
+
+// wmic ... get A,B /value 的输出：每个实例为若干 "属性=值" 行，实例之间以空行分隔；值去掉首尾空白
+#[cfg(any(test, target_os = "windows"))]
+pub(crate) fn parse_wmic_records(output: &str) -> Vec<WmiRecord> {
+    let mut records = Vec::new();
+    let mut current = WmiRecord::new();
+    for line in output.lines().map(str::trim) {
+        match line.split_once('=') {
+            Some((name, value)) if !name.is_empty() => {
+                current.insert(name.to_string(), value.trim().to_string());
+            }
+            _ if line.is_empty() && !current.is_empty() => records.push(std::mem::take(&mut current)),
+            _ => {}
+        }
+    }
+    if !current.is_empty() {
+        records.push(current);
+    }
+    records
+}
+
+// 命令的错误输出是否表示权限不足：wmic/CIM 的 "Access denied"（WBEM_E_ACCESS_DENIED、E_ACCESSDENIED）
+// 以及 sudo -n 需要密码
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+pub(crate) fn is_access_denied(message: &str) -> bool {
+    let message = message.to_lowercase();
+    ["access denied", "access is denied", "0x80041003", "0x80070005", "a password is required"]
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+// Get-CimInstance 的查询脚本，以 UTF-8 输出压缩 JSON；日期属性转换为与 wmic 相同的 DMTF 格式
+#[cfg(target_os = "windows")]
+pub(crate) fn cim_query_script(class: &str, property: &str) -> String {
+    let select = if property == "ReleaseDate" {
+        format!(
+            "@{{n='{0}';e={{[Management.ManagementDateTimeConverter]::ToDmtfDateTime($_.{0})}}}}",
+            property
+        )
+    } else {
+        property.to_string()
+    };
+    format!(
+        "[Console]::OutputEncoding = [Text.Encoding]::UTF8; \
+         Get-CimInstance -ClassName {} | Select-Object -Property {} | ConvertTo-Json -Compress",
+        class, select
+    )
+}
+
+// ConvertTo-Json 输出：单个实例为对象，多个实例为数组，没有实例时为空；与 wmic 一样去掉空值
+#[cfg(any(test, target_os = "windows"))]
+pub(crate) fn parse_cim_json(output: &str, property: &str) -> Result<Vec<String>, HardwareError> {
+    Ok(parse_cim_records(output)?
+        .into_iter()
+        .filter_map(|mut record| record.remove(property))
+        .filter(|value| !value.is_empty())
+        .collect())
+}
+
+// 同上，每个实例为一条记录；null 属性不出现在记录中，数字等非字符串值保留其 JSON 文本
+#[cfg(any(test, target_os = "windows"))]
+pub(crate) fn parse_cim_records(output: &str) -> Result<Vec<WmiRecord>, HardwareError> {
+    let output = output.trim();
+    if output.is_empty() {
+        return Ok(Vec::new());
+    }
+    let json: serde_json::Value = serde_json::from_str(output)
+        .map_err(|e| HardwareError::ParseError(format!("invalid Get-CimInstance JSON: {}", e)))?;
+    let instances = match json {
+        serde_json::Value::Array(items) => items,
+        object @ serde_json::Value::Object(_) => vec![object],
+        other => return Err(HardwareError::ParseError(format!("unexpected Get-CimInstance output: {}", other))),
+    };
+    Ok(instances
+        .iter()
+        .filter_map(serde_json::Value::as_object)
+        .map(|instance| {
+            instance
+                .iter()
+                .filter_map(|(name, value)| {
+                    let value = match value {
+                        serde_json::Value::String(value) => value.trim().to_string(),
+                        serde_json::Value::Null => return None,
+                        value => value.to_string(),
+                    };
+                    Some((name.clone(), value))
+                })
+                .collect()
+        })
+        .collect())
+}
+
+// "Label: value" 形式（system_profiler、diskutil、dmidecode），取第一处包含 label 的行；
+// 值中的冒号保留（"1916.40.8.0.0 (iBridge: 20.16.3045.0.0,0)"）
+#[cfg(any(test, target_os = "macos"))]
+pub(crate) fn parse_labeled_value(output: &str, label: &str) -> Result<String, HardwareError> {
+    output
+        .lines()
+        .find(|line| line.contains(label))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, value)| value)
+        .map(|value| value.trim().to_string())
+        .ok_or_else(|| HardwareError::ParseError(format!("no '{}' line in output", label)))
+}
+
+// ioreg -rd1 的属性行：  "IOPlatformUUID" = "564D…"，数据类型的值写作 <"MacBookPro15,1">；
+// 空值视为没有
+#[cfg(any(test, target_os = "macos"))]
+pub(crate) fn parse_ioreg_property(output: &str, key: &str) -> Option<String> {
+    let prefix = format!("\"{}\" = ", key);
+    output
+        .lines()
+        .find_map(|line| line.trim_start_matches([' ', '|']).strip_prefix(prefix.as_str()))
+        .map(|value| {
+            let value = value.trim();
+            let value = value.strip_prefix('<').and_then(|v| v.strip_suffix('>')).unwrap_or(value);
+            value.trim_matches('"').trim_end_matches('\0').trim().to_string()
+        })
+        .filter(|value| !value.is_empty())
+}
+
+// 同上，取所有包含 label 的行（每条内存一行）
+#[cfg(any(test, target_os = "linux", target_os = "macos"))]
+pub(crate) fn parse_labeled_values(output: &str, label: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| line.contains(label))
+        .filter_map(|line| line.split(':').nth(1))
+        .map(|value| value.trim().to_string())
+        .collect()
+}
+
+// /proc/cpuinfo 中以 name 开头的第一个字段
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn parse_cpuinfo_field(cpuinfo: &str, name: &str) -> Result<String, HardwareError> {
+    cpuinfo
+        .lines()
+        .find(|line| line.starts_with(name))
+        .and_then(|line| line.split(':').nth(1))
+        .map(|value| value.trim().to_string())
+        .ok_or_else(|| HardwareError::ParseError(format!("no '{}' field in /proc/cpuinfo", name)))
+}
+
+// 树莓派 /proc/cpuinfo 的 "Serial" 行（SoC 序列号）；部分固件写入全 0，视为没有
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn parse_cpuinfo_serial(cpuinfo: &str) -> Option<String> {
+    parse_cpuinfo_field(cpuinfo, "Serial").ok().filter(|serial| !is_zero_serial(serial))
+}
+
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn is_zero_serial(serial: &str) -> bool {
+    serial.trim().chars().all(|c| c == '0')
+}
+
+// 设备树字符串列表属性（compatible 等）：各项以 NUL 分隔
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn parse_device_tree_strings(raw: &str) -> Vec<String> {
+    raw.split('\0').map(str::trim).filter(|item| !item.is_empty()).map(str::to_string).collect()
+}
+
+// MMC 块设备（eMMC 与 SD 卡）：mmcblk0、mmcblk1 及 eMMC 的硬件分区 mmcblk0boot0、mmcblk0rpmb
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn is_mmc_disk(disk: &str) -> bool {
+    disk.starts_with("mmcblk")
+}
+
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn is_mmc_hardware_partition(disk: &str) -> bool {
+    is_mmc_disk(disk) && (disk.contains("boot") || disk.ends_with("rpmb"))
+}
+
+// CID 为 128 位（32 个十六进制字符）：MID(8) OID(16) PNM(40/48) PRV(8) PSN(32) MDT CRC，
+// 其中 MID、OID、PNM、PSN 组合在厂商范围内唯一；eMMC 与 SD 的字段宽度略有不同，因此整体使用，
+// 序列号格式为 "mmc-" + 去掉末字节（CRC 与保留位）后的 30 个小写十六进制字符
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn mmc_serial_from_cid(cid: &str) -> Option<String> {
+    let cid = cid.trim().to_lowercase();
+    if cid.len() != 32 || !cid.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(format!("mmc-{}", &cid[..30]))
+}
+
+// udevadm info --query=property 的 KEY=value 行
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn parse_udev_property(output: &str, key: &str) -> Result<String, HardwareError> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+        .map(str::to_string)
+        .ok_or_else(|| HardwareError::ParseError(format!("no {} property in udevadm output", key)))
+}
+
+// 首行去掉首尾空白（lsblk -no）
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn parse_first_line(output: &str) -> Result<String, HardwareError> {
+    output
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+        .ok_or_else(|| HardwareError::ParseError("empty output".to_string()))
+}
+
+// /proc/self/mountinfo 中挂载点为 / 的最后一条（后挂载的覆盖先挂载的）：返回 "主:次" 设备号与挂载源
+// 行格式：ID 父ID 主:次 根 挂载点 选项 [可选字段...] - 类型 挂载源 超级块选项
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn parse_root_mount(mountinfo: &str) -> Option<(String, String)> {
+    mountinfo.lines().rev().find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.get(4) != Some(&"/") {
+            return None;
+        }
+        let separator = fields.iter().position(|field| *field == "-")?;
+        Some((fields.get(2)?.to_string(), fields.get(separator + 2)?.to_string()))
+    })
+}
+
+// 覆盖 path 的挂载（挂载点是 path 的最长前缀，同一挂载点取最后一条）：返回 (文件系统类型, 挂载源, 超级块选项)
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn parse_mount_for(mountinfo: &str, path: &str) -> Option<(String, String, String)> {
+    let covers = |mount_point: &str| {
+        mount_point == "/" || path == mount_point || path.starts_with(&format!("{}/", mount_point))
+    };
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let mount_point = *fields.get(4)?;
+            let separator = fields.iter().position(|field| *field == "-")?;
+            let mount = (
+                fields.get(separator + 1)?.to_string(),
+                fields.get(separator + 2)?.to_string(),
+                fields.get(separator + 3).unwrap_or(&"").to_string(),
+            );
+            covers(mount_point).then_some((mount_point.len(), mount))
+        })
+        .max_by_key(|(length, _)| *length)
+        .map(|(_, mount)| mount)
+}
+
+// NVMe 命名空间所属的控制器：nvme0n1 → nvme0，多路径的 nvme0c1n1 → nvme0
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn parse_nvme_controller(disk: &str) -> Option<String> {
+    let rest = disk.strip_prefix("nvme")?;
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 || !rest[digits..].starts_with(['n', 'c']) {
+        return None;
+    }
+    Some(format!("nvme{}", &rest[..digits]))
+}
+
+// 描述符中的 RemovableMedia（偏移 10）与 BusType（偏移 28）：排除可移动介质以及 USB、SD、
+// 虚拟磁盘与文件型虚拟磁盘（挂载的 VHD）
+#[cfg(target_os = "windows")]
+pub(crate) fn is_fixed_storage(buffer: &[u8]) -> bool {
+    const EXCLUDED_BUS_TYPES: [u32; 4] = [0x07, 0x0C, 0x0E, 0x0F];
+    let removable = buffer.get(10).is_some_and(|removable| *removable != 0);
+    let bus_type = buffer.get(28..32).map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+    !removable && bus_type.is_some_and(|bus_type| !EXCLUDED_BUS_TYPES.contains(&bus_type))
+}
+
+// IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS 的 VOLUME_DISK_EXTENTS：偏移 0 为区段数，第一个 DISK_EXTENT
+// 从偏移 8 开始（按 8 字节对齐），其 DiskNumber 为卷所在的物理磁盘编号
+#[cfg(target_os = "windows")]
+pub(crate) fn parse_volume_disk_number(buffer: &[u8]) -> Option<u32> {
+    let count = u32::from_le_bytes(buffer.get(0..4)?.try_into().ok()?);
+    if count == 0 {
+        return None;
+    }
+    Some(u32::from_le_bytes(buffer.get(8..12)?.try_into().ok()?))
+}
+
+// Win32_PhysicalMedia 的 Tag（"\\.\PHYSICALDRIVE0"）中的物理磁盘编号
+#[cfg(any(test, target_os = "windows"))]
+pub(crate) fn physical_drive_index(tag: &str) -> Option<u32> {
+    let tag = tag.trim().to_ascii_uppercase();
+    tag.rsplit_once("PHYSICALDRIVE")?.1.parse().ok()
+}
+
+// SCSI VPD 0x80 页（单元序列号）：第 1 字节为页码，2..4 为大端长度，之后是 ASCII 序列号
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn parse_vpd_serial(page: &[u8]) -> Option<String> {
+    if page.get(1) != Some(&0x80) {
+        return None;
+    }
+    let length = u16::from_be_bytes([*page.get(2)?, *page.get(3)?]) as usize;
+    let serial = page.get(4..4 + length).or_else(|| page.get(4..))?;
+    let serial = String::from_utf8_lossy(serial).trim_matches(|c: char| c.is_whitespace() || c == '\0').to_string();
+    Some(serial).filter(|serial| !serial.is_empty())
+}
+
+// diskutil list physical：标题行形如 "/dev/disk0 (internal, physical):"，只保留内置磁盘
+#[cfg(any(test, target_os = "macos"))]
+pub(crate) fn parse_diskutil_internal_disks(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| line.contains("(internal"))
+        .filter_map(|line| line.split_whitespace().next()?.strip_prefix("/dev/").map(str::to_string))
+        .collect()
+}
+
+// 分区或 APFS 物理存储所在的整块磁盘："disk0s2" -> "disk0"
+#[cfg(any(test, target_os = "macos"))]
+pub(crate) fn diskutil_whole_disk(device: &str) -> &str {
+    let device = device.trim().trim_start_matches("/dev/");
+    match device.strip_prefix("disk") {
+        Some(rest) => {
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            &device[..4 + digits]
+        }
+        None => device,
+    }
+}
+
+// diskutil info -plist 等 XML plist 中第一个 <key>key</key> 之后的值：<string>、<integer> 取文本，
+// <true/>、<false/> 为 "true"、"false"；不是简单值（<array>、<dict>）时为 None
+#[cfg(any(test, target_os = "macos"))]
+pub(crate) fn parse_plist_value(plist: &str, key: &str) -> Option<String> {
+    let marker = format!("<key>{}</key>", key);
+    let rest = plist[plist.find(&marker)? + marker.len()..].trim_start();
+    if rest.starts_with("<true/>") {
+        return Some("true".to_string());
+    }
+    if rest.starts_with("<false/>") {
+        return Some("false".to_string());
+    }
+    let (tag, rest) = rest.strip_prefix('<')?.split_once('>')?;
+    if !matches!(tag, "string" | "integer") {
+        return None;
+    }
+    let value = rest.split_once(&format!("</{}>", tag))?.0;
+    let value = value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    Some(value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+// system_profiler SPNVMeDataType / SPSerialATADataType：每块磁盘的字段是一段缩进相同的连续行，
+// 其后的分区（Volumes）缩进更深；按段中的 "BSD Name" 找到 disk，返回 labels 中第一个存在的字段
+#[cfg(any(test, target_os = "macos"))]
+pub(crate) fn parse_profiler_disk_field(output: &str, disk: &str, labels: &[&str]) -> Option<String> {
+    output.split("\n\n").find_map(|paragraph| {
+        let indent = |line: &str| line.len() - line.trim_start().len();
+        let lines: Vec<&str> = paragraph.lines().filter(|line| !line.trim().is_empty()).collect();
+        let level = indent(lines.first()?);
+        let fields: Vec<(&str, &str)> = lines
+            .into_iter()
+            .filter(|line| indent(line) == level)
+            .filter_map(|line| line.trim().split_once(':'))
+            .map(|(name, value)| (name, value.trim()))
+            .collect();
+        fields.iter().any(|(name, value)| *name == "BSD Name" && *value == disk).then_some(())?;
+        labels.iter().find_map(|label| {
+            fields
+                .iter()
+                .find(|(name, value)| name == label && !value.is_empty())
+                .map(|(_, value)| value.to_string())
+        })
+    })
+}
+
+// networksetup -listallhardwareports：每个端口为 "Hardware Port"、"Device"、"Ethernet Address" 三行；
+// 返回 (端口名称, 设备名, 小写的 MAC)，没有 MAC（"N/A"）的端口不返回
+#[cfg(any(test, target_os = "macos"))]
+pub(crate) fn parse_hardware_ports(output: &str) -> Vec<(String, String, String)> {
+    let mut ports = Vec::new();
+    let (mut port, mut device) = (None, None);
+    for line in output.lines() {
+        let Some((name, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        match name.trim() {
+            "Hardware Port" => (port, device) = (Some(value.to_string()), None),
+            "Device" => device = Some(value.to_string()),
+            "Ethernet Address" => {
+                let octets: Vec<&str> = value.split(':').collect();
+                let is_mac = octets.len() == 6 && octets.iter().all(|octet| u8::from_str_radix(octet, 16).is_ok());
+                if let (Some(port), Some(device), true) = (port.take(), device.take(), is_mac) {
+                    ports.push((port, device, value.to_lowercase()));
+                }
+            }
+            _ => {}
+        }
+    }
+    ports
+}
+
+// ioreg -r -c IOEthernetInterface：每个接口一段（"+-o en0  <class …>"），返回 "IOBuiltin" = Yes 的 BSD 名称
+#[cfg(any(test, target_os = "macos"))]
+pub(crate) fn parse_ioreg_builtin_interfaces(output: &str) -> Vec<String> {
+    output
+        .split("+-o ")
+        .filter(|entry| parse_ioreg_property(entry, "IOBuiltin").as_deref() == Some("Yes"))
+        .filter_map(|entry| parse_ioreg_property(entry, "BSD Name"))
+        .collect()
+}
+
+// /proc/net/route：Destination 与 Mask 均为 00000000 且已启用（Flags 含 RTF_UP）的默认路由中 Metric 最小的接口
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn parse_default_route_interface(route: &str) -> Option<String> {
+    const RTF_UP: u16 = 0x1;
+    route
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                [iface, "00000000", _, flags, _, _, metric, "00000000", ..]
+                    if u16::from_str_radix(flags, 16).is_ok_and(|flags| flags & RTF_UP != 0) =>
+                {
+                    Some((metric.parse::<u32>().unwrap_or(u32::MAX), iface.to_string()))
+                }
+                _ => None,
+            }
+        })
+        .min()
+        .map(|(_, iface)| iface)
+}
+
+// route -n get default 的 "interface: en0" 行
+#[cfg(target_os = "macos")]
+pub(crate) fn parse_route_get_interface(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("interface:"))
+        .map(|iface| iface.trim().to_string())
+        .filter(|iface| !iface.is_empty())
+}
+
+// ip link / ifconfig：接口标题行不缩进（"2: eth0: <…>"、"en0: flags=…"），其后缩进的 marker 行给出 MAC；
+// 按输出顺序返回 (接口名, MAC)，接口名去掉 "@if5" 这类对端后缀
+#[cfg(any(test, target_os = "linux", target_os = "macos"))]
+pub(crate) fn parse_interface_ethers(output: &str, marker: &str) -> Vec<(String, String)> {
+    let mut interfaces = Vec::new();
+    let mut current: Option<String> = None;
+    for line in output.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            let mut tokens = line.split_whitespace();
+            let first = tokens.next().unwrap_or_default();
+            // ip link 的标题行以接口序号开头
+            let name = if first.trim_end_matches(':').chars().all(|c| c.is_ascii_digit()) {
+                tokens.next()
+            } else {
+                Some(first)
+            };
+            current = name.and_then(|name| name.trim_end_matches(':').split('@').next()).map(str::to_string);
+        } else if let Some(mac) = line.trim_start().strip_prefix(marker).and_then(|rest| rest.split_whitespace().next()) {
+            if let Some(name) = current.take() {
+                interfaces.push((name, mac.to_lowercase()));
+            }
+        }
+    }
+    interfaces
+}
+
+// 磁盘序列号：部分驱动以十六进制编码并用空格补齐（"2020574437..."），按字节对解码；
+// 去掉首尾空格与 NUL，使 Win32_DiskDrive、Win32_PhysicalMedia 与 IOCTL 得到相同的值
+#[cfg(any(
+    target_os = "windows",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "illumos",
+    target_os = "solaris"
+))]
+pub(crate) fn normalize_disk_serial(raw: &str) -> String {
+    let raw = raw.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+    let decoded = (raw.len() >= 16 && raw.len().is_multiple_of(2))
+        .then(|| hex::decode(raw).ok())
+        .flatten()
+        .filter(|bytes| bytes.iter().all(|b| b.is_ascii_graphic() || *b == b' ' || *b == 0))
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+    match decoded {
+        Some(serial) => serial.trim_matches(|c: char| c.is_whitespace() || c == '\0').to_string(),
+        None => raw.to_string(),
+    }
+}
+
+// IOCTL_STORAGE_QUERY_PROPERTY 返回的 STORAGE_DEVICE_DESCRIPTOR 中各字符串偏移量字段的位置
+#[cfg(target_os = "windows")]
+pub(crate) const STORAGE_PRODUCT_ID: usize = 16;
+#[cfg(target_os = "windows")]
+pub(crate) const STORAGE_PRODUCT_REVISION: usize = 20;
+#[cfg(target_os = "windows")]
+pub(crate) const STORAGE_SERIAL_NUMBER: usize = 24;
+
+// 偏移量字段指向以 NUL 结尾的 ASCII 字符串，偏移为 0 表示设备没有提供；去掉首尾空白
+#[cfg(target_os = "windows")]
+pub(crate) fn parse_storage_descriptor_string(buffer: &[u8], field: usize) -> Option<String> {
+    let offset = u32::from_le_bytes(buffer.get(field..field + 4)?.try_into().ok()?) as usize;
+    if offset == 0 {
+        return None;
+    }
+    let bytes = buffer.get(offset..)?;
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    let value = String::from_utf8_lossy(&bytes[..end]).trim().to_string();
+    Some(value).filter(|value| !value.is_empty())
+}
+
+// lspci -mm 的一行：各字段用双引号括起（槽位号除外），依次为类别、厂商、设备；返回 (厂商, 设备)
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn parse_lspci_mm(output: &str) -> Option<(String, String)> {
+    let line = output.lines().find(|line| !line.trim().is_empty())?;
+    let fields: Vec<&str> = line.split('"').skip(1).step_by(2).collect();
+    Some((fields.get(1)?.trim().to_string(), fields.get(2)?.trim().to_string()))
+}
+
+// nvidia-smi --format=csv,noheader：每行一块显卡，列以 ", " 分隔
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+pub(crate) fn parse_csv_rows(output: &str) -> Vec<Vec<String>> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.split(',').map(|value| value.trim().to_string()).collect())
+        .collect()
+}
+
+// PCI 地址去掉域号后的 "总线:设备.功能"（小写）：nvidia-smi 的域号为 8 位，sysfs 为 4 位
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn pci_bus_address(address: &str) -> String {
+    address
+        .trim()
+        .split_once(':')
+        .map_or(address, |(_, rest)| rest)
+        .to_ascii_lowercase()
+}
+
+// 统一各平台的显卡厂商名称（lspci 的 "Advanced Micro Devices, Inc. [AMD/ATI]"、
+// Win32_VideoController 的 "NVIDIA GeForce RTX 3080"、系统信息的 "Apple (0x106b)" 等）
+#[cfg(any(test, target_os = "windows", target_os = "linux", target_os = "macos"))]
+pub(crate) fn gpu_vendor(text: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    let known = [
+        ("nvidia", "NVIDIA"),
+        ("advanced micro devices", "AMD"),
+        ("amd", "AMD"),
+        ("radeon", "AMD"),
+        ("intel", "Intel"),
+        ("apple", "Apple"),
+    ];
+    let words: Vec<&str> = lower.split(|c: char| !c.is_ascii_alphanumeric()).collect();
+    known
+        .iter()
+        .find(|(name, _)| if name.contains(' ') { lower.contains(name) } else { words.contains(name) })
+        .map(|(_, vendor)| vendor.to_string())
+        .unwrap_or_else(|| text.split(" (").next().unwrap_or_default().trim().to_string())
+}
+
+// PCI 厂商 ID（sysfs 的 "0x10de"）对应的名称，未知厂商保留十六进制
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn pci_vendor_name(id: &str) -> String {
+    let id = id.trim().trim_start_matches("0x").to_ascii_lowercase();
+    match id.as_str() {
+        "10de" => "NVIDIA".to_string(),
+        "1002" => "AMD".to_string(),
+        "8086" => "Intel".to_string(),
+        "106b" => "Apple".to_string(),
+        _ => id,
+    }
+}
+
+// system_profiler SPDisplaysDataType：每块显卡以 "Chipset Model:" 开头，其后可能有 "Vendor:"；
+// 返回 (厂商, 型号)，缺少 Vendor 行时为空串
+#[cfg(target_os = "macos")]
+pub(crate) fn parse_profiler_displays(output: &str) -> Vec<(String, String)> {
+    let mut gpus: Vec<(String, String)> = Vec::new();
+    for line in output.lines().map(str::trim) {
+        if let Some(model) = line.strip_prefix("Chipset Model:") {
+            gpus.push((String::new(), model.trim().to_string()));
+        } else if let (Some(vendor), Some(gpu)) = (line.strip_prefix("Vendor:"), gpus.last_mut()) {
+            gpu.0 = vendor.trim().to_string();
+        }
+    }
+    gpus
+}
+
+// tpm2_readpublic 输出中的 "name:"：名称为 2 字节的名称算法 ID 加上公开区的摘要，
+// 算法为 SHA-256（000b）时去掉前缀即公开区的 SHA-256
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn parse_tpm2_name_sha256(output: &str) -> Option<String> {
+    let name = output.lines().find_map(|line| line.trim().strip_prefix("name:"))?.trim();
+    let digest = name.strip_prefix("000b")?;
+    (digest.len() == 64 && digest.bytes().all(|b| b.is_ascii_hexdigit())).then(|| digest.to_ascii_lowercase())
+}
+
+// 由 CPUID 签名或厂商/型号字符串（DMI、Win32_ComputerSystem、hw.model）识别虚拟化平台
+#[cfg(any(test, not(target_os = "android")))]
+pub(crate) fn hypervisor_name(text: &str) -> Option<&'static str> {
+    let lower = text.to_lowercase();
+    let known = [
+        ("kvmkvmkvm", "KVM"),
+        ("kvm", "KVM"),
+        ("tcgtcgtcg", "QEMU"),
+        ("qemu", "QEMU"),
+        ("vmware", "VMware"),
+        ("vboxvboxvbox", "VirtualBox"),
+        ("virtualbox", "VirtualBox"),
+        ("innotek", "VirtualBox"),
+        ("microsoft hv", "Hyper-V"),
+        ("virtual machine", "Hyper-V"),
+        ("xenvmm", "Xen"),
+        ("xen", "Xen"),
+        ("parallels", "Parallels"),
+        ("prl hyperv", "Parallels"),
+        ("bhyve", "bhyve"),
+        ("virtualmac", "Apple Virtualization"),
+    ];
+    known.iter().find(|(marker, _)| lower.contains(marker)).map(|(_, name)| *name)
+}
+
+// reg query 的输出："    MachineGuid    REG_SZ    <值>"，取类型之后的部分
+#[cfg(any(test, target_os = "linux"))]
+pub(crate) fn parse_reg_query_value(output: &str, name: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        if fields.next()? != name {
+            return None;
+        }
+        fields.next()?.starts_with("REG_").then(|| fields.collect::<Vec<_>>().join(" "))
+    }).filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record<'a>(records: &'a [WmiRecord], index: &str) -> &'a WmiRecord {
+        records.iter().find(|record| record.get("Index").map(String::as_str) == Some(index)).unwrap()
+    }
+
+    #[test]
+    fn wmic_rows_skip_blank_lines_between_header_and_data() {
+        let rows = parse_wmic_rows(include_str!("../tests/fixtures/windows/wmic_diskdrive_serial.txt")).unwrap();
+        assert_eq!(rows, ["0025_3852_81B0_1A2C.", "ZFL1ABCD"]);
+        assert!(parse_wmic_rows("").is_err());
+        assert_eq!(parse_wmic_rows("SerialNumber\r\r\n").unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn wmic_records_keep_each_disk_together() {
+        let records = parse_wmic_records(include_str!("../tests/fixtures/windows/wmic_diskdrive_records.txt"));
+        assert_eq!(records.len(), 2);
+        assert_eq!(record(&records, "0")["SerialNumber"], "ZFL1ABCD");
+        assert_eq!(record(&records, "0")["Model"], "ST2000DM008-2FR102");
+        assert_eq!(record(&records, "1")["FirmwareRevision"], "2B2QEXM7");
+        assert!(parse_wmic_records("\r\r\n\r\r\n").is_empty());
+    }
+
+    #[test]
+    fn disk_selected_by_index_does_not_depend_on_enumeration_order() {
+        let mut records = parse_wmic_records(include_str!("../tests/fixtures/windows/wmic_diskdrive_records.txt"));
+        let first = record(&records, "0").clone();
+        records.reverse();
+        assert_eq!(*record(&records, "0"), first);
+    }
+
+    #[test]
+    fn cim_records_match_wmic_records() {
+        let cim = parse_cim_records(include_str!("../tests/fixtures/windows/cim_diskdrive.json")).unwrap();
+        let wmic = parse_wmic_records(include_str!("../tests/fixtures/windows/wmic_diskdrive_records.txt"));
+        for index in ["0", "1"] {
+            assert_eq!(record(&cim, index)["SerialNumber"], record(&wmic, index)["SerialNumber"]);
+            assert_eq!(record(&cim, index)["Model"], record(&wmic, index)["Model"]);
+        }
+        // null 属性不出现在记录中
+        assert!(!record(&cim, "0").contains_key("FirmwareRevision"));
+        assert!(parse_cim_records("").unwrap().is_empty());
+        assert!(parse_cim_records("{not json").is_err());
+    }
+
+    #[test]
+    fn cim_json_single_instance_is_an_object() {
+        let rows = parse_cim_json(r#"{"SerialNumber":" ZFL1ABCD "}"#, "SerialNumber").unwrap();
+        assert_eq!(rows, ["ZFL1ABCD"]);
+        assert!(parse_cim_json(r#"{"SerialNumber":null}"#, "SerialNumber").unwrap().is_empty());
+    }
+
+    #[test]
+    fn physical_drive_index_from_media_tag() {
+        assert_eq!(physical_drive_index(r"\\.\PHYSICALDRIVE0"), Some(0));
+        assert_eq!(physical_drive_index(r"\\.\PhysicalDrive11"), Some(11));
+        assert_eq!(physical_drive_index(r"\\.\CDROM0"), None);
+    }
+
+    const SP_HARDWARE: &str = include_str!("../tests/fixtures/macos/system_profiler_hardware.txt");
+    const SP_HARDWARE_DE: &str = include_str!("../tests/fixtures/macos/system_profiler_hardware_de.txt");
+    const IOREG_PLATFORM_INTEL: &str = include_str!("../tests/fixtures/macos/ioreg_platform_intel.txt");
+    const IOREG_PLATFORM_APPLE_SILICON: &str = include_str!("../tests/fixtures/macos/ioreg_platform_apple_silicon.txt");
+    const SP_NVME: &str = include_str!("../tests/fixtures/macos/system_profiler_nvme.txt");
+    const SP_SATA: &str = include_str!("../tests/fixtures/macos/system_profiler_sata.txt");
+    const SP_FUSION: &str = include_str!("../tests/fixtures/macos/system_profiler_fusion.txt");
+    const DISKUTIL_LIST_PHYSICAL: &str = include_str!("../tests/fixtures/macos/diskutil_list_physical.txt");
+    const DISKUTIL_INFO_ROOT: &str = include_str!("../tests/fixtures/macos/diskutil_info_root.plist");
+    const DISKUTIL_INFO_ROOT_EXTERNAL: &str = include_str!("../tests/fixtures/macos/diskutil_info_root_external.plist");
+    const DISKUTIL_INFO_DISK0: &str = include_str!("../tests/fixtures/macos/diskutil_info_disk0.plist");
+    const NETWORKSETUP_PORTS: &str = include_str!("../tests/fixtures/macos/networksetup_ports.txt");
+    const NETWORKSETUP_PORTS_DOCK: &str = include_str!("../tests/fixtures/macos/networksetup_ports_dock.txt");
+    const IOREG_ETHERNET_DOCK: &str = include_str!("../tests/fixtures/macos/ioreg_ethernet_dock.txt");
+    const SP_HARDWARE_BOOTROM: &str = include_str!("../tests/fixtures/macos/system_profiler_hardware_bootrom.txt");
+    const SP_HARDWARE_M2: &str = include_str!("../tests/fixtures/macos/system_profiler_hardware_m2.txt");
+    const CPUINFO_X86: &str = include_str!("../tests/fixtures/linux/cpuinfo_x86.txt");
+    const CPUINFO_RPI: &str = include_str!("../tests/fixtures/linux/cpuinfo_rpi.txt");
+    const UDEVADM_NVME: &str = include_str!("../tests/fixtures/linux/udevadm_nvme.txt");
+
+    #[test]
+    fn system_profiler_hardware_overview() {
+        assert_eq!(parse_labeled_value(SP_HARDWARE, "Serial Number (system)").unwrap(), "C02XL0GHJHD2");
+        assert_eq!(parse_labeled_value(SP_HARDWARE, "Hardware UUID").unwrap(), "8A3C6F1E-2B4D-5E6F-9A0B-1C2D3E4F5A6B");
+        assert_eq!(parse_labeled_values(SP_HARDWARE, "Model Identifier"), ["MacBookPro15,2"]);
+        assert!(parse_labeled_value(SP_HARDWARE, "Chip").is_err());
+        assert!(parse_labeled_value("", "Hardware UUID").is_err());
+        assert!(parse_labeled_values("", "Serial Number:").is_empty());
+    }
+
+    // 德语系统上的标签均已翻译（"Seriennummer (System)"、"Hardware-UUID"），按英文标签查找失败
+    #[test]
+    fn localized_profiler_labels_do_not_match() {
+        assert!(parse_labeled_value(SP_HARDWARE_DE, "Serial Number").is_err());
+        assert!(parse_labeled_value(SP_HARDWARE_DE, "Hardware UUID").is_err());
+        assert!(parse_labeled_values(SP_HARDWARE_DE, "Model Identifier").is_empty());
+    }
+
+    #[test]
+    fn ioreg_platform_properties_on_intel_and_apple_silicon() {
+        for (output, serial, uuid, model) in [
+            (IOREG_PLATFORM_INTEL, "C02XL0GHJHD2", "8A3C6F1E-2B4D-5E6F-9A0B-1C2D3E4F5A6B", "MacBookPro15,2"),
+            (IOREG_PLATFORM_APPLE_SILICON, "K7VY2Q1PMR", "5D3B2E1A-7C4F-5A9B-8E6D-0F1A2B3C4D5E", "MacBookPro18,3"),
+        ] {
+            assert_eq!(parse_ioreg_property(output, "IOPlatformSerialNumber").as_deref(), Some(serial));
+            assert_eq!(parse_ioreg_property(output, "IOPlatformUUID").as_deref(), Some(uuid));
+            // 数据类型的值写作 <"…">
+            assert_eq!(parse_ioreg_property(output, "model").as_deref(), Some(model));
+            assert_eq!(parse_ioreg_property(output, "manufacturer").as_deref(), Some("Apple Inc."));
+        }
+        // "model" 不匹配 "model-number" 等前缀相同的键
+        assert_eq!(parse_ioreg_property(IOREG_PLATFORM_APPLE_SILICON, "model-number").as_deref(), Some("Z15G00005"));
+        assert_eq!(parse_ioreg_property(IOREG_PLATFORM_INTEL, "target-type"), None);
+        assert_eq!(parse_ioreg_property("      \"IOPlatformSerialNumber\" = \"\"\n", "IOPlatformSerialNumber"), None);
+        assert_eq!(parse_ioreg_property("", "IOPlatformUUID"), None);
+    }
+
+    #[test]
+    fn profiler_disk_fields_belong_to_the_named_disk() {
+        let field = |output, disk, labels: &[&str]| parse_profiler_disk_field(output, disk, labels);
+        let firmware = ["Revision", "Firmware Version"];
+        assert_eq!(field(SP_NVME, "disk0", &firmware).as_deref(), Some("1161.100"));
+        assert_eq!(field(SP_NVME, "disk0", &["Serial Number"]).as_deref(), Some("0ba0123456789a1b"));
+        assert_eq!(field(SP_SATA, "disk0", &firmware).as_deref(), Some("BXW1SA0Q"));
+        // 分区（Volumes）的 BSD Name 不是磁盘
+        assert_eq!(field(SP_NVME, "disk0s2", &firmware), None);
+        assert_eq!(field(SP_SATA, "disk0s1", &firmware), None);
+        // NVMe 的磁盘不在 SATA 的输出中，反之亦然
+        assert_eq!(field(SP_SATA, "disk1", &firmware), None);
+        assert_eq!(field(SP_NVME, "disk1", &firmware), None);
+        assert_eq!(field("", "disk0", &firmware), None);
+    }
+
+    // Fusion Drive 的 SSD 与硬盘在同一控制器下，各自按 BSD Name 取值
+    #[test]
+    fn fusion_drive_members_are_told_apart() {
+        let firmware = ["Revision", "Firmware Version"];
+        assert_eq!(parse_profiler_disk_field(SP_FUSION, "disk0", &firmware).as_deref(), Some("CXS4LA0Q"));
+        assert_eq!(parse_profiler_disk_field(SP_FUSION, "disk1", &firmware).as_deref(), Some("AQ04"));
+        assert_eq!(parse_profiler_disk_field(SP_FUSION, "disk1", &["Model"]).as_deref(), Some("APPLE HDD ST1000DM003"));
+        assert_eq!(parse_profiler_disk_field(SP_FUSION, "disk2", &firmware), None);
+    }
+
+    #[test]
+    fn diskutil_plist_values_by_type() {
+        assert_eq!(parse_plist_value(DISKUTIL_INFO_ROOT, "APFSContainerReference").as_deref(), Some("disk3"));
+        assert_eq!(parse_plist_value(DISKUTIL_INFO_ROOT, "APFSPhysicalStore").as_deref(), Some("disk0s2"));
+        assert_eq!(parse_plist_value(DISKUTIL_INFO_ROOT, "Size").as_deref(), Some("494384795648"));
+        assert_eq!(parse_plist_value(DISKUTIL_INFO_ROOT, "Internal").as_deref(), Some("true"));
+        assert_eq!(parse_plist_value(DISKUTIL_INFO_ROOT_EXTERNAL, "Internal").as_deref(), Some("false"));
+        assert_eq!(parse_plist_value(DISKUTIL_INFO_ROOT_EXTERNAL, "VolumeName").as_deref(), Some("Test & Recovery"));
+        assert_eq!(parse_plist_value(DISKUTIL_INFO_DISK0, "MediaName").as_deref(), Some("APPLE SSD AP0512Q"));
+        // APFS 卷本身没有介质名称；数组不是简单值
+        assert_eq!(parse_plist_value(DISKUTIL_INFO_ROOT, "MediaName"), None);
+        assert_eq!(parse_plist_value(DISKUTIL_INFO_ROOT, "APFSPhysicalStores"), None);
+        assert_eq!(parse_plist_value(DISKUTIL_INFO_ROOT, "SMARTStatus"), None);
+        assert_eq!(parse_plist_value("", "MediaName"), None);
+    }
+
+    // APFS 卷所在的合成磁盘（disk3）沿物理存储（disk0s2）回到整块磁盘
+    #[test]
+    fn apfs_volumes_resolve_to_the_physical_whole_disk() {
+        let store = parse_plist_value(DISKUTIL_INFO_ROOT, "APFSPhysicalStore").unwrap();
+        assert_eq!(diskutil_whole_disk(&store), "disk0");
+        assert_eq!(diskutil_whole_disk("/dev/disk12s3"), "disk12");
+        assert_eq!(diskutil_whole_disk("disk4"), "disk4");
+        assert_eq!(diskutil_whole_disk("disk3s1s1"), "disk3");
+        assert_eq!(diskutil_whole_disk("nvme0"), "nvme0");
+
+        assert_eq!(parse_diskutil_internal_disks(DISKUTIL_LIST_PHYSICAL), ["disk0"]);
+        assert!(parse_diskutil_internal_disks("").is_empty());
+    }
+
+    #[test]
+    fn hardware_ports_with_a_mac_address() {
+        let port = |port: &str, device: &str, mac: &str| (port.to_string(), device.to_string(), mac.to_string());
+        let builtin = [port("Ethernet", "en0", "3c:22:fb:7a:10:4e"), port("Wi-Fi", "en1", "3c:22:fb:6e:21:9f")];
+        assert_eq!(parse_hardware_ports(NETWORKSETUP_PORTS), builtin);
+        let docked = parse_hardware_ports(NETWORKSETUP_PORTS_DOCK);
+        assert_eq!(docked[..2], [
+            port("Thunderbolt Ethernet Slot 0", "en7", "00:e0:4c:68:0a:51"),
+            port("USB 10/100/1000 LAN", "en8", "8c:ae:4c:e9:c3:22"),
+        ]);
+        assert_eq!(docked[2..], builtin);
+        assert!(parse_hardware_ports("").is_empty());
+    }
+
+    // 扩展坞的网卡 IOBuiltin 为 No
+    #[test]
+    fn ioreg_builtin_interfaces_exclude_the_dock() {
+        assert_eq!(parse_ioreg_builtin_interfaces(IOREG_ETHERNET_DOCK), ["en0", "en1"]);
+        assert!(parse_ioreg_builtin_interfaces("").is_empty());
+    }
+
+    // 较早的 Intel Mac 只有 Boot ROM Version；带 T2 芯片的 Intel Mac 与 Apple Silicon 改为 System Firmware Version
+    #[test]
+    fn firmware_version_labels_on_intel_and_apple_silicon() {
+        assert_eq!(parse_labeled_value(SP_HARDWARE_BOOTROM, "Boot ROM Version").unwrap(), "199.0.0.0.0");
+        assert!(parse_labeled_value(SP_HARDWARE_BOOTROM, "System Firmware Version").is_err());
+        assert_eq!(
+            parse_labeled_value(SP_HARDWARE, "System Firmware Version").unwrap(),
+            "1916.40.8.0.0 (iBridge: 20.16.3045.0.0,0)"
+        );
+        assert_eq!(parse_labeled_value(SP_HARDWARE_M2, "System Firmware Version").unwrap(), "10151.101.3");
+        assert_eq!(parse_labeled_value(SP_HARDWARE_M2, "OS Loader Version").unwrap(), "10151.101.3");
+        assert!(parse_labeled_value(SP_HARDWARE_M2, "Boot ROM Version").is_err());
+    }
+
+    #[test]
+    fn cpuinfo_fields_come_from_the_first_processor() {
+        assert_eq!(parse_cpuinfo_field(CPUINFO_X86, "model name").unwrap(), "Intel(R) Core(TM) i7-8565U CPU @ 1.80GHz");
+        assert_eq!(parse_cpuinfo_field(CPUINFO_X86, "physical id").unwrap(), "0");
+        assert_eq!(parse_cpuinfo_field(CPUINFO_RPI, "Hardware").unwrap(), "BCM2835");
+        assert!(parse_cpuinfo_field(CPUINFO_RPI, "model name").is_err());
+        assert!(parse_cpuinfo_field("", "model name").is_err());
+    }
+
+    #[test]
+    fn cpuinfo_serial_ignores_missing_and_zero_serials() {
+        assert_eq!(parse_cpuinfo_serial(CPUINFO_RPI).as_deref(), Some("10000000a3b4c5d6"));
+        assert_eq!(parse_cpuinfo_serial(CPUINFO_X86), None);
+        assert_eq!(parse_cpuinfo_serial("Serial\t\t: 0000000000000000\n"), None);
+        // 只有字段名、没有冒号的行不是有效的序列号
+        assert_eq!(parse_cpuinfo_serial("Serial\n"), None);
+    }
+
+    #[test]
+    fn device_tree_string_lists_split_on_nul() {
+        let compatible = include_str!("../tests/fixtures/linux/device_tree/rock5b/compatible");
+        assert_eq!(parse_device_tree_strings(compatible), ["radxa,rock-5b", "rockchip,rk3588"]);
+        assert_eq!(parse_device_tree_strings("Raspberry Pi 4 Model B Rev 1.4\0"), ["Raspberry Pi 4 Model B Rev 1.4"]);
+        assert!(parse_device_tree_strings("\0\0").is_empty());
+    }
+
+    // CID 的前 15 字节（去掉 CRC 字节），小写；长度或字符不对时不作为序列号
+    #[test]
+    fn mmc_serial_is_the_cid_without_its_crc() {
+        assert_eq!(
+            mmc_serial_from_cid("450100444734303136085C3A7B916E31\n").as_deref(),
+            Some("mmc-450100444734303136085c3a7b916e")
+        );
+        assert_eq!(mmc_serial_from_cid("450100444734303136085c3a7b916e"), None);
+        assert_eq!(mmc_serial_from_cid("45010044473430313608zz3a7b916e31"), None);
+        assert!(is_mmc_disk("mmcblk0") && is_mmc_hardware_partition("mmcblk0boot1") && is_mmc_hardware_partition("mmcblk0rpmb"));
+        assert!(!is_mmc_hardware_partition("mmcblk0") && !is_mmc_disk("sda"));
+    }
+
+    #[test]
+    fn udev_properties_match_the_whole_key() {
+        assert_eq!(parse_udev_property(UDEVADM_NVME, "ID_SERIAL_SHORT").unwrap(), "S4EWNX0R123456K");
+        assert_eq!(parse_udev_property(UDEVADM_NVME, "ID_MODEL").unwrap(), "Samsung SSD 970 EVO Plus 1TB");
+        assert_eq!(
+            parse_udev_property(UDEVADM_NVME, "ID_SERIAL").unwrap(),
+            "Samsung_SSD_970_EVO_Plus_1TB_S4EWNX0R123456K"
+        );
+        assert!(parse_udev_property(UDEVADM_NVME, "ID_VENDOR").is_err());
+        assert!(parse_udev_property("", "ID_SERIAL_SHORT").is_err());
+        assert!(parse_udev_property("ID_SERIAL_SHORT S4EWNX0R123456K", "ID_SERIAL_SHORT").is_err());
+    }
+
+    #[test]
+    fn first_line_is_trimmed_and_empty_output_is_an_error() {
+        assert_eq!(parse_first_line("  Samsung SSD 970 EVO Plus 1TB  \nWDC WD10EZEX\n").unwrap(), "Samsung SSD 970 EVO Plus 1TB");
+        assert!(parse_first_line("").is_err());
+    }
+
+    // CPUID 签名、DMI 厂商/型号与 /sys/hypervisor/type 中的写法
+    #[test]
+    fn hypervisor_names_cover_signatures_and_dmi_strings() {
+        let cases = [
+            ("KVMKVMKVM", Some("KVM")),
+            ("QEMU Standard PC (Q35 + ICH9, 2009)", Some("QEMU")),
+            ("VMware, Inc. VMware7,1", Some("VMware")),
+            ("innotek GmbH VirtualBox", Some("VirtualBox")),
+            ("Microsoft Corporation Virtual Machine", Some("Hyper-V")),
+            ("Microsoft Hv", Some("Hyper-V")),
+            ("xen\n", Some("Xen")),
+            ("Parallels Software International Inc. Parallels Virtual Platform", Some("Parallels")),
+            ("Dell Inc. OptiPlex 7070", None),
+            ("", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(hypervisor_name(text), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn reg_query_value_follows_the_type() {
+        let output = "\r\nHKEY_LOCAL_MACHINE\\SOFTWARE\\Microsoft\\Cryptography\r\n    \
+                      MachineGuid    REG_SZ    6d1a3f2e-8b4c-4d5e-9f6a-7b8c9d0e1f2a\r\n\r\n";
+        assert_eq!(parse_reg_query_value(output, "MachineGuid").as_deref(), Some("6d1a3f2e-8b4c-4d5e-9f6a-7b8c9d0e1f2a"));
+        assert_eq!(parse_reg_query_value(output, "ProductId"), None);
+        assert_eq!(parse_reg_query_value("ERROR: The system was unable to find the specified registry key or value.\r\n", "MachineGuid"), None);
+    }
+
+    const MOUNTINFO_BTRFS: &str = include_str!("../tests/fixtures/linux/mountinfo_btrfs.txt");
+    const PROC_NET_ROUTE: &str = include_str!("../tests/fixtures/linux/proc_net_route.txt");
+    const IP_LINK: &str = include_str!("../tests/fixtures/linux/ip_link.txt");
+    const LSPCI_MM: &str = include_str!("../tests/fixtures/linux/lspci_mm.txt");
+    const NVIDIA_SMI: &str = include_str!("../tests/fixtures/linux/nvidia_smi.txt");
+    const TPM2_READPUBLIC: &str = include_str!("../tests/fixtures/linux/tpm2_readpublic.txt");
+
+    #[test]
+    fn access_denied_markers_are_case_insensitive() {
+        assert!(is_access_denied("ERROR:\r\nDescription = Access denied\r\n"));
+        assert!(is_access_denied("Get-CimInstance : Access is denied. (0x80070005)"));
+        assert!(is_access_denied("sudo: a password is required"));
+        assert!(!is_access_denied("sudo: dmidecode: command not found"));
+        assert!(!is_access_denied(""));
+    }
+
+    // btrfs 的 / 为匿名设备号，挂载源仍是分区
+    #[test]
+    fn mountinfo_gives_the_root_device_and_the_covering_mount() {
+        assert_eq!(parse_root_mount(MOUNTINFO_BTRFS), Some(("0:33".to_string(), "/dev/nvme0n1p3".to_string())));
+        assert_eq!(parse_root_mount("29 1 8:2 / / rw,relatime shared:1 - ext4 /dev/sda2 rw\n").unwrap().0, "8:2");
+        assert_eq!(parse_root_mount("22 28 0:21 / /proc rw - proc proc rw\n"), None);
+        assert_eq!(parse_root_mount(""), None);
+
+        let mount = |path| parse_mount_for(MOUNTINFO_BTRFS, path).map(|(fs, source, _)| (fs, source));
+        assert_eq!(mount("/home/media/photos"), Some(("ext4".to_string(), "/dev/sdb1".to_string())));
+        assert_eq!(mount("/home/user"), Some(("btrfs".to_string(), "/dev/nvme0n1p3".to_string())));
+        // /homework 不在 /home 之下
+        assert_eq!(parse_mount_for(MOUNTINFO_BTRFS, "/homework").unwrap().2, "rw,ssd,space_cache=v2,subvolid=256,subvol=/@");
+        assert_eq!(parse_mount_for("", "/"), None);
+    }
+
+    #[test]
+    fn nvme_namespaces_map_to_their_controller() {
+        assert_eq!(parse_nvme_controller("nvme0n1").as_deref(), Some("nvme0"));
+        assert_eq!(parse_nvme_controller("nvme12n3").as_deref(), Some("nvme12"));
+        assert_eq!(parse_nvme_controller("nvme0c1n1").as_deref(), Some("nvme0"));
+        assert_eq!(parse_nvme_controller("nvme0"), None);
+        assert_eq!(parse_nvme_controller("nvmen1"), None);
+        assert_eq!(parse_nvme_controller("sda"), None);
+    }
+
+    #[test]
+    fn vpd_page_serial_is_length_prefixed() {
+        let mut page = vec![0x00, 0x80, 0x00, 0x14];
+        page.extend_from_slice(b"        WD-WCC4N7KL1234");
+        assert_eq!(parse_vpd_serial(&page).as_deref(), Some("WD-WCC4N7KL1"));
+        page[3] = 0x17;
+        assert_eq!(parse_vpd_serial(&page).as_deref(), Some("WD-WCC4N7KL1234"));
+        // 长度超出实际数据时取到末尾
+        page[3] = 0xFF;
+        assert_eq!(parse_vpd_serial(&page).as_deref(), Some("WD-WCC4N7KL1234"));
+        assert_eq!(parse_vpd_serial(&[0x00, 0x83, 0x00, 0x04, b'A', b'B', b'C', b'D']), None);
+        assert_eq!(parse_vpd_serial(&[0x00, 0x80, 0x00, 0x04, 0, 0, 0, 0]), None);
+        assert_eq!(parse_vpd_serial(&[0x00, 0x80]), None);
+    }
+
+    #[test]
+    fn default_route_is_the_lowest_metric_up_route() {
+        assert_eq!(parse_default_route_interface(PROC_NET_ROUTE).as_deref(), Some("enp0s31f6"));
+        // 只有未启用的默认路由与普通路由时没有默认接口
+        let header = PROC_NET_ROUTE.lines().next().unwrap();
+        let without_defaults: String = PROC_NET_ROUTE.lines().filter(|line| !line.starts_with("enp0s31f6\t0000") && !line.starts_with("wlp3s0")).collect::<Vec<_>>().join("\n");
+        assert_eq!(parse_default_route_interface(&without_defaults), None);
+        assert_eq!(parse_default_route_interface(header), None);
+    }
+
+    #[test]
+    fn interface_ethers_follow_their_header_line() {
+        assert_eq!(
+            parse_interface_ethers(IP_LINK, "link/ether "),
+            [
+                ("enp0s31f6".to_string(), "8c:16:45:5a:6b:7c".to_string()),
+                ("wlp3s0".to_string(), "3c:a9:f4:10:20:30".to_string()),
+                ("veth1a2b3c4".to_string(), "76:3e:9d:00:11:22".to_string()),
+            ]
+        );
+        let ifconfig = "en0: flags=8863<UP,BROADCAST,SMART,RUNNING,SIMPLEX,MULTICAST> mtu 1500\n\
+                        \tether a4:83:e7:1b:2c:3d\n\tinet 192.168.1.20 netmask 0xffffff00\n\
+                        lo0: flags=8049<UP,LOOPBACK,RUNNING,MULTICAST> mtu 16384\n";
+        assert_eq!(parse_interface_ethers(ifconfig, "ether "), [("en0".to_string(), "a4:83:e7:1b:2c:3d".to_string())]);
+        assert!(parse_interface_ethers("", "ether ").is_empty());
+    }
+
+    #[test]
+    fn gpu_identity_from_lspci_nvidia_smi_and_sysfs() {
+        assert_eq!(
+            parse_lspci_mm(LSPCI_MM),
+            Some(("NVIDIA Corporation".to_string(), "TU116 [GeForce GTX 1660]".to_string()))
+        );
+        assert_eq!(parse_lspci_mm("\n"), None);
+        assert_eq!(parse_lspci_mm("01:00.0 \"VGA compatible controller\""), None);
+
+        let rows = parse_csv_rows(NVIDIA_SMI);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1], ["00000000:02:00.0", "NVIDIA RTX A2000", "GPU-1f2e3d4c-5b6a-7980-a1b2-c3d4e5f6a7b8"]);
+        assert!(parse_csv_rows("\n\n").is_empty());
+        // nvidia-smi 与 sysfs 的 PCI 地址去掉域号后相同
+        assert_eq!(pci_bus_address(&rows[0][0]), pci_bus_address("0000:01:00.0"));
+        assert_eq!(pci_bus_address("0000:3D:00.0"), "3d:00.0");
+
+        assert_eq!(gpu_vendor("Advanced Micro Devices, Inc. [AMD/ATI]"), "AMD");
+        assert_eq!(gpu_vendor("NVIDIA GeForce RTX 3080"), "NVIDIA");
+        assert_eq!(gpu_vendor("Apple (0x106b)"), "Apple");
+        // "amd" 只按整词匹配
+        assert_eq!(gpu_vendor("Matrox Electronics Systems Ltd. (0x102b)"), "Matrox Electronics Systems Ltd.");
+        assert_eq!(pci_vendor_name("0x10de\n"), "NVIDIA");
+        assert_eq!(pci_vendor_name("0x8086"), "Intel");
+        assert_eq!(pci_vendor_name("0x1A03"), "1a03");
+    }
+
+    #[test]
+    fn tpm2_name_is_the_sha256_of_the_public_area() {
+        assert_eq!(
+            parse_tpm2_name_sha256(TPM2_READPUBLIC).as_deref(),
+            Some("7a5e3d1f0c2b4a69788796a5b4c3d2e1f00112233445566778899aabbccddeef")
+        );
+        // 名称算法不是 SHA-256（SHA-1 为 0004）或长度不对时没有值
+        assert_eq!(parse_tpm2_name_sha256("name: 00047a5e3d1f0c2b4a69788796a5b4c3d2e1f0011\n"), None);
+        assert_eq!(parse_tpm2_name_sha256("name: 000b7a5e\n"), None);
+        assert_eq!(parse_tpm2_name_sha256(""), None);
+    }
+}
@@ -0,0 +1,128 @@
+// 采集进度：按可以提前并发执行的命令（见 HardwareInfo::probe_command_lines）报告，每条命令恰好一个
+// 结束事件（Finished、Failed 或 Skipped），done 最终等于 total；其余命令与文件读取很快，不单独报告
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use crate::error::HardwareError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressStatus {
+    Started,
+    Finished(Duration),
+    Failed { elapsed: Duration, error: String },
+    // 没有提前执行：按需采集时不需要，或未启用 parallel_probes（之后需要时照常执行，不再报告）
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgressEvent {
+    command: String,
+    status: ProgressStatus,
+    done: usize,
+    total: usize,
+}
+
+impl ProgressEvent {
+    pub fn new(command: &str, status: ProgressStatus, done: usize, total: usize) -> Self {
+        Self { command: command.to_string(), status, done, total }
+    }
+
+    // 完整命令行，与命令错误信息中的写法相同
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    pub fn status(&self) -> &ProgressStatus {
+        &self.status
+    }
+
+    // 包括本事件在内已结束的命令数
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.status != ProgressStatus::Started && self.done == self.total
+    }
+}
+
+// "[3/7] ioreg -rd1 -c IOPlatformExpertDevice: done in 0.4s"
+impl fmt::Display for ProgressEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}/{}] {}: ", self.done, self.total, self.command)?;
+        match &self.status {
+            ProgressStatus::Started => f.write_str("started"),
+            ProgressStatus::Finished(elapsed) => write!(f, "done in {:.1}s", elapsed.as_secs_f64()),
+            ProgressStatus::Failed { elapsed, error } => {
+                write!(f, "failed after {:.1}s: {}", elapsed.as_secs_f64(), error)
+            }
+            ProgressStatus::Skipped => f.write_str("skipped"),
+        }
+    }
+}
+
+// CollectOptions 中的回调：不影响采集结果，比较与 Debug 输出（SnapshotCache 以此区分采集选项）都忽略它
+#[derive(Clone, Default)]
+pub(crate) struct ProgressCallback(Option<Arc<ProgressFn>>);
+
+type ProgressFn = dyn Fn(&ProgressEvent) + Send + Sync;
+
+impl ProgressCallback {
+    pub(crate) fn new(callback: impl Fn(&ProgressEvent) + Send + Sync + 'static) -> Self {
+        Self(Some(Arc::new(callback)))
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+impl PartialEq for ProgressCallback {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for ProgressCallback {}
+
+// 一次采集的计数；在执行命令的线程上直接调用回调，回调应尽快返回
+pub(crate) struct ProgressTracker {
+    callback: ProgressCallback,
+    done: AtomicUsize,
+    total: usize,
+}
+
+impl ProgressTracker {
+    pub(crate) fn new(callback: &ProgressCallback, total: usize) -> Self {
+        Self { callback: callback.clone(), done: AtomicUsize::new(0), total }
+    }
+
+    pub(crate) fn started(&self, command: &str) {
+        self.emit(command, ProgressStatus::Started, self.done.load(Ordering::Relaxed));
+    }
+
+    pub(crate) fn finished<T>(&self, command: &str, elapsed: Duration, result: &Result<T, HardwareError>) {
+        let status = match result {
+            Ok(_) => ProgressStatus::Finished(elapsed),
+            Err(e) => ProgressStatus::Failed { elapsed, error: e.to_string() },
+        };
+        self.emit(command, status, self.done.fetch_add(1, Ordering::Relaxed) + 1);
+    }
+
+    pub(crate) fn skipped(&self, command: &str) {
+        self.emit(command, ProgressStatus::Skipped, self.done.fetch_add(1, Ordering::Relaxed) + 1);
+    }
+
+    fn emit(&self, command: &str, status: ProgressStatus, done: usize) {
+        if let Some(callback) = &self.callback.0 {
+            callback(&ProgressEvent::new(command, status, done, self.total));
+        }
+    }
+}
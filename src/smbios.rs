@@ -0,0 +1,421 @@
+// SMBIOS 结构表解析（DMTF DSP0134），直接从固件表读取主板、BIOS、内存等信息，不依赖 wmic/dmidecode
+// 只解析唯一码用到的少数字段，偏移量均来自规范；结构表只在 Windows 与 Linux 上读取，illumos 只用到机箱类型名称
+
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+use uuid::Uuid;
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+use crate::error::HardwareError;
+
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+const TYPE_BIOS: u8 = 0;
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+const TYPE_SYSTEM: u8 = 1;
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+const TYPE_BASEBOARD: u8 = 2;
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+const TYPE_CHASSIS: u8 = 3;
+#[cfg(any(test, target_os = "windows"))]
+const TYPE_PROCESSOR: u8 = 4;
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+const TYPE_MEMORY_DEVICE: u8 = 17;
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+const TYPE_END_OF_TABLE: u8 = 127;
+
+// 一个结构：格式化区（含 4 字节头）与其后的字符串池
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+pub(crate) struct Structure<'a> {
+    kind: u8,
+    formatted: &'a [u8],
+    strings: Vec<&'a [u8]>,
+}
+
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+impl<'a> Structure<'a> {
+    fn byte(&self, offset: usize) -> Option<u8> {
+        self.formatted.get(offset).copied()
+    }
+
+    fn bytes(&self, offset: usize, len: usize) -> Option<&'a [u8]> {
+        self.formatted.get(offset..offset + len)
+    }
+
+    fn word(&self, offset: usize) -> Option<u16> {
+        self.bytes(offset, 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    // 格式化区中的字符串编号从 1 开始，0 表示未提供；去掉首尾空白，空串视为未提供
+    fn string(&self, offset: usize) -> Option<String> {
+        let index = self.byte(offset)? as usize;
+        let raw = self.strings.get(index.checked_sub(1)?)?;
+        let value = String::from_utf8_lossy(raw).trim().to_string();
+        Some(value).filter(|value| !value.is_empty())
+    }
+}
+
+// 结构表及其规范版本（UUID 字节序与版本有关）
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+pub(crate) struct SmbiosTable {
+    major: u8,
+    minor: u8,
+    data: Vec<u8>,
+}
+
+#[cfg(any(test, target_os = "windows", target_os = "linux"))]
+impl SmbiosTable {
+    pub(crate) fn new(major: u8, minor: u8, data: Vec<u8>) -> Self {
+        Self { major, minor, data }
+    }
+
+    // GetSystemFirmwareTable('RSMB') 的 RawSMBIOSData：1 字节调用方式、主次版本、DMI 修订号、
+    // 4 字节表长度，之后是结构表
+    #[cfg(any(test, target_os = "windows"))]
+    pub(crate) fn from_raw_smbios_data(blob: &[u8]) -> Result<Self, HardwareError> {
+        let header = blob
+            .get(..8)
+            .ok_or_else(|| HardwareError::ParseError("RawSMBIOSData is shorter than its header".to_string()))?;
+        let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let data = blob
+            .get(8..8 + length)
+            .ok_or_else(|| HardwareError::ParseError("RawSMBIOSData is shorter than its table length".to_string()))?;
+        Ok(Self::new(header[1], header[2], data.to_vec()))
+    }
+
+    // Linux 的 smbios_entry_point：64 位入口 "_SM3_" 的版本在偏移 7、8，32 位入口 "_SM_" 在偏移 6、7，
+    // 旧式 "_DMI_" 入口只有偏移 14 处的 BCD 修订号
+    #[cfg(any(test, target_os = "linux"))]
+    pub(crate) fn from_entry_point(entry_point: &[u8], data: Vec<u8>) -> Result<Self, HardwareError> {
+        let version = if entry_point.starts_with(b"_SM3_") {
+            entry_point.get(7).zip(entry_point.get(8)).map(|(major, minor)| (*major, *minor))
+        } else if entry_point.starts_with(b"_SM_") {
+            entry_point.get(6).zip(entry_point.get(7)).map(|(major, minor)| (*major, *minor))
+        } else if entry_point.starts_with(b"_DMI_") {
+            entry_point.get(14).map(|revision| (revision >> 4, revision & 0x0F))
+        } else {
+            None
+        };
+        let (major, minor) =
+            version.ok_or_else(|| HardwareError::ParseError("unrecognized SMBIOS entry point".to_string()))?;
+        Ok(Self::new(major, minor, data))
+    }
+
+    // 依次遍历结构，遇到结束标记或截断的数据即停止
+    pub(crate) fn structures(&self) -> impl Iterator<Item = Structure<'_>> {
+        let data = &self.data[..];
+        let mut position = 0;
+        std::iter::from_fn(move || {
+            let kind = *data.get(position)?;
+            let length = *data.get(position + 1)? as usize;
+            if kind == TYPE_END_OF_TABLE || length < 4 {
+                return None;
+            }
+            let formatted = data.get(position..position + length)?;
+
+            // 字符串池以两个连续的 NUL 结束；没有字符串时格式化区之后紧跟两个 NUL
+            let pool_start = position + length;
+            let pool = data.get(pool_start..)?;
+            let pool_len = pool.windows(2).position(|pair| pair == [0, 0])?;
+            let strings = if pool_len == 0 {
+                Vec::new()
+            } else {
+                pool[..pool_len].split(|b| *b == 0).collect()
+            };
+            position = pool_start + pool_len + 2;
+            Some(Structure { kind, formatted, strings })
+        })
+    }
+
+    fn first(&self, kind: u8) -> Option<Structure<'_>> {
+        self.structures().find(|structure| structure.kind == kind)
+    }
+
+    pub(crate) fn bios_vendor(&self) -> Option<String> {
+        self.first(TYPE_BIOS)?.string(0x04)
+    }
+
+    #[cfg(any(test, target_os = "linux"))]
+    pub(crate) fn bios_version(&self) -> Option<String> {
+        self.first(TYPE_BIOS)?.string(0x05)
+    }
+
+    // 固件中的格式为 mm/dd/yyyy（旧版本为 mm/dd/yy）
+    pub(crate) fn bios_release_date(&self) -> Option<String> {
+        self.first(TYPE_BIOS)?.string(0x08)
+    }
+
+    pub(crate) fn baseboard_manufacturer(&self) -> Option<String> {
+        self.first(TYPE_BASEBOARD)?.string(0x04)
+    }
+
+    pub(crate) fn baseboard_product(&self) -> Option<String> {
+        self.first(TYPE_BASEBOARD)?.string(0x05)
+    }
+
+    pub(crate) fn baseboard_serial(&self) -> Option<String> {
+        self.first(TYPE_BASEBOARD)?.string(0x07)
+    }
+
+    pub(crate) fn baseboard_asset_tag(&self) -> Option<String> {
+        self.first(TYPE_BASEBOARD)?.string(0x08)
+    }
+
+    pub(crate) fn system_serial(&self) -> Option<String> {
+        self.first(TYPE_SYSTEM)?.string(0x07)
+    }
+
+    pub(crate) fn chassis_serial(&self) -> Option<String> {
+        self.first(TYPE_CHASSIS)?.string(0x07)
+    }
+
+    pub(crate) fn chassis_asset_tag(&self) -> Option<String> {
+        self.first(TYPE_CHASSIS)?.string(0x08)
+    }
+
+    // 最高位为机箱锁标志，低 7 位为类型编号
+    pub(crate) fn chassis_type(&self) -> Option<u8> {
+        self.first(TYPE_CHASSIS)?.byte(0x05).map(|kind| kind & 0x7F)
+    }
+
+    // 2.6 起前三段按小端存储，之前的版本按网络字节序；统一转换为系统工具显示的 RFC 4122 形式（小写）
+    pub(crate) fn system_uuid(&self) -> Option<String> {
+        let bytes: [u8; 16] = self.first(TYPE_SYSTEM)?.bytes(0x08, 16)?.try_into().ok()?;
+        let uuid = if (self.major, self.minor) >= (2, 6) {
+            Uuid::from_bytes_le(bytes)
+        } else {
+            Uuid::from_bytes(bytes)
+        };
+        Some(uuid.hyphenated().to_string())
+    }
+
+    // 第一个已安装处理器的 ProcessorID，与 Win32_Processor.ProcessorId 相同：EDX 在前、EAX 在后的十六进制
+    #[cfg(any(test, target_os = "windows"))]
+    pub(crate) fn processor_id(&self) -> Option<String> {
+        let processor = self.populated_processor()?;
+        let id = processor.bytes(0x08, 8)?;
+        let eax = u32::from_le_bytes([id[0], id[1], id[2], id[3]]);
+        let edx = u32::from_le_bytes([id[4], id[5], id[6], id[7]]);
+        Some(format!("{:08X}{:08X}", edx, eax))
+    }
+
+    #[cfg(any(test, target_os = "windows"))]
+    pub(crate) fn processor_version(&self) -> Option<String> {
+        self.populated_processor()?.string(0x10)
+    }
+
+    // Status 字节的第 6 位表示插槽上有处理器
+    #[cfg(any(test, target_os = "windows"))]
+    fn populated_processor(&self) -> Option<Structure<'_>> {
+        self.structures()
+            .filter(|structure| structure.kind == TYPE_PROCESSOR)
+            .find(|processor| processor.byte(0x18).is_none_or(|status| status & 0x40 != 0))
+    }
+
+    // 已安装内存条（Size 不为 0）的序列号，按表中顺序
+    pub(crate) fn memory_serials(&self) -> Vec<String> {
+        self.structures()
+            .filter(|structure| structure.kind == TYPE_MEMORY_DEVICE)
+            .filter(|device| device.word(0x0C).is_some_and(|size| size != 0))
+            .filter_map(|device| device.string(0x18))
+            .collect()
+    }
+}
+
+// mm/dd/yyyy 转换为 wmic 输出的 DMTF 格式（yyyymmdd000000.000000+000）
+#[cfg(any(test, target_os = "windows"))]
+pub(crate) fn dmtf_date(date: &str) -> Option<String> {
+    let mut parts = date.trim().split('/');
+    let (month, day, year) = (parts.next()?, parts.next()?, parts.next()?);
+    let year = match year.len() {
+        4 => year.to_string(),
+        2 => format!("19{}", year),
+        _ => return None,
+    };
+    if month.len() != 2 || day.len() != 2 || !format!("{}{}{}", year, month, day).bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(format!("{}{}{}000000.000000+000", year, month, day))
+}
+
+// 机箱类型编号（规范 7.4.1）对应的名称；接受 "3"、wmic 的 "{3}" 与 CIM JSON 的 "[3]"，多个值时取第一个
+pub(crate) fn chassis_type_name(raw: &str) -> Option<&'static str> {
+    let code: u8 = raw.split(|c: char| !c.is_ascii_digit()).find(|part| !part.is_empty())?.parse().ok()?;
+    let name = match code {
+        0x01 => "Other",
+        0x02 => "Unknown",
+        0x03 => "Desktop",
+        0x04 => "Low Profile Desktop",
+        0x05 => "Pizza Box",
+        0x06 => "Mini Tower",
+        0x07 => "Tower",
+        0x08 => "Portable",
+        0x09 => "Laptop",
+        0x0A => "Notebook",
+        0x0B => "Hand Held",
+        0x0C => "Docking Station",
+        0x0D => "All in One",
+        0x0E => "Sub Notebook",
+        0x0F => "Space-saving",
+        0x10 => "Lunch Box",
+        0x11 => "Main Server Chassis",
+        0x12 => "Expansion Chassis",
+        0x13 => "SubChassis",
+        0x14 => "Bus Expansion Chassis",
+        0x15 => "Peripheral Chassis",
+        0x16 => "RAID Chassis",
+        0x17 => "Rack Mount Chassis",
+        0x18 => "Sealed-case PC",
+        0x19 => "Multi-system Chassis",
+        0x1A => "Compact PCI",
+        0x1B => "Advanced TCA",
+        0x1C => "Blade",
+        0x1D => "Blade Enclosure",
+        0x1E => "Tablet",
+        0x1F => "Convertible",
+        0x20 => "Detachable",
+        0x21 => "IoT Gateway",
+        0x22 => "Embedded PC",
+        0x23 => "Mini PC",
+        0x24 => "Stick PC",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 一个 Type 1（System Information）结构加表尾，UUID 字段为 44 45 4C 4C 58 00 10 4A 80 38 ...
+    const SYSTEM: &[u8] = include_bytes!("../tests/fixtures/smbios/system_type1.bin");
+
+    #[test]
+    fn system_uuid_is_little_endian_from_2_6() {
+        let table = SmbiosTable::new(2, 6, SYSTEM.to_vec());
+        assert_eq!(table.system_uuid().as_deref(), Some("4c4c4544-0058-4a10-8038-b7c04f513532"));
+        assert_eq!(SmbiosTable::new(3, 4, SYSTEM.to_vec()).system_uuid(), table.system_uuid());
+    }
+
+    #[test]
+    fn system_uuid_is_network_order_before_2_6() {
+        let table = SmbiosTable::new(2, 5, SYSTEM.to_vec());
+        assert_eq!(table.system_uuid().as_deref(), Some("44454c4c-5800-104a-8038-b7c04f513532"));
+    }
+
+    #[test]
+    fn byte_orders_are_each_others_alternate() {
+        let old = SmbiosTable::new(2, 5, SYSTEM.to_vec()).system_uuid().unwrap();
+        let new = SmbiosTable::new(2, 6, SYSTEM.to_vec()).system_uuid().unwrap();
+        assert_eq!(crate::normalize::swap_uuid_byte_order(&old), Some(new.clone()));
+        assert_eq!(crate::normalize::swap_uuid_byte_order(&new), Some(old));
+    }
+
+    #[test]
+    fn system_serial_reads_the_string_pool() {
+        let table = SmbiosTable::new(2, 6, SYSTEM.to_vec());
+        assert_eq!(table.system_serial().as_deref(), Some("7XJ8Q52"));
+    }
+
+    // 一台 Dell OptiPlex 7070 的结构表（SMBIOS 3.2）：BIOS、系统、主板、机箱，一个空插槽和一个已安装的处理器，
+    // 三个内存插槽中 DIMM2 为空；主板资产标签只有空白
+    const OPTIPLEX_TABLE: &[u8] = include_bytes!("../tests/fixtures/smbios/dell_optiplex_7070_dmi.bin");
+    const OPTIPLEX_ENTRY_POINT: &[u8] = include_bytes!("../tests/fixtures/smbios/dell_optiplex_7070_entry_point.bin");
+
+    fn optiplex() -> SmbiosTable {
+        SmbiosTable::from_entry_point(OPTIPLEX_ENTRY_POINT, OPTIPLEX_TABLE.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn captured_table_fields() {
+        let table = optiplex();
+        assert_eq!(table.bios_vendor().as_deref(), Some("Dell Inc."));
+        assert_eq!(table.bios_version().as_deref(), Some("1.21.0"));
+        assert_eq!(table.bios_release_date().as_deref(), Some("06/13/2023"));
+        assert_eq!(table.baseboard_manufacturer().as_deref(), Some("Dell Inc."));
+        assert_eq!(table.baseboard_product().as_deref(), Some("0YNVJG"));
+        assert_eq!(table.baseboard_serial().as_deref(), Some("/7XJ8Q52/CN1296385B00KL/"));
+        assert_eq!(table.baseboard_asset_tag(), None);
+        assert_eq!(table.system_serial().as_deref(), Some("7XJ8Q52"));
+        assert_eq!(table.system_uuid().as_deref(), Some("4c4c4544-0058-4a10-8038-b7c04f513532"));
+        assert_eq!(table.chassis_serial().as_deref(), Some("7XJ8Q52"));
+        // 资产标签的字符串编号为 0，即未提供
+        assert_eq!(table.chassis_asset_tag(), None);
+        // 机箱锁标志不属于类型编号
+        assert_eq!(table.chassis_type(), Some(0x03));
+    }
+
+    #[test]
+    fn captured_table_skips_empty_sockets_and_slots() {
+        let table = optiplex();
+        assert_eq!(table.processor_id().as_deref(), Some("BFEBFBFF000906ED"));
+        assert_eq!(table.processor_version().as_deref(), Some("Intel(R) Core(TM) i7-9700 CPU @ 3.00GHz"));
+        assert_eq!(table.memory_serials(), ["41A2C0F3", "41A2C1D8"]);
+        let kinds: Vec<u8> = table.structures().map(|structure| structure.kind).collect();
+        assert_eq!(kinds, [TYPE_BIOS, TYPE_SYSTEM, TYPE_BASEBOARD, TYPE_CHASSIS, 4, 4, 17, 17, 17]);
+    }
+
+    #[test]
+    fn entry_point_and_raw_smbios_data_read_the_same_table() {
+        let mut raw = vec![0, 3, 2, 0];
+        raw.extend_from_slice(&(OPTIPLEX_TABLE.len() as u32).to_le_bytes());
+        raw.extend_from_slice(OPTIPLEX_TABLE);
+        let windows = SmbiosTable::from_raw_smbios_data(&raw).unwrap();
+        let linux = optiplex();
+        assert_eq!((windows.major, windows.minor), (linux.major, linux.minor));
+        assert_eq!(windows.system_uuid(), linux.system_uuid());
+        assert_eq!(windows.memory_serials(), linux.memory_serials());
+        assert_eq!(dmtf_date(&linux.bios_release_date().unwrap()).as_deref(), Some("20230613000000.000000+000"));
+    }
+
+
+    #[test]
+    fn entry_point_version_selects_the_uuid_byte_order() {
+        let table = |entry_point: &[u8]| SmbiosTable::from_entry_point(entry_point, OPTIPLEX_TABLE.to_vec()).unwrap();
+        // 旧式 _DMI_ 入口的 BCD 修订号 0x25 即 2.5，按网络字节序
+        let mut dmi = b"_DMI_".to_vec();
+        dmi.extend_from_slice(&[0; 9]);
+        dmi.push(0x25);
+        let old = table(&dmi).system_uuid().unwrap();
+        assert_eq!(old, "44454c4c-5800-104a-8038-b7c04f513532");
+        let mut sm = b"_SM_".to_vec();
+        sm.extend_from_slice(&[0, 0x1F, 2, 8]);
+        let new = table(&sm).system_uuid().unwrap();
+        assert_eq!(new, optiplex().system_uuid().unwrap());
+        // 两种读法互为另一种字节序，固件声明的版本不可靠时可以互相换算
+        assert_eq!(crate::normalize::swap_uuid_byte_order(&old), Some(new));
+        assert!(SmbiosTable::from_entry_point(b"_XYZ_", OPTIPLEX_TABLE.to_vec()).is_err());
+        assert!(SmbiosTable::from_entry_point(b"_SM3_\0\x18", OPTIPLEX_TABLE.to_vec()).is_err());
+    }
+
+    #[test]
+    fn truncated_tables_keep_the_complete_structures() {
+        let full = optiplex().structures().count();
+        let mut previous = 0;
+        for len in 0..=OPTIPLEX_TABLE.len() {
+            let count = SmbiosTable::new(3, 2, OPTIPLEX_TABLE[..len].to_vec()).structures().count();
+            assert!(count >= previous && count <= full, "{} structures from {} bytes", count, len);
+            previous = count;
+        }
+        assert_eq!(previous, full);
+
+        // 截断在 DIMM3 中间：只剩前两个内存插槽
+        // DIMM3 的字符串池紧跟在 0x28 字节的格式化区之后
+        let dimm3 = OPTIPLEX_TABLE.windows(5).position(|window| window == b"DIMM3").unwrap() - 0x28;
+        let table = SmbiosTable::new(3, 2, OPTIPLEX_TABLE[..dimm3 + 20].to_vec());
+        assert_eq!(table.memory_serials(), ["41A2C0F3"]);
+        // 截断在系统结构（0x35 起，字符串池 0x50 起）的字符串池中：之前的 BIOS 结构照常读取
+        let table = SmbiosTable::new(3, 2, OPTIPLEX_TABLE[..0x60].to_vec());
+        assert_eq!(table.bios_version().as_deref(), Some("1.21.0"));
+        assert_eq!(table.system_uuid(), None);
+    }
+
+    #[test]
+    fn malformed_headers_are_parse_errors() {
+        assert!(matches!(SmbiosTable::from_raw_smbios_data(&[0, 3, 2]), Err(HardwareError::ParseError(_))));
+        let mut raw = vec![0, 3, 2, 0];
+        raw.extend_from_slice(&(OPTIPLEX_TABLE.len() as u32 + 1).to_le_bytes());
+        raw.extend_from_slice(OPTIPLEX_TABLE);
+        assert!(matches!(SmbiosTable::from_raw_smbios_data(&raw), Err(HardwareError::ParseError(_))));
+        // 长度小于 4 的结构头无法继续遍历
+        assert_eq!(SmbiosTable::new(3, 2, vec![TYPE_BIOS, 2, 0, 0, 0, 0]).structures().count(), 0);
+    }
+
+}
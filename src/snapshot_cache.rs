@@ -0,0 +1,214 @@
+// 采集结果的磁盘缓存：每次构建都调用命令行工具时，TTL 内复用上次采集的 HardwareInfo
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use log::{debug, warn};
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+use crate::error::HardwareError;
+use crate::fingerprint::FingerprintVersion;
+use crate::hardware_info::{CollectOptions, HardwareInfo};
+use crate::state_file;
+
+// 缓存文件内容：写入时的工具版本与指纹方案不一致时整个文件作废；写入时 I 为 &HardwareInfo
+#[derive(Serialize, Deserialize)]
+struct Snapshot<I> {
+    tool_version: String,
+    scheme: String,
+    // 采集选项的摘要，见 SnapshotCache::with_options；早期版本写入的文件没有此字段
+    #[serde(default)]
+    options: String,
+    // 写入时间，RFC 3339 UTC（与 Export 的 collected_at 相同）
+    #[serde(with = "crate::export::rfc3339")]
+    created_at: SystemTime,
+    info: I,
+}
+
+// 读取不到、已损坏、已过期、版本或采集选项不一致的缓存都按未命中处理，由调用方重新采集
+#[derive(Debug, Clone)]
+pub struct SnapshotCache {
+    path: PathBuf,
+    ttl: Duration,
+    scheme: FingerprintVersion,
+    options: String,
+}
+
+impl SnapshotCache {
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+    // 缓存文件位于当前用户的缓存目录：Windows 为 %LOCALAPPDATA%，macOS 为 ~/Library/Caches，
+    // 其他系统为 $XDG_CACHE_HOME 或 ~/.cache；找不到目录时为 None
+    pub fn new(scheme: FingerprintVersion) -> Option<Self> {
+        Self::default_path().map(|path| Self::with_path(path, scheme))
+    }
+
+    pub fn with_path(path: impl Into<PathBuf>, scheme: FingerprintVersion) -> Self {
+        Self { path: path.into(), ttl: Self::DEFAULT_TTL, scheme, options: Self::options_key(&CollectOptions::default()) }
+    }
+
+    // 缓存只对以影响采集结果的相同选项（按需采集的组件、WSL 宿主机身份、ANDROID_ID、追加的占位值规则）
+    // 采集的结果命中；超时、并发执行与进度回调不影响结果，不同也命中
+    pub fn with_options(mut self, options: &CollectOptions) -> Self {
+        self.options = Self::options_key(options);
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn load(&self) -> Option<HardwareInfo> {
+        let contents = state_file::read(&self.path).ok()?;
+        let snapshot: Snapshot<HardwareInfo> = match serde_json::from_str(&contents) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                warn!("Ignoring corrupted snapshot cache {}: {}", self.path.display(), e);
+                return None;
+            }
+        };
+        if snapshot.options != self.options {
+            debug!("Snapshot cache was collected with different options, ignoring it");
+            return None;
+        }
+        if snapshot.tool_version != env!("CARGO_PKG_VERSION") || snapshot.scheme != self.scheme.name() {
+            debug!(
+                "Snapshot cache was written by {} ({}), ignoring it",
+                snapshot.tool_version, snapshot.scheme
+            );
+            return None;
+        }
+        // 写入时间在未来（系统时间被回拨）时同样视为过期
+        let age = SystemTime::now().duration_since(snapshot.created_at).ok()?;
+        if age > self.ttl {
+            debug!("Snapshot cache is {}s old, collecting again", age.as_secs());
+            return None;
+        }
+        Some(snapshot.info)
+    }
+
+    // 先写入同目录的临时文件再改名，中途失败不会留下半个缓存文件；类 Unix 系统上文件权限为 0600、新建目录为 0700
+    pub fn store(&self, info: &HardwareInfo) -> Result<(), HardwareError> {
+        let snapshot = Snapshot {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            scheme: self.scheme.name().to_string(),
+            options: self.options.clone(),
+            created_at: SystemTime::now(),
+            info,
+        };
+        let json = serde_json::to_vec(&snapshot)
+            .map_err(|e| HardwareError::ParseError(format!("failed to serialize the snapshot: {}", e)))?;
+        state_file::write_atomic(&self.path, &json)?;
+        Ok(())
+    }
+
+    // 逐项列出影响采集结果的选项；其中可能含有 ANDROID_ID，文件中只保存其 SHA-256
+    fn options_key(options: &CollectOptions) -> String {
+        let components = match options.components() {
+            Some(components) => components.iter().map(|component| component.name()).collect::<Vec<_>>().join(","),
+            None => "all".to_string(),
+        };
+        let rules: Vec<String> = options.extra_placeholder_patterns().iter().map(ToString::to_string).collect();
+        let key = format!(
+            "components={}\nwsl_host_identity={}\nandroid_id={}\nplaceholder_rules={}",
+            components,
+            options.wsl_host_identity(),
+            options.android_id().unwrap_or(""),
+            rules.join("\n")
+        );
+        hex::encode(Sha256::digest(key))
+    }
+
+    fn default_path() -> Option<PathBuf> {
+        #[cfg(target_os = "windows")]
+        let base = std::env::var_os("LOCALAPPDATA").map(PathBuf::from);
+
+        #[cfg(target_os = "macos")]
+        let base = std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Caches"));
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")));
+
+        base.map(|base| base.join("hardware_id").join("snapshot.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::denylist::PlaceholderRule;
+    use crate::fingerprint::ComponentSet;
+    use crate::hardware_info;
+
+    fn scratch_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("hardware_id-snapshot-{}", uuid::Uuid::new_v4().simple()))
+            .join("snapshot.json")
+    }
+
+    #[test]
+    fn snapshot_round_trips_with_the_same_options() {
+        let path = scratch_path();
+        let options = CollectOptions::new().with_timeout(Duration::from_secs(2));
+        let cache = SnapshotCache::with_path(&path, FingerprintVersion::V2).with_options(&options);
+        let info = hardware_info::tests::sample();
+        cache.store(&info).unwrap();
+        let loaded = cache.load().unwrap();
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&info).unwrap());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn snapshot_collected_with_other_options_is_a_miss() {
+        let path = scratch_path();
+        let cache = SnapshotCache::with_path(&path, FingerprintVersion::V2);
+        cache.store(&hardware_info::tests::sample()).unwrap();
+        let other = [
+            CollectOptions::new().with_wsl_host_identity(true),
+            CollectOptions::new().with_components(ComponentSet::default()),
+            CollectOptions::new().with_android_id("9774d56d682e549c"),
+            CollectOptions::new().with_extra_placeholder_patterns(vec![PlaceholderRule::exact("N/A")]),
+        ];
+        for options in &other {
+            assert!(cache.clone().with_options(options).load().is_none(), "{options:?}");
+        }
+        // 不影响采集结果的选项不同仍然命中
+        let same = [
+            CollectOptions::new().with_timeout(Duration::from_secs(30)),
+            CollectOptions::new().with_parallel_probes(false),
+            CollectOptions::new().on_progress(|_| {}),
+        ];
+        for options in &same {
+            assert!(cache.clone().with_options(options).load().is_some(), "{options:?}");
+        }
+        assert!(SnapshotCache::with_path(&path, FingerprintVersion::V1).load().is_none());
+        assert!(cache.load().is_some());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    // 写入时间为 RFC 3339；晚于当前时间（系统时间被回拨）的缓存按过期处理
+    #[test]
+    fn created_at_is_rfc3339_and_future_snapshots_are_stale() {
+        let path = scratch_path();
+        let cache = SnapshotCache::with_path(&path, FingerprintVersion::V2);
+        cache.store(&hardware_info::tests::sample()).unwrap();
+        let mut json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let created_at = json["created_at"].as_str().unwrap().to_string();
+        assert!(humantime::parse_rfc3339(&created_at).is_ok(), "{}", created_at);
+        assert!(cache.load().is_some());
+
+        json["created_at"] = "2999-01-01T00:00:00Z".into();
+        std::fs::write(&path, json.to_string()).unwrap();
+        assert!(cache.load().is_none());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
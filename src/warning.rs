@@ -0,0 +1,307 @@
+// 采集结果的提示：每种提示有固定的 ID（WarningId::name，属于对外接口，只增不改），
+// 命令行输出到 stderr，并写入 collect --format 的 warnings；
+// 在本机确认过的提示（Acknowledgements，hardware_id warn ack <ID>）不再输出到 stderr，但仍写入 warnings
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use crate::error::HardwareError;
+use crate::hardware_info::{CollectionMode, CollectionReport, HardwareInfo};
+use crate::state_file;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningId {
+    // 在容器中运行，硬件字段属于宿主机或缺失
+    Container,
+    // 在 WSL 中运行，DMI 为虚拟值且不含网卡 MAC
+    Wsl,
+    // 从外置磁盘启动，磁盘字段随该磁盘而变
+    ExternalBootDisk,
+    // live 启动，磁盘字段来自启动介质
+    LiveBoot,
+    // 有字段未能读取（CollectionReport::errors）
+    IncompleteCollection,
+    // diff 读取的快照的时间晚于本机时间（export::future_skew）
+    FutureSnapshot,
+    // 从离线镜像中读取（hardware_id offline），没有固件与硬件设备的信息
+    OfflineImage,
+}
+
+impl WarningId {
+    pub const ALL: [WarningId; 7] = [
+        WarningId::Container,
+        WarningId::Wsl,
+        WarningId::ExternalBootDisk,
+        WarningId::LiveBoot,
+        WarningId::IncompleteCollection,
+        WarningId::FutureSnapshot,
+        WarningId::OfflineImage,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WarningId::Container => "container",
+            WarningId::Wsl => "wsl",
+            WarningId::ExternalBootDisk => "external_boot_disk",
+            WarningId::LiveBoot => "live_boot",
+            WarningId::IncompleteCollection => "incomplete_collection",
+            WarningId::FutureSnapshot => "future_snapshot",
+            WarningId::OfflineImage => "offline_image",
+        }
+    }
+}
+
+impl fmt::Display for WarningId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// 按 name() 解析，忽略大小写，"-" 与 "_" 等同；未知名称的错误信息列出全部 ID
+impl FromStr for WarningId {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let name = value.trim().to_ascii_lowercase().replace('-', "_");
+        WarningId::ALL.iter().copied().find(|id| id.name() == name).ok_or_else(|| {
+            let names: Vec<&str> = WarningId::ALL.iter().map(WarningId::name).collect();
+            format!("unknown warning '{}' (valid warnings: {})", value.trim(), names.join(", "))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Warning {
+    id: WarningId,
+    message: String,
+}
+
+impl Warning {
+    pub fn new(id: WarningId, message: impl Into<String>) -> Self {
+        Self { id, message: message.into() }
+    }
+
+    pub fn id(&self) -> WarningId {
+        self.id
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+// 采集结果对应的提示，按 WarningId 的顺序；读取缓存时没有采集报告，依赖报告的提示随之省略
+pub fn warnings(info: &HardwareInfo, report: Option<&CollectionReport>) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    if info.environment().is_container() {
+        warnings.push(Warning::new(
+            WarningId::Container,
+            format!("running inside a {}; the hardware fields belong to the host or are missing", info.environment()),
+        ));
+    }
+    if let Some(version) = info.environment().wsl_version() {
+        warnings.push(Warning::new(
+            WarningId::Wsl,
+            format!(
+                "running under {}; the DMI data is virtual and the network MAC is excluded, \
+                 so codes may collide across machines (see --wsl-host-identity)",
+                version
+            ),
+        ));
+    }
+    if report.is_some_and(CollectionReport::is_external_boot_disk) {
+        warnings.push(Warning::new(
+            WarningId::ExternalBootDisk,
+            "booted from an external disk; the disk fields follow that disk, not this machine",
+        ));
+    }
+    if info.live_boot() {
+        warnings.push(Warning::new(
+            WarningId::LiveBoot,
+            "live boot session; the disk fields describe the boot medium and are excluded from the unique code",
+        ));
+    }
+    // 离线镜像中本就没有的字段由 offline_image 说明，不计入
+    let offline = info.collection_mode() == CollectionMode::Offline;
+    let missing = report.map_or(0, |report| {
+        let expected = |field: &str| offline && HardwareInfo::OFFLINE_UNSUPPORTED_FIELDS.contains(&field);
+        report.errors().iter().filter(|(field, _)| !expected(field)).count()
+    });
+    if missing > 0 {
+        warnings.push(Warning::new(
+            WarningId::IncompleteCollection,
+            format!("{} hardware field(s) could not be collected; --verbose lists them with the reasons", missing),
+        ));
+    }
+    if offline {
+        warnings.push(Warning::new(
+            WarningId::OfflineImage,
+            "read from an offline image; only the machine-id, the MAC addresses and the root filesystem UUID \
+             are available, so the code differs from the one collected on the running machine",
+        ));
+    }
+    warnings
+}
+
+// 确认记录文件中的一项：确认的提示与确认时间（RFC 3339 UTC）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Acknowledgement {
+    id: WarningId,
+    #[serde(with = "crate::export::rfc3339")]
+    acknowledged_at: SystemTime,
+}
+
+impl Acknowledgement {
+    pub fn id(&self) -> WarningId {
+        self.id
+    }
+
+    pub fn acknowledged_at(&self) -> SystemTime {
+        self.acknowledged_at
+    }
+}
+
+// 本机已确认的提示；文件不存在或已损坏时视为没有确认过任何提示
+#[derive(Debug, Clone)]
+pub struct Acknowledgements {
+    path: PathBuf,
+}
+
+impl Acknowledgements {
+    // 记录文件位于当前用户的状态目录：Windows 为 %LOCALAPPDATA%，macOS 为 ~/Library/Application Support，
+    // 其他系统为 $XDG_STATE_HOME 或 ~/.local/state；找不到目录时为 None
+    pub fn new() -> Option<Self> {
+        Self::default_path().map(Self::with_path)
+    }
+
+    pub fn with_path(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> Vec<Acknowledgement> {
+        let Ok(contents) = state_file::read(&self.path) else {
+            return Vec::new();
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Ignoring corrupted warning acknowledgements {}: {}", self.path.display(), e);
+            Vec::new()
+        })
+    }
+
+    pub fn is_acknowledged(&self, id: WarningId) -> bool {
+        self.load().iter().any(|acknowledgement| acknowledgement.id == id)
+    }
+
+    // 已确认过的提示保留最初的确认时间；返回记录中的确认时间
+    pub fn acknowledge(&self, id: WarningId) -> Result<SystemTime, HardwareError> {
+        let mut acknowledgements = self.load();
+        if let Some(existing) = acknowledgements.iter().find(|acknowledgement| acknowledgement.id == id) {
+            return Ok(existing.acknowledged_at);
+        }
+        // 只保留到秒，与写入的 RFC 3339 一致
+        let now = UNIX_EPOCH + Duration::from_secs(SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs()));
+        acknowledgements.push(Acknowledgement { id, acknowledged_at: now });
+        acknowledgements.sort_by_key(|acknowledgement| acknowledgement.id);
+        let json = serde_json::to_vec_pretty(&acknowledgements)
+            .map_err(|e| HardwareError::ParseError(format!("failed to serialize the acknowledgements: {}", e)))?;
+        state_file::write_atomic(&self.path, &json)?;
+        Ok(now)
+    }
+
+    fn default_path() -> Option<PathBuf> {
+        #[cfg(target_os = "windows")]
+        let base = std::env::var_os("LOCALAPPDATA").map(PathBuf::from);
+
+        #[cfg(target_os = "macos")]
+        let base = std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"));
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let base = std::env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")));
+
+        base.map(|base| base.join("hardware_id").join("acknowledged-warnings.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware_info::tests::{sample, sample_with};
+    use uuid::Uuid;
+
+    fn scratch_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("hardware_id-warnings-{}", Uuid::new_v4().simple()))
+            .join("acknowledged-warnings.json")
+    }
+
+    // ID 写入 JSON 与确认记录，改名会使已有的记录失效
+    #[test]
+    fn ids_are_stable() {
+        let names: Vec<&str> = WarningId::ALL.iter().map(WarningId::name).collect();
+        assert_eq!(
+            names,
+            [
+                "container",
+                "wsl",
+                "external_boot_disk",
+                "live_boot",
+                "incomplete_collection",
+                "future_snapshot",
+                "offline_image",
+            ]
+        );
+        for id in WarningId::ALL {
+            assert_eq!(serde_json::to_value(id).unwrap(), id.name());
+            assert_eq!(id.name().parse::<WarningId>(), Ok(id));
+        }
+        assert_eq!("Live-Boot".parse::<WarningId>(), Ok(WarningId::LiveBoot));
+        assert!("nonsense".parse::<WarningId>().unwrap_err().contains("container"));
+    }
+
+    #[test]
+    fn warnings_follow_the_collected_information() {
+        assert_eq!(warnings(&sample(), None), []);
+        let live = sample_with(|value| value["live_boot"] = true.into());
+        let ids: Vec<WarningId> = warnings(&live, Some(&CollectionReport::default())).iter().map(Warning::id).collect();
+        assert_eq!(ids, [WarningId::LiveBoot]);
+    }
+
+    #[test]
+    fn acknowledgements_persist() {
+        let path = scratch_path();
+        let acknowledgements = Acknowledgements::with_path(&path);
+        assert!(acknowledgements.load().is_empty());
+        let at = acknowledgements.acknowledge(WarningId::LiveBoot).unwrap();
+        acknowledgements.acknowledge(WarningId::Container).unwrap();
+
+        // 另一个实例（下一次运行）读到同样的记录，按 ID 排序；再次确认不改变时间
+        let reopened = Acknowledgements::with_path(&path);
+        assert!(reopened.is_acknowledged(WarningId::LiveBoot) && !reopened.is_acknowledged(WarningId::Wsl));
+        let ids: Vec<WarningId> = reopened.load().iter().map(Acknowledgement::id).collect();
+        assert_eq!(ids, [WarningId::Container, WarningId::LiveBoot]);
+        assert_eq!(reopened.load()[1].acknowledged_at(), at);
+        assert_eq!(reopened.acknowledge(WarningId::LiveBoot).unwrap(), at);
+        assert_eq!(reopened.load().len(), 2);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains(&format!("\"{}\"", humantime::format_rfc3339_seconds(at))), "{}", contents);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(reopened.load().is_empty());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,58 @@
+// hardware_id_agent 与 hardware_id 的一致性，以及 agent 不依赖命令行程序专用的依赖
+#![cfg(feature = "cli")]
+use std::process::Output;
+use assert_cmd::Command;
+
+fn run(binary: &str, args: &[&str]) -> Output {
+    Command::cargo_bin(binary).unwrap().args(args).output().unwrap()
+}
+
+// 当前机器上两者的唯一码（或无法生成时的退出码）相同；容器等环境中两者以同样的退出码失败
+#[test]
+fn agent_prints_the_same_code_as_the_cli() {
+    let (cli, agent) = (run("hardware_id", &["hash"]), run("hardware_id_agent", &[]));
+    assert_eq!(agent.status.code(), cli.status.code(), "{}", String::from_utf8_lossy(&agent.stderr));
+    assert_eq!(String::from_utf8_lossy(&agent.stdout), String::from_utf8_lossy(&cli.stdout));
+}
+
+#[test]
+fn agent_verify_agrees_with_the_cli() {
+    let code = String::from_utf8_lossy(&run("hardware_id", &["hash"]).stdout).trim().to_string();
+    for expected in [code.as_str(), "0000-0000-0000-0000"] {
+        let (cli, agent) = (run("hardware_id", &["verify", expected]), run("hardware_id_agent", &["verify", expected]));
+        assert_eq!(agent.status.code(), cli.status.code(), "{}", String::from_utf8_lossy(&agent.stderr));
+        assert_eq!(String::from_utf8_lossy(&agent.stdout), String::from_utf8_lossy(&cli.stdout));
+    }
+}
+
+#[test]
+fn agent_rejects_unknown_arguments() {
+    Command::cargo_bin("hardware_id_agent").unwrap().arg("--fields").assert().code(2);
+    Command::cargo_bin("hardware_id_agent").unwrap().arg("verify").assert().code(2);
+}
+
+// 去掉默认的 cli 特性后（agent 的构建方式），依赖树中不再有 clap
+#[test]
+fn agent_build_drops_the_cli_dependencies() {
+    let tree = |features: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO"))
+            .args(["tree", "--offline", "-e", "normal", "--prefix", "none"])
+            .args(features)
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let has_clap = |tree: &str| tree.lines().any(|line| line.starts_with("clap "));
+    assert!(has_clap(&tree(&[])));
+    assert!(!has_clap(&tree(&["--no-default-features"])));
+}
+
+// 体积回归检查：agent 不链接 clap 与各子命令的代码，应明显小于 hardware_id
+#[test]
+fn agent_binary_is_smaller_than_the_cli() {
+    let size = |path: &str| std::fs::metadata(path).unwrap().len();
+    let (agent, cli) = (size(env!("CARGO_BIN_EXE_hardware_id_agent")), size(env!("CARGO_BIN_EXE_hardware_id")));
+    assert!(agent * 10 < cli * 9, "agent {} bytes, hardware_id {} bytes", agent, cli);
+}
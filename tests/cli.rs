@@ -0,0 +1,294 @@
+// hardware_id 各子命令的输出与退出码：0 成功/一致，1 verify/diff/conformance check 不一致，2 参数错误，3–5 采集错误。
+// 唯一码能否生成取决于运行的机器（容器中为 5，缺少主板信息时为 3），相关断言对两种结果都成立
+#![cfg(feature = "cli")]
+use std::path::PathBuf;
+use std::process::Output;
+use assert_cmd::Command;
+use hardware_id::{Export, ExportFormat};
+use uuid::Uuid;
+
+// 在容器中也生成唯一码，主板信息缺失时改用机器身份，使大多数环境下 hash 都能成功
+const ANYWHERE: &[&str] = &["--allow-container", "--fallback"];
+
+fn run(args: &[&str]) -> Output {
+    Command::cargo_bin("hardware_id").unwrap().args(args).output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+// 测试结束时删除的快照文件
+struct Snapshot(PathBuf);
+
+impl Snapshot {
+    fn write(contents: &str, extension: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("hardware_id-cli-{}.{}", Uuid::new_v4().simple(), extension));
+        std::fs::write(&path, contents).unwrap();
+        Snapshot(path)
+    }
+
+    fn path(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn collect(format: &str) -> Output {
+    let output = run(&["collect", "--format", format]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    output
+}
+
+#[test]
+fn collect_prints_an_export_in_every_format() {
+    for format in [ExportFormat::Json, ExportFormat::Yaml, ExportFormat::Toml] {
+        let output = collect(format.name());
+        let export = Export::deserialize(format, &stdout(&output)).unwrap();
+        assert_eq!(export.schema_version(), Export::SCHEMA_VERSION);
+        assert_eq!(export.hostname(), None);
+    }
+    let output = run(&["collect", "--format", "json", "--compact", "--hostname"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(stdout(&output).lines().count(), 1);
+    assert!(Export::deserialize(ExportFormat::Json, &stdout(&output)).unwrap().hostname().is_some());
+}
+
+// 不带子命令时与 collect 相同
+#[test]
+fn no_subcommand_runs_collect() {
+    let (bare, collect) = (run(&[]), run(&["collect"]));
+    assert_eq!(bare.status.code(), collect.status.code(), "{}", stderr(&bare));
+    assert!(matches!(bare.status.code(), Some(0 | 3 | 4 | 5)), "{}", stderr(&bare));
+}
+
+// 成功时 stdout 恰好是唯一码加换行；失败时 stdout 为空，stderr 只有一行 "error: <kind>: <message>"
+#[test]
+fn hash_prints_exactly_the_code_or_one_error_line() {
+    for args in [&[][..], ANYWHERE] {
+        let output = run(&[&["hash"][..], args].concat());
+        match output.status.code() {
+            Some(0) => {
+                let code = stdout(&output);
+                assert_eq!(code.lines().count(), 1, "{:?}", code);
+                assert!(code.ends_with('\n') && !code.trim().is_empty(), "{:?}", code);
+                assert_eq!(stderr(&output), "");
+            }
+            Some(3..=5) => {
+                assert_eq!(stdout(&output), "");
+                let error = stderr(&output);
+                assert_eq!(error.lines().count(), 1, "{:?}", error);
+                assert!(error.starts_with("error: "), "{:?}", error);
+            }
+            other => panic!("unexpected exit status {:?}: {}", other, stderr(&output)),
+        }
+    }
+}
+
+// hash 的唯一码 verify 为 0，其他唯一码为 1；无法生成唯一码时 verify 与 hash 以同样的退出码失败
+#[test]
+fn verify_exit_status_follows_the_comparison() {
+    let hash = run(&[&["hash"][..], ANYWHERE].concat());
+    let code = stdout(&hash).trim().to_string();
+    let verify = |expected: &str| run(&[&["verify", expected][..], ANYWHERE].concat());
+    match hash.status.code() {
+        Some(0) => {
+            let matching = verify(&code);
+            assert_eq!(matching.status.code(), Some(0), "{}", stderr(&matching));
+            assert_eq!(stdout(&matching).trim(), "match");
+            let other = verify("0000-0000-0000-0000");
+            assert_eq!(other.status.code(), Some(1), "{}", stderr(&other));
+            assert_eq!(stdout(&other).trim(), "mismatch");
+            // 安静模式只有退出码
+            let quiet = run(&[&["-q", "verify", "0000-0000-0000-0000"][..], ANYWHERE].concat());
+            assert_eq!(quiet.status.code(), Some(1));
+            assert_eq!(stdout(&quiet), "");
+        }
+        status => assert_eq!(verify("0000-0000-0000-0000").status.code(), status, "{}", stderr(&hash)),
+    }
+}
+
+// 方案与是否排除 MAC 以唯一码的 v2:、nomac: 前缀为准，不需要与生成时相同的参数
+#[test]
+fn verify_follows_the_prefixes_of_the_code() {
+    for flags in [&["--scheme", "v2"][..], &["--no-mac"], &["--scheme", "v2", "--no-mac"]] {
+        let hash = run(&[&["hash"][..], flags, ANYWHERE].concat());
+        if hash.status.code() != Some(0) {
+            continue;
+        }
+        let code = stdout(&hash).trim().to_string();
+        let verify = run(&[&["verify", &code][..], ANYWHERE].concat());
+        assert_eq!(verify.status.code(), Some(0), "{:?} {}: {}", flags, code, stderr(&verify));
+    }
+}
+
+// 刚导出的快照没有变化（0）；修改其中的字段后 diff 列出变化并以 1 退出
+#[test]
+fn diff_exit_status_follows_the_changes() {
+    let json = stdout(&collect("json"));
+    let snapshot = Snapshot::write(&json, "json");
+    let unchanged = run(&["diff", snapshot.path()]);
+    assert_eq!(unchanged.status.code(), Some(0), "{}\n{}", stdout(&unchanged), stderr(&unchanged));
+    assert!(stdout(&unchanged).contains("no changes"), "{}", stdout(&unchanged));
+
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["info"]["cpu"]["brand"] = serde_json::json!("Replaced CPU @ 1.00GHz");
+    let changed = Snapshot::write(&value.to_string(), "json");
+    let output = run(&["diff", changed.path()]);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Replaced CPU @ 1.00GHz"), "{}", stdout(&output));
+    // diff 只附带说明唯一码是否变化，无法生成唯一码时不记录错误
+    assert!(!stderr(&output).contains("Failed to generate unique code"), "{}", stderr(&output));
+
+    let quiet = run(&["-q", "diff", changed.path()]);
+    assert_eq!(quiet.status.code(), Some(1));
+    assert_eq!(stdout(&quiet), "");
+
+    // 其他格式的快照按扩展名读取
+    let yaml = Snapshot::write(&stdout(&collect("yaml")), "yaml");
+    let output = run(&["diff", yaml.path()]);
+    assert_eq!(output.status.code(), Some(0), "{}\n{}", stdout(&output), stderr(&output));
+}
+
+// 快照的采集时间远晚于本机时间时只提示，照常比较
+#[test]
+fn snapshots_from_the_future_are_a_warning() {
+    let mut value: serde_json::Value = serde_json::from_str(&stdout(&collect("json"))).unwrap();
+    value["collected_at"] = serde_json::json!("2999-01-01T00:00:00Z");
+    let future = Snapshot::write(&value.to_string(), "json");
+    let output = run(&["diff", future.path()]);
+    assert_eq!(output.status.code(), Some(0), "{}\n{}", stdout(&output), stderr(&output));
+    assert!(stderr(&output).contains("in the future"), "{}", stderr(&output));
+
+    // 刚写入的快照不提示；安静模式不输出提示
+    let current = Snapshot::write(&stdout(&collect("json")), "json");
+    assert!(!stderr(&run(&["diff", current.path()])).contains("in the future"));
+    assert_eq!(stderr(&run(&["-q", "diff", future.path()])), "");
+}
+
+// 生成的一致性测试套件通过检查；期望值被改动时以 1 退出并列出不一致的用例
+#[test]
+fn conformance_kit_round_trips() {
+    let dir = std::env::temp_dir().join(format!("hardware_id-cli-{}", Uuid::new_v4().simple()));
+    let dir_arg = dir.to_str().unwrap();
+    let generate = run(&["conformance", "generate", "--output", dir_arg]);
+    assert_eq!(generate.status.code(), Some(0), "{}", stderr(&generate));
+    let check = run(&["conformance", "check", "--dir", dir_arg]);
+    assert_eq!(check.status.code(), Some(0), "{}\n{}", stdout(&check), stderr(&check));
+
+    let manifest = dir.join("manifest.json");
+    let edited = std::fs::read_to_string(&manifest).unwrap().replace("v2:cf45-e033-05c8-f56f", "v2:0000-0000-0000-0000");
+    std::fs::write(&manifest, edited).unwrap();
+    let check = run(&["conformance", "check", "--dir", dir_arg]);
+    assert_eq!(check.status.code(), Some(1), "{}", stderr(&check));
+    assert!(stdout(&check).starts_with("baseline v2: expected v2:0000-0000-0000-0000"), "{}", stdout(&check));
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(run(&["conformance", "check", "--dir", dir_arg]).status.code(), Some(2));
+}
+
+// 确认过的提示与 --no-warnings 只影响 stderr，collect --format 的 warnings 始终是全部提示
+#[test]
+fn acknowledged_warnings_stay_in_the_json() {
+    let state = std::env::temp_dir().join(format!("hardware_id-cli-{}", Uuid::new_v4().simple()));
+    let run = |args: &[&str]| {
+        Command::cargo_bin("hardware_id").unwrap().env("XDG_STATE_HOME", &state).args(args).output().unwrap()
+    };
+    let printed = |output: &Output| stderr(output).lines().filter(|line| line.starts_with("warning: ")).count();
+    let warnings = |output: &Output| {
+        assert_eq!(output.status.code(), Some(0), "{}", stderr(output));
+        serde_json::from_str::<serde_json::Value>(&stdout(output)).unwrap()["warnings"].as_array().unwrap().clone()
+    };
+
+    let before = run(&["collect", "--format", "json"]);
+    let all = warnings(&before);
+    assert_eq!(printed(&before), all.len(), "{}", stderr(&before));
+    let silenced = run(&["collect", "--format", "json", "--no-warnings"]);
+    assert_eq!((printed(&silenced), warnings(&silenced)), (0, all.clone()));
+
+    for warning in &all {
+        let id = warning["id"].as_str().unwrap();
+        let ack = run(&["warn", "ack", id]);
+        assert_eq!(ack.status.code(), Some(0), "{}", stderr(&ack));
+        assert!(stdout(&run(&["warn", "list"])).contains(&format!("{} acknowledged at ", id)));
+    }
+    let after = run(&["collect", "--format", "json"]);
+    assert_eq!((printed(&after), warnings(&after)), (0, all));
+    assert_eq!(run(&["warn", "ack", "nonsense"]).status.code(), Some(2));
+    let _ = std::fs::remove_dir_all(&state);
+}
+
+// 离线镜像：以 MAC 与 machine-id 生成唯一码并提示来自镜像；Windows 镜像为 5，目录不存在为 2
+#[test]
+fn offline_images_print_a_code() {
+    let image = std::env::temp_dir().join(format!("hardware_id-cli-{}", Uuid::new_v4().simple()));
+    let write = |path: &str, contents: &str| {
+        let path = image.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    };
+    write("etc/machine-id", "4c4c4544004a3810803fb7c04f513532\n");
+    write(
+        "etc/udev/rules.d/70-persistent-net.rules",
+        "SUBSYSTEM==\"net\", ATTR{address}==\"d8:9e:f3:12:34:56\", KERNEL==\"eth*\", NAME=\"eth0\"\n",
+    );
+    let root = image.to_str().unwrap();
+    let run = |args: &[&str]| {
+        let mut command = Command::cargo_bin("hardware_id").unwrap();
+        command.env("XDG_STATE_HOME", image.join("state")).args(args).output().unwrap()
+    };
+
+    let output = run(&["offline", "--root", root]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(stdout(&output).lines().count(), 1, "{}", stdout(&output));
+    assert!(stderr(&output).contains("warning: read from an offline image"), "{}", stderr(&output));
+    assert_eq!(stdout(&run(&["offline", "--root", root, "-q"])), stdout(&output));
+
+    let json = run(&["offline", "--root", root, "--format", "json"]);
+    let value: serde_json::Value = serde_json::from_str(&stdout(&json)).unwrap();
+    assert_eq!(value["info"]["collection_mode"], "offline");
+    assert_eq!(value["info"]["machine_id"], "4c4c4544004a3810803fb7c04f513532");
+    // 没有 /etc/fstab，root_fs_uuid 另记为未能读取
+    let warnings = value["warnings"].as_array().unwrap();
+    let ids: Vec<&str> = warnings.iter().map(|warning| warning["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, ["incomplete_collection", "offline_image"]);
+
+    write("Windows/System32/config/SYSTEM", "regf");
+    assert_eq!(run(&["offline", "--root", root]).status.code(), Some(5));
+    std::fs::remove_dir_all(&image).unwrap();
+    assert_eq!(run(&["offline", "--root", root]).status.code(), Some(2));
+}
+
+#[test]
+fn invalid_arguments_exit_with_2() {
+    for args in [
+        &["frobnicate"][..],
+        &["verify"],
+        &["hash", "--timeout", "0"],
+        &["collect", "--compact"],
+        &["collect", "--format", "xml"],
+        &["hash", "--quiet", "--verbose"],
+    ] {
+        let output = run(args);
+        assert_eq!(output.status.code(), Some(2), "{:?}: {}", args, stderr(&output));
+        assert_eq!(stdout(&output), "", "{:?}", args);
+    }
+}
+
+// 快照不存在或无法解析时同样为 2
+#[test]
+fn unreadable_snapshots_exit_with_2() {
+    let missing = std::env::temp_dir().join(format!("hardware_id-cli-{}.json", Uuid::new_v4().simple()));
+    assert_eq!(run(&["diff", missing.to_str().unwrap()]).status.code(), Some(2));
+    let garbage = Snapshot::write("not a snapshot", "json");
+    let output = run(&["diff", garbage.path()]);
+    assert_eq!(output.status.code(), Some(2), "{}", stderr(&output));
+}
@@ -0,0 +1,106 @@
+// 同一台机器上连续运行两次，唯一码与导出的硬件信息必须完全相同（VOLATILE_KEYS 列出的字段除外）
+use std::process::Output;
+use assert_cmd::Command;
+use hardware_id::{CommandRunner, Export, ExportFormat, HardwareError, HardwareInfo};
+use serde_json::Value;
+
+// 允许在两次运行之间变化的字段（按 JSON 路径），这里即是对“哪些字段可以变化”的权威说明：
+//   collected_at  采集时间
+const VOLATILE_KEYS: &[&str] = &["collected_at"];
+
+// 所有命令都失败，采集只依赖文件读取与系统调用，结果不受外部工具的输出影响
+struct UnavailableRunner;
+
+impl CommandRunner for UnavailableRunner {
+    fn run(&self, program: &str, _args: &[&str]) -> Result<Output, HardwareError> {
+        Err(HardwareError::CommandError(format!("{}: not available in this test", program)))
+    }
+}
+
+fn strip_volatile(value: &mut Value) {
+    if let Value::Object(map) = value {
+        for key in VOLATILE_KEYS {
+            map.remove(*key);
+        }
+    }
+}
+
+// 第一个不同之处的 JSON 路径与两次的值，便于定位不稳定的字段
+fn first_difference(path: &str, first: &Value, second: &Value) -> Option<String> {
+    match (first, second) {
+        (Value::Object(a), Value::Object(b)) => a
+            .keys()
+            .chain(b.keys().filter(|key| !a.contains_key(*key)))
+            .find_map(|key| {
+                let (x, y) = (a.get(key).unwrap_or(&Value::Null), b.get(key).unwrap_or(&Value::Null));
+                first_difference(&format!("{}.{}", path, key), x, y)
+            }),
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => a
+            .iter()
+            .zip(b)
+            .enumerate()
+            .find_map(|(i, (x, y))| first_difference(&format!("{}[{}]", path, i), x, y)),
+        _ if first != second => Some(format!("{}: {} then {}", path, first, second)),
+        _ => None,
+    }
+}
+
+fn assert_same(first: &Value, second: &Value) {
+    if let Some(difference) = first_difference("$", first, second) {
+        panic!("unstable field between two runs: {}", difference);
+    }
+}
+
+fn run_binary(args: &[&str]) -> Output {
+    Command::cargo_bin("hardware_id").unwrap().args(args).output().unwrap()
+}
+
+fn export_json(output: &Output) -> Value {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let export = Export::deserialize(ExportFormat::Json, &stdout).expect("collect --format json prints an export");
+    let mut value = serde_json::to_value(&export).unwrap();
+    strip_volatile(&mut value);
+    value
+}
+
+#[test]
+fn collection_with_failing_commands_is_deterministic() {
+    let collect = || {
+        let (info, report) = HardwareInfo::collect_lenient_with_runner(&UnavailableRunner);
+        let fields: Vec<&str> = report.errors().iter().map(|(field, _)| *field).collect();
+        (serde_json::to_value(&info).unwrap(), fields)
+    };
+    let (first, first_fields) = collect();
+    let (second, second_fields) = collect();
+    assert_same(&first, &second);
+    assert_eq!(first_fields, second_fields);
+}
+
+// 在真实硬件上运行构建出的程序；CI 环境中的硬件信息多不完整，默认忽略：cargo test -- --ignored
+#[test]
+#[ignore]
+fn binary_runs_back_to_back_are_identical() {
+    let args = ["collect", "--format", "json", "--allow-container"];
+    let (first, second) = (run_binary(&args), run_binary(&args));
+    assert!(first.status.success(), "{}", String::from_utf8_lossy(&first.stderr));
+    assert_same(&export_json(&first), &export_json(&second));
+
+    // 唯一码（或无法生成时的错误与退出码）同样不变
+    let args = ["hash", "--allow-container"];
+    let (first, second) = (run_binary(&args), run_binary(&args));
+    assert_eq!(first.status.code(), second.status.code());
+    assert_eq!(String::from_utf8_lossy(&first.stdout), String::from_utf8_lossy(&second.stdout));
+    assert_eq!(String::from_utf8_lossy(&first.stderr), String::from_utf8_lossy(&second.stderr));
+}
+
+#[test]
+fn volatile_keys_are_the_only_difference_allowed() {
+    let mut first = serde_json::json!({ "collected_at": 1, "info": { "board": { "serial": "A" } } });
+    let mut second = serde_json::json!({ "collected_at": 2, "info": { "board": { "serial": "B" } } });
+    strip_volatile(&mut first);
+    strip_volatile(&mut second);
+    assert_eq!(
+        first_difference("$", &first, &second).as_deref(),
+        Some("$.info.board.serial: \"A\" then \"B\"")
+    );
+}